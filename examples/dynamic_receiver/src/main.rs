@@ -1,7 +1,7 @@
 use fe2o3_amqp::{
     connection::Connection,
     session::Session,
-    types::{messaging::Source},
+    types::messaging::{DeleteOnClose, Source},
     Receiver,
 };
 
@@ -13,11 +13,25 @@ async fn main() {
     let mut session = Session::begin(&mut connection).await.unwrap();
     let receiver = Receiver::builder()
         .name("dynamic-receiver")
-        .source(Source::builder().dynamic(true).build())
+        .source(
+            Source::builder()
+                .dynamic(true)
+                .add_lifetime_policy(DeleteOnClose::new())
+                .build(),
+        )
         .attach(&mut session)
         .await
         .unwrap();
 
+    println!("address: {:?}", receiver.dynamic_address());
+
+    // The sender holds the authoritative version of the source, which carries the actual
+    // properties of the dynamically created node
+    if let Some(source) = receiver.source() {
+        println!("lifetime policy: {:?}", source.lifetime_policy());
+        println!("node properties: {:?}", source.dynamic_node_properties);
+    }
+
     receiver.close().await.unwrap();
     session.end().await.unwrap();
     connection.close().await.unwrap();
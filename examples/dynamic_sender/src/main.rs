@@ -1,4 +1,9 @@
-use fe2o3_amqp::{connection::Connection, session::Session, types::messaging::Target, Sender};
+use fe2o3_amqp::{
+    connection::Connection,
+    session::Session,
+    types::messaging::{DeleteOnClose, Target},
+    Sender,
+};
 
 #[tokio::main]
 async fn main() {
@@ -8,11 +13,25 @@ async fn main() {
     let mut session = Session::begin(&mut connection).await.unwrap();
     let sender = Sender::builder()
         .name("dynamic-sender")
-        .target(Target::builder().dynamic(true).build())
+        .target(
+            Target::builder()
+                .dynamic(true)
+                .add_lifetime_policy(DeleteOnClose::new())
+                .build(),
+        )
         .attach(&mut session)
         .await
         .unwrap();
 
+    println!("address: {:?}", sender.dynamic_address());
+
+    // The receiver holds the authoritative version of the target, which carries the actual
+    // properties of the dynamically created node
+    if let Some(target) = sender.target() {
+        println!("lifetime policy: {:?}", target.lifetime_policy());
+        println!("node properties: {:?}", target.dynamic_node_properties);
+    }
+
     sender.close().await.unwrap();
     session.end().await.unwrap();
     connection.close().await.unwrap();
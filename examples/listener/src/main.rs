@@ -7,6 +7,7 @@ use fe2o3_amqp::{
     types::primitives::Value,
     Receiver, Sender,
 };
+use futures_util::StreamExt;
 use tokio::net::TcpListener;
 
 #[tokio::main]
@@ -14,32 +15,50 @@ async fn main() {
     let tcp_listener = TcpListener::bind("localhost:5672").await.unwrap();
     let connection_acceptor = ConnectionAcceptor::new("example_connection_acceptor");
 
-    while let Ok((stream, addr)) = tcp_listener.accept().await {
-        println!("Incoming connection from {:?}", addr);
-        let connection = connection_acceptor.accept(stream).await.unwrap();
-
-        let _ = tokio::spawn(connection_main(connection));
-    }
+    connection_acceptor
+        .incoming(tcp_listener)
+        .for_each(|result| async move {
+            match result {
+                Ok(connection) => {
+                    let _ = tokio::spawn(connection_main(connection));
+                }
+                Err(error) => println!("Failed to accept connection: {:?}", error),
+            }
+        })
+        .await;
 }
 
 async fn connection_main(mut connection: ListenerConnectionHandle) {
     let session_acceptor = SessionAcceptor::default();
 
-    while let Ok(session) = session_acceptor.accept(&mut connection).await {
-        let _ = tokio::spawn(session_main(session));
-    }
+    session_acceptor
+        .incoming(&mut connection)
+        .for_each(|result| async move {
+            if let Ok(session) = result {
+                let _ = tokio::spawn(session_main(session));
+            }
+        })
+        .await;
     connection.on_close().await.unwrap();
 }
 
 async fn session_main(mut session: ListenerSessionHandle) {
     let link_acceptor = LinkAcceptor::new();
 
-    while let Ok(link) = link_acceptor.accept(&mut session).await {
-        match link {
-            LinkEndpoint::Sender(sender) => tokio::spawn(sender_main(sender)),
-            LinkEndpoint::Receiver(receiver) => tokio::spawn(receiver_main(receiver)),
-        };
-    }
+    link_acceptor
+        .incoming(&mut session)
+        .for_each(|result| async move {
+            match result {
+                Ok(LinkEndpoint::Sender(sender)) => {
+                    let _ = tokio::spawn(sender_main(sender));
+                }
+                Ok(LinkEndpoint::Receiver(receiver)) => {
+                    let _ = tokio::spawn(receiver_main(receiver));
+                }
+                Err(error) => println!("Failed to accept link: {:?}", error),
+            }
+        })
+        .await;
 
     session.on_end().await.unwrap();
 }
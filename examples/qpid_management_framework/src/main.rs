@@ -116,11 +116,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create a receiver that will be used to receive the response from QMF.
     // This doesn't have to be a dynamic receiver. This is using a dynamic receiver just for convenience.
     let mut receiver = create_dynamic_receiver(&mut session).await?;
-    let source_addr = receiver
-        .source()
-        .as_ref()
-        .and_then(|source| source.address.as_ref())
-        .unwrap(); // There has to be an address if the dynamic receiver is created successfully
+    let source_addr = receiver.dynamic_address().unwrap(); // There has to be an address if the dynamic receiver is created successfully
     println!("Source Addr {:?}", source_addr);
 
     // Create a sender that sends QMF command messages.
@@ -57,7 +57,9 @@ async fn sender_main(mut sender: Sender) {
     let outcome = sender.send(sendable).await.unwrap();
     outcome.accepted_or_else(|outcome| outcome).unwrap();
 
-    sender.on_detach().await;
+    if let Err(error) = sender.on_detach().await {
+        println!("Link detached with error: {:?}", error);
+    }
     sender.close().await.unwrap();
 }
 
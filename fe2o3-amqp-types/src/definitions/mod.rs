@@ -93,6 +93,9 @@ pub use session_error::SessionError;
 mod link_error;
 pub use link_error::LinkError;
 
+mod redirect;
+pub use redirect::Redirect;
+
 /// 2.8.19 Constant definition
 mod constant_def;
 pub use constant_def::{MAJOR, MINOR, MIN_MAX_FRAME_SIZE, PORT, REVISION, SECURE_PORT};
@@ -0,0 +1,106 @@
+use serde_amqp::{primitives::Symbol, Value};
+
+use super::{ConnectionError, Error, ErrorCondition, Fields, LinkError};
+
+/// The `info` of an `amqp:connection:redirect` or `amqp:link:redirect` error
+///
+/// The info map of a redirect error MAY contain the following fields:
+///
+/// - `hostname`: the hostname of the container hosting the terminus
+/// - `network-host`: the DNS hostname or IP address of the machine hosting the container
+/// - `port`: the port number on the machine hosting the container
+/// - `address`: the address of the terminus at the container (only for link redirect)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Redirect {
+    /// The hostname of the container
+    ///
+    /// This is the value to be used in the hostname field of the `Open` frame when connecting to
+    /// the redirected container
+    pub hostname: Option<String>,
+
+    /// The DNS hostname or IP address of the machine hosting the container
+    pub network_host: Option<String>,
+
+    /// The port number on the machine hosting the container
+    pub port: Option<u16>,
+
+    /// The address of the terminus at the container
+    pub address: Option<String>,
+}
+
+impl Redirect {
+    /// Reads the redirect fields from the `info` of an error
+    ///
+    /// Fields of an unexpected type are ignored
+    pub fn from_info(info: &Fields) -> Self {
+        let string = |key: &str| match info.get(&Symbol::from(key)) {
+            Some(Value::String(value)) => Some(value.clone()),
+            Some(Value::Symbol(value)) => Some(value.0.clone()),
+            _ => None,
+        };
+        let port = match info.get(&Symbol::from("port")) {
+            Some(Value::Ushort(port)) => Some(*port),
+            Some(Value::Uint(port)) => u16::try_from(*port).ok(),
+            Some(Value::Int(port)) => u16::try_from(*port).ok(),
+            _ => None,
+        };
+        Self {
+            hostname: string("hostname"),
+            network_host: string("network-host"),
+            port,
+            address: string("address"),
+        }
+    }
+}
+
+impl Error {
+    /// Returns the redirect information if the condition is either `amqp:connection:redirect`
+    /// or `amqp:link:redirect`
+    pub fn redirect(&self) -> Option<Redirect> {
+        match &self.condition {
+            ErrorCondition::ConnectionError(ConnectionError::Redirect)
+            | ErrorCondition::LinkError(LinkError::Redirect) => Some(
+                self.info
+                    .as_ref()
+                    .map(Redirect::from_info)
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_amqp::{primitives::Symbol, Value};
+
+    use crate::definitions::{AmqpError, ConnectionError, Error, Fields, LinkError};
+
+    use super::Redirect;
+
+    #[test]
+    fn test_redirect_from_error_info() {
+        let mut info = Fields::new();
+        info.insert(Symbol::from("hostname"), Value::from("vhost-1"));
+        info.insert(Symbol::from("network-host"), Value::from("10.0.0.5"));
+        info.insert(Symbol::from("port"), Value::Ushort(5673));
+        info.insert(Symbol::from("address"), Value::from("q2"));
+
+        let error = Error::new(LinkError::Redirect, None, info.clone());
+        assert_eq!(
+            error.redirect(),
+            Some(Redirect {
+                hostname: Some(String::from("vhost-1")),
+                network_host: Some(String::from("10.0.0.5")),
+                port: Some(5673),
+                address: Some(String::from("q2")),
+            })
+        );
+
+        let error = Error::new(ConnectionError::Redirect, None, None);
+        assert_eq!(error.redirect(), Some(Redirect::default()));
+
+        let error = Error::new(AmqpError::InternalError, None, info);
+        assert_eq!(error.redirect(), None);
+    }
+}
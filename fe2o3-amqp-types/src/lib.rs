@@ -87,6 +87,39 @@
 // 41. "sasl-code"
 //
 
+/// Implements `From<$ty> for Value` and `TryFrom<Value> for $ty` for composite types.
+///
+/// The conversions go through the binary encoding so that described types (including the
+/// enums like `DeliveryState` and `Outcome`) are handled the same way as when they are
+/// decoded from the wire.
+///
+/// Types with a blanket `impl<T: Into<_>> From<T>` (ie. `Source`, `Target` and `Error`) cannot
+/// use this macro because `TryFrom<Value>` would conflict with the blanket impl in `core`.
+#[allow(unused_macros)]
+macro_rules! impl_value_conversions {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl From<$ty> for serde_amqp::Value {
+                fn from(value: $ty) -> Self {
+                    // Encoding a composite type into a `Value` never fails
+                    serde_amqp::to_vec(&value)
+                        .and_then(|buf| serde_amqp::from_slice(&buf))
+                        .expect("Composite types should always convert to Value")
+                }
+            }
+
+            impl TryFrom<serde_amqp::Value> for $ty {
+                type Error = serde_amqp::Error;
+
+                fn try_from(value: serde_amqp::Value) -> Result<Self, serde_amqp::Error> {
+                    let buf = serde_amqp::to_vec(&value)?;
+                    serde_amqp::from_slice(&buf)
+                }
+            }
+        )*
+    };
+}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "primitive")))]
 #[cfg(feature = "primitive")]
 pub mod primitives;
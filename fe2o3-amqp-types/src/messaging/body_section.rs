@@ -1,6 +1,6 @@
 use std::{
     borrow::Cow,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     rc::Rc,
     sync::Arc,
 };
@@ -172,6 +172,24 @@ pub trait DeserializableBody<'de>: Deserialize<'de> + BodySection {}
 ///    then the [`Batch<Data>`] would probably be the best choice.
 /// 6. Use [`Body`] if a type may need to change the body section type.
 ///
+/// # Encoding of standard collections
+///
+/// The following types are wrapped in an [`AmqpValue`] body section with the AMQP encoding
+/// listed below. [`FromBody`] is implemented for the same types and expects the same encoding.
+///
+/// | Rust type | AMQP encoding |
+/// |-----------|---------------|
+/// | `Vec<T>`, `VecDeque<T>` | `list` |
+/// | `HashSet<T>`, `BTreeSet<T>` | `list` |
+/// | [`Array<T>`](serde_amqp::primitives::Array) | `array` |
+/// | `HashMap<K, V>`, `BTreeMap<K, V>`, [`OrderedMap<K, V>`](serde_amqp::primitives::OrderedMap) | `map` |
+/// | `String`, `&str`, `Cow<'_, str>` | `string` |
+/// | [`Binary`](serde_amqp::primitives::Binary) | `binary` |
+///
+/// Please note that `Vec<u8>` is encoded as a `list` of `ubyte`s. Use [`Binary`](serde_amqp::primitives::Binary)
+/// or the [`Data`] body section for opaque bytes, and use [`Array<T>`](serde_amqp::primitives::Array)
+/// if the receiving side expects an AMQP `array`.
+///
 /// # Example
 ///
 /// ```rust
@@ -233,6 +251,8 @@ where
 /// Please note that this also requires the type to implement the [`FromEmptyBody`] trait, which
 /// handles the case when an empty body is found. Please see [`FromEmptyBody`] for more information.
 ///
+/// Please see [`IntoBody`] for the AMQP encoding expected for the standard collections.
+///
 /// # `Body` section type?
 ///
 /// # Example
@@ -388,8 +408,31 @@ impl_into_serializable_body! {
 }
 
 impl_into_serializable_body!(AmqpValue, T:ser::Serialize; Vec);
+impl_into_serializable_body!(AmqpValue, T:ser::Serialize; VecDeque);
 impl_into_serializable_body!(AmqpValue, T:ser::Serialize; Array);
 
+impl<T> IntoBody for HashSet<T>
+where
+    T: ser::Serialize + std::hash::Hash + Eq,
+{
+    type Body = AmqpValue<Self>;
+
+    fn into_body(self) -> Self::Body {
+        AmqpValue(self)
+    }
+}
+
+impl<T> IntoBody for BTreeSet<T>
+where
+    T: ser::Serialize + Ord,
+{
+    type Body = AmqpValue<Self>;
+
+    fn into_body(self) -> Self::Body {
+        AmqpValue(self)
+    }
+}
+
 impl<'a> IntoBody for &'a str {
     type Body = AmqpValue<&'a str>;
 
@@ -510,8 +553,33 @@ impl_from_deserializable_or_empty_body! {
 }
 
 impl_from_deserializable_or_empty_body!(AmqpValue, T:Deserialize<'de>; Vec);
+impl_from_deserializable_or_empty_body!(AmqpValue, T:Deserialize<'de>; VecDeque);
 impl_from_deserializable_or_empty_body!(AmqpValue, T:Deserialize<'de>; Array);
 
+blanket_impl_from_empty_body!(T; HashSet);
+impl<'de, T> FromBody<'de> for HashSet<T>
+where
+    T: de::Deserialize<'de> + std::hash::Hash + Eq,
+{
+    type Body = AmqpValue<Self>;
+
+    fn from_body(deserializable: Self::Body) -> Self {
+        deserializable.0
+    }
+}
+
+blanket_impl_from_empty_body!(T; BTreeSet);
+impl<'de, T> FromBody<'de> for BTreeSet<T>
+where
+    T: de::Deserialize<'de> + Ord,
+{
+    type Body = AmqpValue<Self>;
+
+    fn from_body(deserializable: Self::Body) -> Self {
+        deserializable.0
+    }
+}
+
 blanket_impl_from_empty_body!(K, V; OrderedMap);
 impl<'de, K, V> FromBody<'de> for OrderedMap<K, V>
 where
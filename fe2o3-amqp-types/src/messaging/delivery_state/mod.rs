@@ -353,6 +353,54 @@ impl Outcome {
         }
     }
 
+    /// Whether the outcome is a terminal state, which is always true.
+    ///
+    /// This mirrors [`DeliveryState::is_terminal`]
+    pub fn is_terminal(&self) -> bool {
+        true
+    }
+
+    /// Returns a reference to the [`Accepted`] outcome or `None` if the outcome is not accepted
+    pub fn as_accepted(&self) -> Option<&Accepted> {
+        match self {
+            Self::Accepted(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the [`Rejected`] outcome or `None` if the outcome is not rejected
+    pub fn as_rejected(&self) -> Option<&Rejected> {
+        match self {
+            Self::Rejected(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the [`Released`] outcome or `None` if the outcome is not released
+    pub fn as_released(&self) -> Option<&Released> {
+        match self {
+            Self::Released(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the [`Modified`] outcome or `None` if the outcome is not modified
+    pub fn as_modified(&self) -> Option<&Modified> {
+        match self {
+            Self::Modified(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the [`Declared`] outcome or `None` if the outcome is not declared
+    #[cfg(feature = "transaction")]
+    pub fn as_declared(&self) -> Option<&Declared> {
+        match self {
+            Self::Declared(value) => Some(value),
+            _ => None,
+        }
+    }
+
     /// Transforms the [`DeliveryState`] into a `Result<Accepted, E>`,
     /// mapping Accepted(accepted) to Ok(accepted) and other variants to Err(err).
     pub fn accepted_or<E>(self, err: E) -> Result<Accepted, E> {
@@ -623,12 +671,26 @@ impl From<Modified> for Outcome {
     }
 }
 
+impl_value_conversions!(
+    DeliveryState,
+    Outcome,
+    Received,
+    Accepted,
+    Rejected,
+    Released,
+    Modified
+);
+
 #[cfg(test)]
 mod tests {
     //! Test serialization and deserialization
     use serde_amqp::{de::from_slice, format_code::EncodingCodes, from_reader, ser::to_vec};
 
-    use super::{Accepted, DeliveryState, Modified, Received, Rejected, Released};
+    use serde_amqp::Value;
+
+    use crate::definitions::{AmqpError, Error};
+
+    use super::{Accepted, DeliveryState, Modified, Outcome, Received, Rejected, Released};
 
     /* ---------------------------- // test Accepted ---------------------------- */
     #[test]
@@ -847,4 +909,57 @@ mod tests {
         };
         assert!(smaller == larger);
     }
+
+    #[test]
+    fn test_outcome_accessors() {
+        let outcome = Outcome::from(Rejected { error: None });
+        assert!(outcome.is_terminal());
+        assert!(outcome.as_rejected().is_some());
+        assert!(outcome.as_accepted().is_none());
+        assert!(outcome.as_released().is_none());
+        assert!(outcome.as_modified().is_none());
+
+        let outcome = Outcome::from(Modified {
+            delivery_failed: Some(true),
+            undeliverable_here: Some(false),
+            message_annotations: None,
+        });
+        let modified = outcome.as_modified().unwrap();
+        assert_eq!(modified.delivery_failed, Some(true));
+        assert_eq!(modified.undeliverable_here, Some(false));
+        assert!(outcome.as_rejected().is_none());
+
+        assert!(Outcome::from(Accepted {}).as_accepted().is_some());
+        assert!(Outcome::from(Released {}).as_released().is_some());
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        let modified = Modified {
+            delivery_failed: Some(true),
+            undeliverable_here: None,
+            message_annotations: None,
+        };
+        let value = Value::from(modified.clone());
+        assert!(matches!(value, Value::Described(_)));
+
+        let state = DeliveryState::try_from(value.clone()).unwrap();
+        assert!(matches!(state, DeliveryState::Modified(_)));
+        let outcome = Outcome::try_from(value.clone()).unwrap();
+        assert!(matches!(outcome, Outcome::Modified(_)));
+        let modified = Modified::try_from(value).unwrap();
+        assert_eq!(modified.delivery_failed, Some(true));
+        assert!(modified.undeliverable_here.is_none());
+
+        let error = Error::new(AmqpError::NotAllowed, None, None);
+        let rejected = Rejected {
+            error: Some(error.clone()),
+        };
+        let value = Value::from(DeliveryState::Rejected(rejected));
+        let rejected = Rejected::try_from(value.clone()).unwrap();
+        assert_eq!(rejected.error, Some(error));
+        assert!(Modified::try_from(value).is_err());
+
+        assert!(Accepted::try_from(Value::from(Released {})).is_err());
+    }
 }
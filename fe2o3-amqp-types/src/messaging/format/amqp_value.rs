@@ -12,6 +12,10 @@ use crate::messaging::{
 /// <type name="amqp-value" class="restricted" source="*" provides="section">
 ///     <descriptor name="amqp:amqp-value:*" code="0x00000000:0x00000077"/>
 /// </type>
+///
+/// A custom type that derives `SerializeComposite` and `DeserializeComposite` is encoded as a
+/// described value inside the section and can be received as `Message<AmqpValue<T>>` once `T`
+/// implements [`FromEmptyBody`].
 #[derive(
     Debug, Clone, PartialEq, Eq, PartialOrd, Ord, SerializeComposite, DeserializeComposite, Hash,
 )]
@@ -99,7 +103,10 @@ where
 #[cfg(test)]
 mod tests {
     use serde::{Deserialize, Serialize};
-    use serde_amqp::{from_slice, lazy::LazyValue, to_vec, Value};
+    use serde_amqp::{
+        descriptor::Descriptor, from_slice, lazy::LazyValue, to_vec, DeserializeComposite,
+        SerializeComposite, Value,
+    };
 
     use crate::messaging::{
         message::__private::{Deserializable, Serializable},
@@ -219,4 +226,112 @@ mod tests {
         let expected = to_vec(&TEST_STR).unwrap();
         assert_eq!(msg.0.body.0.as_slice(), expected);
     }
+
+    fn assert_round_trip_with_format_code<T>(value: T, format_code: u8)
+    where
+        T: IntoBody + for<'de> FromBody<'de> + std::fmt::Debug + PartialEq + Clone,
+    {
+        let msg = Message::from(value.clone());
+        let buf = to_vec(&Serializable(msg)).unwrap();
+        // [0x00, 0x53, 0x77] is the descriptor of the amqp-value section
+        assert_eq!(&buf[..3], &[0x00, 0x53, 0x77]);
+        assert_eq!(buf[3], format_code);
+
+        let decoded: Deserializable<Message<T>> = from_slice(&buf).unwrap();
+        assert_eq!(decoded.0.body, value);
+    }
+
+    #[test]
+    fn test_encoding_standard_collections() {
+        use serde_amqp::primitives::{Array, OrderedMap};
+        use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+        const LIST8: u8 = 0xc0;
+        const MAP8: u8 = 0xc1;
+        const ARRAY8: u8 = 0xe0;
+
+        assert_round_trip_with_format_code(vec![1i32, 2, 3], LIST8);
+        assert_round_trip_with_format_code(VecDeque::from(vec![1i32, 2, 3]), LIST8);
+        assert_round_trip_with_format_code(HashSet::from([1i32, 2, 3]), LIST8);
+        assert_round_trip_with_format_code(BTreeSet::from([1i32, 2, 3]), LIST8);
+        assert_round_trip_with_format_code(Array::from(vec![1i32, 2, 3]), ARRAY8);
+
+        let map = HashMap::from([(String::from("a"), String::from("b"))]);
+        assert_round_trip_with_format_code(map, MAP8);
+        let map = BTreeMap::from([(String::from("a"), String::from("b"))]);
+        assert_round_trip_with_format_code(map, MAP8);
+        let mut map = OrderedMap::new();
+        map.insert(String::from("a"), String::from("b"));
+        assert_round_trip_with_format_code(map, MAP8);
+    }
+
+    #[derive(Debug, Clone, PartialEq, SerializeComposite, DeserializeComposite)]
+    #[amqp_contract(
+        name = "example:test-composite:list",
+        code = "0x0000_0137:0x0000_0001",
+        encoding = "list",
+        rename_all = "kebab-case"
+    )]
+    struct TestComposite {
+        a: i32,
+        b: Option<String>,
+    }
+
+    impl FromEmptyBody for TestComposite {}
+
+    #[test]
+    fn test_encoding_decoding_described_custom_type() {
+        let expected = TestComposite {
+            a: 9,
+            b: Some(String::from("b")),
+        };
+        let msg = Message::builder().value(expected.clone()).build();
+        let buf = to_vec(&Serializable(msg)).unwrap();
+        let expected_buf = [
+            0x00, 0x53, 0x77, // amqp-value descriptor
+            0x00, 0x80, 0x00, 0x00, 0x01, 0x37, 0x00, 0x00, 0x00, 0x01, // custom descriptor
+            0xc0, 0x06, 0x02, 0x54, 0x09, 0xa1, 0x01, b'b', // list of fields
+        ];
+        assert_eq!(buf, expected_buf);
+
+        let decoded: Deserializable<Message<AmqpValue<TestComposite>>> = from_slice(&buf).unwrap();
+        assert_eq!(decoded.0.body.0, expected);
+
+        // The described type is kept when the body is decoded as a `Value`
+        let decoded: Deserializable<Message<Value>> = from_slice(&buf).unwrap();
+        match decoded.0.body {
+            Value::Described(described) => {
+                assert_eq!(
+                    described.descriptor,
+                    Descriptor::Code(0x0000_0137_0000_0001)
+                );
+            }
+            _ => panic!("Expecting a described value"),
+        }
+    }
+
+    #[test]
+    fn test_decoding_value_keeps_descriptor_name() {
+        use serde_amqp::{described::Described, primitives::Symbol};
+
+        let inner = Value::Described(Box::new(Described {
+            descriptor: Descriptor::Code(0x0000_0137_0000_0002),
+            value: Value::Uint(7),
+        }));
+        let expected = Value::Described(Box::new(Described {
+            descriptor: Descriptor::Name(Symbol::from("example:routing:key")),
+            value: Value::List(vec![Value::String(String::from("foo")), inner]),
+        }));
+        let msg = Message::builder().value(expected.clone()).build();
+        let buf = to_vec(&Serializable(msg)).unwrap();
+
+        let decoded: Deserializable<Message<Value>> = from_slice(&buf).unwrap();
+        assert_eq!(decoded.0.body, expected);
+
+        let decoded: Deserializable<Message<Body<Value>>> = from_slice(&buf).unwrap();
+        match decoded.0.body {
+            Body::Value(AmqpValue(value)) => assert_eq!(value, expected),
+            _ => panic!("Expecting an amqp-value body"),
+        }
+    }
 }
@@ -114,3 +114,5 @@ impl From<Builder> for Option<Header> {
         Some(builder.build())
     }
 }
+
+impl_value_conversions!(Header);
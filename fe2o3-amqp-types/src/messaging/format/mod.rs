@@ -322,9 +322,11 @@ pub const MESSAGE_FORMAT: u32 = 0; // FIXME: type of message format?
 
 #[cfg(test)]
 mod tests {
-    use serde_amqp::{primitives::Binary, to_vec};
+    use serde_amqp::{from_slice, primitives::Binary, to_vec};
 
-    use super::{AmqpSequence, Header, Priority};
+    use crate::primitives::SimpleValue;
+
+    use super::{AmqpSequence, ApplicationProperties, Header, Priority};
 
     #[test]
     fn test_serialize_deserialize_header() {
@@ -339,6 +341,21 @@ mod tests {
         println!("{:x?}", &serialized);
     }
 
+    #[test]
+    fn test_application_properties_char() {
+        let properties = ApplicationProperties::builder()
+            .insert("ascii", 'a')
+            .insert("emoji", '\u{1F600}')
+            .build();
+        let buf = to_vec(&properties).unwrap();
+        let deserialized: ApplicationProperties = from_slice(&buf).unwrap();
+        assert_eq!(
+            deserialized.get("emoji"),
+            Some(&SimpleValue::Char('\u{1F600}'))
+        );
+        assert_eq!(deserialized, properties);
+    }
+
     #[test]
     fn test_display_data() {
         let b = Binary::from(vec![1u8, 2]);
@@ -14,6 +14,9 @@ use super::{Address, MessageId};
 /// <type name="properties" class="composite" source="list" provides="section">
 ///     <descriptor name="amqp:properties:list" code="0x00000000:0x00000073"/>
 /// </type>
+///
+/// A `None` field is encoded as `null` (`0x40`) when a later field is present and is omitted from
+/// the list otherwise. When decoding, a `null` and a missing field both become `None`.
 #[derive(
     Debug, Clone, SerializeComposite, DeserializeComposite, PartialEq, Eq, PartialOrd, Ord, Hash,
 )]
@@ -109,6 +112,8 @@ impl From<Builder> for Option<Properties> {
     }
 }
 
+impl_value_conversions!(Properties);
+
 /// Builder for [`Properties`]
 #[derive(Debug, Clone)]
 pub struct Builder {
@@ -215,3 +220,74 @@ impl Builder {
         self.inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_amqp::{format_code::EncodingCodes, from_slice, to_vec};
+
+    use super::Properties;
+
+    #[test]
+    fn test_serialize_all_none_properties_as_list0() {
+        let properties = Properties::default();
+        let buf = to_vec(&properties).unwrap();
+        let expected = vec![
+            EncodingCodes::DescribedType as u8,
+            EncodingCodes::SmallUlong as u8,
+            0x73,
+            EncodingCodes::List0 as u8,
+        ];
+        assert_eq!(buf, expected);
+
+        let decoded: Properties = from_slice(&buf).unwrap();
+        assert_eq!(decoded, properties);
+    }
+
+    #[test]
+    fn test_serialize_none_fields_as_null_or_omitted() {
+        let properties = Properties::builder()
+            .user_id(vec![0x01, 0x02])
+            .subject("a")
+            .build();
+        let buf = to_vec(&properties).unwrap();
+        let expected = vec![
+            EncodingCodes::DescribedType as u8,
+            EncodingCodes::SmallUlong as u8,
+            0x73,
+            EncodingCodes::List8 as u8,
+            0x0a,                       // size
+            0x04,                       // count
+            EncodingCodes::Null as u8,  // message-id
+            EncodingCodes::Vbin8 as u8, // user-id
+            0x02,
+            0x01,
+            0x02,
+            EncodingCodes::Null as u8, // to
+            EncodingCodes::Str8 as u8, // subject
+            0x01,
+            b'a',
+        ];
+        assert_eq!(buf, expected);
+
+        let decoded: Properties = from_slice(&buf).unwrap();
+        assert_eq!(decoded, properties);
+    }
+
+    #[test]
+    fn test_deserialize_trailing_nulls_as_none() {
+        let buf = vec![
+            EncodingCodes::DescribedType as u8,
+            EncodingCodes::SmallUlong as u8,
+            0x73,
+            EncodingCodes::List8 as u8,
+            0x05,                      // size
+            0x04,                      // count
+            EncodingCodes::Null as u8, // message-id
+            EncodingCodes::Null as u8, // user-id
+            EncodingCodes::Null as u8, // to
+            EncodingCodes::Null as u8, // subject
+        ];
+        let decoded: Properties = from_slice(&buf).unwrap();
+        assert_eq!(decoded, Properties::default());
+    }
+}
@@ -198,6 +198,55 @@ pub enum LifetimePolicy {
     DeleteOnNoLinksOrMessages(DeleteOnNoLinksOrMessages),
 }
 
+/// Key of the lifetime policy entry in the node properties
+const LIFETIME_POLICY: &str = "lifetime-policy";
+
+impl LifetimePolicy {
+    /// Reads the "lifetime-policy" entry of node properties (eg. the "dynamic-node-properties"
+    /// field of a [`Source`](super::Source) or [`Target`](super::Target))
+    ///
+    /// Returns `None` if the entry is absent or is not a known lifetime policy
+    pub fn from_node_properties(properties: &Fields) -> Option<Self> {
+        let value = properties.get(LIFETIME_POLICY)?;
+        Self::try_from(value).ok()
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for LifetimePolicy {
+    type Error = &'a Value;
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        let described = match value {
+            Value::Described(described) => described,
+            _ => return Err(value),
+        };
+
+        let policy = match &described.descriptor {
+            Descriptor::Code(0x0000_0000_0000_002b) => Self::DeleteOnClose(DeleteOnClose {}),
+            Descriptor::Code(0x0000_0000_0000_002c) => Self::DeleteOnNoLinks(DeleteOnNoLinks {}),
+            Descriptor::Code(0x0000_0000_0000_002d) => {
+                Self::DeleteOnNoMessages(DeleteOnNoMessages {})
+            }
+            Descriptor::Code(0x0000_0000_0000_002e) => {
+                Self::DeleteOnNoLinksOrMessages(DeleteOnNoLinksOrMessages {})
+            }
+            Descriptor::Name(name) => match name.as_str() {
+                "amqp:delete-on-close:list" => Self::DeleteOnClose(DeleteOnClose {}),
+                "amqp:delete-on-no-links:list" => Self::DeleteOnNoLinks(DeleteOnNoLinks {}),
+                "amqp:delete-on-no-messages:list" => {
+                    Self::DeleteOnNoMessages(DeleteOnNoMessages {})
+                }
+                "amqp:delete-on-no-links-or-messages:list" => {
+                    Self::DeleteOnNoLinksOrMessages(DeleteOnNoLinksOrMessages {})
+                }
+                _ => return Err(value),
+            },
+            Descriptor::Code(_) => return Err(value),
+        };
+        Ok(policy)
+    }
+}
+
 impl ser::Serialize for LifetimePolicy {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -341,16 +390,16 @@ impl From<LifetimePolicy> for Fields {
         let mut map = Self::new();
         match value {
             LifetimePolicy::DeleteOnClose(value) => {
-                map.insert(Symbol::from("lifetime-policy"), Value::from(value));
+                map.insert(Symbol::from(LIFETIME_POLICY), Value::from(value));
             }
             LifetimePolicy::DeleteOnNoLinks(value) => {
-                map.insert(Symbol::from("lifetime-policy"), Value::from(value));
+                map.insert(Symbol::from(LIFETIME_POLICY), Value::from(value));
             }
             LifetimePolicy::DeleteOnNoMessages(value) => {
-                map.insert(Symbol::from("lifetime-policy"), Value::from(value));
+                map.insert(Symbol::from(LIFETIME_POLICY), Value::from(value));
             }
             LifetimePolicy::DeleteOnNoLinksOrMessages(value) => {
-                map.insert(Symbol::from("lifetime-policy"), Value::from(value));
+                map.insert(Symbol::from(LIFETIME_POLICY), Value::from(value));
             }
         }
         map
@@ -361,7 +410,9 @@ impl From<LifetimePolicy> for Fields {
 mod tests {
     use serde_amqp::{from_slice, to_value, to_vec};
 
-    use super::{DeleteOnClose, LifetimePolicy};
+    use crate::definitions::Fields;
+
+    use super::{DeleteOnClose, DeleteOnNoLinks, LifetimePolicy};
 
     #[test]
     fn test_serialize_enum_and_struct() {
@@ -385,6 +436,21 @@ mod tests {
         assert!(matches!(enum_value, LifetimePolicy::DeleteOnClose(_)));
     }
 
+    #[test]
+    fn test_lifetime_policy_from_node_properties() {
+        let properties = Fields::from(LifetimePolicy::DeleteOnNoLinks(DeleteOnNoLinks::new()));
+        let policy = LifetimePolicy::from_node_properties(&properties);
+        assert!(matches!(policy, Some(LifetimePolicy::DeleteOnNoLinks(_))));
+
+        // Decoded properties carry the descriptor as read from the wire
+        let buf = to_vec(&properties).unwrap();
+        let decoded: Fields = from_slice(&buf).unwrap();
+        let policy = LifetimePolicy::from_node_properties(&decoded);
+        assert!(matches!(policy, Some(LifetimePolicy::DeleteOnNoLinks(_))));
+
+        assert!(LifetimePolicy::from_node_properties(&Fields::new()).is_none());
+    }
+
     #[test]
     fn test_lifetime_to_value() {
         let delete_on_close = DeleteOnClose::new();
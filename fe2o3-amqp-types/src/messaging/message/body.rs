@@ -113,6 +113,95 @@ impl<T> Body<T> {
     }
 }
 
+impl<T> Body<T> {
+    /// Creates a body from an ordered list of body sections
+    ///
+    /// The sections are validated against the combinations allowed by the core specification:
+    /// one or more [`Data`] sections, one or more [`AmqpSequence`] sections, or a single
+    /// [`AmqpValue`] section. An empty list of sections results in [`Body::Empty`].
+    pub fn try_from_sections(
+        sections: impl IntoIterator<Item = Section<T>>,
+    ) -> Result<Self, BodySectionError> {
+        let mut body = Body::Empty;
+        for section in sections {
+            body = match (body, section) {
+                (Body::Empty, Section::Data(data)) => Body::Data(Batch::new(vec![data])),
+                (Body::Empty, Section::Sequence(seq)) => Body::Sequence(Batch::new(vec![seq])),
+                (Body::Empty, Section::Value(value)) => Body::Value(value),
+                (Body::Data(mut batch), Section::Data(data)) => {
+                    batch.push(data);
+                    Body::Data(batch)
+                }
+                (Body::Sequence(mut batch), Section::Sequence(seq)) => {
+                    batch.push(seq);
+                    Body::Sequence(batch)
+                }
+                (Body::Value(_), Section::Value(_)) => {
+                    return Err(BodySectionError::MultipleValueSections)
+                }
+                _ => return Err(BodySectionError::MixedSections),
+            };
+        }
+        Ok(body)
+    }
+}
+
+/// A single body section of a message
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Section<T> {
+    /// A data section
+    Data(Data),
+
+    /// An amqp-sequence section
+    Sequence(AmqpSequence<T>),
+
+    /// An amqp-value section
+    Value(AmqpValue<T>),
+}
+
+impl<T> From<Data> for Section<T> {
+    fn from(value: Data) -> Self {
+        Self::Data(value)
+    }
+}
+
+impl<T> From<AmqpSequence<T>> for Section<T> {
+    fn from(value: AmqpSequence<T>) -> Self {
+        Self::Sequence(value)
+    }
+}
+
+impl<T> From<AmqpValue<T>> for Section<T> {
+    fn from(value: AmqpValue<T>) -> Self {
+        Self::Value(value)
+    }
+}
+
+/// Error with an illegal combination of body sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodySectionError {
+    /// Different kinds of body sections are mixed (eg. an [`AmqpValue`] and a [`Data`])
+    MixedSections,
+
+    /// There is more than one [`AmqpValue`] section
+    MultipleValueSections,
+}
+
+impl Display for BodySectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BodySectionError::MixedSections => {
+                write!(f, "Body sections of different kinds cannot be mixed")
+            }
+            BodySectionError::MultipleValueSections => {
+                write!(f, "Only a single amqp-value body section is allowed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BodySectionError {}
+
 impl<T> Display for Body<T>
 where
     T: Display,
@@ -356,4 +445,56 @@ where
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use serde_amqp::primitives::Binary;
+
+    use crate::messaging::{AmqpSequence, AmqpValue, Data};
+
+    use super::{Body, BodySectionError, Section};
+
+    #[test]
+    fn test_body_from_sections() {
+        let body = Body::<i32>::try_from_sections(vec![]).unwrap();
+        assert!(body.is_empty());
+
+        let body = Body::<i32>::try_from_sections(vec![
+            Section::Data(Data(Binary::from("foo"))),
+            Section::Data(Data(Binary::from("bar"))),
+        ])
+        .unwrap();
+        let data: Vec<_> = body.try_into_data().unwrap().collect();
+        assert_eq!(data, vec![Binary::from("foo"), Binary::from("bar")]);
+
+        let body = Body::try_from_sections(vec![
+            Section::Sequence(AmqpSequence(vec![1, 2])),
+            Section::Sequence(AmqpSequence(vec![3])),
+        ])
+        .unwrap();
+        let sequences: Vec<_> = body.try_into_sequence().unwrap().collect();
+        assert_eq!(sequences, vec![vec![1, 2], vec![3]]);
+
+        let body = Body::try_from_sections(vec![Section::Value(AmqpValue(1))]).unwrap();
+        assert_eq!(body.try_into_value().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_body_from_illegal_sections() {
+        let result = Body::try_from_sections(vec![
+            Section::Value(AmqpValue(1)),
+            Section::Data(Data(Binary::from("foo"))),
+        ]);
+        assert_eq!(result, Err(BodySectionError::MixedSections));
+
+        let result = Body::try_from_sections(vec![
+            Section::Data(Data(Binary::from("foo"))),
+            Section::Sequence(AmqpSequence(vec![1])),
+        ]);
+        assert_eq!(result, Err(BodySectionError::MixedSections));
+
+        let result = Body::try_from_sections(vec![
+            Section::Value(AmqpValue(1)),
+            Section::Value(AmqpValue(2)),
+        ]);
+        assert_eq!(result, Err(BodySectionError::MultipleValueSections));
+    }
+}
@@ -2,12 +2,16 @@
 
 use std::marker::PhantomData;
 
+use bytes::Bytes;
 use serde::{
     de::{self},
     ser::SerializeStruct,
     Serialize,
 };
-use serde_amqp::__constants::{DESCRIBED_BASIC, DESCRIPTOR};
+use serde_amqp::{
+    __constants::{DESCRIBED_BASIC, DESCRIPTOR},
+    primitives::Array,
+};
 
 use super::{
     AmqpSequence, AmqpValue, ApplicationProperties, Batch, Data, DeliveryAnnotations, Footer,
@@ -62,6 +66,14 @@ where
 }
 
 /// AMQP 1.0 Message
+///
+/// # Order of sections
+///
+/// Sections are always encoded in the order defined by the specification. By default, decoding
+/// fails if the sections are out of order. [`Message::from_bytes_lenient`] and
+/// [`Message::deserialize_lenient`] fill in each section whenever it is encountered regardless of
+/// its position, so messages from non-conformant peers (eg. `delivery-annotations` sent before
+/// `header`) can still be decoded.
 #[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Message<B> {
     /// Transport headers for a message.
@@ -123,6 +135,91 @@ impl<'de, B: FromBody<'de>> de::Deserialize<'de> for Deserializable<Message<B>>
     }
 }
 
+/// Serializes a [`Message`] into a `Vec<u8>`
+///
+/// The bytes are the concatenated sections of the message, which is the same format as the
+/// payload of a transfer. This can be used to persist a message without going through the
+/// serialization wrapper types.
+///
+/// # Example
+///
+/// ```rust
+/// use fe2o3_amqp_types::messaging::{message_from_slice, message_to_vec, Message};
+///
+/// let message = Message::from("hello");
+/// let buf = message_to_vec(&message).unwrap();
+/// let decoded: Message<String> = message_from_slice(&buf).unwrap();
+/// assert_eq!(decoded.body, "hello");
+/// ```
+pub fn message_to_vec<B>(message: &Message<B>) -> Result<Vec<u8>, serde_amqp::Error>
+where
+    B: SerializableBody,
+{
+    serde_amqp::to_vec(&Serializable(message))
+}
+
+/// Deserializes a [`Message`] from a slice of bytes
+///
+/// This is the counterpart of [`message_to_vec`].
+pub fn message_from_slice<'de, B>(slice: &'de [u8]) -> Result<Message<B>, serde_amqp::Error>
+where
+    B: FromBody<'de>,
+{
+    let message: Deserializable<Message<B>> = serde_amqp::from_slice(slice)?;
+    Ok(message.0)
+}
+
+/// Serializes the bare message (ie. the `properties`, `application-properties` and body
+/// sections) of a [`Message`] into a `Vec<u8>`
+///
+/// The bare message is immutable within the AMQP network, which makes these bytes suitable for
+/// computing or verifying a signature carried in the [`Footer`]. The sections are encoded with
+/// the same encoding that is used when the message is sent, so the result matches the bytes on
+/// the wire as long as the peer that produced the message uses the same encoding for each field.
+///
+/// # Example
+///
+/// ```rust
+/// use fe2o3_amqp_types::messaging::{bare_message_to_vec, message_to_vec, Header, Message};
+///
+/// let message = Message::builder()
+///     .header(Header::builder().durable(true).build())
+///     .value("hello")
+///     .build();
+/// let bare = bare_message_to_vec(&message).unwrap();
+/// let full = message_to_vec(&message).unwrap();
+/// assert!(full.ends_with(&bare));
+/// ```
+pub fn bare_message_to_vec<B>(message: &Message<B>) -> Result<Vec<u8>, serde_amqp::Error>
+where
+    B: SerializableBody,
+{
+    serde_amqp::to_vec(&BareMessage(message))
+}
+
+struct BareMessage<'a, B>(&'a Message<B>);
+
+impl<B> Serialize for BareMessage<'_, B>
+where
+    B: SerializableBody,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let message = self.0;
+        let mut state = serializer.serialize_struct(DESCRIBED_BASIC, 3)?;
+        if let Some(properties) = &message.properties {
+            state.serialize_field("properties", properties)?;
+        }
+        if let Some(application_properties) = &message.application_properties {
+            state.serialize_field("application_properties", application_properties)?
+        }
+        state.serialize_field("body", &message.body)?;
+        state.end()
+    }
+}
+
 impl Message<EmptyBody> {
     /// Creates a Builder for [`Message`]
     pub fn builder() -> Builder<EmptyBody> {
@@ -168,6 +265,39 @@ impl<T> Message<T> {
         }
     }
 
+    /// Clone all sections except for the body
+    ///
+    /// This is useful for transform-and-forward pipelines where a new message with a transformed
+    /// body is built from the header, annotations, properties and footer of the incoming message
+    /// without cloning a potentially large body. The returned message has an [`EmptyBody`], which
+    /// can be replaced with [`Message::map_body`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fe2o3_amqp_types::messaging::{AmqpValue, Message, Properties};
+    ///
+    /// let message = Message::builder()
+    ///     .properties(Properties::builder().message_id(1u64).build())
+    ///     .value(String::from("hello"))
+    ///     .build();
+    /// let forwarded: Message<AmqpValue<usize>> = message
+    ///     .headers_only()
+    ///     .map_body(|_| AmqpValue(message.body.0.len()));
+    /// assert_eq!(forwarded.properties, message.properties);
+    /// ```
+    pub fn headers_only(&self) -> Message<EmptyBody> {
+        Message {
+            header: self.header.clone(),
+            delivery_annotations: self.delivery_annotations.clone(),
+            message_annotations: self.message_annotations.clone(),
+            properties: self.properties.clone(),
+            application_properties: self.application_properties.clone(),
+            body: EmptyBody {},
+            footer: self.footer.clone(),
+        }
+    }
+
     /// Map body to SerializableBody
     pub fn map_body<F, B>(self, op: F) -> Message<B>
     where
@@ -185,6 +315,49 @@ impl<T> Message<T> {
     }
 }
 
+impl<B> Message<B>
+where
+    B: SerializableBody,
+{
+    /// Serializes the full message (all sections) into [`Bytes`]
+    ///
+    /// The encoding is the same as the payload of a transfer, which makes the bytes suitable for
+    /// persisting a message (eg. in an outbox) independent of the transport. The message can be
+    /// restored with [`Message::from_bytes`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fe2o3_amqp_types::messaging::{AmqpValue, Message};
+    ///
+    /// let message = Message::builder().value(String::from("hello")).build();
+    /// let bytes = message.to_bytes().unwrap();
+    /// let decoded = Message::<AmqpValue<String>>::from_bytes(&bytes).unwrap();
+    /// assert_eq!(decoded, message);
+    /// ```
+    pub fn to_bytes(&self) -> Result<Bytes, serde_amqp::Error> {
+        message_to_vec(self).map(Bytes::from)
+    }
+}
+
+impl<'de, B> Message<B>
+where
+    B: FromBody<'de>,
+{
+    /// Deserializes the full message (all sections) from a slice of bytes
+    ///
+    /// This is the counterpart of [`Message::to_bytes`].
+    pub fn from_bytes(slice: &'de [u8]) -> Result<Self, serde_amqp::Error> {
+        message_from_slice(slice)
+    }
+
+    /// Same as [`Message::from_bytes`] but tolerates sections that are out of order
+    pub fn from_bytes_lenient(slice: &'de [u8]) -> Result<Self, serde_amqp::Error> {
+        let mut de = serde_amqp::de::Deserializer::new(serde_amqp::read::SliceReader::new(slice));
+        Self::deserialize_lenient(&mut de)
+    }
+}
+
 // impl<T> Serialize for Message<T>
 impl<B> Message<B>
 where
@@ -218,6 +391,7 @@ where
     }
 }
 
+#[derive(PartialEq, PartialOrd)]
 enum Field {
     Header,
     DeliveryAnnotations,
@@ -282,6 +456,7 @@ impl<'de> de::Deserialize<'de> for Field {
 }
 
 struct Visitor<B> {
+    lenient: bool,
     marker: PhantomData<B>,
 }
 
@@ -308,14 +483,20 @@ where
         let mut footer = None;
 
         let mut count = 0;
+        let mut prev_field = None;
         while count < 7 {
             let field: Field = match seq.next_element()? {
                 Some(val) => val,
                 None => break,
             };
 
+            // The variants of `Field` are declared in the order defined by the specification
+            if !self.lenient && prev_field.as_ref().is_some_and(|prev| *prev >= field) {
+                return Err(de::Error::custom("Message sections are out of order"));
+            }
+
             #[allow(unused_variables)]
-            match field {
+            match &field {
                 Field::Header => {
                     header = seq.next_element()?;
                     count += 1;
@@ -346,6 +527,7 @@ where
                     count += 1;
                 }
             }
+            prev_field = Some(field);
         }
 
         let body = match body {
@@ -371,6 +553,28 @@ where
     B: FromBody<'de>,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, <D as serde::Deserializer<'de>>::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Self::deserialize_with(deserializer, false)
+    }
+
+    /// Deserializes the message while tolerating sections that are out of order
+    ///
+    /// Each section is filled in whenever it is encountered regardless of its position.
+    pub fn deserialize_lenient<D>(
+        deserializer: D,
+    ) -> Result<Self, <D as serde::Deserializer<'de>>::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Self::deserialize_with(deserializer, true)
+    }
+
+    fn deserialize_with<D>(
+        deserializer: D,
+        lenient: bool,
+    ) -> Result<Self, <D as serde::Deserializer<'de>>::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -393,6 +597,7 @@ where
                 "footer",
             ],
             Visitor::<B> {
+                lenient,
                 marker: PhantomData,
             },
         )
@@ -521,6 +726,9 @@ impl<T> Builder<T> {
     }
 
     /// Set the body as `Body::Value`
+    ///
+    /// A `Vec<V>` passed to this function is encoded as an AMQP `list`. Use
+    /// [`array`](#method.array) if an AMQP `array` is expected instead.
     pub fn value<V: Serialize>(self, value: V) -> Builder<AmqpValue<V>> {
         Builder {
             header: self.header,
@@ -533,7 +741,27 @@ impl<T> Builder<T> {
         }
     }
 
+    /// Set the body as `Body::Value` that holds an AMQP `array`
+    ///
+    /// An AMQP `array` is a sequence of values of a single type, whereas [`value`](#method.value)
+    /// with a `Vec<V>` produces a (polymorphic) AMQP `list` and [`sequence`](#method.sequence)
+    /// produces an `amqp-sequence` body section. Use this if the receiving side expects an `array`.
+    pub fn array<V: Serialize>(self, values: impl Into<Array<V>>) -> Builder<AmqpValue<Array<V>>> {
+        Builder {
+            header: self.header,
+            delivery_annotations: self.delivery_annotations,
+            message_annotations: self.message_annotations,
+            properties: self.properties,
+            application_properties: self.application_properties,
+            body: AmqpValue(values.into()),
+            footer: self.footer,
+        }
+    }
+
     /// Set the body as a single `Body::Sequence` section
+    ///
+    /// This produces an `amqp-sequence` body section, which is different from an `amqp-value`
+    /// body section that holds a `list` or an `array`
     pub fn sequence<V: Serialize>(
         self,
         values: impl Into<AmqpSequence<V>>,
@@ -594,6 +822,35 @@ impl<T> Builder<T> {
         }
     }
 
+    /// Set the body from an ordered list of body sections
+    ///
+    /// This gives full control over the body sections that are sent. An error is returned if the
+    /// sections are not one of the combinations allowed by the core specification: one or more
+    /// [`Data`] sections, one or more [`AmqpSequence`] sections, or a single [`AmqpValue`] section.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use fe2o3_amqp_types::messaging::{message::Section, Data, Message};
+    /// use fe2o3_amqp_types::primitives::{Binary, Value};
+    ///
+    /// let message = Message::builder()
+    ///     .raw_body_sections(vec![
+    ///         Section::<Value>::Data(Data(Binary::from("foo"))),
+    ///         Section::Data(Data(Binary::from("bar"))),
+    ///     ])
+    ///     .unwrap()
+    ///     .build();
+    /// assert!(message.body.is_data());
+    /// ```
+    pub fn raw_body_sections<B>(
+        self,
+        sections: impl IntoIterator<Item = Section<B>>,
+    ) -> Result<Builder<Body<B>>, BodySectionError> {
+        let body = Body::try_from_sections(sections)?;
+        Ok(self.body(body))
+    }
+
     /// Build the [`Message`]
     pub fn build(self) -> Message<T> {
         Message {
@@ -617,8 +874,8 @@ mod tests {
 
     use crate::messaging::{
         message::{
-            Body,
             __private::{Deserializable, Serializable},
+            Body,
         },
         AmqpSequence, AmqpValue, ApplicationProperties, Batch, Data, DeliveryAnnotations, Footer,
         Header, MessageAnnotations, Properties,
@@ -649,6 +906,19 @@ mod tests {
         assert_eq!(buf[2], 0x76);
     }
 
+    #[test]
+    fn test_builder_array_body() {
+        let message = Message::builder().array(vec![1i32, 2, 3]).build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        assert_eq!(buf[2], 0x77); // amqp-value
+        assert_eq!(buf[3], 0xe0); // array8
+
+        let message = Message::builder().value(vec![1i32, 2, 3]).build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        assert_eq!(buf[2], 0x77); // amqp-value
+        assert_eq!(buf[3], 0xc0); // list8
+    }
+
     #[test]
     fn test_convert_amqp_value_into_message() {
         let value = AmqpValue(vec![1, 2, 3, 4]);
@@ -699,6 +969,26 @@ mod tests {
         println!("{:?}", field);
     }
 
+    #[test]
+    fn test_user_id_round_trip_as_binary() {
+        let message = Message::builder()
+            .properties(Properties::builder().user_id(b"guest".to_vec()).build())
+            .value("hello")
+            .build();
+        let buf = to_vec(&Serializable(message)).unwrap();
+        // properties descriptor, list8, size, count, null message-id, vbin8 user-id
+        assert_eq!(&buf[..3], &[0x00, 0x53, 0x73]);
+        assert_eq!(&buf[6..9], &[0x40, 0xa0, 0x05]);
+        assert_eq!(&buf[9..14], b"guest");
+
+        let deserialized: Deserializable<Message<AmqpValue<String>>> = from_slice(&buf).unwrap();
+        let properties = deserialized.0.properties.unwrap();
+        assert_eq!(
+            properties.user_id.as_ref().map(|id| id.as_slice()),
+            Some(&b"guest"[..])
+        );
+    }
+
     #[test]
     fn test_serialize_message() {
         let message = Message {
@@ -754,6 +1044,28 @@ mod tests {
         assert!(deserialized.0.footer.is_some());
     }
 
+    #[test]
+    fn test_decoding_message_with_out_of_order_sections_is_strict_by_default() {
+        let delivery_annotations = DeliveryAnnotations::builder().insert("key", 1u32).build();
+        let header = Header {
+            durable: true,
+            ..Default::default()
+        };
+
+        // delivery-annotations before header
+        let mut buf = to_vec(&delivery_annotations).unwrap();
+        buf.extend(to_vec(&header).unwrap());
+        buf.extend(to_vec(&AmqpValue("hello")).unwrap());
+
+        let result: Result<Deserializable<Message<AmqpValue<String>>>, _> = from_slice(&buf);
+        assert!(result.is_err());
+
+        let message = Message::<AmqpValue<String>>::from_bytes_lenient(&buf).unwrap();
+        assert_eq!(message.header, Some(header));
+        assert_eq!(message.delivery_annotations, Some(delivery_annotations));
+        assert_eq!(message.body, AmqpValue(String::from("hello")));
+    }
+
     #[test]
     fn test_decoding_message_with_no_body_section_from_slice() {
         let buf: [u8; 8] = [0x0, 0x53, 0x70, 0x45, 0x0, 0x53, 0x73, 0x45];
@@ -942,4 +1254,129 @@ mod tests {
             .build();
         assert_eq!(message.0, expected);
     }
+
+    #[test]
+    fn test_message_to_vec_and_message_from_slice() {
+        use super::{message_from_slice, message_to_vec};
+
+        let message = Message::builder()
+            .properties(Properties::builder().message_id(1u64).build())
+            .value(String::from("hello"))
+            .build();
+        let buf = message_to_vec(&message).unwrap();
+        assert_eq!(buf, to_vec(&Serializable(&message)).unwrap());
+
+        let decoded: Message<AmqpValue<String>> = message_from_slice(&buf).unwrap();
+        assert_eq!(decoded, message);
+
+        let decoded: Message<String> = message_from_slice(&buf).unwrap();
+        assert_eq!(decoded.body, "hello");
+        assert_eq!(decoded.properties, message.properties);
+    }
+
+    #[test]
+    fn test_message_to_bytes_and_from_bytes() {
+        let message = Message::builder()
+            .header(Header::builder().durable(true).build())
+            .delivery_annotations(
+                DeliveryAnnotations::builder()
+                    .insert("x-opt-baz", "qux")
+                    .build(),
+            )
+            .message_annotations(
+                MessageAnnotations::builder()
+                    .insert("x-opt-foo", "bar")
+                    .build(),
+            )
+            .properties(Properties::builder().message_id(1u64).build())
+            .application_properties(ApplicationProperties::builder().insert("key", 1i32).build())
+            .data(vec![1u8, 2, 3])
+            .footer(
+                Footer::builder()
+                    .insert("signature", Binary::from(vec![1u8, 2, 3]))
+                    .build(),
+            )
+            .build();
+        let bytes = message.to_bytes().unwrap();
+        let decoded = Message::<Data>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn test_bare_message_to_vec() {
+        use super::{bare_message_to_vec, message_to_vec};
+
+        let message = Message::builder()
+            .header(Header::builder().durable(true).build())
+            .message_annotations(
+                MessageAnnotations::builder()
+                    .insert("x-opt-foo", "bar")
+                    .build(),
+            )
+            .properties(Properties::builder().message_id(1u64).build())
+            .value(String::from("hello"))
+            .footer(
+                Footer::builder()
+                    .insert("signature", Binary::from(vec![1u8, 2, 3]))
+                    .build(),
+            )
+            .build();
+        let bare = bare_message_to_vec(&message).unwrap();
+
+        let bare_message = Message::builder()
+            .properties(Properties::builder().message_id(1u64).build())
+            .value(String::from("hello"))
+            .build();
+        assert_eq!(bare, message_to_vec(&bare_message).unwrap());
+
+        // The bare message is the same regardless of the annotations
+        let mut annotated = message.clone();
+        annotated.delivery_annotations = None;
+        annotated.message_annotations = None;
+        assert_eq!(bare, bare_message_to_vec(&annotated).unwrap());
+    }
+
+    #[test]
+    fn test_headers_only() {
+        use super::message_to_vec;
+
+        let message = Message::builder()
+            .header(Header::builder().durable(true).build())
+            .delivery_annotations(
+                DeliveryAnnotations::builder()
+                    .insert("x-opt-baz", "qux")
+                    .build(),
+            )
+            .message_annotations(
+                MessageAnnotations::builder()
+                    .insert("x-opt-foo", "bar")
+                    .build(),
+            )
+            .properties(Properties::builder().message_id(1u64).build())
+            .application_properties(ApplicationProperties::builder().insert("key", 1i32).build())
+            .data(vec![0u8; 1024])
+            .footer(
+                Footer::builder()
+                    .insert("signature", Binary::from(vec![1u8, 2, 3]))
+                    .build(),
+            )
+            .build();
+
+        let forwarded = message
+            .headers_only()
+            .map_body(|_| AmqpValue(String::from("transformed")));
+        let expected = Message {
+            header: message.header.clone(),
+            delivery_annotations: message.delivery_annotations.clone(),
+            message_annotations: message.message_annotations.clone(),
+            properties: message.properties.clone(),
+            application_properties: message.application_properties.clone(),
+            body: AmqpValue(String::from("transformed")),
+            footer: message.footer.clone(),
+        };
+        assert_eq!(
+            message_to_vec(&forwarded).unwrap(),
+            message_to_vec(&expected).unwrap()
+        );
+    }
 }
@@ -9,7 +9,7 @@ mod body_section;
 pub use body_section::*;
 
 pub mod message;
-pub use message::{Body, Message};
+pub use message::{bare_message_to_vec, message_from_slice, message_to_vec, Body, Message};
 
 /* -------------------------- 3.2 Messaging Format -------------------------- */
 mod format;
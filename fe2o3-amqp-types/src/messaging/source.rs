@@ -76,6 +76,46 @@ impl Source {
     pub fn builder() -> SourceBuilder {
         SourceBuilder::new()
     }
+
+    /// Creates a [`Source`] for a durable subscription to `address` with a single filter entry
+    ///
+    /// This is a shorthand for a [`SourceBuilder`] with the `address` set, with
+    /// [`SourceBuilder::durable_subscription`] applied, and with `filter` added under `filter_name`.
+    /// The filter is commonly a JMS selector (eg. `SelectorFilter` from the `fe2o3-amqp-ext`
+    /// crate), which is conventionally added under the name `"jms-selector"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use fe2o3_amqp_ext::filters::SelectorFilter;
+    ///
+    /// let source = Source::durable_filtered(
+    ///     "topic://orders",
+    ///     "jms-selector",
+    ///     SelectorFilter::new("region = 'EU'"),
+    /// );
+    /// ```
+    pub fn durable_filtered(
+        address: impl Into<Address>,
+        filter_name: impl Into<Symbol>,
+        filter: impl Into<Option<Described<Value>>>,
+    ) -> Source {
+        Self::builder()
+            .address(address)
+            .durable_subscription()
+            .add_to_filter(filter_name, filter)
+            .build()
+    }
+
+    /// Get the "lifetime-policy" entry of the "dynamic-node-properties" field
+    ///
+    /// When set by the sending link endpoint, this contains the actual lifetime policy of the
+    /// dynamically created node
+    pub fn lifetime_policy(&self) -> Option<LifetimePolicy> {
+        self.dynamic_node_properties
+            .as_ref()
+            .and_then(LifetimePolicy::from_node_properties)
+    }
 }
 
 /// [`Source`] builder
@@ -116,6 +156,16 @@ impl SourceBuilder {
         self
     }
 
+    /// Configure the source for a durable subscription
+    ///
+    /// This sets the "durable" field to [`TerminusDurability::UnsettledState`] and the
+    /// "expiry-policy" field to [`TerminusExpiryPolicy::Never`] so that the subscription and its
+    /// unsettled state outlive the link, the session and the connection.
+    pub fn durable_subscription(self) -> Self {
+        self.durable(TerminusDurability::UnsettledState)
+            .expiry_policy(TerminusExpiryPolicy::Never)
+    }
+
     /// Set the "timeout" field
     pub fn timeout(mut self, timeout: Seconds) -> Self {
         self.source.timeout = timeout;
@@ -290,8 +340,78 @@ impl SourceBuilder {
     }
 }
 
+/// Creates a [`Source`] with only the `address` field set, eg. from a `&str` or `String`
 impl<T: Into<Address>> From<T> for Source {
     fn from(val: T) -> Self {
         Self::builder().address(val.into()).build()
     }
 }
+
+/// Builds the [`Source`] so that a [`SourceBuilder`] can be passed wherever an `impl Into<Source>`
+/// is expected without calling [`SourceBuilder::build`]
+impl From<SourceBuilder> for Source {
+    fn from(builder: SourceBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_amqp::{described::Described, descriptor::Descriptor, primitives::Symbol, Value};
+
+    use crate::messaging::{TerminusDurability, TerminusExpiryPolicy};
+
+    use super::Source;
+
+    #[test]
+    fn test_durable_filtered() {
+        let selector = Described {
+            descriptor: Descriptor::Code(0x0000_468c_0000_0004),
+            value: Value::String(String::from("region = 'EU'")),
+        };
+        let source = Source::durable_filtered("topic", "jms-selector", selector.clone());
+
+        assert_eq!(source.address.as_deref(), Some("topic"));
+        assert_eq!(source.durable, TerminusDurability::UnsettledState);
+        assert_eq!(source.expiry_policy, TerminusExpiryPolicy::Never);
+
+        let filter = source.filter.unwrap();
+        assert_eq!(filter.len(), 1);
+        assert_eq!(
+            filter.get(&Symbol::from("jms-selector")),
+            Some(&Value::from(selector))
+        );
+    }
+
+    #[test]
+    fn test_decode_source_with_extra_trailing_field() {
+        let source = Source::builder()
+            .address("q1")
+            .dynamic(true)
+            .capabilities(vec![Symbol::from("shared")])
+            .build();
+
+        // A source from a newer spec revision with one more field than is known here
+        let mut fields = match serde_amqp::to_value(&source).unwrap() {
+            Value::Described(described) => match described.value {
+                Value::List(fields) => fields,
+                _ => panic!("Expecting a list"),
+            },
+            _ => panic!("Expecting a described type"),
+        };
+        assert_eq!(fields.len(), 11);
+        fields.push(Value::String(String::from("unknown")));
+        let extended = Described {
+            descriptor: Descriptor::Code(0x0000_0000_0000_0028),
+            value: Value::List(fields),
+        };
+
+        // The extra field must be skipped without disturbing what follows it
+        let buf = serde_amqp::to_vec(&(extended, 42u32)).unwrap();
+        let (decoded, trailing): (Source, u32) = serde_amqp::from_slice(&buf).unwrap();
+        assert_eq!(decoded.address.as_deref(), Some("q1"));
+        assert!(decoded.dynamic);
+        assert_eq!(decoded.capabilities, source.capabilities);
+        assert_eq!(trailing, 42);
+    }
+}
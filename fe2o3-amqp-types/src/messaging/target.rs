@@ -204,6 +204,16 @@ impl Target {
     pub fn builder() -> TargetBuilder {
         TargetBuilder::new()
     }
+
+    /// Get the "lifetime-policy" entry of the "dynamic-node-properties" field
+    ///
+    /// When set by the receiving link endpoint, this contains the actual lifetime policy of the
+    /// dynamically created node
+    pub fn lifetime_policy(&self) -> Option<LifetimePolicy> {
+        self.dynamic_node_properties
+            .as_ref()
+            .and_then(LifetimePolicy::from_node_properties)
+    }
 }
 
 impl TryFrom<TargetArchetype> for Target {
@@ -218,6 +228,7 @@ impl TryFrom<TargetArchetype> for Target {
     }
 }
 
+/// Creates a [`Target`] with only the `address` field set, eg. from a `&str` or `String`
 impl<T: Into<Address>> From<T> for Target {
     fn from(val: T) -> Self {
         Self {
@@ -228,6 +239,16 @@ impl<T: Into<Address>> From<T> for Target {
     }
 }
 
+/// Builds the [`Target`] so that a [`TargetBuilder`] can be passed wherever an `impl Into<Target>`
+/// is expected without calling [`TargetBuilder::build`]
+impl From<TargetBuilder> for Target {
+    fn from(builder: TargetBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Any value that can be converted into a [`Target`] (eg. an address string or a
+/// [`TargetBuilder`]) can be converted into a [`TargetArchetype`]
 impl<T: Into<Target>> From<T> for TargetArchetype {
     fn from(value: T) -> Self {
         let target = value.into();
@@ -387,6 +408,8 @@ impl TargetBuilder {
 mod tests {
     use serde_amqp::{from_slice, to_vec};
 
+    use crate::messaging::{Source, TerminusDurability};
+
     use super::{Target, TargetArchetype};
 
     #[test]
@@ -399,6 +422,51 @@ mod tests {
         // println!("{:?}", std::mem::size_of::<Target>());
     }
 
+    #[test]
+    fn test_target_lifetime_policy() {
+        use crate::messaging::{DeleteOnClose, LifetimePolicy};
+
+        let target = Target::builder()
+            .dynamic(true)
+            .add_lifetime_policy(DeleteOnClose::new())
+            .build();
+        let buf = to_vec(&target).unwrap();
+        let decoded: Target = from_slice(&buf).unwrap();
+        assert!(matches!(
+            decoded.lifetime_policy(),
+            Some(LifetimePolicy::DeleteOnClose(_))
+        ));
+
+        assert!(Target::default().lifetime_policy().is_none());
+    }
+
+    #[test]
+    fn test_terminus_conversions() {
+        let target = Target::from("q1");
+        assert_eq!(target.address.as_deref(), Some("q1"));
+
+        let target = Target::from(
+            Target::builder()
+                .address(String::from("q1"))
+                .durable(TerminusDurability::UnsettledState),
+        );
+        assert_eq!(target.address.as_deref(), Some("q1"));
+        assert_eq!(target.durable, TerminusDurability::UnsettledState);
+
+        let archetype = TargetArchetype::from(Target::builder().address("q1"));
+        assert!(
+            matches!(archetype, TargetArchetype::Target(t) if t.address.as_deref() == Some("q1"))
+        );
+
+        let source = Source::from(
+            Source::builder()
+                .address("q1")
+                .durable(TerminusDurability::Configuration),
+        );
+        assert_eq!(source.address.as_deref(), Some("q1"));
+        assert_eq!(source.durable, TerminusDurability::Configuration);
+    }
+
     #[cfg(feature = "transaction")]
     #[test]
     fn test_target_archetype_variant_coordinator() {
@@ -71,6 +71,21 @@ mod tests {
         assert_eq!(buf, expected);
     }
 
+    #[test]
+    fn test_deserialize_explicit_null_settled() {
+        // settled and batchable are encoded as explicit null
+        let buf = &[
+            0x0u8, 0x53, 0x15, 0xc0, 0xa, 0x6, 0x41, 0x43, 0x40, 0x40, 0x0, 0x53, 0x24, 0x45, 0x40,
+        ];
+        let disposition: crate::performatives::Disposition = from_slice(buf).unwrap();
+        assert!(!disposition.settled);
+        assert!(!disposition.batchable);
+        assert!(matches!(
+            disposition.state,
+            Some(DeliveryState::Accepted(Accepted {}))
+        ));
+    }
+
     #[allow(dead_code)]
     #[derive(Debug, Clone)]
     pub struct Disposition {
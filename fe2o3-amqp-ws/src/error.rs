@@ -65,6 +65,11 @@ pub enum Error {
     #[error("Expect \"Sec-WebSocket-Protocol\" equal to \"amqp\"")]
     SecWebSocketProtocolIsNotAmqp,
 
+    /// The server selected a WebSocket extension (eg. `permessage-deflate`) that was not offered
+    /// by the client
+    #[error("Unexpected \"Sec-WebSocket-Extensions\": {0}")]
+    UnexpectedSecWebSocketExtensions(String),
+
     /// `tungstenite::Error::AttackAttempt` error. Attack attempt detected.
     #[error("Attack attempt detected")]
     AttackAttempt,
@@ -66,6 +66,15 @@
 //! }
 //! ```
 //!
+//! ## Compression
+//!
+//! The WebSocket `permessage-deflate` extension (RFC 7692) is not supported because the underlying
+//! `tungstenite` does not implement it. The client never offers the extension, and the handshake
+//! fails with [`Error::UnexpectedSecWebSocketExtensions`] if the server selects an extension
+//! anyway. AMQP frames are thus always sent uncompressed over the WebSocket. Compressing the
+//! message bodies at the application level (eg. with the `content-encoding` message property)
+//! is the alternative for bandwidth-constrained clients, at the cost of CPU time on both peers.
+//!
 //! ## WebAssembly support
 //!
 //! Experimental support for `wasm32-unknown-unknown` target has been added since "0.3.0" and uses a
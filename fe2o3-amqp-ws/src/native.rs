@@ -23,6 +23,7 @@ use crate::WsMessage;
 use super::{Error, WebSocketStream};
 
 const SEC_WEBSOCKET_PROTOCOL: &str = "Sec-WebSocket-Protocol";
+const SEC_WEBSOCKET_EXTENSIONS: &str = "Sec-WebSocket-Extensions";
 
 pin_project! {
     /// This a simple wrapper around [`tokio_tungstenite::WebSocketStream`]
@@ -312,6 +313,14 @@ fn verify_response(response: Response) -> Result<Response, Error> {
         return Err(Error::StatucCodeIsNotSwitchingProtocols);
     }
 
+    // No extension is offered by the client, so the server must not select any. Otherwise the
+    // frames from the server could be compressed (eg. with `permessage-deflate`), which
+    // `tungstenite` cannot decode
+    if let Some(extensions) = response.headers().get(SEC_WEBSOCKET_EXTENSIONS) {
+        let extensions = String::from_utf8_lossy(extensions.as_bytes()).into_owned();
+        return Err(Error::UnexpectedSecWebSocketExtensions(extensions));
+    }
+
     match response
         .headers()
         .get(SEC_WEBSOCKET_PROTOCOL)
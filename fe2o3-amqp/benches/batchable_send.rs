@@ -0,0 +1,81 @@
+//! Throughput of `send_batchable` with and without coalesced writes
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fe2o3_amqp::{
+    acceptor::{ConnectionAcceptor, LinkAcceptor, LinkEndpoint, SessionAcceptor},
+    Connection, Sender, Session,
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    runtime::Runtime,
+};
+
+const MESSAGES_PER_ITER: usize = 100;
+
+/// Attaches a sender to a listener on the loopback interface that accepts every message
+async fn attach_sender(coalesce_writes: bool) -> Sender {
+    let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp_listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = tcp_listener.accept().await.unwrap();
+        stream.set_nodelay(true).unwrap();
+        let mut listener = ConnectionAcceptor::new("bench-listener")
+            .accept(stream)
+            .await
+            .unwrap();
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut receiver = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        while let Ok(delivery) = receiver.recv::<String>().await {
+            receiver.accept(&delivery).await.unwrap();
+        }
+    });
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    // Nagle's algorithm would otherwise coalesce the writes whether or not it is enabled
+    stream.set_nodelay(true).unwrap();
+    let mut connection = Connection::builder()
+        .container_id("bench-connection")
+        .coalesce_writes(coalesce_writes)
+        .open_with_stream(stream)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let sender = Sender::attach(&mut session, "bench-sender", "q1")
+        .await
+        .unwrap();
+    // The connection and the session are stopped if their handles are dropped
+    std::mem::forget(session);
+    std::mem::forget(connection);
+    sender
+}
+
+async fn send_batch(sender: &mut Sender) {
+    let mut outcomes = Vec::with_capacity(MESSAGES_PER_ITER);
+    for _ in 0..MESSAGES_PER_ITER {
+        outcomes.push(sender.send_batchable("hello AMQP").await.unwrap());
+    }
+    for outcome in outcomes {
+        outcome.await.unwrap().accepted_or_else(|o| o).unwrap();
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("batchable_send");
+    group.throughput(Throughput::Elements(MESSAGES_PER_ITER as u64));
+    for coalesce_writes in [false, true] {
+        let mut sender = rt.block_on(attach_sender(coalesce_writes));
+        group.bench_with_input(
+            BenchmarkId::new("coalesce_writes", coalesce_writes),
+            &coalesce_writes,
+            |b, _| b.iter(|| rt.block_on(send_batch(&mut sender))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 //! Builder for acceptors
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use fe2o3_amqp_types::{
     definitions::{
@@ -11,16 +11,20 @@ use fe2o3_amqp_types::{
     performatives::{ChannelMax, MaxFrameSize, Open},
     primitives::{Array, Symbol, Ulong},
 };
+use tokio::sync::Semaphore;
 
 use crate::{
     connection::{DEFAULT_CHANNEL_MAX, DEFAULT_MAX_FRAME_SIZE, DEFAULT_OUTGOING_BUFFER_SIZE},
+    session::WindowPolicy,
     util::{Initialized, Uninitialized},
 };
 
 use super::{
-    link::LinkAcceptor, local_receiver_link::LocalReceiverLinkAcceptor,
-    local_sender_link::LocalSenderLinkAcceptor, session::SessionAcceptor, ConnectionAcceptor,
-    SaslAcceptor, SupportedReceiverSettleModes, SupportedSenderSettleModes,
+    link::LinkAcceptor,
+    local_receiver_link::LocalReceiverLinkAcceptor,
+    local_sender_link::LocalSenderLinkAcceptor,
+    session::{DuplicateLinkNamePolicy, SessionAcceptor},
+    ConnectionAcceptor, SaslAcceptor, SupportedReceiverSettleModes, SupportedSenderSettleModes,
 };
 
 cfg_transaction! {
@@ -74,6 +78,7 @@ impl Builder<ConnectionAcceptor<(), ()>, Uninitialized> {
             tls_acceptor: (),
             sasl_acceptor: (),
             buffer_size: DEFAULT_OUTGOING_BUFFER_SIZE,
+            connection_permits: None,
         };
 
         Self {
@@ -132,9 +137,13 @@ impl<M, Tls, Sasl> Builder<ConnectionAcceptor<Tls, Sasl>, M> {
         self
     }
 
-    /// Idle time-out
-    pub fn idle_time_out(mut self, idle_time_out: impl Into<Milliseconds>) -> Self {
-        self.inner.local_open.idle_time_out = Some(idle_time_out.into());
+    /// Idle time-out in milliseconds
+    ///
+    /// `None` disables the local idle time-out, which is the default. `Some(0)` is invalid and
+    /// accepting a connection will fail with
+    /// [`OpenError::InvalidIdleTimeOut`](crate::connection::OpenError::InvalidIdleTimeOut).
+    pub fn idle_time_out(mut self, idle_time_out: impl Into<Option<Milliseconds>>) -> Self {
+        self.inner.local_open.idle_time_out = idle_time_out.into();
         self
     }
 
@@ -215,6 +224,7 @@ impl<M, Tls, Sasl> Builder<ConnectionAcceptor<Tls, Sasl>, M> {
             tls_acceptor,
             sasl_acceptor: self.inner.sasl_acceptor,
             buffer_size: self.inner.buffer_size,
+            connection_permits: self.inner.connection_permits,
         };
         Builder {
             inner,
@@ -232,6 +242,7 @@ impl<M, Tls, Sasl> Builder<ConnectionAcceptor<Tls, Sasl>, M> {
             tls_acceptor: self.inner.tls_acceptor,
             sasl_acceptor,
             buffer_size: self.inner.buffer_size,
+            connection_permits: self.inner.connection_permits,
         };
         Builder {
             inner,
@@ -244,6 +255,24 @@ impl<M, Tls, Sasl> Builder<ConnectionAcceptor<Tls, Sasl>, M> {
         self.inner.buffer_size = buffer_size;
         self
     }
+
+    /// Maximum number of connections that can be open at the same time
+    ///
+    /// Once the limit is reached, [`ConnectionAcceptor::accept`] closes the incoming stream and
+    /// returns [`OpenError::ConnectionLimitReached`](crate::connection::OpenError::ConnectionLimitReached)
+    /// until one of the accepted connections is closed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_connections` is zero
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        assert!(
+            max_connections > 0,
+            "max_connections must be greater than zero"
+        );
+        self.inner.connection_permits = Some(Arc::new(Semaphore::new(max_connections)));
+        self
+    }
 }
 
 // =============================================================================
@@ -279,6 +308,12 @@ impl Builder<SessionAcceptor, Initialized> {
         self
     }
 
+    /// How the incoming-window is advertised to the remote peer
+    pub fn incoming_window_policy(mut self, policy: WindowPolicy) -> Self {
+        self.inner.0.incoming_window_policy = policy;
+        self
+    }
+
     /// The initial outgoing-window of the sender
     pub fn outgoing_window(mut self, value: TransferNumber) -> Self {
         self.inner.0.outgoing_window = value;
@@ -334,6 +369,15 @@ impl Builder<SessionAcceptor, Initialized> {
         self
     }
 
+    /// Sets how an incoming attach with a link name that is already used by an attached link on
+    /// the session is handled. See [`DuplicateLinkNamePolicy`]
+    ///
+    /// Default value: `DuplicateLinkNamePolicy::Reject`
+    pub fn duplicate_link_name_policy(mut self, policy: DuplicateLinkNamePolicy) -> Self {
+        self.inner.0.duplicate_link_name_policy = policy;
+        self
+    }
+
     cfg_transaction! {
         /// Enable handling remotely initiated control link and transaction by setting the
         /// `control_link_acceptor` field
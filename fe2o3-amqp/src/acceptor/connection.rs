@@ -1,7 +1,6 @@
 //! Connection Listener
 
-use std::{io, marker::PhantomData, time::Duration};
-
+use std::{io, marker::PhantomData, net::SocketAddr, sync::Arc, time::Duration};
 
 use fe2o3_amqp_types::{
     definitions::{self},
@@ -9,10 +8,14 @@ use fe2o3_amqp_types::{
     sasl::{SaslCode, SaslOutcome},
     states::ConnectionState,
 };
-use futures_util::{Sink, SinkExt, StreamExt};
+use futures_util::{stream, Sink, SinkExt, Stream, StreamExt};
 use tokio::{
     io::{AsyncRead, AsyncWrite, ReadHalf, WriteHalf},
-    sync::mpsc::{self, Receiver},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver},
+        OwnedSemaphorePermit, Semaphore,
+    },
 };
 use tokio_util::codec::{FramedRead, FramedWrite};
 
@@ -26,6 +29,7 @@ use crate::{
         amqp::{self, Frame},
         sasl,
     },
+    link::builder::LinkDefaults,
     session::frame::{SessionFrame, SessionFrameBody},
     transport::{protocol_header::ProtocolHeaderCodec, Transport},
     util::{Initialized, Uninitialized},
@@ -33,7 +37,7 @@ use crate::{
 
 use super::{
     builder::Builder,
-    sasl_acceptor::{SaslAcceptor, SaslAcceptorExt},
+    sasl_acceptor::{SaslAcceptor, SaslAcceptorExt, SaslIdentity},
     IncomingSession,
 };
 
@@ -45,6 +49,14 @@ impl ListenerConnectionHandle {
     pub async fn next_incoming_session(&mut self) -> Option<IncomingSession> {
         self.session_listener.recv().await
     }
+
+    /// The identity of the client that is authenticated by the SASL negotiation
+    ///
+    /// Returns `None` if no SASL negotiation took place or if the SASL acceptor doesn't expose
+    /// the identity (see [`SaslAcceptor::take_identity`])
+    pub fn sasl_identity(&self) -> Option<&SaslIdentity> {
+        self.sasl_identity.as_ref()
+    }
 }
 
 /// Acceptor for an incoming connection
@@ -76,6 +88,7 @@ impl ListenerConnectionHandle {
 /// |`offered_capabilities`| `None` |
 /// |`desired_capabilities`| `None` |
 /// |`Properties`| `None` |
+/// |`max_connections`| `None` (unlimited) |
 ///
 /// # Customize configuration
 ///
@@ -91,6 +104,23 @@ impl ListenerConnectionHandle {
 ///     .build();
 /// ```
 ///
+/// # Limiting the number of connections
+///
+/// The number of concurrently open connections can be limited with
+/// [`Builder::max_connections`]. Once the limit is reached, `accept` drops the incoming stream,
+/// which closes the socket, and returns [`OpenError::ConnectionLimitReached`] until one of the
+/// previously accepted connections is closed. The connection slot is released when the
+/// connection event loop stops.
+///
+/// ```rust
+/// use fe2o3_amqp::acceptor::ConnectionAcceptor;
+///
+/// let connection_acceptor = ConnectionAcceptor::builder()
+///     .container_id("example-listener")
+///     .max_connections(1024)
+///     .build();
+/// ```
+///
 /// # TLS Acceptor
 ///
 /// TLS acceptor can be added to handle TLS negotiation.
@@ -149,6 +179,9 @@ pub struct ConnectionAcceptor<Tls, Sasl> {
 
     /// Buffer size for the underlying channel
     pub buffer_size: usize,
+
+    /// Permits limiting the number of concurrently open connections
+    pub(crate) connection_permits: Option<Arc<Semaphore>>,
 }
 
 impl ConnectionAcceptor<(), ()> {
@@ -173,10 +206,37 @@ impl<Tls, Sasl> ConnectionAcceptor<Tls, Sasl> {
         }
     }
 
+    /// Returns the number of connections that can still be accepted before reaching the limit
+    /// set by [`Builder::max_connections`]. Returns `None` if the number of connections is not
+    /// limited
+    pub fn available_connections(&self) -> Option<usize> {
+        self.connection_permits
+            .as_ref()
+            .map(|permits| permits.available_permits())
+    }
+
+    /// Checks the local configuration and takes a connection slot without waiting if the number
+    /// of connections is limited
+    fn prepare_accept(&self) -> Result<Option<OwnedSemaphorePermit>, OpenError> {
+        if self.local_open.idle_time_out == Some(0) {
+            return Err(OpenError::InvalidIdleTimeOut);
+        }
+
+        match &self.connection_permits {
+            Some(permits) => permits
+                .clone()
+                .try_acquire_owned()
+                .map(Some)
+                .map_err(|_| OpenError::ConnectionLimitReached),
+            None => Ok(None),
+        }
+    }
+
     async fn negotiate_amqp_with_framed<Io>(
         &self,
         framed_write: FramedWrite<WriteHalf<Io>, ProtocolHeaderCodec>,
         framed_read: FramedRead<ReadHalf<Io>, ProtocolHeaderCodec>,
+        permit: Option<OwnedSemaphorePermit>,
     ) -> Result<ListenerConnectionHandle, OpenError>
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
@@ -202,19 +262,26 @@ impl<Tls, Sasl> ConnectionAcceptor<Tls, Sasl> {
         let listener_connection = ListenerConnection {
             connection,
             session_listener: begin_tx,
+            _permit: permit,
         };
 
         let engine =
             ConnectionEngine::open(transport, listener_connection, control_rx, outgoing_rx).await?;
+        let container_id = engine.local_open().container_id.clone();
+        let remote_open = engine.remote_open().cloned();
         let (handle, outcome) = engine.spawn();
 
         let connection_handle = ConnectionHandle {
+            container_id,
+            remote_open,
             is_closed: false,
             control: control_tx,
             handle,
             outcome,
             outgoing: outgoing_tx,
             session_listener: begin_rx,
+            link_defaults: LinkDefaults::default(),
+            sasl_identity: None,
         };
         Ok(connection_handle)
     }
@@ -222,6 +289,7 @@ impl<Tls, Sasl> ConnectionAcceptor<Tls, Sasl> {
     async fn negotiate_amqp_with_stream<Io>(
         &self,
         stream: Io,
+        permit: Option<OwnedSemaphorePermit>,
     ) -> Result<ListenerConnectionHandle, OpenError>
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
@@ -229,7 +297,7 @@ impl<Tls, Sasl> ConnectionAcceptor<Tls, Sasl> {
         let (reader, writer) = tokio::io::split(stream);
         let framed_write = FramedWrite::new(writer, ProtocolHeaderCodec::new());
         let framed_read = FramedRead::new(reader, ProtocolHeaderCodec::new());
-        self.negotiate_amqp_with_framed(framed_write, framed_read)
+        self.negotiate_amqp_with_framed(framed_write, framed_read, permit)
             .await
     }
 }
@@ -243,6 +311,7 @@ where
         &self,
         framed_write: FramedWrite<WriteHalf<Io>, ProtocolHeaderCodec>,
         framed_read: FramedRead<ReadHalf<Io>, ProtocolHeaderCodec>,
+        permit: Option<OwnedSemaphorePermit>,
     ) -> Result<ListenerConnectionHandle, OpenError>
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
@@ -290,29 +359,47 @@ where
                     transport.send(frame).await?;
                 }
                 SaslServerFrame::Outcome(outcome) => {
+                    let rejection = match outcome.code {
+                        SaslCode::Ok => None,
+                        _ => Some((outcome.code.clone(), outcome.additional_data.clone())),
+                    };
                     let frame = sasl::Frame::Outcome(outcome);
                     #[cfg(feature = "tracing")]
                     tracing::trace!(sending = ?frame);
                     #[cfg(feature = "log")]
                     log::trace!("sending = {:?}", frame);
                     transport.send(frame).await?;
+
+                    // The outcome has been delivered to the client, so the connection can be
+                    // closed gracefully if the negotiation has failed
+                    if let Some((code, additional_data)) = rejection {
+                        return Err(OpenError::SaslError {
+                            code,
+                            additional_data,
+                        });
+                    }
                     break;
                 }
             }
         }
+        let sasl_identity = sasl_acceptor.take_identity();
 
         // NOTE: LengthDelimitedCodec itself doesn't seem to carry any buffer, so
         // it should be fine to simply drop it.
         let (framed_write, framed_read) = transport.into_framed_codec();
         let framed_write = framed_write.map_encoder(|_| ProtocolHeaderCodec::new());
         let framed_read = framed_read.map_decoder(|_| ProtocolHeaderCodec::new());
-        self.negotiate_amqp_with_framed(framed_write, framed_read)
-            .await
+        let mut connection_handle = self
+            .negotiate_amqp_with_framed(framed_write, framed_read, permit)
+            .await?;
+        connection_handle.sasl_identity = sasl_identity;
+        Ok(connection_handle)
     }
 
     async fn negotiate_sasl_with_stream<Io>(
         &self,
         stream: Io,
+        permit: Option<OwnedSemaphorePermit>,
     ) -> Result<ListenerConnectionHandle, OpenError>
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
@@ -320,7 +407,7 @@ where
         let (reader, writer) = tokio::io::split(stream);
         let framed_write = FramedWrite::new(writer, ProtocolHeaderCodec::new());
         let framed_read = FramedRead::new(reader, ProtocolHeaderCodec::new());
-        self.negotiate_sasl_with_framed(framed_write, framed_read)
+        self.negotiate_sasl_with_framed(framed_write, framed_read, permit)
             .await
     }
 }
@@ -329,7 +416,11 @@ where
 #[cfg(any(feature = "rustls", feature = "native-tls"))]
 macro_rules! connect_tls {
     ($fn_ident:ident, $next_proto_header_handler:ident) => {
-        async fn $fn_ident<Io>(&self, mut stream: Io) -> Result<ListenerConnectionHandle, OpenError>
+        async fn $fn_ident<Io>(
+            &self,
+            mut stream: Io,
+            permit: Option<OwnedSemaphorePermit>,
+        ) -> Result<ListenerConnectionHandle, OpenError>
         where
             Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
         {
@@ -353,7 +444,7 @@ macro_rules! connect_tls {
                 OpenError::Io(io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))
             })?;
 
-            self.$next_proto_header_handler(tls_stream).await
+            self.$next_proto_header_handler(tls_stream, permit).await
         }
     };
 }
@@ -384,6 +475,142 @@ cfg_rustls! {
     }
 }
 
+mod private {
+    use tokio::net::TcpStream;
+
+    use crate::connection::OpenError;
+
+    use super::ListenerConnectionHandle;
+
+    /// Accepts a TCP stream with the `accept` method of the acceptor
+    pub trait AcceptTcpStream {
+        fn accept_tcp_stream(
+            &self,
+            stream: TcpStream,
+        ) -> impl std::future::Future<Output = Result<ListenerConnectionHandle, OpenError>> + '_;
+    }
+}
+
+/// Whether an error returned by `TcpListener::accept` is specific to the connection that is being
+/// accepted, in which case the listener can keep accepting new connections
+fn is_connection_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::WouldBlock
+    )
+}
+
+impl<Tls, Sasl> ConnectionAcceptor<Tls, Sasl>
+where
+    Self: private::AcceptTcpStream,
+{
+    /// Returns a stream of incoming connections accepted from the `TcpListener`
+    ///
+    /// Each item is the result of accepting the next TCP stream with
+    /// [`accept`](#method.accept). The next TCP stream is only accepted when the stream is
+    /// polled again. An error accepting a TCP stream is yielded as `OpenError::Io`, and the stream
+    /// ends after yielding an error that is not specific to the connection being accepted (eg.
+    /// running out of file descriptors).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let listener = TcpListener::bind("localhost:5672").await.unwrap();
+    /// let acceptor = ConnectionAcceptor::new("example-listener");
+    /// acceptor
+    ///     .incoming(listener)
+    ///     .for_each_concurrent(None, |result| async move {
+    ///         if let Ok(connection) = result {
+    ///             tokio::spawn(connection_main(connection));
+    ///         }
+    ///     })
+    ///     .await;
+    /// ```
+    pub fn incoming(
+        &self,
+        listener: TcpListener,
+    ) -> impl Stream<Item = Result<ListenerConnectionHandle, OpenError>> + '_ {
+        stream::unfold(Some(listener), move |listener| async move {
+            let listener = listener?;
+            let accepted = listener.accept().await;
+            Some(self.on_tcp_accepted(accepted, listener).await)
+        })
+    }
+
+    /// Returns a stream of incoming connections accepted from the `TcpListener` that ends
+    /// when `shutdown` completes
+    ///
+    /// This is the same as [`incoming`](#method.incoming) except that the stream ends once
+    /// the `shutdown` future completes, which allows a listener to stop accepting new
+    /// connections for a graceful shutdown. A connection whose TCP stream has already been
+    /// accepted is still negotiated and yielded before the stream ends, and the connections
+    /// that have been yielded are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let listener = TcpListener::bind("localhost:5672").await.unwrap();
+    /// let acceptor = ConnectionAcceptor::new("example-listener");
+    /// let mut handles = Vec::new();
+    /// let mut connections =
+    ///     std::pin::pin!(acceptor.incoming_with_shutdown(listener, tokio::signal::ctrl_c().map(|_| ())));
+    /// while let Some(result) = connections.next().await {
+    ///     if let Ok(connection) = result {
+    ///         handles.push(tokio::spawn(connection_main(connection)));
+    ///     }
+    /// }
+    /// // Drain the in-flight connections
+    /// futures_util::future::join_all(handles).await;
+    /// ```
+    pub fn incoming_with_shutdown<'a, F>(
+        &'a self,
+        listener: TcpListener,
+        shutdown: F,
+    ) -> impl Stream<Item = Result<ListenerConnectionHandle, OpenError>> + 'a
+    where
+        F: std::future::Future<Output = ()> + 'a,
+    {
+        let shutdown = Box::pin(shutdown);
+        stream::unfold(
+            (Some(listener), shutdown),
+            move |(listener, mut shutdown)| async move {
+                let listener = listener?;
+                let accepted = tokio::select! {
+                    biased;
+                    _ = &mut shutdown => return None,
+                    accepted = listener.accept() => accepted,
+                };
+                let (result, listener) = self.on_tcp_accepted(accepted, listener).await;
+                Some((result, (listener, shutdown)))
+            },
+        )
+    }
+
+    /// Negotiates the accepted TCP stream and returns the listener if it can keep accepting
+    async fn on_tcp_accepted(
+        &self,
+        accepted: io::Result<(TcpStream, SocketAddr)>,
+        listener: TcpListener,
+    ) -> (
+        Result<ListenerConnectionHandle, OpenError>,
+        Option<TcpListener>,
+    ) {
+        use private::AcceptTcpStream;
+
+        match accepted {
+            Ok((tcp_stream, _)) => (self.accept_tcp_stream(tcp_stream).await, Some(listener)),
+            Err(error) if is_connection_error(&error) => {
+                (Err(OpenError::Io(error)), Some(listener))
+            }
+            Err(error) => (Err(OpenError::Io(error)), None),
+        }
+    }
+}
 
 impl ConnectionAcceptor<(), ()> {
     /// Accepts an incoming connection
@@ -391,7 +618,17 @@ impl ConnectionAcceptor<(), ()> {
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
     {
-        self.negotiate_amqp_with_stream(stream).await
+        let permit = self.prepare_accept()?;
+        self.negotiate_amqp_with_stream(stream, permit).await
+    }
+}
+
+impl private::AcceptTcpStream for ConnectionAcceptor<(), ()> {
+    async fn accept_tcp_stream(
+        &self,
+        stream: TcpStream,
+    ) -> Result<ListenerConnectionHandle, OpenError> {
+        self.accept(stream).await
     }
 }
 
@@ -404,7 +641,20 @@ where
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
     {
-        self.negotiate_sasl_with_stream(stream).await
+        let permit = self.prepare_accept()?;
+        self.negotiate_sasl_with_stream(stream, permit).await
+    }
+}
+
+impl<Sasl> private::AcceptTcpStream for ConnectionAcceptor<(), Sasl>
+where
+    Sasl: SaslAcceptor,
+{
+    async fn accept_tcp_stream(
+        &self,
+        stream: TcpStream,
+    ) -> Result<ListenerConnectionHandle, OpenError> {
+        self.accept(stream).await
     }
 }
 
@@ -415,7 +665,17 @@ cfg_native_tls! {
         where
             Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
         {
-            self.negotiate_tls_with_native_tls(stream).await
+            let permit = self.prepare_accept()?;
+            self.negotiate_tls_with_native_tls(stream, permit).await
+        }
+    }
+
+    impl private::AcceptTcpStream for ConnectionAcceptor<tokio_native_tls::TlsAcceptor, ()> {
+        async fn accept_tcp_stream(
+            &self,
+            stream: TcpStream,
+        ) -> Result<ListenerConnectionHandle, OpenError> {
+            self.accept(stream).await
         }
     }
     
@@ -428,7 +688,20 @@ cfg_native_tls! {
         where
             Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
         {
-            self.negotiate_tls_with_native_tls(stream).await
+            let permit = self.prepare_accept()?;
+            self.negotiate_tls_with_native_tls(stream, permit).await
+        }
+    }
+
+    impl<Sasl> private::AcceptTcpStream for ConnectionAcceptor<tokio_native_tls::TlsAcceptor, Sasl>
+    where
+        Sasl: SaslAcceptor,
+    {
+        async fn accept_tcp_stream(
+            &self,
+            stream: TcpStream,
+        ) -> Result<ListenerConnectionHandle, OpenError> {
+            self.accept(stream).await
         }
     }
 }
@@ -440,7 +713,17 @@ cfg_rustls! {
         where
             Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
         {
-            self.negotiate_tls_with_rustls(stream).await
+            let permit = self.prepare_accept()?;
+            self.negotiate_tls_with_rustls(stream, permit).await
+        }
+    }
+
+    impl private::AcceptTcpStream for ConnectionAcceptor<tokio_rustls::TlsAcceptor, ()> {
+        async fn accept_tcp_stream(
+            &self,
+            stream: TcpStream,
+        ) -> Result<ListenerConnectionHandle, OpenError> {
+            self.accept(stream).await
         }
     }
     
@@ -453,7 +736,20 @@ cfg_rustls! {
         where
             Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
         {
-            self.negotiate_tls_with_rustls(stream).await
+            let permit = self.prepare_accept()?;
+            self.negotiate_tls_with_rustls(stream, permit).await
+        }
+    }
+
+    impl<Sasl> private::AcceptTcpStream for ConnectionAcceptor<tokio_rustls::TlsAcceptor, Sasl>
+    where
+        Sasl: SaslAcceptor,
+    {
+        async fn accept_tcp_stream(
+            &self,
+            stream: TcpStream,
+        ) -> Result<ListenerConnectionHandle, OpenError> {
+            self.accept(stream).await
         }
     }
 }
@@ -463,6 +759,9 @@ cfg_rustls! {
 pub struct ListenerConnection {
     pub(crate) connection: connection::Connection,
     pub(crate) session_listener: mpsc::Sender<IncomingSession>,
+
+    /// Released when the connection event loop stops
+    pub(crate) _permit: Option<OwnedSemaphorePermit>,
 }
 
 
@@ -484,6 +783,11 @@ impl endpoint::Connection for ListenerConnection {
         self.connection.local_open()
     }
 
+    #[inline]
+    fn remote_open(&self) -> Option<&fe2o3_amqp_types::performatives::Open> {
+        self.connection.remote_open()
+    }
+
     #[inline]
     fn allocate_session(
         &mut self,
@@ -625,3 +929,30 @@ impl endpoint::Connection for ListenerConnection {
         self.connection.session_tx_by_incoming_channel(channel)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::connection::OpenError;
+
+    use super::{is_connection_error, ConnectionAcceptor};
+
+    #[test]
+    fn test_is_connection_error() {
+        let error = |kind| std::io::Error::new(kind, "");
+        assert!(is_connection_error(&error(std::io::ErrorKind::ConnectionAborted)));
+        assert!(is_connection_error(&error(std::io::ErrorKind::ConnectionReset)));
+        assert!(!is_connection_error(&error(std::io::ErrorKind::Other)));
+        assert!(!is_connection_error(&error(std::io::ErrorKind::PermissionDenied)));
+    }
+
+    #[tokio::test]
+    async fn test_zero_idle_time_out_is_rejected() {
+        let acceptor = ConnectionAcceptor::builder()
+            .container_id("test-listener")
+            .idle_time_out(0u32)
+            .build();
+        let (_client_io, server_io) = tokio::io::duplex(1024);
+        let result = acceptor.accept(server_io).await;
+        assert!(matches!(result, Err(OpenError::InvalidIdleTimeOut)));
+    }
+}
@@ -12,6 +12,7 @@ use fe2o3_amqp_types::{
     performatives::Attach,
     primitives::{Symbol, Ulong},
 };
+use futures_util::{stream, Stream};
 
 use crate::{connection::DEFAULT_OUTGOING_BUFFER_SIZE, session::SessionHandle, util::Initialized};
 
@@ -221,4 +222,36 @@ where
             .ok_or(AcceptorAttachError::IllegalSessionState)?;
         self.accept_incoming_attach(remote_attach, session).await
     }
+
+    /// Returns a stream of incoming links on the session
+    ///
+    /// Each item is the result of accepting the next incoming `Attach` with
+    /// [`accept_incoming_attach`](#method.accept_incoming_attach). The stream ends once the
+    /// session stops sending incoming attaches (ie. the session is ended).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let link_acceptor = LinkAcceptor::new();
+    /// link_acceptor
+    ///     .incoming(&mut session)
+    ///     .for_each(|result| async move {
+    ///         match result {
+    ///             Ok(LinkEndpoint::Sender(sender)) => { tokio::spawn(sender_main(sender)); }
+    ///             Ok(LinkEndpoint::Receiver(receiver)) => { tokio::spawn(receiver_main(receiver)); }
+    ///             Err(_) => {}
+    ///         }
+    ///     })
+    ///     .await;
+    /// ```
+    pub fn incoming<'a>(
+        &'a self,
+        session: &'a mut ListenerSessionHandle,
+    ) -> impl Stream<Item = Result<LinkEndpoint, AcceptorAttachError>> + 'a {
+        stream::unfold(session, move |session| async move {
+            let remote_attach = session.next_incoming_attach().await?;
+            let result = self.accept_incoming_attach(remote_attach, session).await;
+            Some((result, session))
+        })
+    }
 }
@@ -22,7 +22,7 @@ use crate::{
         target_archetype::TargetArchetypeExt,
         LinkFrame, LinkIncomingItem, LinkRelay, ReceiverAttachError, ReceiverLink,
     },
-    session::SessionHandle,
+    session::{SessionHandle, SessionId},
     Receiver,
 };
 
@@ -92,6 +92,7 @@ where
             remote_attach,
             session.control.clone(),
             session.outgoing.clone(),
+            session.id.clone(),
         )
         .await
         .map(|inner| Receiver { inner })
@@ -110,6 +111,7 @@ where
         remote_attach: Attach,
         control: mpsc::Sender<SessionControl>,
         outgoing: mpsc::Sender<LinkFrame>,
+        session_id: SessionId,
     ) -> Result<ReceiverInner<ReceiverLink<T>>, ReceiverAttachError>
     where
         T: Into<TargetArchetype>
@@ -163,6 +165,7 @@ where
             unsettled: unsettled.clone(),
             receiver_settle_mode: rcv_settle_mode.clone(),
             more: false,
+            on_settled: None,
         };
 
         // Allocate link in session
@@ -214,10 +217,12 @@ where
             max_message_size: shared.max_message_size.unwrap_or(0),
             offered_capabilities: shared.offered_capabilities.clone(),
             desired_capabilities: shared.desired_capabilities.clone(),
+            remote_properties: None, // Will take value from incoming attach
             flow_state: flow_state_consumer,
             unsettled,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            max_unsettled_in_attach: None,
         };
 
         // `on_incoming_attach` should always be evaluated
@@ -251,10 +256,14 @@ where
             credit_mode: self.credit_mode.clone(),
             processed: AtomicU32::new(0),
             auto_accept: self.auto_accept,
+            on_decode_error: Default::default(),
             session: control.clone(),
+            session_id,
             outgoing,
             incoming: incoming_rx,
             incomplete_transfer: None,
+            deferred_error: None,
+            on_settled: None,
         };
 
         if let CreditMode::Auto(credit) = inner.credit_mode {
@@ -118,6 +118,7 @@ where
             flow_state: flow_state_producer,
             unsettled: unsettled.clone(),
             receiver_settle_mode: remote_attach.rcv_settle_mode.clone(),
+            on_settled: None,
         };
 
         // Allocate link in session
@@ -159,10 +160,12 @@ where
             max_message_size: shared.max_message_size.unwrap_or(0),
             offered_capabilities: shared.offered_capabilities.clone(),
             desired_capabilities: shared.desired_capabilities.clone(),
+            remote_properties: None, // Will take value from incoming attach
             flow_state: flow_state_consumer,
             unsettled,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            max_unsettled_in_attach: None,
         };
 
         let outgoing = session.outgoing.clone();
@@ -195,8 +198,11 @@ where
             link,
             buffer_size: shared.buffer_size,
             session: session.control.clone(),
+            session_id: session.id.clone(),
             outgoing,
             incoming: incoming_rx,
+            on_settled: None,
+            settle_on_fut_drop: false,
         };
         Ok(Sender { inner })
     }
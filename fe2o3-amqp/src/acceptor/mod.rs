@@ -20,8 +20,11 @@ use fe2o3_amqp_types::{
 
 pub use self::connection::{ConnectionAcceptor, ListenerConnectionHandle};
 pub use self::link::{LinkAcceptor, LinkEndpoint};
-pub use self::sasl_acceptor::{SaslAcceptor, SaslAnonymousMechanism, SaslPlainMechanism};
-pub use self::session::{ListenerSessionHandle, SessionAcceptor};
+pub use self::sasl_acceptor::{
+    PlainAuthenticator, SaslAcceptor, SaslAnonymousMechanism, SaslIdentity, SaslPlainAuthenticator,
+    SaslPlainMechanism, SaslRejection,
+};
+pub use self::session::{DuplicateLinkNamePolicy, ListenerSessionHandle, SessionAcceptor};
 
 /// A half established session that is initiated by the remote peer
 #[derive(Debug)]
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 
 use fe2o3_amqp_types::{
-    primitives::{Array, Symbol},
+    primitives::{Array, Binary, Symbol},
     sasl::{SaslChallenge, SaslCode, SaslInit, SaslMechanisms, SaslOutcome, SaslResponse},
 };
 
@@ -29,6 +29,15 @@ pub trait SaslAcceptor: Clone {
 
     /// Respond to a SaslResponse frame
     fn on_response(&mut self, response: SaslResponse) -> SaslServerFrame;
+
+    /// Takes the identity of the client once the negotiation has succeeded
+    ///
+    /// The identity is exposed with
+    /// [`ListenerConnectionHandle::sasl_identity`](crate::acceptor::ListenerConnectionHandle::sasl_identity).
+    /// The default implementation returns `None`.
+    fn take_identity(&mut self) -> Option<SaslIdentity> {
+        None
+    }
 }
 
 /// Extension trait of SaslAcceptor
@@ -56,6 +65,92 @@ pub trait SaslAcceptorExt: SaslAcceptor {
 
 impl<T: SaslAcceptor> SaslAcceptorExt for T {}
 
+/// Identity of a successfully authenticated SASL client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaslIdentity {
+    /// Authentication identity
+    pub authcid: String,
+
+    /// Authorization identity, if any was supplied by the client
+    pub authzid: Option<String>,
+}
+
+/// Rejection of a SASL negotiation that will be sent to the client as a `SaslOutcome`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaslRejection {
+    /// Outcome code. This should NOT be [`SaslCode::Ok`]
+    pub code: SaslCode,
+
+    /// Additional data sent to the client along with the outcome
+    pub additional_data: Option<Binary>,
+}
+
+impl SaslRejection {
+    /// Creates a new rejection with the specified code and no additional data
+    pub fn new(code: SaslCode) -> Self {
+        Self {
+            code,
+            additional_data: None,
+        }
+    }
+
+    /// Creates a rejection with [`SaslCode::Auth`], which indicates a problem with the supplied
+    /// credentials
+    pub fn auth() -> Self {
+        Self::new(SaslCode::Auth)
+    }
+
+    /// Sets the additional data sent to the client
+    pub fn additional_data(mut self, data: impl Into<Binary>) -> Self {
+        self.additional_data = Some(data.into());
+        self
+    }
+}
+
+impl From<SaslCode> for SaslRejection {
+    fn from(code: SaslCode) -> Self {
+        Self::new(code)
+    }
+}
+
+impl From<SaslRejection> for SaslOutcome {
+    fn from(rejection: SaslRejection) -> Self {
+        SaslOutcome {
+            code: rejection.code,
+            additional_data: rejection.additional_data,
+        }
+    }
+}
+
+/// Authenticates the credentials supplied with the SASL PLAIN mechanism
+///
+/// This is implemented for any `Fn(Option<&str>, &str, &str) -> Result<SaslIdentity,
+/// SaslRejection>` that is `Clone`
+pub trait PlainAuthenticator: Clone {
+    /// Authenticate the credentials. Returning an `Err` will send a `SaslOutcome` with the code
+    /// and additional data carried by the [`SaslRejection`]
+    fn authenticate(
+        &self,
+        authzid: Option<&str>,
+        authcid: &str,
+        passwd: &str,
+    ) -> Result<SaslIdentity, SaslRejection>;
+}
+
+impl<F> PlainAuthenticator for F
+where
+    F: Fn(Option<&str>, &str, &str) -> Result<SaslIdentity, SaslRejection> + Clone,
+{
+    fn authenticate(
+        &self,
+        authzid: Option<&str>,
+        authcid: &str,
+        passwd: &str,
+    ) -> Result<SaslIdentity, SaslRejection> {
+        (self)(authzid, authcid, passwd)
+    }
+}
+
 // /// Supported SASL mechanism
 // #[derive(Debug)]
 // pub enum Mechanism {
@@ -124,6 +219,104 @@ impl SaslAcceptor for SaslPlainMechanism {
     }
 }
 
+/// An acceptor for SASL PLAIN mechanism that delegates the validation of the credentials to a
+/// [`PlainAuthenticator`]
+///
+/// # Example
+///
+/// ```rust
+/// use fe2o3_amqp::acceptor::{
+///     ConnectionAcceptor, SaslIdentity, SaslPlainAuthenticator, SaslRejection,
+/// };
+///
+/// let sasl_acceptor = SaslPlainAuthenticator::new(|authzid: Option<&str>, authcid: &str, passwd: &str| {
+///     if authcid == "guest" && passwd == "guest" {
+///         Ok(SaslIdentity {
+///             authcid: authcid.to_string(),
+///             authzid: authzid.map(String::from),
+///         })
+///     } else {
+///         Err(SaslRejection::auth().additional_data(b"invalid credentials".to_vec()))
+///     }
+/// });
+///
+/// let connection_acceptor = ConnectionAcceptor::builder()
+///     .container_id("example-listener")
+///     .sasl_acceptor(sasl_acceptor)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaslPlainAuthenticator<A> {
+    authenticator: A,
+    identity: Option<SaslIdentity>,
+}
+
+impl<A> SaslPlainAuthenticator<A>
+where
+    A: PlainAuthenticator,
+{
+    /// Creates a new PLAIN mechanism acceptor with the authenticator
+    pub fn new(authenticator: A) -> Self {
+        Self {
+            authenticator,
+            identity: None,
+        }
+    }
+
+    fn authenticate_init(&self, init: SaslInit) -> Result<SaslIdentity, SaslRejection> {
+        let response = init
+            .initial_response
+            .ok_or_else(SaslRejection::auth)?
+            .into_vec();
+
+        let mut split = response.split(|b| *b == 0u8);
+        let (authzid, authcid, passwd) = match (split.next(), split.next(), split.next()) {
+            (Some(authzid), Some(authcid), Some(passwd)) => (authzid, authcid, passwd),
+            _ => return Err(SaslRejection::auth()),
+        };
+
+        let authzid = match authzid {
+            [] => None,
+            _ => Some(std::str::from_utf8(authzid).map_err(|_| SaslRejection::auth())?),
+        };
+        let authcid = std::str::from_utf8(authcid).map_err(|_| SaslRejection::auth())?;
+        let passwd = std::str::from_utf8(passwd).map_err(|_| SaslRejection::auth())?;
+        self.authenticator.authenticate(authzid, authcid, passwd)
+    }
+}
+
+impl<A> SaslAcceptor for SaslPlainAuthenticator<A>
+where
+    A: PlainAuthenticator,
+{
+    fn mechanisms(&self) -> Array<Symbol> {
+        Array::from(vec![Symbol::from(PLAIN)])
+    }
+
+    fn on_init(&mut self, init: SaslInit) -> SaslServerFrame {
+        let outcome = match self.authenticate_init(init) {
+            Ok(identity) => {
+                self.identity = Some(identity);
+                SaslOutcome {
+                    code: SaslCode::Ok,
+                    additional_data: None,
+                }
+            }
+            Err(rejection) => rejection.into(),
+        };
+        SaslServerFrame::Outcome(outcome)
+    }
+
+    fn on_response(&mut self, _response: SaslResponse) -> SaslServerFrame {
+        // This is not expected
+        SaslServerFrame::Outcome(SaslRejection::new(SaslCode::Sys).into())
+    }
+
+    fn take_identity(&mut self) -> Option<SaslIdentity> {
+        self.identity.take()
+    }
+}
+
 /// A SASL Anonymous acceptor that is going to accept anything
 #[derive(Debug, Clone)]
 pub struct SaslAnonymousMechanism {}
@@ -164,3 +357,67 @@ impl SaslAcceptor for SaslAnonymousMechanism {
         SaslServerFrame::Outcome(outcome)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        primitives::{Binary, Symbol},
+        sasl::{SaslCode, SaslInit},
+    };
+
+    use crate::sasl_profile::PLAIN;
+
+    use super::{
+        SaslAcceptor, SaslIdentity, SaslPlainAuthenticator, SaslRejection, SaslServerFrame,
+    };
+
+    fn plain_init(authcid: &str, passwd: &str) -> SaslInit {
+        let mut response = vec![0u8];
+        response.extend_from_slice(authcid.as_bytes());
+        response.push(0);
+        response.extend_from_slice(passwd.as_bytes());
+        SaslInit {
+            mechanism: Symbol::from(PLAIN),
+            initial_response: Some(Binary::from(response)),
+            hostname: None,
+        }
+    }
+
+    #[test]
+    fn test_sasl_plain_authenticator_rejection() {
+        let mut acceptor =
+            SaslPlainAuthenticator::new(|authzid: Option<&str>, authcid: &str, passwd: &str| {
+                if authcid == "guest" && passwd == "guest" {
+                    Ok(SaslIdentity {
+                        authcid: authcid.to_string(),
+                        authzid: authzid.map(String::from),
+                    })
+                } else {
+                    Err(SaslRejection::auth().additional_data(b"invalid credentials".to_vec()))
+                }
+            });
+
+        match acceptor.clone().on_init(plain_init("guest", "wrong")) {
+            SaslServerFrame::Outcome(outcome) => {
+                assert_eq!(outcome.code, SaslCode::Auth);
+                assert_eq!(
+                    outcome.additional_data,
+                    Some(Binary::from(b"invalid credentials".to_vec()))
+                );
+            }
+            _ => panic!("Expecting outcome"),
+        }
+
+        match acceptor.on_init(plain_init("guest", "guest")) {
+            SaslServerFrame::Outcome(outcome) => {
+                assert_eq!(outcome.code, SaslCode::Ok);
+                assert_eq!(outcome.additional_data, None);
+            }
+            _ => panic!("Expecting outcome"),
+        }
+        let identity = acceptor.take_identity().unwrap();
+        assert_eq!(identity.authcid, "guest");
+        assert_eq!(identity.authzid, None);
+        assert_eq!(acceptor.take_identity(), None);
+    }
+}
@@ -1,11 +1,15 @@
 //! Session Listener
 
 
+use std::collections::{HashMap, HashSet, VecDeque};
+
 use fe2o3_amqp_types::{
-    definitions::{self, ConnectionError},
+    definitions::{self, ConnectionError, DeliveryNumber, DeliveryTag, LinkError},
+    messaging::DeliveryState,
     performatives::{Attach, Begin, Detach, Disposition, End, Flow, Transfer},
     states::SessionState,
 };
+use futures_util::{stream, Stream};
 use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
 
@@ -15,12 +19,12 @@ use crate::{
     endpoint::{
         self, IncomingChannel, InputHandle, LinkFlow, OutgoingChannel, OutputHandle, Session,
     },
-    link::{LinkFrame, LinkRelay},
+    link::{builder::LinkDefaults, LinkFrame, LinkRelay},
     session::{
         self,
         engine::SessionEngine,
-        frame::{SessionFrame, SessionIncomingItem, SessionOutgoingItem},
-        error::{AllocLinkError, BeginError, Error, SessionInnerError}, SessionHandle, 
+        frame::{SessionFrame, SessionFrameBody, SessionIncomingItem, SessionOutgoingItem},
+        error::{AllocLinkError, BeginError, Error, SessionInnerError}, SessionHandle, SessionId,
         DEFAULT_SESSION_CONTROL_BUFFER_SIZE,
     },
     util::Initialized,
@@ -114,6 +118,7 @@ pub(crate) async fn allocate_incoming_link(
 /// |`offered_capabilities` | `None` |
 /// |`desired_capabilities`| `None` |
 /// |`Properties`| `None` |
+/// |`duplicate_link_name_policy`| `DuplicateLinkNamePolicy::Reject` |
 ///
 /// # Customize the acceptor
 ///
@@ -188,10 +193,15 @@ impl SessionAcceptor {
                 Some(control_link_acceptor) => {
                     let txn_manager =
                         TransactionManager::new(control_link_outgoing.clone(), control_link_acceptor);
+                    let id = SessionId {
+                        container_id: connection.container_id.clone(),
+                        channel: listener_session.session.outgoing_channel,
+                    };
                     let listener_session = TxnSession {
                         control: session_control_tx.clone(),
                         session: listener_session,
                         txn_manager,
+                        id,
                     };
     
                     let engine = SessionEngine::begin_listener_session(
@@ -255,6 +265,9 @@ impl SessionAcceptor {
 
                     return Err(BeginError::LocalChannelMaxReached);
                 }
+                AllocSessionError::SessionLimitReached => {
+                    return Err(BeginError::SessionLimitReached)
+                }
             },
         };
         let mut session = self.0.clone().into_session(outgoing_channel, local_state);
@@ -266,6 +279,8 @@ impl SessionAcceptor {
         let listener_session = ListenerSession {
             session,
             link_listener: link_listener_tx,
+            duplicate_link_name_policy: self.0.duplicate_link_name_policy,
+            stolen_output_handles: HashSet::new(),
         };
 
         let (engine_handle, outcome) = self
@@ -287,6 +302,13 @@ impl SessionAcceptor {
             outcome,
             outgoing: outgoing_tx,
             link_listener: link_listener_rx,
+            link_defaults: LinkDefaults::default(),
+            #[cfg(feature = "transaction")]
+            txn_capabilities: None,
+            id: SessionId {
+                container_id: connection.container_id.clone(),
+                channel: outgoing_channel,
+            },
         };
         Ok(handle)
     }
@@ -304,6 +326,38 @@ impl SessionAcceptor {
         self.accept_incoming_session(incoming_session, connection)
             .await
     }
+
+    /// Returns a stream of incoming sessions on the connection
+    ///
+    /// Each item is the result of accepting the next incoming `Begin` with
+    /// [`accept_incoming_session`](#method.accept_incoming_session). The stream ends once the
+    /// connection stops sending incoming sessions (ie. the connection is closed).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let session_acceptor = SessionAcceptor::new();
+    /// session_acceptor
+    ///     .incoming(&mut connection)
+    ///     .for_each_concurrent(None, |result| async move {
+    ///         if let Ok(session) = result {
+    ///             tokio::spawn(session_main(session));
+    ///         }
+    ///     })
+    ///     .await;
+    /// ```
+    pub fn incoming<'a>(
+        &'a self,
+        connection: &'a mut ListenerConnectionHandle,
+    ) -> impl Stream<Item = Result<ListenerSessionHandle, BeginError>> + 'a {
+        stream::unfold(connection, move |connection| async move {
+            let incoming_session = connection.next_incoming_session().await?;
+            let result = self
+                .accept_incoming_session(incoming_session, connection)
+                .await;
+            Some((result, connection))
+        })
+    }
 }
 
 impl<S> SessionEngine<S>
@@ -330,6 +384,10 @@ where
             incoming,
             outgoing,
             outgoing_link_frames,
+            pending_flushes: Vec::new(),
+            max_concurrent_attaches: None,
+            attaching: HashMap::new(),
+            pending_allocations: VecDeque::new(),
         };
 
         // send a begin
@@ -343,6 +401,63 @@ where
 pub struct ListenerSession {
     pub(crate) session: session::Session,
     pub(crate) link_listener: mpsc::Sender<Attach>,
+    pub(crate) duplicate_link_name_policy: DuplicateLinkNamePolicy,
+
+    // Output handles of the links whose names are stolen by a duplicate attach. Their names now
+    // belong to the new links and must not be released when they are detached
+    pub(crate) stolen_output_handles: HashSet<OutputHandle>,
+}
+
+/// How a listener session handles an incoming Attach whose link name is already used by an
+/// attached link on the same session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateLinkNamePolicy {
+    /// Ends the session with the `amqp:session:handle-in-use` error
+    #[default]
+    Reject,
+
+    /// Detaches the existing link with the `amqp:link:stolen` error and passes the incoming
+    /// Attach to the link acceptor as a new link, which supports link-stealing failover clients
+    Steal,
+}
+
+impl ListenerSession {
+    /// Detaches the attached link that currently owns `link_name` so that the name can be
+    /// allocated again by the link acceptor
+    async fn steal_link_name(&mut self, link_name: &str) -> Result<(), SessionInnerError> {
+        let output_handle = self
+            .session
+            .link_name_by_output_handle
+            .iter()
+            .find(|(_, name)| name.as_str() == link_name)
+            .map(|(key, _)| OutputHandle(key as u32))
+            .ok_or(SessionInnerError::HandleInUse)?;
+        let input_handle = self
+            .session
+            .link_by_input_handle
+            .iter()
+            .find(|(_, relay)| relay.output_handle() == &output_handle)
+            .map(|(input_handle, _)| input_handle.clone())
+            .ok_or(SessionInnerError::HandleInUse)?;
+
+        let _ = self.session.link_by_name.remove(link_name);
+        self.stolen_output_handles.insert(output_handle);
+        if let Some(mut relay) = self.session.link_by_input_handle.remove(&input_handle) {
+            let error = definitions::Error::new(
+                LinkError::Stolen,
+                "Link name is used by a new attach".to_string(),
+                None,
+            );
+            let detach = Detach {
+                handle: input_handle.into(),
+                closed: true,
+                error: Some(error),
+            };
+            // The local link may have already been dropped
+            let _ = relay.on_incoming_detach(detach).await;
+        }
+        Ok(())
+    }
 }
 
 impl endpoint::Session for ListenerSession {
@@ -416,12 +531,17 @@ impl endpoint::Session for ListenerSession {
                         .insert(input_handle, relay);
                     Ok(())
                 }
-                None => {
-                    self.link_listener.send(attach).await.map_err(|_| {
-                        // SessionHandle must have been dropped, then treat it as if the acceptor doesn't exist
-                        SessionInnerError::HandleInUse
-                    })
-                }
+                None => match self.duplicate_link_name_policy {
+                    // Link name is found but is already in use
+                    DuplicateLinkNamePolicy::Reject => Err(SessionInnerError::HandleInUse),
+                    DuplicateLinkNamePolicy::Steal => {
+                        self.steal_link_name(&attach.name).await?;
+                        self.link_listener.send(attach).await.map_err(|_| {
+                            // SessionHandle must have been dropped, then treat it as if the acceptor doesn't exist
+                            SessionInnerError::HandleInUse
+                        })
+                    }
+                },
             },
             None => {
                 // If no such terminus exists, the application MAY
@@ -514,7 +634,46 @@ impl endpoint::Session for ListenerSession {
         self.session.on_outgoing_disposition(disposition)
     }
 
+    fn settle_outgoing_delivery(
+        &mut self,
+        input_handle: InputHandle,
+        delivery_tag: DeliveryTag,
+        state: DeliveryState,
+    ) -> Option<Disposition> {
+        self.session
+            .settle_outgoing_delivery(input_handle, delivery_tag, state)
+    }
+
+    fn settle_incoming_range(
+        &mut self,
+        input_handle: InputHandle,
+        first: DeliveryNumber,
+        last: DeliveryNumber,
+        state: DeliveryState,
+    ) -> Vec<Disposition> {
+        self.session
+            .settle_incoming_range(input_handle, first, last, state)
+    }
+
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame> {
+        self.session.replenish_incoming_window()
+    }
+
+    fn has_buffered_outgoing_transfers(&self) -> bool {
+        self.session.has_buffered_outgoing_transfers()
+    }
+
     fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame {
+        let output_handle = OutputHandle::from(detach.handle.clone());
+        if self.stolen_output_handles.remove(&output_handle) {
+            // Only release the handle because the link name belongs to the new link
+            let _ = self
+                .session
+                .link_name_by_output_handle
+                .try_remove(output_handle.0 as usize);
+            let body = SessionFrameBody::Detach(detach);
+            return SessionFrame::new(self.session.outgoing_channel, body);
+        }
         self.session.on_outgoing_detach(detach)
     }
 }
@@ -548,3 +707,201 @@ cfg_transaction! {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use fe2o3_amqp_types::{
+        definitions::{
+            ErrorCondition, Handle, LinkError, ReceiverSettleMode, Role, SenderSettleMode,
+            SessionError,
+        },
+        messaging::{Source, Target},
+        performatives::{Attach, Begin, ChannelMax, MaxFrameSize, Open},
+    };
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+
+    use crate::{
+        acceptor::{ConnectionAcceptor, LinkAcceptor, LinkEndpoint, SessionAcceptor},
+        frames::amqp::{Frame, FrameBody},
+        link::{LinkStateError, RecvError},
+        transport::Transport,
+        Receiver,
+    };
+
+    use super::DuplicateLinkNamePolicy;
+
+    /// Opens a connection and begins a session by writing the frames directly so that links with
+    /// the same name can be attached
+    async fn begin_raw_session(mut io: DuplexStream) -> Transport<DuplexStream, Frame> {
+        io.write_all(b"AMQP\x00\x01\x00\x00").await.unwrap();
+        let mut proto_header = [0u8; 8];
+        io.read_exact(&mut proto_header).await.unwrap();
+
+        let mut transport = Transport::bind(io, 65536, None);
+        let open = Open {
+            container_id: String::from("raw-client"),
+            hostname: None,
+            max_frame_size: MaxFrameSize::default(),
+            channel_max: ChannelMax::default(),
+            idle_time_out: None,
+            outgoing_locales: None,
+            incoming_locales: None,
+            offered_capabilities: None,
+            desired_capabilities: None,
+            properties: None,
+        };
+        let begin = Begin {
+            remote_channel: None,
+            next_outgoing_id: 0,
+            incoming_window: 2048,
+            outgoing_window: 2048,
+            handle_max: Handle::default(),
+            offered_capabilities: None,
+            desired_capabilities: None,
+            properties: None,
+        };
+        transport
+            .send(Frame::new(0u16, FrameBody::Open(open)))
+            .await
+            .unwrap();
+        transport
+            .send(Frame::new(0u16, FrameBody::Begin(begin)))
+            .await
+            .unwrap();
+        next_frame(&mut transport, |body| matches!(body, FrameBody::Begin(_))).await;
+        transport
+    }
+
+    fn attach(handle: u32) -> Frame {
+        let attach = Attach {
+            name: String::from("test-link"),
+            handle: Handle(handle),
+            role: Role::Sender,
+            snd_settle_mode: SenderSettleMode::Mixed,
+            rcv_settle_mode: ReceiverSettleMode::First,
+            source: Some(Box::new(Source::default())),
+            target: Some(Box::new(Target::builder().address("q1").build().into())),
+            unsettled: None,
+            incomplete_unsettled: false,
+            initial_delivery_count: Some(0),
+            max_message_size: None,
+            offered_capabilities: None,
+            desired_capabilities: None,
+            properties: None,
+        };
+        Frame::new(0u16, FrameBody::Attach(attach))
+    }
+
+    /// Skips the frames until one matches `predicate`
+    async fn next_frame(
+        transport: &mut Transport<DuplexStream, Frame>,
+        predicate: impl Fn(&FrameBody) -> bool,
+    ) -> FrameBody {
+        let find = async {
+            loop {
+                let frame = transport.next().await.unwrap().unwrap();
+                if predicate(&frame.body) {
+                    return frame.body;
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), find)
+            .await
+            .expect("Expected frame is not received")
+    }
+
+    fn is_detach(handle: u32) -> impl Fn(&FrameBody) -> bool {
+        move |body| matches!(body, FrameBody::Detach(detach) if detach.handle == Handle(handle))
+    }
+
+    fn into_receiver(link: LinkEndpoint) -> Receiver {
+        match link {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        }
+    }
+
+    async fn assert_stolen(receiver: &mut Receiver) {
+        match receiver.recv::<String>().await {
+            Err(RecvError::LinkStateError(LinkStateError::RemoteClosedWithError(error))) => {
+                assert_eq!(
+                    error.condition,
+                    ErrorCondition::LinkError(LinkError::Stolen)
+                )
+            }
+            other => panic!("Expecting the link to be stolen, found {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reject_duplicate_link_name() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            let acceptor = ConnectionAcceptor::new("test-listener");
+            let mut connection = acceptor.accept(server_io).await.unwrap();
+            let mut session = SessionAcceptor::new()
+                .accept(&mut connection)
+                .await
+                .unwrap();
+            let link = LinkAcceptor::new().accept(&mut session).await.unwrap();
+            let _ = session.on_end().await;
+            drop(link);
+        });
+
+        let mut transport = begin_raw_session(client_io).await;
+        transport.send(attach(0)).await.unwrap();
+        next_frame(&mut transport, |body| matches!(body, FrameBody::Attach(_))).await;
+        transport.send(attach(1)).await.unwrap();
+
+        let body = next_frame(&mut transport, |body| matches!(body, FrameBody::End(_))).await;
+        match body {
+            FrameBody::End(end) => assert_eq!(
+                end.error.unwrap().condition,
+                ErrorCondition::SessionError(SessionError::HandleInUse)
+            ),
+            _ => unreachable!(),
+        }
+        drop(transport);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_steal_duplicate_link_name() {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            let acceptor = ConnectionAcceptor::new("test-listener");
+            let mut connection = acceptor.accept(server_io).await.unwrap();
+            let mut session = SessionAcceptor::builder()
+                .duplicate_link_name_policy(DuplicateLinkNamePolicy::Steal)
+                .build()
+                .accept(&mut connection)
+                .await
+                .unwrap();
+            let link_acceptor = LinkAcceptor::new();
+
+            let mut old = into_receiver(link_acceptor.accept(&mut session).await.unwrap());
+            let mut new = into_receiver(link_acceptor.accept(&mut session).await.unwrap());
+            assert_stolen(&mut old).await;
+
+            // The name still belongs to the new link after the old link is detached, so the name
+            // is stolen again by the next attach
+            let _newest = link_acceptor.accept(&mut session).await.unwrap();
+            assert_stolen(&mut new).await;
+        });
+
+        let mut transport = begin_raw_session(client_io).await;
+        transport.send(attach(0)).await.unwrap();
+        next_frame(&mut transport, |body| matches!(body, FrameBody::Attach(_))).await;
+        transport.send(attach(1)).await.unwrap();
+
+        // The old link echoes the detach after the new link is attached with output handle 1
+        next_frame(&mut transport, is_detach(0)).await;
+        transport.send(attach(2)).await.unwrap();
+        next_frame(&mut transport, is_detach(1)).await;
+
+        server.await.unwrap();
+    }
+}
@@ -1,5 +1,5 @@
-// /// a: This is an optional attribute, and is part of the GS2 [RFC5801] bridge between the GSS-API and SASL.
-// pub const GS2: char = 'a';
+/// a: This is an optional attribute, and is part of the GS2 [RFC5801] bridge between the GSS-API and SASL.
+pub const AUTHZID_KEY: &str = "a=";
 
 pub const GS2_HEADER: &str = "n,,";
 
@@ -17,6 +17,7 @@ enum ScramClientState {
 
 #[derive(Debug, Clone)]
 pub(crate) struct ScramClient {
+    authzid: Option<String>,
     username: String,
     password: String,
     scram: ScramVersion,
@@ -30,6 +31,7 @@ impl ScramClient {
         scram_version: ScramVersion,
     ) -> Self {
         Self {
+            authzid: None,
             username: username.into(),
             password: password.into(),
             scram: scram_version,
@@ -37,13 +39,19 @@ impl ScramClient {
         }
     }
 
+    pub fn set_authzid(&mut self, authzid: impl Into<String>) {
+        self.authzid = Some(authzid.into());
+    }
+
     pub fn compute_client_first_message(&mut self) -> Bytes {
         use base64::Engine;
 
         let nonce = base64::engine::general_purpose::STANDARD.encode(generate_nonce());
-        let (client_first_message, client_first_message_bare) = self
-            .scram
-            .client_first_message(self.username.as_bytes(), nonce.as_bytes());
+        let (client_first_message, client_first_message_bare) = self.scram.client_first_message(
+            self.authzid.as_deref(),
+            self.username.as_bytes(),
+            nonce.as_bytes(),
+        );
         self.state = ScramClientState::ClientFirstSent {
             client_nonce: nonce,
             client_first_message_bare,
@@ -61,6 +69,7 @@ impl ScramClient {
                 client_first_message_bare,
             } => {
                 let (client_final, server_signature) = self.scram.compute_client_final_message(
+                    self.authzid.as_deref(),
                     client_nonce,
                     &self.password,
                     server_first,
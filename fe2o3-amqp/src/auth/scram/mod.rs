@@ -12,8 +12,8 @@ use sha1::Sha1;
 use sha2::{Sha256, Sha512};
 
 use attributes::{
-    CHANNEL_BINDING_KEY, GS2_HEADER, ITERATION_COUNT_KEY, NONCE_KEY, PROOF_KEY, RESERVED_MEXT,
-    SALT_KEY, USERNAME_KEY, VERIFIER_KEY,
+    AUTHZID_KEY, CHANNEL_BINDING_KEY, GS2_HEADER, ITERATION_COUNT_KEY, NONCE_KEY, PROOF_KEY,
+    RESERVED_MEXT, SALT_KEY, USERNAME_KEY, VERIFIER_KEY,
 };
 use error::XorLengthMismatch;
 
@@ -98,9 +98,15 @@ impl ScramVersion {
     }
 
     /// Returns (client_first_message, client_first_message_bare)
-    pub(crate) fn client_first_message(&self, username: &[u8], nonce: &[u8]) -> (Bytes, Bytes) {
+    pub(crate) fn client_first_message(
+        &self,
+        authzid: Option<&str>,
+        username: &[u8],
+        nonce: &[u8],
+    ) -> (Bytes, Bytes) {
+        let gs2_header = gs2_header(authzid);
         let mut bytes = BytesMut::new();
-        bytes.put_slice(GS2_HEADER.as_bytes());
+        bytes.put_slice(gs2_header.as_bytes());
 
         bytes.put_slice(USERNAME_KEY.as_bytes());
         bytes.put_slice(username);
@@ -111,7 +117,7 @@ impl ScramVersion {
         bytes.put_slice(nonce);
 
         let client_first_message = bytes.freeze();
-        let gs2_header_len = GS2_HEADER.as_bytes().len();
+        let gs2_header_len = gs2_header.len();
         let client_first_message_bare = client_first_message.slice(gs2_header_len..);
         (client_first_message, client_first_message_bare)
     }
@@ -174,6 +180,7 @@ impl ScramVersion {
 
     pub(crate) fn compute_client_final_message(
         &self,
+        authzid: Option<&str>,
         client_nonce: &str,
         password: &str,
         server_first: &str,
@@ -221,7 +228,8 @@ impl ScramVersion {
 
         let salted_password =
             self.compute_salted_password::<ScramErrorKind>(password, &salt[..], iterations)?;
-        let client_final_message_without_proof = without_proof(client_server_nonce);
+        let client_final_message_without_proof =
+            without_proof(&gs2_header(authzid), client_server_nonce);
         let auth_message = auth_message(
             client_first_message_bare,
             server_first.as_bytes(),
@@ -322,10 +330,22 @@ fn auth_message(
     buf
 }
 
-fn without_proof(client_server_nonce: &str) -> Vec<u8> {
+/// The GS2 header without channel binding, ie. `"n,,"` or `"n,a=<authzid>,"`
+fn gs2_header(authzid: Option<&str>) -> String {
+    match authzid {
+        Some(authzid) => {
+            // saslname: "," and "=" are encoded as "=2C" and "=3D"
+            let saslname = authzid.replace('=', "=3D").replace(',', "=2C");
+            format!("n,{}{},", AUTHZID_KEY, saslname)
+        }
+        None => String::from(GS2_HEADER),
+    }
+}
+
+fn without_proof(gs2_header: &str, client_server_nonce: &str) -> Vec<u8> {
     use base64::Engine;
 
-    let encoded_gs2_header = base64::engine::general_purpose::STANDARD.encode(GS2_HEADER).into_bytes();
+    let encoded_gs2_header = base64::engine::general_purpose::STANDARD.encode(gs2_header).into_bytes();
     let total_len = CHANNEL_BINDING_KEY.len()
         + encoded_gs2_header.len()
         + 1
@@ -518,7 +538,7 @@ mod tests {
         use scram_sha1::*;
 
         let (client_first_message, client_first_message_bare) =
-            VERSION.client_first_message(TEST_USERNAME.as_bytes(), CLIENT_NONCE.as_bytes());
+            VERSION.client_first_message(None, TEST_USERNAME.as_bytes(), CLIENT_NONCE.as_bytes());
         assert_eq!(
             client_first_message,
             EXPECTED_CLIENT_INITIAL_RESPONSE.as_bytes()
@@ -526,6 +546,7 @@ mod tests {
 
         let (client_final, server_signature) = VERSION
             .compute_client_final_message(
+                None,
                 CLIENT_NONCE,
                 TEST_PASSWORD,
                 SERVER_FIRST_MESSAGE,
@@ -543,7 +564,7 @@ mod tests {
         use scram_sha256::*;
 
         let (client_first_message, client_first_message_bare) =
-            VERSION.client_first_message(TEST_USERNAME.as_bytes(), CLIENT_NONCE.as_bytes());
+            VERSION.client_first_message(None, TEST_USERNAME.as_bytes(), CLIENT_NONCE.as_bytes());
         assert_eq!(
             client_first_message,
             EXPECTED_CLIENT_INITIAL_RESPONSE.as_bytes()
@@ -551,6 +572,7 @@ mod tests {
 
         let (client_final, server_signature) = VERSION
             .compute_client_final_message(
+                None,
                 CLIENT_NONCE,
                 TEST_PASSWORD,
                 SERVER_FIRST_MESSAGE,
@@ -568,7 +590,7 @@ mod tests {
         use scram_sha512::*;
 
         let (client_first_message, client_first_message_bare) =
-            VERSION.client_first_message(TEST_USERNAME.as_bytes(), CLIENT_NONCE.as_bytes());
+            VERSION.client_first_message(None, TEST_USERNAME.as_bytes(), CLIENT_NONCE.as_bytes());
         assert_eq!(
             client_first_message,
             EXPECTED_CLIENT_INITIAL_RESPONSE.as_bytes()
@@ -576,6 +598,7 @@ mod tests {
 
         let (client_final, server_signature) = VERSION
             .compute_client_final_message(
+                None,
                 CLIENT_NONCE,
                 TEST_PASSWORD,
                 SERVER_FIRST_MESSAGE,
@@ -588,6 +611,41 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn test_sasl_scram_client_with_authzid() {
+        use base64::Engine;
+        use scram_sha256::*;
+
+        let (client_first_message, client_first_message_bare) = VERSION.client_first_message(
+            Some("admin,a=b"),
+            TEST_USERNAME.as_bytes(),
+            CLIENT_NONCE.as_bytes(),
+        );
+        assert_eq!(
+            client_first_message,
+            "n,a=admin=2Ca=3Db,n=user,r=rOprNGfwEbeRWgbNEkqO".as_bytes()
+        );
+        assert_eq!(
+            client_first_message_bare,
+            "n=user,r=rOprNGfwEbeRWgbNEkqO".as_bytes()
+        );
+
+        let (client_final, _) = VERSION
+            .compute_client_final_message(
+                Some("admin,a=b"),
+                CLIENT_NONCE,
+                TEST_PASSWORD,
+                SERVER_FIRST_MESSAGE,
+                &client_first_message_bare,
+            )
+            .unwrap();
+        let channel_binding = format!(
+            "c={},",
+            base64::engine::general_purpose::STANDARD.encode("n,a=admin=2Ca=3Db,")
+        );
+        assert!(client_final.starts_with(channel_binding.as_bytes()));
+    }
+
     cfg_acceptor! {
         #[test]
         fn test_sasl_scram_sha1_server() {
@@ -1,30 +1,38 @@
 //! Builder for [`crate::Connection`]
 
-use std::{io, marker::PhantomData, time::Duration};
+use std::{future::Future, io, marker::PhantomData, time::Duration};
 
 use fe2o3_amqp_types::{
-    definitions::{Fields, IetfLanguageTag, Milliseconds, MIN_MAX_FRAME_SIZE},
+    definitions::{
+        Fields, IetfLanguageTag, Milliseconds, ReceiverSettleMode, SenderSettleMode, SequenceNo,
+        MIN_MAX_FRAME_SIZE,
+    },
     performatives::{ChannelMax, MaxFrameSize, Open},
     sasl::SaslCode,
 };
 use futures_util::{SinkExt, StreamExt};
-use serde_amqp::primitives::Symbol;
+use serde_amqp::{primitives::Symbol, Value};
 use tokio::{
     io::{AsyncRead, AsyncWrite, ReadHalf, WriteHalf},
     sync::mpsc::{self},
 };
 use tokio_util::codec::{FramedRead, FramedWrite};
+use uuid::Uuid;
 
 cfg_not_wasm32! {
     use std::convert::TryInto;
+    use std::net::SocketAddr;
+    use std::pin::Pin;
     use url::Url;
     use tokio::net::TcpStream;
+    use fe2o3_amqp_types::definitions::Redirect;
 }
 
 use crate::{
     connection::{Connection, ConnectionState},
     control::ConnectionControl,
     frames::sasl,
+    link::builder::LinkDefaults,
     sasl_profile::{Negotiation, SaslProfile},
     session::frame::SessionFrame,
     transport::Transport,
@@ -40,6 +48,12 @@ use super::{
 #[cfg(feature = "tracing")]
 use tracing::instrument;
 
+cfg_test_util! {
+    use std::sync::Arc;
+    use crate::frames::amqp::Frame;
+    use super::interceptor::{Interception, OutgoingInterceptor};
+}
+
 pub(crate) const DEFAULT_CONTROL_CHAN_BUF: usize = 128;
 pub(crate) const DEFAULT_OUTGOING_BUFFER_SIZE: usize = u16::MAX as usize;
 
@@ -51,6 +65,93 @@ cfg_not_wasm32! {
             _ => None,
         }
     }
+
+    impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
+        /// Use `hostname` for the fields that would otherwise be taken from the url when the
+        /// connection is opened with a pre-resolved address
+        fn use_hostname_for_addr(&mut self, hostname: &'a str) {
+            if self.hostname.is_none() {
+                self.hostname = Some(hostname);
+            }
+            if self.sasl_hostname.is_none() {
+                self.sasl_hostname = Some(hostname);
+            }
+            self.domain = Some(hostname);
+        }
+
+        /// Connects to the container indicated by a connection redirect and returns the builder
+        /// that should be used to open the redirected connection
+        ///
+        /// Returns `None` if the redirect doesn't carry the host to connect to.
+        async fn connect_redirect<'b>(
+            self,
+            redirect: &'b Redirect,
+        ) -> Option<Result<(Builder<'b, Mode, Tls>, TcpStream), OpenError>>
+        where
+            'a: 'b,
+        {
+            let network_host = redirect
+                .network_host
+                .as_deref()
+                .or(redirect.hostname.as_deref())?;
+            let port = redirect
+                .port
+                .or_else(|| default_port(self.scheme))
+                .unwrap_or(fe2o3_amqp_types::definitions::PORT);
+
+            let mut builder: Builder<'b, Mode, Tls> = self;
+            let hostname = redirect.hostname.as_deref().unwrap_or(network_host);
+            builder.hostname = Some(hostname);
+            builder.sasl_hostname = Some(hostname);
+            builder.domain = Some(hostname);
+            builder.follow_redirects = false;
+
+            match TcpStream::connect((network_host, port)).await {
+                Ok(stream) => Some(Ok((builder, stream))),
+                Err(err) => Some(Err(err.into())),
+            }
+        }
+    }
+
+    /// Returns the redirect information if following redirects is enabled and the error is an
+    /// `amqp:connection:redirect`
+    fn take_redirect(
+        follow_redirects: bool,
+        result: &Result<ConnectionHandle<()>, OpenError>,
+    ) -> Option<Redirect> {
+        match result {
+            Err(OpenError::RemoteClosedWithError(error)) if follow_redirects => error.redirect(),
+            _ => None,
+        }
+    }
+
+    /// Opens the connection over `stream` with `open` and, if the remote peer answers with an
+    /// `amqp:connection:redirect` error and following redirects is enabled, opens the redirected
+    /// connection with `open` again
+    async fn open_following_redirect<'a, Mode, Tls, F>(
+        builder: Builder<'a, Mode, Tls>,
+        stream: TcpStream,
+        open: F,
+    ) -> Result<ConnectionHandle<()>, OpenError>
+    where
+        Builder<'a, Mode, Tls>: Clone,
+        F: for<'b> Fn(
+            Builder<'b, Mode, Tls>,
+            TcpStream,
+        ) -> Pin<Box<dyn Future<Output = Result<ConnectionHandle<()>, OpenError>> + Send + 'b>>,
+    {
+        let follow_redirects = builder.follow_redirects;
+        let redirect_builder = follow_redirects.then(|| builder.clone());
+        let result = open(builder, stream).await;
+        match (take_redirect(follow_redirects, &result), redirect_builder) {
+            (Some(redirect), Some(builder)) => match builder.connect_redirect(&redirect).await {
+                Some(Ok((builder, stream))) => open(builder, stream).await,
+                Some(Err(err)) => Err(err),
+                None => result,
+            },
+            _ => result,
+        }
+    }
 }
 
 pub(crate) mod mode {
@@ -63,27 +164,34 @@ pub(crate) mod mode {
 }
 
 /// Builder for [`crate::Connection`]
-#[derive(Clone)]
 pub struct Builder<'a, Mode, Tls> {
     /// The id of the source container
+    ///
+    /// Defaults to a unique UUID based id
     pub container_id: String,
 
     /// The hostname of the target host
     ///
     /// This will be used as the hostname in the Open frame. If not supplied, the hostname will be
-    /// extracted from the url.
+    /// extracted from the url, or the `domain` will be used if the connection is opened with
+    /// `open_with_stream`. This is also the fallback value of `sasl_hostname` when the connection
+    /// is opened with `open_with_stream`.
     pub hostname: Option<&'a str>,
 
-    /// The hostname used for SASL negotiation
+    /// The hostname sent in the `SaslInit` frame
     ///
-    /// This will be used as the hostname in the SASL negotiation. If not supplied, the hostname will
-    /// be extracted from the url.
+    /// If not supplied, the hostname will be extracted from the url, or `hostname` and then
+    /// `domain` will be used if the connection is opened with `open_with_stream`. Unlike the
+    /// hostname in the Open frame, this is only sent to the SASL layer.
     pub sasl_hostname: Option<&'a str>,
 
     /// URL scheme
     pub scheme: &'a str,
 
     /// URL domain
+    ///
+    /// This is the server name used for TLS negotiation and is the last fallback value of
+    /// `hostname` and `sasl_hostname`
     pub domain: Option<&'a str>,
 
     /// Proposed maximum frame size
@@ -99,6 +207,15 @@ pub struct Builder<'a, Mode, Tls> {
     pub channel_max: ChannelMax,
 
     /// Idle time-out
+    ///
+    /// If set, the remote peer is asked to send a frame at least every half of this value and the
+    /// connection fails with an idle time-out if no frame is received within this value. If
+    /// `None`, no idle time-out is advertised and the connection is never timed out locally.
+    /// `Some(0)` is invalid and opening the connection will fail with
+    /// [`OpenError::InvalidIdleTimeOut`].
+    ///
+    /// Heartbeats (empty frames) are only sent if the remote peer advertises an idle time-out,
+    /// regardless of this value.
     pub idle_time_out: Option<Milliseconds>,
 
     /// Locales available for outgoing text
@@ -142,16 +259,110 @@ pub struct Builder<'a, Mode, Tls> {
     /// PLAIN SASL profile that is interpreted from the url.
     pub sasl_profile: Option<SaslProfile>,
 
+    /// Maximum amount of time that the SASL negotiation is allowed to take
+    ///
+    /// This covers the SASL protocol header exchange and the SASL frames exchange, but not the
+    /// TCP/TLS connect and the AMQP Open exchange. The connection fails with
+    /// [`OpenError::AuthenticationTimeout`] if the negotiation does not complete in time. `None`
+    /// means that the negotiation is not timed out. This has no effect on wasm32.
+    pub authentication_timeout: Option<Duration>,
+
     /// TLS establishment
     ///
     /// This determines whether an AMQP TLS protocol header exchange will be performed prior to
     /// actual TLS handshake
     pub alt_tls_estab: bool,
 
+    /// ALPN protocol identifiers (eg. `b"amqp"`) that are offered in the TLS handshake
+    ///
+    /// This is only applied to the default TLS connector that is used if no `TlsConnector` is
+    /// supplied. A custom connector must be configured with the ALPN protocols before it is passed
+    /// to the builder. With `"native-tls"`, the identifiers must be valid UTF-8.
+    pub alpn_protocols: Option<Vec<Vec<u8>>>,
+
+    /// Whether to follow an `amqp:connection:redirect` error returned by the remote peer when the
+    /// connection is opened with [`open`](#method.open)
+    ///
+    /// If enabled and the remote peer answers the `Open` with a `Close` carrying the redirect, the
+    /// connection is re-established to the `network-host` and `port` found in the `info` of the
+    /// error, using `hostname` for the `Open` frame. Only a single redirect is followed. This has
+    /// no effect on wasm32.
+    pub follow_redirects: bool,
+
+    /// Default settings for the links attached to the sessions on this connection
+    pub(crate) link_defaults: LinkDefaults,
+
+    /// Maximum number of sessions that can be begun concurrently on the connection
+    ///
+    /// Unlike `channel_max`, this is only enforced locally and is not sent to the remote peer.
+    /// `None` means the number of sessions is only bounded by `channel_max`.
+    pub max_sessions: Option<usize>,
+
+    /// Initial capacity in bytes of the buffer that the transport reads incoming frames into
+    ///
+    /// `None` uses the default of `tokio_util::codec::FramedRead`. The buffer grows as needed to
+    /// hold a whole frame, which is bounded by `max_frame_size`.
+    pub read_buffer_size: Option<usize>,
+
+    /// Number of bytes of outgoing frames that the transport buffers before writing them to the
+    /// underlying IO
+    ///
+    /// `None` uses the default backpressure boundary of `tokio_util::codec::FramedWrite`.
+    pub write_buffer_size: Option<usize>,
+
+    /// Whether consecutive batchable transfers and dispositions are written to the underlying IO
+    /// together
+    ///
+    /// If enabled, outgoing frames with the `batchable` field set are held back while the sessions
+    /// have more frames queued, until a frame that is not batchable is sent, the buffered frames
+    /// reach the max frame size negotiated with the remote peer, or a short flush timer expires.
+    /// Defaults to `false`.
+    pub coalesce_writes: bool,
+
+    /// Interceptor that is invoked on every outgoing frame sent by the connection engine
+    #[cfg(feature = "test-util")]
+    pub outgoing_interceptor: Option<OutgoingInterceptor>,
+
     // type state marker
     marker: PhantomData<Mode>,
 }
 
+// Implemented manually so that the type state marker is not required to be `Clone`
+impl<'a, Mode, Tls: Clone> Clone for Builder<'a, Mode, Tls> {
+    fn clone(&self) -> Self {
+        Self {
+            container_id: self.container_id.clone(),
+            hostname: self.hostname,
+            sasl_hostname: self.sasl_hostname,
+            scheme: self.scheme,
+            domain: self.domain,
+            max_frame_size: self.max_frame_size.clone(),
+            channel_max: self.channel_max.clone(),
+            idle_time_out: self.idle_time_out,
+            outgoing_locales: self.outgoing_locales.clone(),
+            incoming_locales: self.incoming_locales.clone(),
+            offered_capabilities: self.offered_capabilities.clone(),
+            desired_capabilities: self.desired_capabilities.clone(),
+            properties: self.properties.clone(),
+            tls_connector: self.tls_connector.clone(),
+            buffer_size: self.buffer_size,
+            sasl_profile: self.sasl_profile.clone(),
+            authentication_timeout: self.authentication_timeout,
+            alt_tls_estab: self.alt_tls_estab,
+            alpn_protocols: self.alpn_protocols.clone(),
+            follow_redirects: self.follow_redirects,
+            link_defaults: self.link_defaults.clone(),
+            max_sessions: self.max_sessions,
+            read_buffer_size: self.read_buffer_size,
+            write_buffer_size: self.write_buffer_size,
+            coalesce_writes: self.coalesce_writes,
+            #[cfg(feature = "test-util")]
+            outgoing_interceptor: self.outgoing_interceptor.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<'a, Tls> From<Builder<'a, mode::ConnectorWithId, Tls>> for Open {
     fn from(builder: Builder<'a, mode::ConnectorWithId, Tls>) -> Self {
         let max_frame_size = MaxFrameSize(std::cmp::max(
@@ -160,7 +371,8 @@ impl<'a, Tls> From<Builder<'a, mode::ConnectorWithId, Tls>> for Open {
         ));
         Open {
             container_id: builder.container_id,
-            hostname: builder.hostname.map(Into::into),
+            // Fall back to the TLS server name, which many brokers use for virtual host routing
+            hostname: builder.hostname.or(builder.domain).map(Into::into),
             max_frame_size,
             channel_max: builder.channel_max,
             // To avoid spurious timeouts, the value in idle-time-out SHOULD be half the peer’s actual timeout threshold.
@@ -193,6 +405,12 @@ impl<Mode: std::fmt::Debug> std::fmt::Debug for Builder<'_, Mode, ()> {
             .field("tls_connector", &"()")
             .field("buffer_size", &self.buffer_size)
             .field("sasl_profile", &self.sasl_profile)
+            .field("authentication_timeout", &self.authentication_timeout)
+            .field("alpn_protocols", &self.alpn_protocols)
+            .field("follow_redirects", &self.follow_redirects)
+            .field("read_buffer_size", &self.read_buffer_size)
+            .field("write_buffer_size", &self.write_buffer_size)
+            .field("coalesce_writes", &self.coalesce_writes)
             .field("marker", &self.marker)
             .finish()
     }
@@ -218,6 +436,9 @@ cfg_rustls! {
                 .field("tls_connector", &"tokio_rustls::TlsConnector")
                 .field("buffer_size", &self.buffer_size)
                 .field("sasl_profile", &self.sasl_profile)
+                .field("authentication_timeout", &self.authentication_timeout)
+                .field("alpn_protocols", &self.alpn_protocols)
+                .field("follow_redirects", &self.follow_redirects)
                 .field("marker", &self.marker)
                 .finish()
         }
@@ -247,6 +468,9 @@ cfg_not_wasm32! {
                     .field("tls_connector", &"tokio_native_tls::TlsConnector")
                     .field("buffer_size", &self.buffer_size)
                     .field("sasl_profile", &self.sasl_profile)
+                    .field("authentication_timeout", &self.authentication_timeout)
+                    .field("alpn_protocols", &self.alpn_protocols)
+                    .field("follow_redirects", &self.follow_redirects)
                     .field("marker", &self.marker)
                     .finish()
             }
@@ -262,9 +486,11 @@ impl<Mode> Default for Builder<'_, Mode, ()> {
 
 impl<Mode> Builder<'_, Mode, ()> {
     /// Creates a new builder for [`crate::Connection`]
+    ///
+    /// The container id defaults to a unique UUID based id
     pub fn new() -> Self {
         Self {
-            container_id: String::new(),
+            container_id: Uuid::new_v4().to_string(),
             hostname: None,
             sasl_hostname: None,
             scheme: "amqp", // Assume non-TLS by default
@@ -283,7 +509,17 @@ impl<Mode> Builder<'_, Mode, ()> {
 
             buffer_size: DEFAULT_OUTGOING_BUFFER_SIZE,
             sasl_profile: None,
+            authentication_timeout: None,
             alt_tls_estab: false,
+            alpn_protocols: None,
+            follow_redirects: false,
+            link_defaults: LinkDefaults::default(),
+            max_sessions: None,
+            read_buffer_size: None,
+            write_buffer_size: None,
+            coalesce_writes: false,
+            #[cfg(feature = "test-util")]
+            outgoing_interceptor: None,
 
             marker: PhantomData,
         }
@@ -316,13 +552,34 @@ impl<'a, Tls> Builder<'a, mode::ConnectorNoId, Tls> {
 
             buffer_size: self.buffer_size,
             sasl_profile: self.sasl_profile,
+            authentication_timeout: self.authentication_timeout,
             alt_tls_estab: self.alt_tls_estab,
+            alpn_protocols: self.alpn_protocols,
+            follow_redirects: self.follow_redirects,
+            link_defaults: self.link_defaults,
+            max_sessions: self.max_sessions,
+            read_buffer_size: self.read_buffer_size,
+            write_buffer_size: self.write_buffer_size,
+            coalesce_writes: self.coalesce_writes,
+            #[cfg(feature = "test-util")]
+            outgoing_interceptor: self.outgoing_interceptor,
 
             marker: PhantomData,
         }
     }
 }
 
+impl<'a, Tls> Builder<'a, mode::ConnectorWithId, Tls> {
+    /// The id of the source container
+    ///
+    /// This overrides the unique UUID based id that is generated by [`Builder::new`]. The id can be
+    /// retrieved with [`ConnectionHandle::container_id`].
+    pub fn container_id(mut self, id: impl Into<String>) -> Self {
+        self.container_id = id.into();
+        self
+    }
+}
+
 #[allow(clippy::needless_lifetimes)]
 impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
     /// Alias for [`rustls_connector`](#method.rustls_connector) if only `"rustls"` is enabled
@@ -366,7 +623,17 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
 
                 buffer_size: self.buffer_size,
                 sasl_profile: self.sasl_profile,
+                authentication_timeout: self.authentication_timeout,
                 alt_tls_estab: self.alt_tls_estab,
+                alpn_protocols: self.alpn_protocols,
+                follow_redirects: self.follow_redirects,
+                link_defaults: self.link_defaults,
+                max_sessions: self.max_sessions,
+                read_buffer_size: self.read_buffer_size,
+                write_buffer_size: self.write_buffer_size,
+                coalesce_writes: self.coalesce_writes,
+                #[cfg(feature = "test-util")]
+                outgoing_interceptor: self.outgoing_interceptor,
 
                 marker: PhantomData,
             }
@@ -430,7 +697,17 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
 
                     buffer_size: self.buffer_size,
                     sasl_profile: self.sasl_profile,
+                    authentication_timeout: self.authentication_timeout,
                     alt_tls_estab: self.alt_tls_estab,
+                    alpn_protocols: self.alpn_protocols,
+                    follow_redirects: self.follow_redirects,
+                    link_defaults: self.link_defaults,
+                    max_sessions: self.max_sessions,
+                    read_buffer_size: self.read_buffer_size,
+                    write_buffer_size: self.write_buffer_size,
+                    coalesce_writes: self.coalesce_writes,
+                    #[cfg(feature = "test-util")]
+                    outgoing_interceptor: self.outgoing_interceptor,
 
                     marker: PhantomData,
                 }
@@ -443,16 +720,20 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
     /// The name of the target host
     ///
     /// This will be used as the hostname in the Open frame. If not supplied, the hostname will be
-    /// extracted from the url.
+    /// extracted from the url, or the `domain` will be used if the connection is opened with
+    /// `open_with_stream`.
     pub fn hostname(mut self, hostname: impl Into<Option<&'a str>>) -> Self {
         self.hostname = hostname.into();
         self
     }
 
-    /// The hostname used for SASL negotiation
+    /// The hostname sent in the `SaslInit` frame, independently of the hostname in the Open frame
     ///
-    /// This will be used as the hostname in the SASL negotiation. If not supplied, the hostname will
-    /// be extracted from the url.
+    /// If not supplied, the hostname will be extracted from the url, or `hostname` and then
+    /// `domain` will be used if the connection is opened with `open_with_stream`. This is useful
+    /// when the SASL layer expects a different name than the transport, eg. Azure Service Bus
+    /// expects the namespace FQDN even when it is reached through a WebSocket url with another
+    /// host.
     pub fn sasl_hostname(mut self, sasl_hostname: impl Into<Option<&'a str>>) -> Self {
         self.sasl_hostname = sasl_hostname.into();
         self
@@ -465,6 +746,10 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
     }
 
     /// URL domain
+    ///
+    /// This is the server name used for TLS negotiation. If `hostname` or `sasl_hostname` is not
+    /// supplied, the domain will be used instead. This is useful when opening a connection with
+    /// `open_with_stream` over a TLS stream, where the hostname cannot be extracted from a url.
     pub fn domain(mut self, domain: impl Into<Option<&'a str>>) -> Self {
         self.domain = domain.into();
         self
@@ -499,9 +784,12 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
         self
     }
 
-    /// Idle time-out
-    pub fn idle_time_out(mut self, idle_time_out: impl Into<Milliseconds>) -> Self {
-        self.idle_time_out = Some(idle_time_out.into());
+    /// Idle time-out in milliseconds
+    ///
+    /// `None` disables the local idle time-out, which is the default. Please see the
+    /// [`idle_time_out`](#structfield.idle_time_out) field for more details.
+    pub fn idle_time_out(mut self, idle_time_out: impl Into<Option<Milliseconds>>) -> Self {
+        self.idle_time_out = idle_time_out.into();
         self
     }
 
@@ -571,6 +859,15 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
         self
     }
 
+    /// Insert a single connection property. Any property previously inserted with the same key is
+    /// replaced
+    pub fn property(mut self, key: impl Into<Symbol>, value: impl Into<Value>) -> Self {
+        self.properties
+            .get_or_insert_with(Fields::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
     /// Buffer size of the underlying [`tokio::sync::mpsc::channel`] that are used by the sessions
     pub fn buffer_size(mut self, buffer_size: usize) -> Self {
         self.buffer_size = buffer_size;
@@ -588,6 +885,16 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
         self
     }
 
+    /// Maximum amount of time that the SASL negotiation is allowed to take
+    ///
+    /// This is timed independently from the rest of the opening process and fails with
+    /// [`OpenError::AuthenticationTimeout`]. See the
+    /// [`authentication_timeout`](#structfield.authentication_timeout) field for more details.
+    pub fn authentication_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.authentication_timeout = timeout.into();
+        self
+    }
+
     /// Set the alternative tls_establishment
     ///
     /// Please see part 5.2.1 of the core spec
@@ -595,9 +902,128 @@ impl<'a, Mode, Tls> Builder<'a, Mode, Tls> {
         self.alt_tls_estab = value;
         self
     }
+
+    /// Set the ALPN protocol identifiers (eg. `b"amqp"`) that are offered in the TLS handshake
+    ///
+    /// Some load balancers and gateways route `amqps` connections based on ALPN. This only applies
+    /// to the default TLS connector, see the [`alpn_protocols`](#structfield.alpn_protocols) field
+    /// for more details.
+    pub fn alpn_protocols(mut self, protocols: Vec<Vec<u8>>) -> Self {
+        self.alpn_protocols = Some(protocols);
+        self
+    }
+
+    /// Follow an `amqp:connection:redirect` error returned by the remote peer when the connection
+    /// is opened with [`open`](#method.open)
+    ///
+    /// See the [`follow_redirects`](#structfield.follow_redirects) field for more details.
+    pub fn follow_redirects(mut self, value: bool) -> Self {
+        self.follow_redirects = value;
+        self
+    }
+
+    /// Maximum number of sessions that can be begun concurrently on the connection
+    ///
+    /// This is only enforced locally. Beginning a session when the limit has been reached fails
+    /// with `BeginError::SessionLimitReached`, even if `channel_max` has not been reached yet.
+    pub fn max_sessions(mut self, max_sessions: usize) -> Self {
+        self.max_sessions = Some(max_sessions);
+        self
+    }
+
+    /// Initial capacity in bytes of the buffer that the transport reads incoming frames into
+    ///
+    /// A larger buffer allows more frames to be read with one syscall at the cost of memory. The
+    /// buffer still grows as needed to hold a whole frame.
+    pub fn read_buffer_size(mut self, size: usize) -> Self {
+        self.read_buffer_size = Some(size);
+        self
+    }
+
+    /// Number of bytes of outgoing frames that the transport buffers before writing them to the
+    /// underlying IO
+    ///
+    /// A larger buffer allows more frames to be written with one syscall at the cost of memory.
+    pub fn write_buffer_size(mut self, size: usize) -> Self {
+        self.write_buffer_size = Some(size);
+        self
+    }
+
+    /// Write consecutive batchable transfers and dispositions to the underlying IO together
+    ///
+    /// This reduces the number of writes when many messages are sent with `send_batchable`.
+    /// Batchable frames are only held back while more frames are queued, and for at most a
+    /// millisecond. A frame that is not batchable is still written right away along with the
+    /// batchable frames held back before it.
+    pub fn coalesce_writes(mut self, coalesce_writes: bool) -> Self {
+        self.coalesce_writes = coalesce_writes;
+        self
+    }
+
+    /// Default credit of the receivers attached to sessions on this connection, which will use
+    /// `CreditMode::Auto` with the given credit unless `credit_mode` is set on the link builder.
+    ///
+    /// This can be overriden for each session with `Session::builder().default_link_credit()`
+    pub fn default_link_credit(mut self, credit: SequenceNo) -> Self {
+        self.link_defaults.credit = Some(credit);
+        self
+    }
+
+    /// Default settlement policy for the senders attached to sessions on this connection unless
+    /// `sender_settle_mode` is set on the link builder.
+    ///
+    /// This can be overriden for each session with
+    /// `Session::builder().default_sender_settle_mode()`
+    pub fn default_sender_settle_mode(mut self, mode: SenderSettleMode) -> Self {
+        self.link_defaults.snd_settle_mode = Some(mode);
+        self
+    }
+
+    /// Default settlement policy of the receivers attached to sessions on this connection unless
+    /// `receiver_settle_mode` is set on the link builder.
+    ///
+    /// This can be overriden for each session with
+    /// `Session::builder().default_receiver_settle_mode()`
+    pub fn default_receiver_settle_mode(mut self, mode: ReceiverSettleMode) -> Self {
+        self.link_defaults.rcv_settle_mode = Some(mode);
+        self
+    }
+
+    cfg_test_util! {
+        /// Set a closure that can inspect, modify, delay or drop each outgoing frame before it is
+        /// written to the transport
+        ///
+        /// This is intended for fault injection in tests.
+        ///
+        /// ```rust,ignore
+        /// use fe2o3_amqp::connection::interceptor::Interception;
+        /// use fe2o3_amqp::frames::amqp::FrameBody;
+        ///
+        /// let connection = Connection::builder()
+        ///     .container_id("connection-1")
+        ///     .outgoing_interceptor(|frame| match frame.body() {
+        ///         FrameBody::Flow(_) => Interception::Drop,
+        ///         _ => Interception::Forward(frame),
+        ///     })
+        ///     .open("amqp://localhost:5672")
+        ///     .await.unwrap();
+        /// ```
+        pub fn outgoing_interceptor<F>(mut self, interceptor: F) -> Self
+        where
+            F: Fn(Frame) -> Interception + Send + Sync + 'static,
+        {
+            self.outgoing_interceptor = Some(Arc::new(interceptor));
+            self
+        }
+    }
 }
 
 impl<'a, Tls> Builder<'a, mode::ConnectorWithId, Tls> {
+    /// The hostname that is sent in the `SaslInit` frame
+    fn sasl_init_hostname(&self) -> Option<&'a str> {
+        self.sasl_hostname.or(self.hostname).or(self.domain)
+    }
+
     /// Performs SASL negotiation
     #[cfg_attr(feature = "tracing", instrument(skip_all, fields(sasl_hostname = ?self.sasl_hostname)))]
     pub async fn negotiate_sasl<Io>(
@@ -619,7 +1045,7 @@ impl<'a, Tls> Builder<'a, mode::ConnectorWithId, Tls> {
             #[cfg(feature = "log")]
             log::trace!("received = {:?}", frame);
 
-            match profile.on_frame(frame, self.sasl_hostname)? {
+            match profile.on_frame(frame, self.sasl_init_hostname())? {
                 Negotiation::Init(init) => {
                     let frame = sasl::Frame::Init(init);
                     #[cfg(feature = "tracing")]
@@ -666,14 +1092,23 @@ impl<'a, Tls> Builder<'a, mode::ConnectorWithId, Tls> {
             mpsc::Sender<SessionFrame>,
         ) -> Result<ConnectionHandle<()>, OpenError>,
     {
+        if self.idle_time_out == Some(0) {
+            return Err(OpenError::InvalidIdleTimeOut);
+        }
+
         match self.sasl_profile.take() {
             Some(profile) => {
-                let (reader, writer) = tokio::io::split(stream);
-                let framed_write = FramedWrite::new(writer, ProtocolHeaderCodec::new());
-                let framed_read = FramedRead::new(reader, ProtocolHeaderCodec::new());
-                let mut transport =
-                    Transport::negotiate_sasl_header(framed_write, framed_read).await?;
-                self.negotiate_sasl(&mut transport, profile).await?;
+                let (framed_write, framed_read) =
+                    split_into_framed(stream, self.read_buffer_size, self.write_buffer_size);
+                let authentication_timeout = self.authentication_timeout;
+                let negotiation = async {
+                    let mut transport =
+                        Transport::negotiate_sasl_header(framed_write, framed_read).await?;
+                    self.negotiate_sasl(&mut transport, profile).await?;
+                    Ok::<_, OpenError>(transport)
+                };
+                let transport =
+                    with_authentication_timeout(authentication_timeout, negotiation).await?;
 
                 // NOTE: LengthDelimitedCodec itself doesn't seem to carry any buffer, so
                 // it should be fine to simply drop it.
@@ -702,9 +1137,8 @@ impl<'a, Tls> Builder<'a, mode::ConnectorWithId, Tls> {
             mpsc::Sender<SessionFrame>,
         ) -> Result<ConnectionHandle<()>, OpenError>,
     {
-        let (reader, writer) = tokio::io::split(stream);
-        let framed_write = FramedWrite::new(writer, ProtocolHeaderCodec::new());
-        let framed_read = FramedRead::new(reader, ProtocolHeaderCodec::new());
+        let (framed_write, framed_read) =
+            split_into_framed(stream, self.read_buffer_size, self.write_buffer_size);
         self.connect_amqp_with_framed(framed_write, framed_read, spawn_engine_fn)
             .await
     }
@@ -729,6 +1163,11 @@ impl<'a, Tls> Builder<'a, mode::ConnectorWithId, Tls> {
             .idle_time_out
             .map(|millis| Duration::from_millis(millis as u64));
         let buffer_size = self.buffer_size;
+        let link_defaults = self.link_defaults.clone();
+        let max_sessions = self.max_sessions;
+        let coalesce_writes = self.coalesce_writes;
+        #[cfg(feature = "test-util")]
+        let outgoing_interceptor = self.outgoing_interceptor.clone();
         let transport = Transport::negotiate_amqp_header(
             framed_write,
             framed_read,
@@ -742,19 +1181,92 @@ impl<'a, Tls> Builder<'a, mode::ConnectorWithId, Tls> {
         // Create channels
         let (control_tx, control_rx) = mpsc::channel(DEFAULT_CONTROL_CHAN_BUF);
         let (outgoing_tx, outgoing_rx) = mpsc::channel(buffer_size);
-        let connection = Connection::new(local_state, local_open);
-
-        let engine = ConnectionEngine::open(transport, connection, control_rx, outgoing_rx).await?;
+        let mut connection = Connection::new(local_state, local_open);
+        connection.max_sessions = max_sessions;
+
+        let engine = ConnectionEngine::open(transport, connection, control_rx, outgoing_rx)
+            .await?
+            .with_coalesced_writes(coalesce_writes);
+        #[cfg(feature = "test-util")]
+        let engine = engine.with_outgoing_interceptor(outgoing_interceptor);
         // Self::spawn_engine(engine, control_tx, outgoing_tx)
-        (spawn_engine_fn)(engine, control_tx, outgoing_tx)
+        let mut connection_handle = (spawn_engine_fn)(engine, control_tx, outgoing_tx)?;
+        connection_handle.link_defaults = link_defaults;
+        Ok(connection_handle)
+    }
+}
+
+cfg_not_wasm32! {
+    async fn with_authentication_timeout<T>(
+        duration: Option<Duration>,
+        fut: impl Future<Output = Result<T, OpenError>>,
+    ) -> Result<T, OpenError> {
+        match duration {
+            Some(duration) => tokio::time::timeout(duration, fut)
+                .await
+                .map_err(|_| OpenError::AuthenticationTimeout)?,
+            None => fut.await,
+        }
     }
 }
 
+cfg_wasm32! {
+    async fn with_authentication_timeout<T>(
+        _duration: Option<Duration>,
+        fut: impl Future<Output = Result<T, OpenError>>,
+    ) -> Result<T, OpenError> {
+        fut.await
+    }
+}
+
+/// Splits the stream into the framed writer and reader used for the protocol header exchange.
+/// The buffers are carried over when the codecs are replaced after the header exchange.
+fn split_into_framed<Io>(
+    stream: Io,
+    read_buffer_size: Option<usize>,
+    write_buffer_size: Option<usize>,
+) -> (
+    FramedWrite<WriteHalf<Io>, ProtocolHeaderCodec>,
+    FramedRead<ReadHalf<Io>, ProtocolHeaderCodec>,
+)
+where
+    Io: AsyncRead + AsyncWrite,
+{
+    let (reader, writer) = tokio::io::split(stream);
+    let mut framed_write = FramedWrite::new(writer, ProtocolHeaderCodec::new());
+    if let Some(size) = write_buffer_size {
+        framed_write.set_backpressure_boundary(size);
+    }
+    let framed_read = match read_buffer_size {
+        Some(size) => FramedRead::with_capacity(reader, ProtocolHeaderCodec::new(), size),
+        None => FramedRead::new(reader, ProtocolHeaderCodec::new()),
+    };
+    (framed_write, framed_read)
+}
+
 /* -------------------------------------------------------------------------- */
 /*                                 Without TLS                                */
 /* -------------------------------------------------------------------------- */
 
 impl Builder<'_, mode::ConnectorWithId, ()> {
+    /// The TLS connector that is used if no `TlsConnector` is supplied
+    #[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+    fn default_rustls_connector(&self) -> tokio_rustls::TlsConnector {
+        use librustls::{ClientConfig, RootCertStore};
+        use std::sync::Arc;
+        use tokio_rustls::TlsConnector;
+
+        let mut root_cert_store = RootCertStore::empty();
+        root_cert_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let mut config = ClientConfig::builder()
+            .with_root_certificates(root_cert_store)
+            .with_no_client_auth();
+        if let Some(protocols) = &self.alpn_protocols {
+            config.alpn_protocols = protocols.clone();
+        }
+        TlsConnector::from(Arc::new(config))
+    }
+
     #[cfg(all(feature = "rustls", not(feature = "native-tls")))]
     async fn connect_tls_with_rustls_default<Io, F>(
         self,
@@ -770,22 +1282,29 @@ impl Builder<'_, mode::ConnectorWithId, ()> {
             mpsc::Sender<SessionFrame>,
         ) -> Result<ConnectionHandle<()>, OpenError>,
     {
-        use librustls::{ClientConfig, RootCertStore};
-        use std::sync::Arc;
-        use tokio_rustls::TlsConnector;
-
-        let mut root_cert_store = RootCertStore::empty();
-        root_cert_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
-        let config = ClientConfig::builder()
-            .with_root_certificates(root_cert_store)
-            .with_no_client_auth();
-        let connector = TlsConnector::from(Arc::new(config));
+        let connector = self.default_rustls_connector();
         let tls_stream =
             Transport::connect_tls_with_rustls(stream, domain, &connector, self.alt_tls_estab)
                 .await?;
         self.connect_with_stream(tls_stream, spawn_engine_fn).await
     }
 
+    /// The ALPN protocol identifiers that are requested by the default `"native-tls"` connector,
+    /// which only accepts UTF-8 identifiers
+    #[cfg(all(
+        feature = "native-tls",
+        not(feature = "rustls"),
+        not(target_arch = "wasm32")
+    ))]
+    fn native_tls_alpn_protocols(&self) -> Result<Vec<&str>, OpenError> {
+        self.alpn_protocols
+            .iter()
+            .flatten()
+            .map(|protocol| std::str::from_utf8(protocol))
+            .collect::<Result<Vec<&str>, _>>()
+            .map_err(|e| OpenError::Io(io::Error::new(io::ErrorKind::InvalidInput, e)))
+    }
+
     #[cfg(all(
         feature = "native-tls",
         not(feature = "rustls"),
@@ -805,7 +1324,13 @@ impl Builder<'_, mode::ConnectorWithId, ()> {
             mpsc::Sender<SessionFrame>,
         ) -> Result<ConnectionHandle<()>, OpenError>,
     {
-        let connector = libnative_tls::TlsConnector::new()
+        let mut builder = libnative_tls::TlsConnector::builder();
+        let protocols = self.native_tls_alpn_protocols()?;
+        if !protocols.is_empty() {
+            builder.request_alpns(&protocols);
+        }
+        let connector = builder
+            .build()
             .map_err(|e| OpenError::Io(io::Error::new(io::ErrorKind::Other, format!("{:?}", e))))?;
         let connector = tokio_native_tls::TlsConnector::from(connector);
         let tls_stream =
@@ -911,6 +1436,34 @@ cfg_not_wasm32! {
             let addr = url.socket_addrs(|| default_port(url.scheme()))?;
             let stream = TcpStream::connect(&*addr).await?; // std::io::Error
 
+            open_following_redirect(self, stream, |builder, stream| {
+                Box::pin(builder.open_with_stream(stream))
+            })
+            .await
+        }
+
+        /// Open a [`crate::Connection`] with a pre-resolved socket address
+        ///
+        /// This connects to `addr` without resolving any DNS name, while `hostname` is used as the
+        /// `hostname` of the `Open` frame and in the SASL negotiation (unless `hostname` or
+        /// `sasl_hostname` is already set on the builder) and as the `domain` for TLS. The scheme is
+        /// taken from the `scheme` field (`"amqp"` by default).
+        ///
+        /// ```rust,ignore
+        /// let addr: SocketAddr = "10.0.0.5:5671".parse().unwrap();
+        /// let connection = Connection::builder()
+        ///     .container_id("connection-1")
+        ///     .scheme("amqps")
+        ///     .open_with_addr(addr, "broker.example.com")
+        ///     .await.unwrap();
+        /// ```
+        pub async fn open_with_addr(
+            mut self,
+            addr: SocketAddr,
+            hostname: &'a str,
+        ) -> Result<ConnectionHandle<()>, OpenError> {
+            self.use_hostname_for_addr(hostname);
+            let stream = TcpStream::connect(addr).await?; // std::io::Error
             self.open_with_stream(stream).await
         }
 
@@ -1184,6 +1737,34 @@ cfg_not_wasm32! {
                 let addr = url.socket_addrs(|| default_port(url.scheme()))?;
                 let stream = TcpStream::connect(&*addr).await?; // std::io::Error
 
+                open_following_redirect(self, stream, |builder, stream| {
+                    Box::pin(builder.open_with_stream(stream))
+                })
+                .await
+            }
+
+            /// Open a [`crate::Connection`] with a pre-resolved socket address
+            ///
+            /// This connects to `addr` without resolving any DNS name, while `hostname` is used as the
+            /// `hostname` of the `Open` frame and in the SASL negotiation (unless `hostname` or
+            /// `sasl_hostname` is already set on the builder) and as the `domain` for TLS. The scheme is
+            /// taken from the `scheme` field (`"amqp"` by default).
+            ///
+            /// ```rust,ignore
+            /// let addr: SocketAddr = "10.0.0.5:5671".parse().unwrap();
+            /// let connection = Connection::builder()
+            ///     .container_id("connection-1")
+            ///     .scheme("amqps")
+            ///     .open_with_addr(addr, "broker.example.com")
+            ///     .await.unwrap();
+            /// ```
+            pub async fn open_with_addr(
+                mut self,
+                addr: SocketAddr,
+                hostname: &'a str,
+            ) -> Result<ConnectionHandle<()>, OpenError> {
+                self.use_hostname_for_addr(hostname);
+                let stream = TcpStream::connect(addr).await?; // std::io::Error
                 self.open_with_stream(stream).await
             }
 
@@ -1317,6 +1898,34 @@ cfg_not_wasm32! {
                 let addr = url.socket_addrs(|| default_port(url.scheme()))?;
                 let stream = TcpStream::connect(&*addr).await?; // std::io::Error
 
+                open_following_redirect(self, stream, |builder, stream| {
+                    Box::pin(builder.open_with_stream(stream))
+                })
+                .await
+            }
+
+            /// Open a [`crate::Connection`] with a pre-resolved socket address
+            ///
+            /// This connects to `addr` without resolving any DNS name, while `hostname` is used as the
+            /// `hostname` of the `Open` frame and in the SASL negotiation (unless `hostname` or
+            /// `sasl_hostname` is already set on the builder) and as the `domain` for TLS. The scheme is
+            /// taken from the `scheme` field (`"amqp"` by default).
+            ///
+            /// ```rust,ignore
+            /// let addr: SocketAddr = "10.0.0.5:5671".parse().unwrap();
+            /// let connection = Connection::builder()
+            ///     .container_id("connection-1")
+            ///     .scheme("amqps")
+            ///     .open_with_addr(addr, "broker.example.com")
+            ///     .await.unwrap();
+            /// ```
+            pub async fn open_with_addr(
+                mut self,
+                addr: SocketAddr,
+                hostname: &'a str,
+            ) -> Result<ConnectionHandle<()>, OpenError> {
+                self.use_hostname_for_addr(hostname);
+                let stream = TcpStream::connect(addr).await?; // std::io::Error
                 self.open_with_stream(stream).await
             }
 
@@ -1360,15 +1969,22 @@ cfg_not_wasm32! {
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Send + Unpin + 'static,
     {
+        let container_id = engine.local_open().container_id.clone();
+        let remote_open = engine.remote_open().cloned();
         let (handle, outcome) = engine.spawn();
 
         let connection_handle = ConnectionHandle {
+            container_id,
+            remote_open,
             is_closed: false,
             control: control_tx,
             handle,
             outcome,
             outgoing: outgoing_tx, // session_control: session_control_tx
             session_listener: (),
+            link_defaults: LinkDefaults::default(),
+            #[cfg(feature = "acceptor")]
+            sasl_identity: None,
         };
 
         Ok(connection_handle)
@@ -1385,15 +2001,22 @@ cfg_wasm32! {
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Unpin + 'static,
     {
+        let container_id = engine.local_open().container_id.clone();
+        let remote_open = engine.remote_open().cloned();
         let (handle, outcome) = engine.spawn_on_local_set(local_set);
 
         let connection_handle = ConnectionHandle {
+            container_id,
+            remote_open,
             is_closed: false,
             control: control_tx,
             handle,
             outcome,
             outgoing: outgoing_tx, // session_control: session_control_tx
             session_listener: (),
+            link_defaults: LinkDefaults::default(),
+            #[cfg(feature = "acceptor")]
+            sasl_identity: None,
         };
 
         Ok(connection_handle)
@@ -1407,15 +2030,22 @@ cfg_wasm32! {
     where
         Io: AsyncRead + AsyncWrite + std::fmt::Debug + Unpin + 'static,
     {
+        let container_id = engine.local_open().container_id.clone();
+        let remote_open = engine.remote_open().cloned();
         let (handle, outcome) = engine.spawn_local();
 
         let connection_handle = ConnectionHandle {
+            container_id,
+            remote_open,
             is_closed: false,
             control: control_tx,
             handle,
             outcome,
             outgoing: outgoing_tx, // session_control: session_control_tx
             session_listener: (),
+            link_defaults: LinkDefaults::default(),
+            #[cfg(feature = "acceptor")]
+            sasl_identity: None,
         };
 
         Ok(connection_handle)
@@ -1424,12 +2054,233 @@ cfg_wasm32! {
 
 #[cfg(test)]
 mod tests {
+    use fe2o3_amqp_types::{definitions::IetfLanguageTag, performatives::Open};
     use url::Url;
 
+    use crate::{connection::OpenError, Connection};
+
     #[test]
     fn test_url_name_resolution() {
         let url: Url = "amqp://example.net/".try_into().unwrap();
         assert_eq!(url.port(), None);
         let _addrs = url.socket_addrs(|| Some(5672)).unwrap();
     }
+
+    #[test]
+    fn test_hostname_falls_back_to_domain() {
+        let open = Open::from(Connection::builder().domain("example.net"));
+        assert_eq!(open.hostname.as_deref(), Some("example.net"));
+
+        let open = Open::from(
+            Connection::builder()
+                .domain("example.net")
+                .hostname("vhost.example.net"),
+        );
+        assert_eq!(open.hostname.as_deref(), Some("vhost.example.net"));
+
+        let open = Open::from(Connection::builder());
+        assert_eq!(open.hostname, None);
+    }
+
+    #[test]
+    fn test_locales_in_open() {
+        let open = Open::from(Connection::builder());
+        assert!(open.outgoing_locales.is_none());
+        assert!(open.incoming_locales.is_none());
+
+        let open = Open::from(
+            Connection::builder()
+                .set_outgoing_locales(vec!["en-US".into()])
+                .add_incoming_locales("de-DE")
+                .add_incoming_locales("en-US"),
+        );
+        let outgoing: Vec<IetfLanguageTag> = open.outgoing_locales.unwrap().into();
+        assert_eq!(outgoing, vec![IetfLanguageTag::from("en-US")]);
+        let incoming: Vec<IetfLanguageTag> = open.incoming_locales.unwrap().into();
+        assert_eq!(
+            incoming,
+            vec![
+                IetfLanguageTag::from("de-DE"),
+                IetfLanguageTag::from("en-US")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_use_hostname_for_addr() {
+        let mut builder = Connection::builder();
+        builder.use_hostname_for_addr("broker.example.net");
+        assert_eq!(builder.sasl_hostname, Some("broker.example.net"));
+        assert_eq!(builder.domain, Some("broker.example.net"));
+        let open = Open::from(builder);
+        assert_eq!(open.hostname.as_deref(), Some("broker.example.net"));
+
+        // Explicitly set hostnames are kept
+        let mut builder = Connection::builder()
+            .hostname("vhost")
+            .sasl_hostname("sasl-host");
+        builder.use_hostname_for_addr("broker.example.net");
+        assert_eq!(builder.hostname, Some("vhost"));
+        assert_eq!(builder.sasl_hostname, Some("sasl-host"));
+        assert_eq!(builder.domain, Some("broker.example.net"));
+    }
+
+    #[test]
+    fn test_sasl_init_hostname() {
+        let builder = Connection::builder().container_id("test");
+        assert_eq!(builder.sasl_init_hostname(), None);
+
+        let builder = builder.domain("ws.example.net");
+        assert_eq!(builder.sasl_init_hostname(), Some("ws.example.net"));
+
+        let builder = builder.hostname("ns.servicebus.windows.net");
+        assert_eq!(
+            builder.sasl_init_hostname(),
+            Some("ns.servicebus.windows.net")
+        );
+
+        let builder = builder.sasl_hostname("sasl.example.net");
+        assert_eq!(builder.sasl_init_hostname(), Some("sasl.example.net"));
+        let open = Open::from(builder);
+        assert_eq!(open.hostname.as_deref(), Some("ns.servicebus.windows.net"));
+    }
+
+    #[tokio::test]
+    async fn test_idle_time_out() {
+        let open = Open::from(Connection::builder().idle_time_out(10_000u32));
+        assert_eq!(open.idle_time_out, Some(5_000));
+
+        let open = Open::from(
+            Connection::builder()
+                .idle_time_out(10_000u32)
+                .idle_time_out(None),
+        );
+        assert_eq!(open.idle_time_out, None);
+
+        let (_client_io, server_io) = tokio::io::duplex(1024);
+        let result = Connection::builder()
+            .idle_time_out(0u32)
+            .open_with_stream(server_io)
+            .await;
+        assert!(matches!(result, Err(OpenError::InvalidIdleTimeOut)));
+    }
+
+    #[tokio::test]
+    async fn test_authentication_timeout() {
+        use std::time::Duration;
+
+        use crate::sasl_profile::SaslProfile;
+
+        // The remote peer never responds to the SASL protocol header
+        let (client_io, _server_io) = tokio::io::duplex(1024);
+        let result = Connection::builder()
+            .container_id("test-connection")
+            .sasl_profile(SaslProfile::Anonymous)
+            .authentication_timeout(Duration::from_millis(50))
+            .open_with_stream(client_io)
+            .await;
+        assert!(matches!(result, Err(OpenError::AuthenticationTimeout)));
+    }
+
+    #[test]
+    fn test_generated_container_id() {
+        let open1 = Open::from(Connection::builder());
+        let open2 = Open::from(Connection::builder());
+        assert!(!open1.container_id.is_empty());
+        assert_ne!(open1.container_id, open2.container_id);
+
+        let open = Open::from(Connection::builder().container_id("connection-1"));
+        assert_eq!(open.container_id, "connection-1");
+    }
+
+    #[test]
+    fn test_max_sessions_is_enforced_before_channel_max() {
+        use fe2o3_amqp_types::states::ConnectionState;
+        use tokio::sync::mpsc;
+
+        use crate::{connection::AllocSessionError, endpoint::Connection as _};
+
+        let builder = Connection::builder()
+            .container_id("connection-1")
+            .max_sessions(1);
+        let max_sessions = builder.max_sessions;
+        let mut connection = Connection::new(ConnectionState::Opened, Open::from(builder));
+        connection.max_sessions = max_sessions;
+
+        let (tx, _rx) = mpsc::channel(1);
+        let channel = connection.allocate_session(tx.clone()).unwrap();
+        assert!(matches!(
+            connection.allocate_session(tx.clone()),
+            Err(AllocSessionError::SessionLimitReached)
+        ));
+
+        connection.deallocate_session(channel);
+        assert!(connection.allocate_session(tx).is_ok());
+    }
+
+    #[test]
+    fn test_split_into_framed_with_buffer_sizes() {
+        let (stream, _) = tokio::io::duplex(64);
+        let (framed_write, framed_read) =
+            super::split_into_framed(stream, Some(64 * 1024), Some(32 * 1024));
+        assert!(framed_read.read_buffer().capacity() >= 64 * 1024);
+        assert_eq!(framed_write.backpressure_boundary(), 32 * 1024);
+    }
+
+    #[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+    #[test]
+    fn test_alpn_protocols_with_rustls() {
+        use std::sync::Arc;
+
+        use librustls::{ClientConfig, RootCertStore};
+
+        let connector = Connection::builder()
+            .container_id("connection-1")
+            .default_rustls_connector();
+        assert!(connector.config().alpn_protocols.is_empty());
+
+        let connector = Connection::builder()
+            .container_id("connection-1")
+            .alpn_protocols(vec![b"amqp".to_vec()])
+            .default_rustls_connector();
+        assert_eq!(connector.config().alpn_protocols, vec![b"amqp".to_vec()]);
+
+        // A user-supplied connector is left untouched
+        let config = ClientConfig::builder()
+            .with_root_certificates(RootCertStore::empty())
+            .with_no_client_auth();
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+        let builder = Connection::builder()
+            .container_id("connection-1")
+            .alpn_protocols(vec![b"amqp".to_vec()])
+            .tls_connector(connector.clone());
+        assert!(Arc::ptr_eq(
+            builder.tls_connector.config(),
+            connector.config()
+        ));
+        assert!(builder.tls_connector.config().alpn_protocols.is_empty());
+    }
+
+    #[cfg(all(
+        feature = "native-tls",
+        not(feature = "rustls"),
+        not(target_arch = "wasm32")
+    ))]
+    #[test]
+    fn test_alpn_protocols_with_native_tls() {
+        let builder = Connection::builder().container_id("connection-1");
+        assert!(builder.native_tls_alpn_protocols().unwrap().is_empty());
+
+        let builder = builder.alpn_protocols(vec![b"amqp".to_vec(), b"amqp/1.0".to_vec()]);
+        assert_eq!(
+            builder.native_tls_alpn_protocols().unwrap(),
+            vec!["amqp", "amqp/1.0"]
+        );
+
+        let builder = builder.alpn_protocols(vec![vec![0xff]]);
+        assert!(matches!(
+            builder.native_tls_alpn_protocols(),
+            Err(OpenError::Io(_))
+        ));
+    }
 }
@@ -5,7 +5,7 @@ use std::io;
 use std::time::Duration;
 
 use fe2o3_amqp_types::definitions::{self, AmqpError};
-use fe2o3_amqp_types::performatives::Close;
+use fe2o3_amqp_types::performatives::{Close, Open};
 use futures_util::{SinkExt, StreamExt};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::mpsc::Receiver;
@@ -17,19 +17,48 @@ use crate::endpoint::{IncomingChannel, OutgoingChannel};
 use crate::frames::amqp::{self, Frame, FrameBody};
 use crate::session::frame::{SessionFrame, SessionFrameBody};
 use crate::transport::Transport;
-use crate::util::Running;
+use crate::util::{FlushTimer, Running};
 use crate::{endpoint, transport, SendBound};
 
-use super::{heartbeat::HeartBeat, ConnectionState};
+use super::heartbeat::HeartBeat;
+#[cfg(feature = "test-util")]
+use super::interceptor::{Interception, OutgoingInterceptor};
+use super::ConnectionState;
 use super::{AllocSessionError, ConnectionInnerError, ConnectionStateError, Error, OpenError};
 
-#[derive(Debug)]
+/// Longest time that batchable frames are held back while the sessions keep queueing more frames
+/// when writes are coalesced
+const COALESCED_WRITES_FLUSH_DELAY: Duration = Duration::from_millis(1);
+
 pub(crate) struct ConnectionEngine<Io, C> {
     transport: Transport<Io, amqp::Frame>,
     connection: C,
     control: Receiver<ConnectionControl>,
     outgoing_session_frames: Receiver<SessionFrame>,
     heartbeat: HeartBeat,
+    /// Pings that are waiting for any incoming frame
+    pending_pings: Vec<oneshot::Sender<()>>,
+    /// Whether consecutive batchable frames are written to the IO together
+    coalesce_writes: bool,
+    flush_timer: FlushTimer,
+    #[cfg(feature = "test-util")]
+    outgoing_interceptor: Option<OutgoingInterceptor>,
+}
+
+// Implemented manually because the outgoing interceptor is not `Debug`
+impl<Io: std::fmt::Debug, C: std::fmt::Debug> std::fmt::Debug for ConnectionEngine<Io, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionEngine")
+            .field("transport", &self.transport)
+            .field("connection", &self.connection)
+            .field("control", &self.control)
+            .field("outgoing_session_frames", &self.outgoing_session_frames)
+            .field("heartbeat", &self.heartbeat)
+            .field("pending_pings", &self.pending_pings)
+            .field("coalesce_writes", &self.coalesce_writes)
+            .field("flush_timer", &self.flush_timer)
+            .finish()
+    }
 }
 
 cfg_not_wasm32! {
@@ -178,10 +207,18 @@ where
         let channel = endpoint::IncomingChannel(channel);
         let remote_open = match body {
             FrameBody::Open(open) => open,
-            FrameBody::Close(close) => match close.error {
-                Some(error) => return Err(OpenError::RemoteClosedWithError(error)),
-                None => return Err(OpenError::RemoteClosed),
-            },
+            FrameBody::Close(close) => {
+                if self.connection.local_open().hostname.is_none() {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Remote peer closed the connection in response to an Open without hostname. Some brokers require the hostname for virtual host routing");
+                    #[cfg(feature = "log")]
+                    log::warn!("Remote peer closed the connection in response to an Open without hostname. Some brokers require the hostname for virtual host routing");
+                }
+                match close.error {
+                    Some(error) => return Err(OpenError::RemoteClosedWithError(error)),
+                    None => return Err(OpenError::RemoteClosed),
+                }
+            }
             _ => return Err(OpenError::IllegalState),
         };
 
@@ -222,6 +259,11 @@ where
             control,
             outgoing_session_frames,
             heartbeat: HeartBeat::never(),
+            pending_pings: Vec::new(),
+            coalesce_writes: false,
+            flush_timer: FlushTimer::new(COALESCED_WRITES_FLUSH_DELAY),
+            #[cfg(feature = "test-util")]
+            outgoing_interceptor: None,
         };
 
         match engine.open_inner().await {
@@ -254,6 +296,74 @@ where
         }
     }
 
+    /// The local Open frame
+    pub(crate) fn local_open(&self) -> &Open {
+        self.connection.local_open()
+    }
+
+    /// The Open frame received from the remote peer
+    pub(crate) fn remote_open(&self) -> Option<&Open> {
+        self.connection.remote_open()
+    }
+
+    /// Set whether consecutive batchable frames are written to the IO together
+    pub(crate) fn with_coalesced_writes(mut self, coalesce_writes: bool) -> Self {
+        self.coalesce_writes = coalesce_writes;
+        self
+    }
+
+    /// Set the interceptor that is invoked on every outgoing frame
+    #[cfg(feature = "test-util")]
+    pub(crate) fn with_outgoing_interceptor(
+        mut self,
+        outgoing_interceptor: Option<OutgoingInterceptor>,
+    ) -> Self {
+        self.outgoing_interceptor = outgoing_interceptor;
+        self
+    }
+
+    #[inline]
+    async fn send_frame(&mut self, frame: Frame) -> Result<(), ConnectionInnerError> {
+        #[cfg(feature = "test-util")]
+        let frame = match &self.outgoing_interceptor {
+            Some(interceptor) => match interceptor(frame) {
+                Interception::Forward(frame) => frame,
+                Interception::Delay(duration, frame) => {
+                    crate::util::sleep(duration).await;
+                    frame
+                }
+                Interception::Drop => return Ok(()),
+            },
+            None => frame,
+        };
+
+        if self.coalesce_writes && frame.is_batchable() {
+            // The frame is held back while more frames are queued by the sessions until the
+            // buffered frames fill up a frame of the negotiated max frame size, a frame that is
+            // not batchable is sent or the flush timer expires
+            self.transport.feed(frame).await?;
+            if self.outgoing_session_frames.is_empty()
+                || self.transport.write_buffer_len() >= self.transport.encoder_max_frame_size()
+            {
+                self.transport.flush().await?;
+                self.flush_timer.stop();
+            } else {
+                self.flush_timer.start();
+            }
+        } else {
+            // This also flushes the frames that are held back
+            self.transport.send(frame).await?;
+            self.flush_timer.stop();
+        }
+        Ok(())
+    }
+
+    #[inline]
+    async fn on_flush_timer(&mut self) -> Result<Running, ConnectionInnerError> {
+        self.transport.flush().await?;
+        Ok(Running::Continue)
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     async fn forward_to_session(
         &mut self,
@@ -289,11 +399,11 @@ where
                 // Set heartbeat here because in pipelined-open, the Open frame
                 // may be recved after mux loop is started
                 match &remote_idle_timeout {
+                    Some(0) | None => self.heartbeat = HeartBeat::never(),
                     Some(millis) => {
                         let period = Duration::from_millis(*millis as u64);
                         self.heartbeat = HeartBeat::new(period);
                     }
-                    None => self.heartbeat = HeartBeat::never(),
                 };
             }
             FrameBody::Begin(begin) => {
@@ -399,6 +509,21 @@ where
                     log::error!("{:?}", error);
                 }
             }
+            ConnectionControl::Ping {
+                wait_for_frame,
+                responder,
+            } => {
+                // Dropping the responder will notify the caller that the ping cannot be sent
+                if let ConnectionState::Opened = self.connection.local_state() {
+                    self.send_frame(Frame::empty()).await?;
+                    match wait_for_frame {
+                        true => self.pending_pings.push(responder),
+                        false => {
+                            let _ = responder.send(());
+                        }
+                    }
+                }
+            }
         }
 
         match self.connection.local_state() {
@@ -445,7 +570,7 @@ where
         tracing::trace!(channel = frame.channel, frame = ?frame.body);
         #[cfg(feature = "log")]
         log::trace!("SEND channel = {}, frame = {:?}", frame.channel, frame.body);
-        self.transport.send(frame).await?;
+        self.send_frame(frame).await?;
         Ok(Running::Continue)
     }
 
@@ -458,7 +583,7 @@ where
         }
 
         let frame = Frame::empty();
-        self.transport.send(frame).await?;
+        self.send_frame(frame).await?;
         Ok(Running::Continue)
     }
 
@@ -468,6 +593,20 @@ where
         error: &ConnectionInnerError,
     ) -> Result<Running, ConnectionInnerError> {
         match error {
+            ConnectionInnerError::TransportError(transport::Error::IdleTimeoutElapsed) => {
+                // The remote peer is most likely gone, so the close frame is sent on a best
+                // effort basis without waiting for the remote close
+                let error = definitions::Error::new(
+                    AmqpError::ResourceLimitExceeded,
+                    Some(String::from("local-idle-timeout expired")),
+                    None,
+                );
+                let _ = self
+                    .connection
+                    .send_close(&mut self.transport, Some(error))
+                    .await;
+                Ok(Running::Stop)
+            }
             ConnectionInnerError::TransportError(_) => Ok(Running::Stop),
             ConnectionInnerError::IllegalState => {
                 let error = definitions::Error::new(AmqpError::IllegalState, None, None);
@@ -497,11 +636,17 @@ where
         loop {
             let result = tokio::select! {
                 _ = self.heartbeat.next() => self.on_heartbeat().await,
+                _ = &mut self.flush_timer => self.on_flush_timer().await,
                 incoming = self.transport.next() => {
                     let result = match incoming {
                         Some(incoming) => {
                             match incoming {
-                                Ok(frame) => self.on_incoming(frame).await,
+                                Ok(frame) => {
+                                    for responder in self.pending_pings.drain(..) {
+                                        let _ = responder.send(());
+                                    }
+                                    self.on_incoming(frame).await
+                                },
                                 Err(err) => Err(err.into()),
                             }
                         },
@@ -6,7 +6,11 @@ use bytes::Bytes;
 use fe2o3_amqp_types::{definitions, primitives::Binary, sasl::SaslCode};
 use tokio::{sync::mpsc, task::JoinError};
 
-use crate::transport::{self, error::NegotiationError};
+use crate::transport::{
+    self,
+    error::{io_error_kind, NegotiationError},
+    ErrorKind,
+};
 
 cfg_scram! {
     use crate::auth::error::ScramErrorKind;
@@ -27,6 +31,14 @@ pub enum OpenError {
     #[error("Invalid domain")]
     InvalidDomain,
 
+    /// TLS negotiation failed
+    ///
+    /// This carries the error returned by the TLS implementation (eg. an alert sent by the remote
+    /// peer, an unsupported protocol version or a certificate that doesn't match the domain), or
+    /// an error describing that the remote peer closed the stream during the negotiation.
+    #[error("TLS negotiation failed: {0}")]
+    Tls(#[source] Box<dyn std::error::Error + Send + Sync>),
+
     /// Missing client config for TLS connection
     #[error("TLS connector is not found")]
     TlsConnectorNotFound,
@@ -77,6 +89,49 @@ pub enum OpenError {
     /// Remote peer closed connection with error during openning process
     #[error("Remote peer closed connection with error {}", .0)]
     RemoteClosedWithError(definitions::Error),
+
+    /// The idle time-out is set to zero
+    #[error("Idle time-out must be greater than zero")]
+    InvalidIdleTimeOut,
+
+    /// SASL negotiation did not complete within the authentication timeout
+    #[error("Authentication timed out")]
+    AuthenticationTimeout,
+
+    /// The maximum number of connections set on the acceptor is reached
+    #[error("Connection limit reached")]
+    ConnectionLimitReached,
+}
+
+impl OpenError {
+    /// Classifies the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            OpenError::Io(err) => io_error_kind(err),
+            OpenError::TransportError(err) => err.kind(),
+            OpenError::Tls(_) => ErrorKind::Tls,
+            OpenError::ProtocolHeaderMismatch(_) | OpenError::DecodeError(_) => ErrorKind::Protocol,
+            OpenError::AuthenticationTimeout => ErrorKind::TimedOut,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Whether the connection to the remote peer is lost while opening the connection
+    ///
+    /// See [`transport::Error::is_connection_lost`]
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(
+            self.kind(),
+            ErrorKind::ConnectionReset | ErrorKind::BrokenPipe | ErrorKind::UnexpectedEof
+        )
+    }
+
+    /// Whether opening a new connection may succeed
+    ///
+    /// See [`transport::Error::is_retryable`]
+    pub fn is_retryable(&self) -> bool {
+        self.is_connection_lost() || matches!(self.kind(), ErrorKind::TimedOut)
+    }
 }
 
 impl From<NegotiationError> for OpenError {
@@ -85,6 +140,7 @@ impl From<NegotiationError> for OpenError {
             NegotiationError::Io(err) => Self::Io(err),
             NegotiationError::ProtocolHeaderMismatch(buf) => Self::ProtocolHeaderMismatch(buf),
             NegotiationError::InvalidDomain => Self::InvalidDomain,
+            NegotiationError::Tls(err) => Self::Tls(err),
             NegotiationError::SaslError {
                 code,
                 additional_data,
@@ -225,6 +281,38 @@ pub enum Error {
     JoinError(#[from] JoinError),
 }
 
+impl Error {
+    /// Classifies the error
+    ///
+    /// Errors other than [`Error::TransportError`] are classified as [`ErrorKind::Other`]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::TransportError(err) => err.kind(),
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Whether the connection to the remote peer is lost
+    ///
+    /// See [`transport::Error::is_connection_lost`]
+    pub fn is_connection_lost(&self) -> bool {
+        match self {
+            Error::TransportError(err) => err.is_connection_lost(),
+            _ => false,
+        }
+    }
+
+    /// Whether reconnecting may succeed
+    ///
+    /// See [`transport::Error::is_retryable`]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::TransportError(err) => err.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
 impl From<ConnectionInnerError> for Error {
     fn from(error: ConnectionInnerError) -> Self {
         match error {
@@ -257,6 +345,9 @@ pub(crate) enum AllocSessionError {
 
     #[error("Reached connection channel max")]
     ChannelMaxReached,
+
+    #[error("Reached connection session limit")]
+    SessionLimitReached,
 }
 
 pub(crate) enum DeallcoSessionError {
@@ -275,3 +366,60 @@ pub enum TryCloseError {
     #[error("The connection has not received a close frame from the remote peer")]
     RemoteCloseNotReceived,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::transport::{self, ErrorKind};
+
+    use super::{Error, OpenError};
+
+    #[test]
+    fn test_open_error_kind() {
+        let error = OpenError::Io(io::Error::from(io::ErrorKind::ConnectionReset));
+        assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+        assert!(error.is_connection_lost());
+        assert!(error.is_retryable());
+
+        let error = OpenError::Io(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        assert!(error.is_connection_lost());
+
+        let error = OpenError::Io(io::Error::from(io::ErrorKind::TimedOut));
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+        assert!(!error.is_connection_lost());
+        assert!(error.is_retryable());
+
+        let error = OpenError::Io(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert!(!error.is_retryable());
+
+        let error = OpenError::TransportError(transport::Error::IdleTimeoutElapsed);
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+        assert!(error.is_retryable());
+
+        let error = OpenError::InvalidScheme;
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn test_connection_error_kind() {
+        let io_error = |kind| Error::TransportError(transport::Error::Io(io::Error::from(kind)));
+
+        let error = io_error(io::ErrorKind::BrokenPipe);
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+        assert!(error.is_connection_lost());
+        assert!(error.is_retryable());
+
+        let error = Error::TransportError(transport::Error::FramingError);
+        assert_eq!(error.kind(), ErrorKind::Protocol);
+        assert!(!error.is_retryable());
+
+        let error = Error::RemoteClosed;
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert!(!error.is_connection_lost());
+        assert!(!error.is_retryable());
+    }
+}
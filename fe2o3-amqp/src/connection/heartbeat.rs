@@ -42,6 +42,12 @@ cfg_wasm32! {
     use fluvio_wasm_timer::{Delay};
     use futures_util::{Future, ready};
 
+    /// Browsers may delay timers by a second or more (eg. in a background tab), so a heartbeat
+    /// scheduled right at the remote idle time-out could arrive too late. The heartbeat is thus
+    /// sent at this fraction of the remote idle time-out on wasm32 targets
+    const WASM32_HEARTBEAT_DIVISOR: u32 = 2;
+
+    /// Heartbeat backed by a `setTimeout` based timer that works on `wasm32-unknown-unknown`
     #[derive(Debug)]
     struct InnerStream {
         delay: Delay,
@@ -50,6 +56,7 @@ cfg_wasm32! {
 
     impl InnerStream {
         fn new(period: Duration) -> Self {
+            let period = period / WASM32_HEARTBEAT_DIVISOR;
             let delay = Delay::new(period);
             Self { delay, period }
         }
@@ -73,6 +80,10 @@ cfg_wasm32! {
 pin_project! {
     /// A wrapper over an `Option<IntervalStream>` which will never tick ready if the underlying
     /// `Interval` is `None`
+    ///
+    /// On wasm32 targets, the interval is driven by `fluvio_wasm_timer` instead of the tokio timer,
+    /// so empty frames are also sent on browser connections. The period is halved on wasm32
+    /// targets to tolerate timer throttling by the browser.
     #[derive(Debug)]
     pub struct HeartBeat {
         #[pin]
@@ -0,0 +1,161 @@
+//! Hook to observe and modify outgoing frames before they are written to the transport
+//!
+//! This is intended for fault injection in tests (eg. dropping a `Flow` or delaying a
+//! `Disposition`) and is only available with the `"test-util"` feature.
+
+use std::{sync::Arc, time::Duration};
+
+use crate::frames::amqp::Frame;
+
+/// What the connection engine should do with an outgoing frame after it has been passed
+/// through an [`OutgoingInterceptor`]
+#[derive(Debug)]
+pub enum Interception {
+    /// Send the (possibly modified) frame immediately
+    Forward(Frame),
+
+    /// Send the (possibly modified) frame after the given delay
+    ///
+    /// The connection engine will not process any other event while waiting
+    Delay(Duration, Frame),
+
+    /// Drop the frame without sending it
+    Drop,
+}
+
+/// A closure that is invoked on every outgoing frame sent by the connection engine
+///
+/// This includes all frames sent by the sessions and links as well as the empty heartbeat
+/// frames. The `Open` and `Close` frames are not intercepted.
+pub type OutgoingInterceptor = Arc<dyn Fn(Frame) -> Interception + Send + Sync>;
+
+#[cfg(all(test, feature = "acceptor"))]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use crate::{
+        acceptor::{
+            ConnectionAcceptor, LinkAcceptor, LinkEndpoint, ListenerConnectionHandle,
+            ListenerSessionHandle, SessionAcceptor,
+        },
+        connection::ConnectionHandle,
+        frames::amqp::{Frame, FrameBody},
+        link::receiver::CreditMode,
+        session::SessionHandle,
+        Connection, Receiver, Sender, Session,
+    };
+
+    use super::Interception;
+
+    type Endpoints = (
+        ConnectionHandle<()>,
+        SessionHandle<()>,
+        Receiver,
+        ListenerConnectionHandle,
+        ListenerSessionHandle,
+        Sender,
+    );
+
+    /// Attaches a receiver with manual credit on a connection whose outgoing frames are passed
+    /// through `interceptor`, and the sender on the listener side of the in-memory stream
+    async fn attach<F>(interceptor: F) -> Endpoints
+    where
+        F: Fn(Frame) -> Interception + Send + Sync + 'static,
+    {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let acceptor = ConnectionAcceptor::new("test-listener");
+        let client = Connection::builder()
+            .container_id("test-connection")
+            .outgoing_interceptor(interceptor)
+            .open_with_stream(client_io);
+        let (connection, listener) = tokio::join!(client, acceptor.accept(server_io));
+        let (mut connection, mut listener) = (connection.unwrap(), listener.unwrap());
+
+        let session_acceptor = SessionAcceptor::new();
+        let (session, listener_session) = tokio::join!(
+            Session::begin(&mut connection),
+            session_acceptor.accept(&mut listener)
+        );
+        let (mut session, mut listener_session) = (session.unwrap(), listener_session.unwrap());
+
+        let receiver = Receiver::builder()
+            .name("test-receiver")
+            .source("q1")
+            .credit_mode(CreditMode::Manual)
+            .attach(&mut session);
+        let link_acceptor = LinkAcceptor::new();
+        let (receiver, sender) =
+            tokio::join!(receiver, link_acceptor.accept(&mut listener_session));
+        let sender = match sender.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        (
+            connection,
+            session,
+            receiver.unwrap(),
+            listener,
+            listener_session,
+            sender,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_forward_modified_frame() {
+        let (_connection, _session, mut receiver, _listener, _listener_session, mut sender) =
+            attach(|mut frame| {
+                if let FrameBody::Flow(flow) = &mut frame.body {
+                    flow.link_credit = Some(1);
+                }
+                Interception::Forward(frame)
+            })
+            .await;
+
+        receiver.set_credit(10).await.unwrap();
+        let (outcome, _) = tokio::join!(sender.send("hello"), async {
+            let delivery = receiver.recv::<String>().await.unwrap();
+            receiver.accept(&delivery).await.unwrap();
+        });
+        assert!(outcome.is_ok());
+
+        // Only the credit in the modified flow is granted to the sender
+        assert_eq!(sender.credit(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_delay_frame() {
+        let delay = Duration::from_millis(100);
+        let (_connection, _session, mut receiver, _listener, _listener_session, mut sender) =
+            attach(move |frame| match frame.body {
+                FrameBody::Flow(_) => Interception::Delay(delay, frame),
+                _ => Interception::Forward(frame),
+            })
+            .await;
+
+        let start = Instant::now();
+        receiver.set_credit(10).await.unwrap();
+        let (outcome, _) = tokio::join!(sender.send("hello"), async {
+            let delivery = receiver.recv::<String>().await.unwrap();
+            receiver.accept(&delivery).await.unwrap();
+        });
+        assert!(outcome.is_ok());
+        assert!(start.elapsed() >= delay);
+    }
+
+    #[tokio::test]
+    async fn test_drop_frame() {
+        let (_connection, _session, mut receiver, _listener, _listener_session, sender) =
+            attach(|frame| match frame.body {
+                FrameBody::Flow(_) => Interception::Drop,
+                _ => Interception::Forward(frame),
+            })
+            .await;
+
+        // The peer never receives the flow and thus the sender never gets any credit
+        receiver.set_credit(10).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(sender.credit(), 0);
+    }
+}
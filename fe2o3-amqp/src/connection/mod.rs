@@ -3,7 +3,7 @@
 use std::{cmp::min, collections::HashMap, sync::Arc};
 
 use fe2o3_amqp_types::{
-    definitions::{self},
+    definitions::{self, Fields},
     performatives::{Begin, Close, End, Open},
     states::ConnectionState,
 };
@@ -26,6 +26,7 @@ use crate::{
     control::ConnectionControl,
     endpoint::{self, IncomingChannel, OutgoingChannel},
     frames::amqp::{Frame, FrameBody},
+    link::builder::LinkDefaults,
     session::frame::{SessionFrame, SessionFrameBody, SessionIncomingItem},
     session::Session,
     SendBound,
@@ -40,6 +41,10 @@ mod error;
 pub mod heartbeat;
 pub use error::*;
 
+cfg_test_util! {
+    pub mod interceptor;
+}
+
 /// Default max-frame-size.
 ///
 /// Please note that this is different from `MaxFrameSize::default()`.
@@ -62,6 +67,9 @@ type SessionRelay = Arc<Sender<SessionIncomingItem>>;
 /// - `R`: The type of the session listener. This will be `()` on the client side.
 #[allow(dead_code)]
 pub struct ConnectionHandle<R> {
+    pub(crate) container_id: String,
+    pub(crate) remote_open: Option<Open>,
+
     /// Only change this value in `on_close` method
     pub(crate) is_closed: bool,
     pub(crate) control: Sender<ConnectionControl>,
@@ -71,6 +79,13 @@ pub struct ConnectionHandle<R> {
     // outgoing channel for session
     pub(crate) outgoing: Sender<SessionFrame>,
     pub(crate) session_listener: R,
+
+    // Default settings for the links attached to the sessions
+    pub(crate) link_defaults: LinkDefaults,
+
+    // Identity of the client authenticated by the SASL negotiation on the listener side
+    #[cfg(feature = "acceptor")]
+    pub(crate) sasl_identity: Option<crate::acceptor::SaslIdentity>,
 }
 
 impl<R> std::fmt::Debug for ConnectionHandle<R> {
@@ -86,6 +101,31 @@ impl<R> Drop for ConnectionHandle<R> {
 }
 
 impl<R> ConnectionHandle<R> {
+    /// The id of the local container
+    ///
+    /// This is either the id supplied to the builder or the id that was generated if none was
+    /// supplied
+    pub fn container_id(&self) -> &str {
+        &self.container_id
+    }
+
+    /// The `properties` field of the Open frame sent by the remote peer
+    ///
+    /// Brokers may put product and version information here, which can be used to identify the
+    /// broker the connection is talking to
+    pub fn remote_properties(&self) -> Option<&Fields> {
+        self.remote_open
+            .as_ref()
+            .and_then(|open| open.properties.as_ref())
+    }
+
+    /// The id of the remote container as sent in the Open frame by the remote peer
+    pub fn remote_container_id(&self) -> Option<&str> {
+        self.remote_open
+            .as_ref()
+            .map(|open| open.container_id.as_str())
+    }
+
     /// Checks if the underlying event loop has stopped
     pub fn is_closed(&self) -> bool {
         match self.is_closed {
@@ -186,6 +226,38 @@ impl<R> ConnectionHandle<R> {
         }
     }
 
+    /// Sends a single empty frame (heartbeat) to the remote peer
+    ///
+    /// This returns once the frame is sent and does not wait for any response. An
+    /// `Error::IllegalState` will be returned if the connection is not open.
+    pub async fn ping(&self) -> Result<(), Error> {
+        self.send_ping(false).await
+    }
+
+    /// Sends a single empty frame (heartbeat) to the remote peer and waits until any frame is
+    /// received from the remote peer afterwards
+    ///
+    /// The remote peer is not required to respond to an empty frame, so this may wait until the
+    /// remote peer sends its next frame (eg. its own heartbeat, which is sent at least as often
+    /// as the local idle-time-out). Consider wrapping this in a timeout. An
+    /// `Error::IllegalState` will be returned if the connection is not open or is closed while
+    /// waiting.
+    pub async fn ping_and_wait(&self) -> Result<(), Error> {
+        self.send_ping(true).await
+    }
+
+    async fn send_ping(&self, wait_for_frame: bool) -> Result<(), Error> {
+        let (responder, resp_rx) = oneshot::channel();
+        self.control
+            .send(ConnectionControl::Ping {
+                wait_for_frame,
+                responder,
+            })
+            .await
+            .map_err(|_| Error::IllegalState)?; // Connection must have stopped
+        resp_rx.await.map_err(|_| Error::IllegalState)
+    }
+
     /// Allocte (channel, session_id) for a new session
     pub(crate) async fn allocate_session(
         &mut self,
@@ -413,12 +485,15 @@ pub struct Connection {
 
     // mutually agreed channel max
     pub(crate) agreed_channel_max: u16,
+
+    // local limit on the number of sessions
+    pub(crate) max_sessions: Option<usize>,
 }
 
 /* ------------------------------- Public API ------------------------------- */
 impl Connection {
     /// Creates a Builder for [`Connection`]
-    pub fn builder<'a>() -> builder::Builder<'a, mode::ConnectorNoId, ()> {
+    pub fn builder<'a>() -> builder::Builder<'a, mode::ConnectorWithId, ()> {
         builder::Builder::new()
     }
 
@@ -526,6 +601,7 @@ impl Connection {
 
             remote_open: None,
             agreed_channel_max,
+            max_sessions: None,
         }
     }
 }
@@ -546,6 +622,10 @@ impl endpoint::Connection for Connection {
         &self.local_open
     }
 
+    fn remote_open(&self) -> Option<&Open> {
+        self.remote_open.as_ref()
+    }
+
     fn allocate_session(
         &mut self,
         tx: Sender<SessionIncomingItem>,
@@ -562,6 +642,12 @@ impl endpoint::Connection for Connection {
             _ => {}
         };
 
+        if let Some(max_sessions) = self.max_sessions {
+            if self.session_by_outgoing_channel.len() >= max_sessions {
+                return Err(AllocSessionError::SessionLimitReached);
+            }
+        }
+
         // get new entry index
         let entry = self.session_by_outgoing_channel.vacant_entry();
         let outgoing_channel = entry.key();
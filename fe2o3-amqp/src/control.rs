@@ -1,7 +1,8 @@
 //! Controls for Connection, Session, and Link
 
 use fe2o3_amqp_types::{
-    definitions::{self, ConnectionError},
+    definitions::{self, ConnectionError, DeliveryNumber, DeliveryTag},
+    messaging::DeliveryState,
     performatives::Disposition,
 };
 use tokio::sync::{mpsc::Sender, oneshot};
@@ -31,6 +32,12 @@ pub(crate) enum ConnectionControl {
     },
     DeallocateSession(OutgoingChannel),
     GetMaxFrameSize(oneshot::Sender<usize>),
+    /// Sends an empty frame. The responder is notified once the frame is sent or, if
+    /// `wait_for_frame` is true, once any frame is received afterwards
+    Ping {
+        wait_for_frame: bool,
+        responder: oneshot::Sender<()>,
+    },
 }
 
 impl std::fmt::Display for ConnectionControl {
@@ -43,6 +50,10 @@ impl std::fmt::Display for ConnectionControl {
             } => write!(f, "AllocateSession"),
             Self::DeallocateSession(id) => write!(f, "DeallocateSession({})", id.0),
             Self::GetMaxFrameSize(_) => write!(f, "GetMaxFrameSize"),
+            Self::Ping {
+                wait_for_frame,
+                responder: _,
+            } => write!(f, "Ping(wait_for_frame: {})", wait_for_frame),
         }
     }
 }
@@ -63,6 +74,22 @@ pub(crate) enum SessionControl {
     },
     DeallocateLink(OutputHandle),
     Disposition(Disposition),
+    /// Settles an outgoing delivery that is still unsettled. The session looks up the delivery
+    /// id as the sender doesn't have access to it
+    SettleOutgoingDelivery {
+        input_handle: InputHandle,
+        delivery_tag: DeliveryTag,
+        state: DeliveryState,
+    },
+    /// Settles the unsettled incoming deliveries of a receiver link with a delivery id in
+    /// `first..=last`. The responder is notified with the number of deliveries that are settled
+    SettleIncomingRange {
+        input_handle: InputHandle,
+        first: DeliveryNumber,
+        last: DeliveryNumber,
+        state: DeliveryState,
+        responder: oneshot::Sender<u32>,
+    },
     CloseConnectionWithError((ConnectionError, Option<String>)),
     GetMaxFrameSize(oneshot::Sender<usize>),
 
@@ -102,6 +129,12 @@ impl std::fmt::Display for SessionControl {
             } => write!(f, "AllocateIncomingLink"),
             SessionControl::DeallocateLink(name) => write!(f, "DeallocateLink({:?})", name),
             SessionControl::Disposition(_) => write!(f, "Disposition"),
+            SessionControl::SettleOutgoingDelivery { delivery_tag, .. } => {
+                write!(f, "SettleOutgoingDelivery({:?})", delivery_tag)
+            }
+            SessionControl::SettleIncomingRange { first, last, .. } => {
+                write!(f, "SettleIncomingRange({}..={})", first, last)
+            }
             SessionControl::CloseConnectionWithError(_) => write!(f, "CloseConnectionWithError"),
             SessionControl::GetMaxFrameSize(_) => write!(f, "GetMaxFrameSize"),
 
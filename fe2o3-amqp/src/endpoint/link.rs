@@ -13,9 +13,9 @@ use crate::{
     link::{
         delivery::{Delivery, DeliveryInfo},
         state::LinkState,
-        LinkFrame,
+        LinkFrame, TryDispositionError,
     },
-    util::{AsByteIterator, IntoReader},
+    util::{AsByteIterator, IntoReader, ToPayloads},
     Payload,
 };
 
@@ -111,6 +111,7 @@ pub(crate) trait SenderLink: Link + LinkExt {
         payload: Payload,
         message_format: MessageFormat,
         settled: Option<bool>,
+        rcv_settle_mode: Option<ReceiverSettleMode>,
         // The delivery state from sender is useful for
         // 1. link resumption
         // 2. transaction
@@ -177,6 +178,16 @@ pub(crate) trait ReceiverLink: Link + LinkExt {
         include_properties: bool,
     ) -> Result<(), Self::FlowError>;
 
+    /// Set and send flow state without waiting for capacity in the outgoing channel
+    fn try_send_flow(
+        &self,
+        writer: &mpsc::Sender<LinkFrame>,
+        link_credit: Option<u32>,
+        drain: Option<bool>,
+        echo: bool,
+        include_properties: bool,
+    ) -> Result<(), TryDispositionError>;
+
     /// Handles delivery state that is carried in a Transfer
     fn on_transfer_state(
         &mut self,
@@ -203,7 +214,7 @@ pub(crate) trait ReceiverLink: Link + LinkExt {
     ) -> Result<Delivery<T>, Self::TransferError>
     where
         for<'de> T: FromBody<'de> + Send,
-        P: IntoReader<'a> + AsByteIterator + Send + 'a;
+        P: IntoReader<'a> + AsByteIterator + ToPayloads + Send + 'a;
 
     async fn dispose(
         &self,
@@ -214,6 +225,17 @@ pub(crate) trait ReceiverLink: Link + LinkExt {
         batchable: bool,
     ) -> Result<(), Self::DispositionError>;
 
+    /// Dispose the delivery without waiting for capacity in the outgoing channel. The unsettled
+    /// map is not modified if the channel is full.
+    fn try_dispose(
+        &self,
+        writer: &mpsc::Sender<LinkFrame>,
+        delivery_info: DeliveryInfo,
+        settled: Option<bool>,
+        state: DeliveryState,
+        batchable: bool,
+    ) -> Result<(), TryDispositionError>;
+
     async fn dispose_all(
         &self,
         writer: &mpsc::Sender<LinkFrame>,
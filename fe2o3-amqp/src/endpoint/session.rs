@@ -3,7 +3,8 @@
 use std::future::Future;
 
 use fe2o3_amqp_types::{
-    definitions::Error,
+    definitions::{DeliveryNumber, DeliveryTag, Error},
+    messaging::DeliveryState,
     performatives::{Attach, Begin, Detach, Disposition, End, Flow, Transfer},
 };
 
@@ -96,6 +97,10 @@ pub(crate) trait Session {
     // Intercepting LinkFrames
     fn on_outgoing_attach(&mut self, attach: Attach) -> Result<SessionFrame, Self::Error>;
 
+    /// Returns a session flow that re-advertises the incoming-window if the window should be
+    /// replenished according to the window policy after an incoming transfer
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame>;
+
     fn on_outgoing_flow(&mut self, flow: LinkFlow) -> Result<SessionFrame, Self::Error>;
 
     fn on_outgoing_transfer(
@@ -110,5 +115,29 @@ pub(crate) trait Session {
         disposition: Disposition,
     ) -> Result<SessionFrame, Self::Error>;
 
+    /// Returns the settled disposition for an outgoing delivery that is still unsettled or `None`
+    /// if the delivery is not found (eg. it has already been settled by the remote receiver)
+    fn settle_outgoing_delivery(
+        &mut self,
+        input_handle: InputHandle,
+        delivery_tag: DeliveryTag,
+        state: DeliveryState,
+    ) -> Option<Disposition>;
+
+    /// Removes the unsettled incoming deliveries of the link with a delivery id in `first..=last`
+    /// and returns the settled dispositions that cover them, one for each run of consecutive
+    /// delivery ids
+    fn settle_incoming_range(
+        &mut self,
+        input_handle: InputHandle,
+        first: DeliveryNumber,
+        last: DeliveryNumber,
+        state: DeliveryState,
+    ) -> Vec<Disposition>;
+
     fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame;
+
+    /// Whether there are outgoing transfers buffered because the remote-incoming-window is
+    /// exhausted
+    fn has_buffered_outgoing_transfers(&self) -> bool;
 }
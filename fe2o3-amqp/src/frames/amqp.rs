@@ -1,14 +1,20 @@
 //! AMQP frame type and corresponding encoder and decoder
 
 use bytes::{Buf, BufMut, BytesMut};
-use fe2o3_amqp_types::performatives::{
-    Attach, Begin, Close, Detach, Disposition, End, Flow, Open, Performative, Transfer,
+use fe2o3_amqp_types::{
+    definitions::MIN_MAX_FRAME_SIZE,
+    performatives::{
+        Attach, Begin, Close, Detach, Disposition, End, Flow, Open, Performative, Transfer,
+    },
 };
 use serde::{ser::Serialize, Deserialize};
 use serde_amqp::{de::Deserializer, read::IoReader};
-use tokio_util::codec::{Decoder, Encoder};
+use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
 
-use crate::Payload;
+use crate::{
+    transport::{length_delimited_decoder, length_delimited_encoder},
+    Payload,
+};
 
 use super::{Error, FRAME_TYPE_AMQP};
 
@@ -46,6 +52,15 @@ impl Frame {
         self.body
     }
 
+    /// Whether the frame is a transfer or a disposition with the `batchable` field set
+    pub fn is_batchable(&self) -> bool {
+        match &self.body {
+            FrameBody::Transfer { performative, .. } => performative.batchable,
+            FrameBody::Disposition(disposition) => disposition.batchable,
+            _ => false,
+        }
+    }
+
     /// Creates an emtpy frame. The empty frame is only used to reset
     /// the remote idle timeout
     pub fn empty() -> Self {
@@ -266,6 +281,102 @@ impl Decoder for FrameDecoder {
     }
 }
 
+/// Length-prefixed codec of the AMQP frames
+///
+/// This implements the complete AMQP framing (ie. the frame size prefix, the data offset, the frame
+/// type and the channel) as a `tokio_util::codec::Encoder` and `tokio_util::codec::Decoder`, which
+/// allows sending and receiving AMQP frames over an arbitrary `AsyncRead`/`AsyncWrite` with
+/// `tokio_util::codec::Framed`. A `Transfer` whose payload doesn't fit in a single frame is split
+/// into multiple frames.
+///
+/// The protocol header exchange is not handled by this codec.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use fe2o3_amqp::frames::amqp::{AmqpFrameCodec, Frame};
+/// use tokio_util::codec::Framed;
+///
+/// let mut framed = Framed::new(io, AmqpFrameCodec::new(max_frame_size));
+/// framed.send(Frame::empty()).await?;
+/// let frame = framed.next().await;
+/// ```
+#[derive(Debug)]
+pub struct AmqpFrameCodec {
+    length_encoder: LengthDelimitedCodec,
+    length_decoder: LengthDelimitedCodec,
+}
+
+impl Default for AmqpFrameCodec {
+    fn default() -> Self {
+        Self::new(MIN_MAX_FRAME_SIZE)
+    }
+}
+
+impl AmqpFrameCodec {
+    /// Creates a new codec with the maximum frame size (including the frame header). A value
+    /// smaller than `MIN_MAX_FRAME_SIZE` (ie. 512) is raised to `MIN_MAX_FRAME_SIZE`
+    pub fn new(max_frame_size: usize) -> Self {
+        let max_frame_size = max_frame_size.max(MIN_MAX_FRAME_SIZE);
+        Self {
+            length_encoder: length_delimited_encoder(max_frame_size),
+            length_decoder: length_delimited_decoder(max_frame_size),
+        }
+    }
+
+    /// Get the maximum frame size (including the frame header)
+    pub fn max_frame_size(&self) -> usize {
+        self.length_decoder.max_frame_length()
+    }
+
+    /// Set the maximum frame size (including the frame header), eg. after the `Open` frames are
+    /// exchanged. A value smaller than `MIN_MAX_FRAME_SIZE` (ie. 512) is raised to
+    /// `MIN_MAX_FRAME_SIZE`
+    pub fn set_max_frame_size(&mut self, max_frame_size: usize) {
+        let max_frame_size = max_frame_size.max(MIN_MAX_FRAME_SIZE);
+        self.length_encoder.set_max_frame_length(max_frame_size - 4);
+        self.length_decoder.set_max_frame_length(max_frame_size);
+    }
+}
+
+impl Encoder<Frame> for AmqpFrameCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        // The frame encoder writes consecutive full size frames if the frame has to be split
+        let max_frame_length = self.length_encoder.max_frame_length();
+        let mut buf = BytesMut::new();
+        FrameEncoder::new(max_frame_length).encode(item, &mut buf)?;
+
+        while buf.len() > max_frame_length {
+            let partial = buf.split_to(max_frame_length);
+            self.length_encoder.encode(partial.freeze(), dst)?;
+        }
+        self.length_encoder.encode(buf.freeze(), dst)?;
+        Ok(())
+    }
+}
+
+impl Decoder for AmqpFrameCodec {
+    type Item = Frame;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut frame = match self.length_decoder.decode(src)? {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+
+        // doff, type and channel
+        if frame.len() < 4 {
+            return Err(Error::DecodeError(
+                "Frame is shorter than the frame header".to_string(),
+            ));
+        }
+        FrameDecoder {}.decode(&mut frame)
+    }
+}
+
 /// AMQP frame body
 // #[derive(Debug)]
 pub enum FrameBody {
@@ -340,7 +451,7 @@ mod tests {
 
     use crate::frames::amqp::{FrameDecoder, FrameEncoder};
 
-    use super::Frame;
+    use super::{AmqpFrameCodec, Frame, FrameBody};
 
     #[test]
     fn test_encoding_empty_frame() {
@@ -356,4 +467,90 @@ mod tests {
         let mut src = BytesMut::from(&[0x02, 0x00, 0x00, 0x00][..]);
         let _frame = decoder.decode(&mut src).unwrap();
     }
+
+    #[test]
+    fn test_amqp_frame_codec() {
+        use fe2o3_amqp_types::{definitions::Handle, performatives::Begin};
+
+        let mut codec = AmqpFrameCodec::default();
+        let mut buf = BytesMut::new();
+        let begin = Begin {
+            remote_channel: None,
+            next_outgoing_id: 0,
+            incoming_window: 100,
+            outgoing_window: 100,
+            handle_max: Handle(u32::MAX),
+            offered_capabilities: None,
+            desired_capabilities: None,
+            properties: None,
+        };
+        codec
+            .encode(Frame::new(1u16, FrameBody::Begin(begin)), &mut buf)
+            .unwrap();
+        codec.encode(Frame::empty(), &mut buf).unwrap();
+
+        // Size prefix, doff, type and channel of the empty frame
+        assert_eq!(&buf[buf.len() - 8..], &[0, 0, 0, 8, 2, 0, 0, 0]);
+
+        // Incomplete frame
+        let mut partial = BytesMut::from(&buf[..5]);
+        let mut other = AmqpFrameCodec::default();
+        assert!(other.decode(&mut partial).unwrap().is_none());
+
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.channel(), 1);
+        assert!(matches!(frame.body(), FrameBody::Begin(_)));
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert!(matches!(frame.body(), FrameBody::Empty));
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_amqp_frame_codec_splits_large_transfer() {
+        use bytes::Bytes;
+        use fe2o3_amqp_types::{definitions::Handle, performatives::Transfer};
+
+        let mut codec = AmqpFrameCodec::new(512);
+        let transfer = Transfer {
+            handle: Handle(0),
+            delivery_id: Some(0),
+            delivery_tag: Some(vec![0u8].into()),
+            message_format: Some(0),
+            settled: Some(false),
+            more: false,
+            rcv_settle_mode: None,
+            state: None,
+            resume: false,
+            aborted: false,
+            batchable: false,
+        };
+        let payload = Bytes::from(vec![7u8; 2000]);
+        let frame = Frame::new(
+            0u16,
+            FrameBody::Transfer {
+                performative: transfer,
+                payload: payload.clone(),
+            },
+        );
+        let mut buf = BytesMut::new();
+        codec.encode(frame, &mut buf).unwrap();
+
+        let mut received = BytesMut::new();
+        let mut frames = 0;
+        while let Some(frame) = codec.decode(&mut buf).unwrap() {
+            frames += 1;
+            match frame.into_body() {
+                FrameBody::Transfer {
+                    performative,
+                    payload,
+                } => {
+                    received.extend_from_slice(&payload);
+                    assert_eq!(performative.more, !buf.is_empty());
+                }
+                _ => panic!("Expecting a transfer"),
+            }
+        }
+        assert!(frames > 1);
+        assert_eq!(received.freeze(), payload);
+    }
 }
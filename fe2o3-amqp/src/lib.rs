@@ -27,6 +27,7 @@
 //! |`"acceptor"`| enables `ConnectionAcceptor`, `SessionAcceptor`, and `LinkAcceptor`|
 //! |`"transaction"`| enables `Controller`, `Transaction`, `OwnedTransaction` and `control_link_acceptor` |
 //! |`"scram"`| enables SCRAM auth |
+//! |`"test-util"`| enables testing utilities like the outgoing frame interceptor |
 //! |`"tracing"`| enables logging with `tracing` |
 //! |`"log"`| enables logging with `log` |
 //!
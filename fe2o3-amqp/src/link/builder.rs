@@ -6,9 +6,13 @@ use std::{
 };
 
 use fe2o3_amqp_types::{
-    definitions::{Fields, ReceiverSettleMode, SenderSettleMode, SequenceNo},
-    messaging::{Source, Target, TargetArchetype},
-    primitives::{Symbol, Ulong},
+    definitions::{
+        self, DeliveryTag, ErrorCondition, Fields, LinkError, ReceiverSettleMode, SenderSettleMode,
+        SequenceNo,
+    },
+    messaging::{Address, DeliveryState, Source, Target, TargetArchetype},
+    performatives::Attach,
+    primitives::{Symbol, Ulong, Value},
 };
 use parking_lot::RwLock;
 use tokio::sync::{mpsc, Notify};
@@ -22,14 +26,15 @@ use crate::{
 };
 
 use super::{
-    receiver::{CreditMode, ReceiverInner},
+    delivery::{OnSettled, DELAYED_DELIVERY},
+    receiver::{CreditMode, DecodeErrorAction, ReceiverInner},
     role,
     sender::SenderInner,
     state::{LinkFlowState, LinkFlowStateInner, LinkState},
     target_archetype::VerifyTargetArchetype,
     ArcUnsettledMap, Receiver, ReceiverAttachError, ReceiverFlowState, ReceiverLink,
     ReceiverRelayFlowState, Sender, SenderAttachError, SenderFlowState, SenderLink,
-    SenderRelayFlowState,
+    SenderRelayFlowState, UnsettledMap,
 };
 
 cfg_transaction! {
@@ -39,29 +44,62 @@ cfg_transaction! {
 }
 
 /// Type state for link::builder::Builder;
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WithoutName;
 
 /// Type state for link::builder::Builder;
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WithName;
 
 /// Type state for link::builder::Builder;
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WithoutTarget;
 
 /// Type state for link::builder::Builder;
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WithTarget;
 
 /// Type state for link::builder::Builder;
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WithoutSource;
 
 /// Type state for link::builder::Builder;
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WithSource;
 
+/// Default link settings that are inherited from the session or the connection
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LinkDefaults {
+    /// Default credit for the receivers
+    pub credit: Option<SequenceNo>,
+
+    /// Default settlement policy for the sender
+    pub snd_settle_mode: Option<SenderSettleMode>,
+
+    /// Default settlement policy of the receiver
+    pub rcv_settle_mode: Option<ReceiverSettleMode>,
+}
+
+impl LinkDefaults {
+    /// Fill the values that are not set with the values from `fallback`
+    pub(crate) fn or(self, fallback: &LinkDefaults) -> Self {
+        Self {
+            credit: self.credit.or(fallback.credit),
+            snd_settle_mode: self.snd_settle_mode.or(fallback.snd_settle_mode.clone()),
+            rcv_settle_mode: self.rcv_settle_mode.or(fallback.rcv_settle_mode.clone()),
+        }
+    }
+}
+
+/// Settings that are explicitly set on the link builder and thus should not be overriden by
+/// [`LinkDefaults`]
+#[derive(Debug, Clone, Default)]
+struct ExplicitSettings {
+    snd_settle_mode: bool,
+    rcv_settle_mode: bool,
+    credit_mode: bool,
+}
+
 /// Builder for a Link
 #[derive(Debug, Clone)]
 pub struct Builder<Role, T, NameState, SS, TS> {
@@ -112,6 +150,25 @@ pub struct Builder<Role, T, NameState, SS, TS> {
     /// `false`
     pub auto_accept: bool,
 
+    /// Action taken by the receiver when an incoming message cannot be decoded
+    ///
+    /// This field has no effect on Sender
+    ///
+    /// # Default
+    ///
+    /// `DecodeErrorAction::Propagate`
+    pub on_decode_error: DecodeErrorAction,
+
+    /// Whether dropping a `DeliveryFut` of an unsettled delivery will settle the delivery as
+    /// `Released`
+    ///
+    /// This field has no effect on Receiver
+    ///
+    /// # Default
+    ///
+    /// `false`
+    pub settle_on_fut_drop: bool,
+
     /// Whether to verify the `source` field of the incoming Attach frame
     ///
     /// Default to true
@@ -122,6 +179,36 @@ pub struct Builder<Role, T, NameState, SS, TS> {
     /// Default to true
     pub verify_incoming_target: bool,
 
+    /// Whether to re-attach the link to the `address` carried in the `info` of an
+    /// `amqp:link:redirect` error returned by the remote peer
+    ///
+    /// The link is re-attached on the same session and only a single redirect is followed. Links
+    /// can only be redirected within the same container, so the `hostname`, `network-host` and
+    /// `port` of the redirect are ignored.
+    ///
+    /// Default to false
+    pub follow_redirects: bool,
+
+    /// The maximum number of entries of the unsettled map that is carried by the Attach when the
+    /// link is resumed
+    ///
+    /// The remaining entries are left out with `incomplete-unsettled` set to true. The unsettled
+    /// map is further halved until the Attach fits in the max frame size of the connection, and
+    /// setting this to zero leaves out the whole map for brokers that cannot handle a large
+    /// (or any) unsettled map in the Attach.
+    ///
+    /// Default to `None`, which sends as many entries as the max frame size allows
+    pub max_unsettled_in_attach: Option<usize>,
+
+    /// Callback invoked when a delivery is settled by the remote peer
+    on_settled: Option<OnSettled>,
+
+    /// Settings that take precedence over the session and connection defaults
+    explicit: ExplicitSettings,
+
+    /// Unsettled map that is sent with the Attach. This is only used by the receiver
+    unsettled: Option<UnsettledMap<Option<DeliveryState>>>,
+
     // Type state markers
     role: PhantomData<Role>,
     name_state: PhantomData<NameState>,
@@ -129,6 +216,14 @@ pub struct Builder<Role, T, NameState, SS, TS> {
     target_state: PhantomData<TS>,
 }
 
+/// Returns the address to re-attach to if the error is an `amqp:link:redirect`
+fn redirect_address(error: &definitions::Error) -> Option<Address> {
+    match error.condition {
+        ErrorCondition::LinkError(LinkError::Redirect) => error.redirect()?.address,
+        _ => None,
+    }
+}
+
 impl<Role, T> Default for Builder<Role, T, WithoutName, WithoutSource, WithoutTarget> {
     fn default() -> Self {
         Self {
@@ -151,8 +246,15 @@ impl<Role, T> Default for Builder<Role, T, WithoutName, WithoutSource, WithoutTa
             target_state: PhantomData,
 
             auto_accept: false,
+            on_decode_error: Default::default(),
+            settle_on_fut_drop: false,
             verify_incoming_source: true,
             verify_incoming_target: true,
+            follow_redirects: false,
+            max_unsettled_in_attach: None,
+            on_settled: None,
+            explicit: Default::default(),
+            unsettled: None,
         }
     }
 }
@@ -178,6 +280,65 @@ impl<T, NameState, SS, TS> Builder<role::ReceiverMarker, T, NameState, SS, TS> {
         self.auto_accept = value;
         self
     }
+
+    /// Sets the action taken when an incoming message cannot be decoded into the type requested
+    /// by the receive call
+    ///
+    /// With any action other than [`DecodeErrorAction::Propagate`], the delivery is settled with
+    /// the corresponding outcome and the receiver continues with the next delivery instead of
+    /// returning [`RecvError::MessageDecode`](crate::link::RecvError::MessageDecode).
+    ///
+    /// Default value: `DecodeErrorAction::Propagate`
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut receiver = Receiver::builder()
+    ///     .name("rust-receiver-link-1")
+    ///     .source("q1")
+    ///     .on_decode_error(DecodeErrorAction::Reject)
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub fn on_decode_error(mut self, action: DecodeErrorAction) -> Self {
+        self.on_decode_error = action;
+        self
+    }
+
+    /// Set a callback that is invoked with the delivery tag and the delivery state whenever a
+    /// disposition from the remote sender settles a delivery that is still unsettled on the
+    /// receiver.
+    ///
+    /// With [`ReceiverSettleMode::Second`], the receiver sends a non-settled disposition and only
+    /// considers the delivery settled once the sender settles it, so this allows the application
+    /// to know when a delivery is truly settled. The delivery state is the one sent by the sender,
+    /// which is `None` if the sender settled the delivery without specifying a state.
+    ///
+    /// The callback is invoked from the session event loop and thus should return quickly and must
+    /// not block.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let receiver = Receiver::builder()
+    ///     .name("rust-receiver-link-1")
+    ///     .source("q1")
+    ///     .receiver_settle_mode(ReceiverSettleMode::Second)
+    ///     .on_settlement_confirmed(|delivery_tag, state| {
+    ///         println!("{:?} settled with {:?}", delivery_tag, state)
+    ///     })
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub fn on_settlement_confirmed<F>(mut self, f: F) -> Self
+    where
+        F: Fn(DeliveryTag, Option<DeliveryState>) + Send + Sync + 'static,
+    {
+        self.on_settled = Some(OnSettled::new(f));
+        self
+    }
 }
 
 impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
@@ -195,7 +356,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: self.role,
             name_state: PhantomData,
@@ -203,8 +364,15 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            on_decode_error: self.on_decode_error,
+            settle_on_fut_drop: self.settle_on_fut_drop,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            follow_redirects: self.follow_redirects,
+            max_unsettled_in_attach: self.max_unsettled_in_attach,
+            on_settled: self.on_settled,
+            explicit: self.explicit,
+            unsettled: self.unsettled,
         }
     }
 
@@ -222,7 +390,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: PhantomData,
             name_state: self.name_state,
@@ -230,8 +398,15 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            on_decode_error: self.on_decode_error,
+            settle_on_fut_drop: self.settle_on_fut_drop,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            follow_redirects: self.follow_redirects,
+            max_unsettled_in_attach: self.max_unsettled_in_attach,
+            on_settled: self.on_settled,
+            explicit: self.explicit,
+            unsettled: self.unsettled,
         }
     }
 
@@ -249,7 +424,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: PhantomData,
             name_state: self.name_state,
@@ -257,24 +432,52 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            on_decode_error: self.on_decode_error,
+            settle_on_fut_drop: self.settle_on_fut_drop,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            follow_redirects: self.follow_redirects,
+            max_unsettled_in_attach: self.max_unsettled_in_attach,
+            on_settled: self.on_settled,
+            explicit: self.explicit,
+            unsettled: self.unsettled,
         }
     }
 
     /// Settlement policy for the sender
+    ///
+    /// This takes precedence over the default set on the session or the connection
     pub fn sender_settle_mode(mut self, mode: SenderSettleMode) -> Self {
         self.snd_settle_mode = mode;
+        self.explicit.snd_settle_mode = true;
         self
     }
 
     /// The settlement policy of the receiver
+    ///
+    /// This takes precedence over the default set on the session or the connection
     pub fn receiver_settle_mode(mut self, mode: ReceiverSettleMode) -> Self {
         self.rcv_settle_mode = mode;
+        self.explicit.rcv_settle_mode = true;
         self
     }
 
     /// The source for messages
+    ///
+    /// Anything that converts into a [`Source`] is accepted, including an address (eg. `&str` or
+    /// `String`), a [`Source`] or a [`SourceBuilder`](fe2o3_amqp_types::messaging::SourceBuilder)
+    /// without calling `build()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let receiver = Receiver::builder()
+    ///     .name("rust-receiver-link-1")
+    ///     .source(Source::builder().address("q1").durable(TerminusDurability::UnsettledState))
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    /// ```
     pub fn source(self, source: impl Into<Source>) -> Builder<Role, T, NameState, WithSource, TS> {
         Builder {
             name: self.name,
@@ -296,12 +499,34 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: self.target_state,
 
             auto_accept: self.auto_accept,
+            on_decode_error: self.on_decode_error,
+            settle_on_fut_drop: self.settle_on_fut_drop,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            follow_redirects: self.follow_redirects,
+            max_unsettled_in_attach: self.max_unsettled_in_attach,
+            on_settled: self.on_settled,
+            explicit: self.explicit,
+            unsettled: self.unsettled,
         }
     }
 
     /// The target for messages
+    ///
+    /// Anything that converts into a [`Target`] is accepted, including an address (eg. `&str` or
+    /// `String`), a [`Target`] or a [`TargetBuilder`](fe2o3_amqp_types::messaging::TargetBuilder)
+    /// without calling `build()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let sender = Sender::builder()
+    ///     .name("rust-sender-link-1")
+    ///     .target(Target::builder().address("q1").durable(TerminusDurability::UnsettledState))
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    /// ```
     pub fn target(
         self,
         target: impl Into<Target>,
@@ -318,7 +543,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             desired_capabilities: self.desired_capabilities,
             buffer_size: self.buffer_size,
             credit_mode: self.credit_mode,
-            properties: Default::default(),
+            properties: self.properties,
 
             role: self.role,
             name_state: self.name_state,
@@ -326,8 +551,15 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             target_state: PhantomData,
 
             auto_accept: self.auto_accept,
+            on_decode_error: self.on_decode_error,
+            settle_on_fut_drop: self.settle_on_fut_drop,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            follow_redirects: self.follow_redirects,
+            max_unsettled_in_attach: self.max_unsettled_in_attach,
+            on_settled: self.on_settled,
+            explicit: self.explicit,
+            unsettled: self.unsettled,
         }
     }
 
@@ -349,7 +581,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
                 desired_capabilities: self.desired_capabilities,
                 buffer_size: self.buffer_size,
                 credit_mode: self.credit_mode,
-                properties: Default::default(),
+                properties: self.properties,
 
                 role: self.role,
                 name_state: self.name_state,
@@ -357,8 +589,15 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
                 target_state: PhantomData,
 
                 auto_accept: self.auto_accept,
+                on_decode_error: self.on_decode_error,
+                settle_on_fut_drop: self.settle_on_fut_drop,
                 verify_incoming_source: self.verify_incoming_source,
                 verify_incoming_target: self.verify_incoming_target,
+                follow_redirects: self.follow_redirects,
+                max_unsettled_in_attach: self.max_unsettled_in_attach,
+                on_settled: self.on_settled,
+                explicit: self.explicit,
+                unsettled: self.unsettled,
             }
         }
     }
@@ -405,6 +644,15 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
         self
     }
 
+    /// Insert a single link property. Any property previously inserted with the same key is
+    /// replaced
+    pub fn property(mut self, key: impl Into<Symbol>, value: impl Into<Value>) -> Self {
+        self.properties
+            .get_or_insert_with(Fields::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
     /// Set whether the link should verify incoming source
     pub fn verify_incoming_source(mut self, verify: bool) -> Self {
         self.verify_incoming_source = verify;
@@ -417,6 +665,39 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
         self
     }
 
+    /// Set whether the link should be re-attached to the address indicated by an
+    /// `amqp:link:redirect` error
+    ///
+    /// Only redirects within the same session are followed. See the
+    /// [`follow_redirects`](#structfield.follow_redirects) field for more details.
+    pub fn follow_redirects(mut self, value: bool) -> Self {
+        self.follow_redirects = value;
+        self
+    }
+
+    /// Set the maximum number of entries of the unsettled map that is carried by the Attach when
+    /// the link is resumed
+    ///
+    /// See the [`max_unsettled_in_attach`](#structfield.max_unsettled_in_attach) field for more
+    /// details.
+    pub fn max_unsettled_in_attach(mut self, max: usize) -> Self {
+        self.max_unsettled_in_attach = Some(max);
+        self
+    }
+
+    /// Apply the session (or connection) defaults to the settings that are not explicitly set
+    pub(crate) fn apply_link_defaults(&mut self, defaults: &LinkDefaults) {
+        if let (false, Some(mode)) = (self.explicit.snd_settle_mode, &defaults.snd_settle_mode) {
+            self.snd_settle_mode = mode.clone();
+        }
+        if let (false, Some(mode)) = (self.explicit.rcv_settle_mode, &defaults.rcv_settle_mode) {
+            self.rcv_settle_mode = mode.clone();
+        }
+        if let (false, Some(credit)) = (self.explicit.credit_mode, defaults.credit) {
+            self.credit_mode = CreditMode::Auto(credit);
+        }
+    }
+
     pub(crate) fn create_link<C, M>(
         self,
         unsettled: ArcUnsettledMap<M>,
@@ -443,6 +724,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             max_message_size,
             offered_capabilities: self.offered_capabilities,
             desired_capabilities: self.desired_capabilities,
+            remote_properties: None, // Will take value from incoming attach
 
             // delivery_count: self.initial_delivery_count,
             // properties: self.properties,
@@ -451,6 +733,7 @@ impl<Role, T, NameState, SS, TS> Builder<Role, T, NameState, SS, TS> {
             unsettled,
             verify_incoming_source: self.verify_incoming_source,
             verify_incoming_target: self.verify_incoming_target,
+            max_unsettled_in_attach: self.max_unsettled_in_attach,
         }
     }
 }
@@ -459,10 +742,62 @@ impl<T, NameState, SS, TS> Builder<role::SenderMarker, T, NameState, SS, TS> {
     /// This MUST NOT be null if role is sender,
     /// and it is ignored if the role is receiver.
     /// See subsection 2.6.7.
+    ///
+    /// The delivery-count of the sender starts from this value, and it wraps around to zero after
+    /// `u32::MAX`. The default delivery tags are also derived from the delivery-count.
     pub fn initial_delivery_count(mut self, count: SequenceNo) -> Self {
         self.initial_delivery_count = count;
         self
     }
+
+    /// Set a callback that is invoked with the delivery tag and the delivery state whenever a
+    /// disposition from the remote receiver settles an outgoing delivery.
+    ///
+    /// This allows recording the outcomes of all deliveries (eg. for metrics) without awaiting
+    /// each `DeliveryFut`. The delivery state is `None` if the receiver settled the delivery
+    /// without specifying a state. Pre-settled deliveries are not reported.
+    ///
+    /// The callback is invoked from the session event loop and thus should return quickly and must
+    /// not block.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let sender = Sender::builder()
+    ///     .name("rust-sender-link-1")
+    ///     .target("q1")
+    ///     .on_settled(|delivery_tag, state| println!("{:?} settled with {:?}", delivery_tag, state))
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub fn on_settled<F>(mut self, f: F) -> Self
+    where
+        F: Fn(DeliveryTag, Option<DeliveryState>) + Send + Sync + 'static,
+    {
+        self.on_settled = Some(OnSettled::new(f));
+        self
+    }
+
+    /// Adds the [`DELAYED_DELIVERY`](crate::link::delivery::DELAYED_DELIVERY) capability to the
+    /// desired capabilities so that the broker honours scheduled messages (see
+    /// [`scheduled_enqueue_time()`](crate::link::delivery::Builder::scheduled_enqueue_time))
+    pub fn delayed_delivery(self) -> Self {
+        self.add_desired_capabilities(DELAYED_DELIVERY)
+    }
+
+    /// Sets the `settle_on_fut_drop` field.
+    ///
+    /// If enabled, dropping the `DeliveryFut` returned by `send_batchable` (or
+    /// `send_batchable_ref`) before it resolves will remove the delivery from the local unsettled
+    /// map and settle it with the `Released` outcome. Otherwise the delivery stays unsettled until
+    /// the remote receiver settles it, and its outcome is silently discarded.
+    ///
+    /// Default value: `false`
+    pub fn settle_on_fut_drop(mut self, value: bool) -> Self {
+        self.settle_on_fut_drop = value;
+        self
+    }
 }
 
 impl<T, NameState, SS, TS> Builder<role::ReceiverMarker, T, NameState, SS, TS> {
@@ -471,13 +806,65 @@ impl<T, NameState, SS, TS> Builder<role::ReceiverMarker, T, NameState, SS, TS> {
     /// If the credit mode is `Auto`, the receiver will automatically send flow frames when the
     /// remaining credit if below 50% of the assigned credit. An initial flow frame will also be
     /// sent if the mode if `Auto`.
+    ///
+    /// This takes precedence over the default link credit set on the session or the connection
     pub fn credit_mode(mut self, credit_mode: CreditMode) -> Self {
         self.credit_mode = credit_mode;
+        self.explicit.credit_mode = true;
         self
     }
 }
 
 impl Builder<role::SenderMarker, Target, WithName, WithSource, WithTarget> {
+    /// Creates a sender link builder from an [`Attach`] performative
+    ///
+    /// The `handle` is allocated by the session and thus ignored. The `unsettled` map and the
+    /// `incomplete_unsettled` flag are also ignored because a sender can only resume deliveries
+    /// whose payload it still holds.
+    pub(crate) fn from_attach(attach: Attach) -> Result<Self, SenderAttachError> {
+        let Attach {
+            name,
+            handle: _,
+            role,
+            snd_settle_mode,
+            rcv_settle_mode,
+            source,
+            target,
+            unsettled: _,
+            incomplete_unsettled: _,
+            initial_delivery_count,
+            max_message_size,
+            offered_capabilities,
+            desired_capabilities,
+            properties,
+        } = attach;
+
+        // The initial-delivery-count MUST NOT be null if role is sender
+        let initial_delivery_count = match (role, initial_delivery_count) {
+            (definitions::Role::Sender, Some(count)) => count,
+            _ => return Err(SenderAttachError::IllegalState),
+        };
+        let target = target
+            .map(|target| Target::try_from(*target))
+            .ok_or(SenderAttachError::IllegalState)?
+            .map_err(|_| SenderAttachError::CoordinatorIsNotImplemented)?;
+
+        let mut builder = Sender::builder()
+            .name(name)
+            .sender_settle_mode(snd_settle_mode)
+            .receiver_settle_mode(rcv_settle_mode)
+            .target(target)
+            .initial_delivery_count(initial_delivery_count);
+        if let Some(source) = source {
+            builder = builder.source(*source);
+        }
+        builder.max_message_size = max_message_size;
+        builder.offered_capabilities = offered_capabilities.map(Into::into);
+        builder.desired_capabilities = desired_capabilities.map(Into::into);
+        builder.properties = properties;
+        Ok(builder)
+    }
+
     /// Attach the link as a sender
     ///
     /// # Example
@@ -496,9 +883,26 @@ impl Builder<role::SenderMarker, Target, WithName, WithSource, WithTarget> {
         self,
         session: &mut SessionHandle<R>,
     ) -> Result<Sender, SenderAttachError> {
-        self.attach_inner(session)
-            .await
-            .map(|inner| Sender { inner })
+        let redirect_builder = self.follow_redirects.then(|| self.clone());
+        match self.attach_inner(session).await {
+            Ok(inner) => Ok(Sender { inner }),
+            Err(SenderAttachError::RemoteClosedWithError(error)) => {
+                match (redirect_builder, redirect_address(&error)) {
+                    (Some(mut builder), Some(address)) => {
+                        builder.follow_redirects = false;
+                        if let Some(target) = builder.target.as_mut() {
+                            target.address = Some(address);
+                        }
+                        builder
+                            .attach_inner(session)
+                            .await
+                            .map(|inner| Sender { inner })
+                    }
+                    _ => Err(SenderAttachError::RemoteClosedWithError(error)),
+                }
+            }
+            Err(error) => Err(error),
+        }
     }
 }
 
@@ -532,13 +936,17 @@ where
         mut self,
         session: &mut SessionHandle<R>,
     ) -> Result<SenderInner<SenderLink<T>>, SenderAttachError> {
+        self.apply_link_defaults(&session.link_defaults);
         let buffer_size = self.buffer_size;
+        let settle_on_fut_drop = self.settle_on_fut_drop;
         let (incoming_tx, mut incoming_rx) = mpsc::channel::<LinkIncomingItem>(self.buffer_size);
         let outgoing = session.outgoing.clone();
         let (producer, consumer) = self.create_flow_state_containers();
         let unsettled = Arc::new(RwLock::new(None));
 
-        let link_relay = LinkRelay::new_sender(incoming_tx, producer, unsettled.clone());
+        let on_settled = self.on_settled.take();
+        let link_relay =
+            LinkRelay::new_sender(incoming_tx, producer, unsettled.clone(), on_settled.clone());
         let output_handle =
             session::allocate_link(&session.control, self.name.clone(), link_relay).await?;
         let mut link = self.create_link(unsettled, output_handle, consumer);
@@ -576,8 +984,11 @@ where
             link,
             buffer_size,
             session: session.control.clone(),
+            session_id: session.id.clone(),
             outgoing,
             incoming: incoming_rx,
+            on_settled,
+            settle_on_fut_drop,
             // marker: PhantomData,
         };
         Ok(inner)
@@ -585,6 +996,51 @@ where
 }
 
 impl Builder<role::ReceiverMarker, Target, WithName, WithSource, WithTarget> {
+    /// Creates a receiver link builder from an [`Attach`] performative
+    ///
+    /// The `handle` is allocated by the session and thus ignored. The `unsettled` map is sent
+    /// as is and is used as the initial unsettled map of the receiver.
+    pub(crate) fn from_attach(attach: Attach) -> Result<Self, ReceiverAttachError> {
+        let Attach {
+            name,
+            handle: _,
+            role,
+            snd_settle_mode,
+            rcv_settle_mode,
+            source,
+            target,
+            unsettled,
+            incomplete_unsettled: _,
+            initial_delivery_count: _,
+            max_message_size,
+            offered_capabilities,
+            desired_capabilities,
+            properties,
+        } = attach;
+
+        let source = match (role, source) {
+            (definitions::Role::Receiver, Some(source)) => source,
+            _ => return Err(ReceiverAttachError::IllegalState),
+        };
+
+        let mut builder = Receiver::builder()
+            .name(name)
+            .sender_settle_mode(snd_settle_mode)
+            .receiver_settle_mode(rcv_settle_mode)
+            .source(*source);
+        if let Some(target) = target {
+            let target =
+                Target::try_from(*target).map_err(|_| ReceiverAttachError::IllegalState)?;
+            builder = builder.target(target);
+        }
+        builder.max_message_size = max_message_size;
+        builder.offered_capabilities = offered_capabilities.map(Into::into);
+        builder.desired_capabilities = desired_capabilities.map(Into::into);
+        builder.properties = properties;
+        builder.unsettled = unsettled;
+        Ok(builder)
+    }
+
     /// Attach the link as a receiver
     ///
     /// # Example
@@ -602,9 +1058,26 @@ impl Builder<role::ReceiverMarker, Target, WithName, WithSource, WithTarget> {
         self,
         session: &mut SessionHandle<R>,
     ) -> Result<Receiver, ReceiverAttachError> {
-        self.attach_inner(session)
-            .await
-            .map(|inner| Receiver { inner })
+        let redirect_builder = self.follow_redirects.then(|| self.clone());
+        match self.attach_inner(session).await {
+            Ok(inner) => Ok(Receiver { inner }),
+            Err(ReceiverAttachError::RemoteClosedWithError(error)) => {
+                match (redirect_builder, redirect_address(&error)) {
+                    (Some(mut builder), Some(address)) => {
+                        builder.follow_redirects = false;
+                        if let Some(source) = builder.source.as_mut() {
+                            source.address = Some(address);
+                        }
+                        builder
+                            .attach_inner(session)
+                            .await
+                            .map(|inner| Receiver { inner })
+                    }
+                    _ => Err(ReceiverAttachError::RemoteClosedWithError(error)),
+                }
+            }
+            Err(error) => Err(error),
+        }
     }
 }
 
@@ -635,20 +1108,25 @@ where
         mut self,
         session: &mut SessionHandle<R>,
     ) -> Result<ReceiverInner<ReceiverLink<T>>, ReceiverAttachError> {
+        self.apply_link_defaults(&session.link_defaults);
+
         // TODO: how to avoid clone?
         let buffer_size = self.buffer_size;
         let credit_mode = self.credit_mode.clone();
         let (incoming_tx, mut incoming_rx) = mpsc::channel::<LinkIncomingItem>(self.buffer_size);
         let outgoing = session.outgoing.clone();
         let (relay_flow_state, flow_state) = self.create_flow_state_containers();
-        let unsettled = Arc::new(RwLock::new(None));
+        let unsettled = Arc::new(RwLock::new(self.unsettled.take()));
         let auto_accept = self.auto_accept;
+        let on_decode_error = self.on_decode_error.clone();
+        let on_settled = self.on_settled.take();
 
         let link_relay = LinkRelay::new_receiver(
             incoming_tx,
             relay_flow_state,
             unsettled.clone(),
             self.rcv_settle_mode.clone(),
+            on_settled.clone(),
         );
         // Create Link in Session
         // Any error here will be on the Session level and thus it should immediately return with an error
@@ -680,10 +1158,14 @@ where
             credit_mode,
             processed: AtomicU32::new(0),
             auto_accept,
+            on_decode_error,
             session: session.control.clone(),
+            session_id: session.id.clone(),
             outgoing,
             incoming: incoming_rx,
             incomplete_transfer: None,
+            deferred_error: None,
+            on_settled,
         };
 
         if let CreditMode::Auto(credit) = inner.credit_mode {
@@ -709,3 +1191,156 @@ cfg_transaction! {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        definitions::{DeliveryTag, ReceiverSettleMode, Role, SenderSettleMode},
+        messaging::{Source, Target},
+        performatives::Attach,
+        primitives::{OrderedMap, Symbol, Value},
+    };
+
+    use crate::link::{
+        receiver::CreditMode, role, Receiver, ReceiverAttachError, Sender, SenderAttachError,
+    };
+
+    use super::{Builder, LinkDefaults};
+
+    fn attach(role: Role) -> Attach {
+        Attach {
+            name: "link".into(),
+            handle: 7.into(),
+            role,
+            snd_settle_mode: SenderSettleMode::Settled,
+            rcv_settle_mode: ReceiverSettleMode::Second,
+            source: Some(Box::new(Source::from("q1"))),
+            target: Some(Box::new(Target::from("q2").into())),
+            unsettled: None,
+            incomplete_unsettled: false,
+            initial_delivery_count: Some(5),
+            max_message_size: Some(1024),
+            offered_capabilities: None,
+            desired_capabilities: Some(vec![Symbol::from("capability")].into()),
+            properties: None,
+        }
+    }
+
+    #[test]
+    fn link_defaults_fill_unset_values() {
+        let connection_defaults = LinkDefaults {
+            credit: Some(10),
+            snd_settle_mode: Some(SenderSettleMode::Settled),
+            rcv_settle_mode: Some(ReceiverSettleMode::Second),
+        };
+        let session_defaults = LinkDefaults {
+            credit: Some(20),
+            ..Default::default()
+        };
+        let defaults = session_defaults.or(&connection_defaults);
+        assert_eq!(defaults.credit, Some(20));
+        assert_eq!(defaults.snd_settle_mode, Some(SenderSettleMode::Settled));
+        assert_eq!(defaults.rcv_settle_mode, Some(ReceiverSettleMode::Second));
+
+        let mut receiver = Receiver::builder().name("receiver");
+        receiver.apply_link_defaults(&defaults);
+        assert!(matches!(receiver.credit_mode, CreditMode::Auto(20)));
+        assert_eq!(receiver.snd_settle_mode, SenderSettleMode::Settled);
+        assert_eq!(receiver.rcv_settle_mode, ReceiverSettleMode::Second);
+    }
+
+    #[test]
+    fn explicit_link_settings_take_precedence_over_defaults() {
+        let defaults = LinkDefaults {
+            credit: Some(10),
+            snd_settle_mode: Some(SenderSettleMode::Settled),
+            rcv_settle_mode: Some(ReceiverSettleMode::Second),
+        };
+
+        let mut receiver = Receiver::builder()
+            .name("receiver")
+            .credit_mode(CreditMode::Manual)
+            .receiver_settle_mode(ReceiverSettleMode::First);
+        receiver.apply_link_defaults(&defaults);
+        assert!(matches!(receiver.credit_mode, CreditMode::Manual));
+        assert_eq!(receiver.snd_settle_mode, SenderSettleMode::Settled);
+        assert_eq!(receiver.rcv_settle_mode, ReceiverSettleMode::First);
+
+        let mut sender = Sender::builder()
+            .sender_settle_mode(SenderSettleMode::Unsettled)
+            .name("sender");
+        sender.apply_link_defaults(&defaults);
+        assert_eq!(sender.snd_settle_mode, SenderSettleMode::Unsettled);
+        assert_eq!(sender.rcv_settle_mode, ReceiverSettleMode::Second);
+    }
+
+    #[test]
+    fn properties_are_kept_across_builder_transitions() {
+        let builder = Sender::builder()
+            .property("product", "fe2o3-amqp")
+            .property("version", "0.13")
+            .property("product", "fe2o3")
+            .name("sender")
+            .target("q1");
+
+        let properties = builder.properties.unwrap();
+        assert_eq!(properties.len(), 2);
+        assert_eq!(
+            properties.get(&Symbol::from("product")),
+            Some(&Value::from("fe2o3"))
+        );
+        assert_eq!(
+            properties.get(&Symbol::from("version")),
+            Some(&Value::from("0.13"))
+        );
+    }
+
+    #[test]
+    fn sender_builder_from_attach() {
+        let builder =
+            Builder::<role::SenderMarker, Target, _, _, _>::from_attach(attach(Role::Sender))
+                .unwrap();
+        assert_eq!(builder.name, "link");
+        assert_eq!(builder.snd_settle_mode, SenderSettleMode::Settled);
+        assert_eq!(builder.rcv_settle_mode, ReceiverSettleMode::Second);
+        assert_eq!(builder.source.unwrap().address.as_deref(), Some("q1"));
+        assert_eq!(builder.target.unwrap().address.as_deref(), Some("q2"));
+        assert_eq!(builder.initial_delivery_count, 5);
+        assert_eq!(builder.max_message_size, Some(1024));
+        assert_eq!(
+            builder.desired_capabilities,
+            Some(vec![Symbol::from("capability")])
+        );
+
+        let mut invalid = attach(Role::Sender);
+        invalid.initial_delivery_count = None;
+        let result = Builder::<role::SenderMarker, Target, _, _, _>::from_attach(invalid);
+        assert!(matches!(result, Err(SenderAttachError::IllegalState)));
+
+        let result =
+            Builder::<role::SenderMarker, Target, _, _, _>::from_attach(attach(Role::Receiver));
+        assert!(matches!(result, Err(SenderAttachError::IllegalState)));
+    }
+
+    #[test]
+    fn receiver_builder_from_attach() {
+        let mut performative = attach(Role::Receiver);
+        let mut unsettled = OrderedMap::new();
+        unsettled.insert(DeliveryTag::from(vec![1u8]), None);
+        performative.unsettled = Some(unsettled);
+
+        let builder =
+            Builder::<role::ReceiverMarker, Target, _, _, _>::from_attach(performative).unwrap();
+        assert_eq!(builder.name, "link");
+        assert_eq!(builder.source.unwrap().address.as_deref(), Some("q1"));
+        assert_eq!(builder.target.unwrap().address.as_deref(), Some("q2"));
+        let unsettled = builder.unsettled.unwrap();
+        assert_eq!(unsettled.len(), 1);
+        assert!(unsettled.contains_key(&DeliveryTag::from(vec![1u8])));
+
+        let mut invalid = attach(Role::Receiver);
+        invalid.source = None;
+        let result = Builder::<role::ReceiverMarker, Target, _, _, _>::from_attach(invalid);
+        assert!(matches!(result, Err(ReceiverAttachError::IllegalState)));
+    }
+}
@@ -2,21 +2,50 @@
 
 use fe2o3_amqp_types::{
     definitions::{DeliveryNumber, DeliveryTag, Handle, MessageFormat, ReceiverSettleMode},
-    messaging::{Accepted, DeliveryState, Message, Outcome, SerializableBody, MESSAGE_FORMAT},
-    primitives::BinaryRef,
+    messaging::{
+        annotations::OwnedKey, Accepted, DeliveryState, Footer, Message, MessageAnnotations,
+        Outcome, Released, SerializableBody, MESSAGE_FORMAT,
+    },
+    primitives::{BinaryRef, Symbol, Timestamp, Value},
 };
 use futures_util::FutureExt;
 use pin_project_lite::pin_project;
-use std::{future::Future, marker::PhantomData, task::Poll};
-use tokio::sync::oneshot::{self, error::RecvError};
+use std::{
+    future::Future,
+    marker::PhantomData,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::{
+    mpsc,
+    oneshot::{self, error::RecvError},
+};
 
 use crate::{
-    endpoint::Settlement,
+    control::SessionControl,
+    endpoint::{InputHandle, Settlement},
     util::{Sealed, Uninitialized},
 };
 use crate::{util::AsDeliveryState, Payload};
 
-use super::{LinkStateError, SendError};
+use super::{ArcSenderUnsettledMap, LinkStateError, SendError};
+
+cfg_transaction! {
+    use fe2o3_amqp_types::transaction::TransactionId;
+}
+
+/// Link capability that indicates the support for delayed delivery
+pub const DELAYED_DELIVERY: &str = "DELAYED_DELIVERY";
+
+/// Message annotation key for the scheduled enqueue time used by Azure Service Bus
+pub const SCHEDULED_ENQUEUE_TIME: &str = "x-opt-scheduled-enqueue-time";
+
+/// Message annotation key for the scheduled delivery time used by ActiveMQ Artemis
+pub const DELIVERY_TIME: &str = "x-opt-delivery-time";
+
+/// Message annotation key for the delivery delay (in milliseconds) used by RabbitMQ
+pub const DELAY: &str = "x-delay";
 
 /// Delivery information that is needed for disposing a message
 #[derive(Clone)]
@@ -93,7 +122,14 @@ pub struct Delivery<T> {
     pub(crate) message_format: Option<MessageFormat>,
     pub(crate) rcv_settle_mode: Option<ReceiverSettleMode>,
 
+    /// Id of the transaction carried by a `TransactionalState` in the transfer frame
+    #[cfg(feature = "transaction")]
+    pub(crate) txn_id: Option<TransactionId>,
+
     pub(crate) message: Message<T>,
+
+    /// The payload of each transfer that the message is decoded from
+    pub(crate) raw_payload: Vec<Payload>,
 }
 
 impl<T> Delivery<T> {
@@ -107,6 +143,19 @@ impl<T> Delivery<T> {
         &self.message
     }
 
+    /// Get the raw bytes of the transfer payload that the message is decoded from
+    ///
+    /// These are the exact bytes received from the remote peer, which can be used to verify a
+    /// signature or for audit logging. The payloads of a delivery that is split across multiple
+    /// transfer frames are copied into a single buffer on every call, while the payload of a
+    /// single transfer is returned without copying
+    pub fn raw_payload(&self) -> Payload {
+        match self.raw_payload.as_slice() {
+            [payload] => payload.clone(),
+            payloads => payloads.concat().into(),
+        }
+    }
+
     /// Get the delivery ID
     pub fn delivery_id(&self) -> &DeliveryNumber {
         &self.delivery_id
@@ -122,6 +171,131 @@ impl<T> Delivery<T> {
         &self.message_format
     }
 
+    /// Get the receiver settle mode carried by the transfer frame, which overrides the receiver
+    /// settle mode negotiated on link attach for this delivery if set
+    pub fn rcv_settle_mode(&self) -> &Option<ReceiverSettleMode> {
+        &self.rcv_settle_mode
+    }
+
+    /// Get the id of the transaction that the delivery is associated with
+    ///
+    /// This is set if the transfer frame carries a `TransactionalState`, which happens when the
+    /// delivery is transferred as part of a transaction (eg. a transactional acquisition).
+    #[cfg_attr(docsrs, doc(cfg(feature = "transaction")))]
+    #[cfg(feature = "transaction")]
+    pub fn transactional_state(&self) -> Option<&TransactionId> {
+        self.txn_id.as_ref()
+    }
+
+    /// Get the `user-id` field of the message properties
+    ///
+    /// Brokers like RabbitMQ validate that the `user-id` matches the authenticated user
+    pub fn user_id(&self) -> Option<&[u8]> {
+        self.message
+            .properties
+            .as_ref()
+            .and_then(|p| p.user_id.as_ref())
+            .map(|user_id| user_id.as_slice())
+    }
+
+    /// Get the `content-type` field of the message properties
+    ///
+    /// This is the RFC-2046 MIME type of the `Data` body, eg. `application/json`
+    pub fn content_type(&self) -> Option<&Symbol> {
+        self.message
+            .properties
+            .as_ref()
+            .and_then(|p| p.content_type.as_ref())
+    }
+
+    /// Get the `content-encoding` field of the message properties
+    ///
+    /// This is the encoding applied to the `Data` body on top of the `content-type`, eg. `gzip`
+    pub fn content_encoding(&self) -> Option<&Symbol> {
+        self.message
+            .properties
+            .as_ref()
+            .and_then(|p| p.content_encoding.as_ref())
+    }
+
+    /// Get the footer of the message
+    ///
+    /// The footer may carry a signature (eg. an HMAC) computed over the bare message. The bytes of
+    /// the bare message can be obtained with
+    /// [`bare_message_to_vec`](fe2o3_amqp_types::messaging::bare_message_to_vec)
+    pub fn footer(&self) -> Option<&Footer> {
+        self.message.footer.as_ref()
+    }
+
+    /// Get the `delivery-count` field of the message header
+    ///
+    /// This is the number of unsuccessful previous attempts to deliver this message. A missing
+    /// header is treated as a count of zero
+    pub fn delivery_count(&self) -> u32 {
+        self.message
+            .header
+            .as_ref()
+            .map(|h| h.delivery_count)
+            .unwrap_or(0)
+    }
+
+    /// Get the `first-acquirer` field of the message header
+    ///
+    /// If this is true, the message has not been acquired by any other link. A missing header is
+    /// treated as `false`, which is the default value of the field
+    pub fn first_acquirer(&self) -> bool {
+        self.message
+            .header
+            .as_ref()
+            .map(|h| h.first_acquirer)
+            .unwrap_or(false)
+    }
+
+    /// Returns whether the message may have been delivered before
+    ///
+    /// This is true if the `delivery-count` field of the message header is greater than zero and
+    /// the `first-acquirer` field is not set. A `first-acquirer` of `false` alone does not mark a
+    /// redelivery because it is also the default value of a missing header, which most brokers
+    /// send on the first delivery.
+    pub fn is_redelivered(&self) -> bool {
+        !self.first_acquirer() && self.delivery_count() > 0
+    }
+
+    /// Get the `ttl` field of the message header
+    pub fn ttl(&self) -> Option<Duration> {
+        self.message
+            .header
+            .as_ref()
+            .and_then(|h| h.ttl)
+            .map(|millis| Duration::from_millis(millis as u64))
+    }
+
+    /// Get the time at which the message is considered expired
+    ///
+    /// This is the `absolute-expiry-time` of the message properties if present. Otherwise, if
+    /// both the `creation-time` of the message properties and the `ttl` of the message header
+    /// are present, the expiry is computed as `creation-time + ttl`.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        let properties = self.message.properties.as_ref();
+        if let Some(expiry) = properties.and_then(|p| p.absolute_expiry_time.as_ref()) {
+            return timestamp_to_system_time(expiry);
+        }
+
+        let creation_time = properties.and_then(|p| p.creation_time.as_ref())?;
+        let ttl = self.ttl()?;
+        timestamp_to_system_time(creation_time)?.checked_add(ttl)
+    }
+
+    /// Checks whether the message has expired at `now`
+    ///
+    /// Returns `false` if the expiry cannot be determined (see [`expires_at`](#method.expires_at))
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        match self.expires_at() {
+            Some(expires_at) => now >= expires_at,
+            None => false,
+        }
+    }
+
     /// Consume the delivery into the message
     pub fn into_message(self) -> Message<T> {
         self.message
@@ -152,6 +326,14 @@ impl<T> Delivery<T> {
     }
 }
 
+fn timestamp_to_system_time(timestamp: &Timestamp) -> Option<SystemTime> {
+    let millis = timestamp.milliseconds();
+    match millis >= 0 {
+        true => UNIX_EPOCH.checked_add(Duration::from_millis(millis as u64)),
+        false => UNIX_EPOCH.checked_sub(Duration::from_millis(millis.unsigned_abs())),
+    }
+}
+
 impl<T: std::fmt::Display> std::fmt::Display for Delivery<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -191,6 +373,13 @@ pub struct Sendable<T> {
     /// Please note that this field will be neglected if the negotiated
     /// sender settle mode is NOT equal to `SenderSettleMode::Mixed`
     pub settled: Option<bool>,
+
+    /// Overrides the receiver settle mode negotiated on link attach for this delivery
+    ///
+    /// This is ignored if the message is sent pre-settled. Please note that it is illegal to set
+    /// this to `ReceiverSettleMode::Second` if the negotiated receiver settle mode is
+    /// `ReceiverSettleMode::First`
+    pub rcv_settle_mode: Option<ReceiverSettleMode>,
 }
 
 impl Sendable<Uninitialized> {
@@ -210,6 +399,7 @@ where
             message: value.into(),
             message_format: MESSAGE_FORMAT,
             settled: None,
+            rcv_settle_mode: None,
         }
     }
 }
@@ -227,6 +417,9 @@ pub struct Builder<T> {
 
     /// Indicates whether the message is considered settled by the sender
     pub settled: Option<bool>,
+
+    /// Overrides the receiver settle mode negotiated on link attach for this delivery
+    pub rcv_settle_mode: Option<ReceiverSettleMode>,
     // pub batchable: bool,
 }
 
@@ -243,6 +436,7 @@ impl Builder<Uninitialized> {
             message: Uninitialized {},
             message_format: MESSAGE_FORMAT,
             settled: None,
+            rcv_settle_mode: None,
             // batchable: false,
         }
     }
@@ -255,6 +449,7 @@ impl<State> Builder<State> {
             message: message.into(),
             message_format: self.message_format,
             settled: self.settled,
+            rcv_settle_mode: self.rcv_settle_mode,
             // batchable: self.batchable,
         }
     }
@@ -272,15 +467,79 @@ impl<State> Builder<State> {
         self.settled = settled.into();
         self
     }
+
+    /// Overrides the receiver settle mode negotiated on link attach for this delivery
+    ///
+    /// This is ignored if the message is sent pre-settled. Please note that it is illegal to set
+    /// this to `ReceiverSettleMode::Second` if the negotiated receiver settle mode is
+    /// `ReceiverSettleMode::First`, and the remote receiver will detach the link with an error
+    pub fn rcv_settle_mode(
+        mut self,
+        rcv_settle_mode: impl Into<Option<ReceiverSettleMode>>,
+    ) -> Self {
+        self.rcv_settle_mode = rcv_settle_mode.into();
+        self
+    }
 }
 
 impl<T> Builder<Message<T>> {
+    /// Schedules the message to be enqueued at `time` by setting the
+    /// [`SCHEDULED_ENQUEUE_TIME`] message annotation
+    ///
+    /// This is the annotation used by Azure Service Bus. Please use
+    /// [`scheduled_enqueue_time_with_key()`](#method.scheduled_enqueue_time_with_key) for brokers
+    /// that use a different annotation (eg. [`DELIVERY_TIME`] for ActiveMQ Artemis). The sender
+    /// link should desire the [`DELAYED_DELIVERY`] capability (see
+    /// [`delayed_delivery()`](crate::link::builder::Builder::delayed_delivery)).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let sendable = Sendable::builder()
+    ///     .message("hello AMQP")
+    ///     .scheduled_enqueue_time(SystemTime::now() + Duration::from_secs(60))
+    ///     .build();
+    /// sender.send(sendable).await.unwrap();
+    /// ```
+    pub fn scheduled_enqueue_time(self, time: SystemTime) -> Self {
+        self.scheduled_enqueue_time_with_key(SCHEDULED_ENQUEUE_TIME, time)
+    }
+
+    /// Schedules the message to be enqueued at `time` by setting the message annotation `key` to
+    /// the corresponding timestamp
+    pub fn scheduled_enqueue_time_with_key(
+        self,
+        key: impl Into<OwnedKey>,
+        time: SystemTime,
+    ) -> Self {
+        let milliseconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_millis() as i64,
+            Err(err) => -(err.duration().as_millis() as i64),
+        };
+        self.message_annotation(key, Timestamp::from_milliseconds(milliseconds))
+    }
+
+    /// Delays the delivery of the message by `delay` by setting the message annotation `key` to
+    /// the delay in milliseconds (eg. [`DELAY`] for RabbitMQ)
+    pub fn delivery_delay_with_key(self, key: impl Into<OwnedKey>, delay: Duration) -> Self {
+        self.message_annotation(key, delay.as_millis() as i64)
+    }
+
+    fn message_annotation(mut self, key: impl Into<OwnedKey>, value: impl Into<Value>) -> Self {
+        self.message
+            .message_annotations
+            .get_or_insert_with(MessageAnnotations::default)
+            .insert(key.into(), value.into());
+        self
+    }
+
     /// Builds a [`Sendable`]
     pub fn build(self) -> Sendable<T> {
         Sendable {
             message: self.message,
             message_format: self.message_format,
             settled: self.settled,
+            rcv_settle_mode: self.rcv_settle_mode,
             // batchable: self.batchable,
         }
     }
@@ -292,6 +551,29 @@ impl<T> From<Builder<Message<T>>> for Sendable<T> {
     }
 }
 
+/// Callback invoked when a disposition from the remote peer settles an outgoing delivery
+#[derive(Clone)]
+pub(crate) struct OnSettled(Arc<dyn Fn(DeliveryTag, Option<DeliveryState>) + Send + Sync>);
+
+impl std::fmt::Debug for OnSettled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OnSettled").finish()
+    }
+}
+
+impl OnSettled {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(DeliveryTag, Option<DeliveryState>) + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    pub fn call(&self, delivery_tag: DeliveryTag, state: Option<DeliveryState>) {
+        (self.0)(delivery_tag, state)
+    }
+}
+
 /// An unsettled message stored in the Sender's unsettled map
 #[derive(Debug)]
 pub(crate) struct UnsettledMessage {
@@ -334,15 +616,71 @@ impl AsDeliveryState for UnsettledMessage {
     }
 }
 
+/// Settles an unsettled outgoing delivery as released when the corresponding `DeliveryFut` is
+/// dropped before it resolves
+pub(crate) struct SettleOnDrop {
+    pub(crate) session: mpsc::Sender<SessionControl>,
+    pub(crate) input_handle: InputHandle,
+    pub(crate) unsettled: ArcSenderUnsettledMap,
+}
+
+impl SettleOnDrop {
+    fn settle(self, delivery_tag: DeliveryTag) {
+        // The delivery is only removed from the unsettled map once the control message can be
+        // queued. Otherwise (ie. the session has stopped or its control channel is full), the
+        // delivery stays unsettled until the remote receiver settles it
+        let permit = match self.session.try_reserve() {
+            Ok(permit) => permit,
+            Err(_) => return,
+        };
+
+        let removed = {
+            let mut guard = self.unsettled.write();
+            guard.as_mut().and_then(|m| m.swap_remove(&delivery_tag))
+        };
+
+        // The delivery is no longer in the unsettled map if the remote receiver has already
+        // settled it
+        if removed.is_some() {
+            let control = SessionControl::SettleOutgoingDelivery {
+                input_handle: self.input_handle,
+                delivery_tag,
+                state: DeliveryState::Released(Released {}),
+            };
+            permit.send(control);
+        }
+    }
+}
+
 pin_project! {
     /// A future for delivery that can be `.await`ed for the settlement
     /// from receiver
+    ///
+    /// # Drop
+    ///
+    /// Dropping the future does not cancel the delivery by default. The delivery stays unsettled
+    /// until the remote receiver settles it, and its outcome is discarded. If the sender is
+    /// built with [`settle_on_fut_drop(true)`](crate::link::builder::Builder::settle_on_fut_drop),
+    /// dropping the future before it resolves settles the delivery locally with the `Released`
+    /// outcome.
     pub struct DeliveryFut<O> {
         #[pin]
         // Reserved for future use on actively sending disposition from Sender
         settlement: Settlement,
+        settle_on_drop: Option<SettleOnDrop>,
         outcome_marker: PhantomData<O>
     }
+
+    impl<O> PinnedDrop for DeliveryFut<O> {
+        fn drop(this: Pin<&mut Self>) {
+            let this = this.project();
+            if let (Some(settle_on_drop), Settlement::Unsettled { delivery_tag, .. }) =
+                (this.settle_on_drop.take(), &*this.settlement)
+            {
+                settle_on_drop.settle(delivery_tag.clone());
+            }
+        }
+    }
 }
 
 impl<O> DeliveryFut<O> {
@@ -356,12 +694,18 @@ impl<O> DeliveryFut<O> {
             } => delivery_tag,
         }
     }
+
+    pub(crate) fn with_settle_on_drop(mut self, settle_on_drop: Option<SettleOnDrop>) -> Self {
+        self.settle_on_drop = settle_on_drop;
+        self
+    }
 }
 
 impl<O> From<Settlement> for DeliveryFut<O> {
     fn from(settlement: Settlement) -> Self {
         Self {
             settlement,
+            settle_on_drop: None,
             outcome_marker: PhantomData,
         }
     }
@@ -454,6 +798,8 @@ where
                 match outcome.poll_unpin(cx) {
                     Poll::Pending => Poll::Pending,
                     Poll::Ready(result) => {
+                        // The delivery is resolved and nothing is left to settle on drop
+                        *this.settle_on_drop = None;
                         match result {
                             Ok(Some(state)) => Poll::Ready(O::from_delivery_state(state)),
                             Ok(None) => Poll::Ready(O::from_none()),
@@ -472,12 +818,27 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, UNIX_EPOCH},
+    };
+
     use fe2o3_amqp_types::{
-        messaging::{AmqpValue, Body, Data, Message},
-        primitives::Binary,
+        definitions::{Handle, ReceiverSettleMode},
+        messaging::{AmqpValue, Body, Data, DeliveryState, Footer, Header, Message, Properties},
+        primitives::{Binary, OrderedMap, Symbol, Timestamp},
+    };
+    use parking_lot::RwLock;
+    use tokio::sync::{mpsc, oneshot};
+
+    use crate::{
+        control::SessionControl,
+        endpoint::{InputHandle, Settlement},
+        link::ArcSenderUnsettledMap,
+        Payload, Sendable,
     };
 
-    use crate::Sendable;
+    use super::{Delivery, DeliveryFut, SendResult, SettleOnDrop, UnsettledMessage};
 
     struct Foo {}
 
@@ -518,4 +879,246 @@ mod tests {
         let sendable = Sendable::from(value);
         assert_eq!(sendable.message.body, Data(Binary::from("Foo")));
     }
+
+    #[test]
+    fn test_sendable_rcv_settle_mode() {
+        let sendable = Sendable::from("hello");
+        assert!(sendable.rcv_settle_mode.is_none());
+
+        let sendable = Sendable::builder()
+            .message("hello")
+            .rcv_settle_mode(ReceiverSettleMode::Second)
+            .build();
+        assert_eq!(sendable.rcv_settle_mode, Some(ReceiverSettleMode::Second));
+    }
+
+    fn delivery_with_message(message: Message<AmqpValue<bool>>) -> Delivery<AmqpValue<bool>> {
+        Delivery {
+            link_output_handle: Handle(0),
+            delivery_id: 0,
+            delivery_tag: Binary::from("tag"),
+            message_format: None,
+            rcv_settle_mode: None,
+            #[cfg(feature = "transaction")]
+            txn_id: None,
+            message,
+            raw_payload: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_delivery_content_type_and_encoding() {
+        let delivery = delivery_with_message(Message::builder().value(true).build());
+        assert_eq!(delivery.content_type(), None);
+        assert_eq!(delivery.content_encoding(), None);
+
+        let properties = Properties::builder()
+            .content_type("application/json")
+            .content_encoding("gzip")
+            .build();
+        let message = Message::builder()
+            .properties(properties)
+            .value(true)
+            .build();
+        let delivery = delivery_with_message(message);
+        assert_eq!(
+            delivery.content_type(),
+            Some(&Symbol::from("application/json"))
+        );
+        assert_eq!(delivery.content_encoding(), Some(&Symbol::from("gzip")));
+    }
+
+    #[test]
+    fn test_delivery_raw_payload() {
+        let b0 = Payload::from(vec![1, 2, 3]);
+        let b1 = Payload::from(vec![4, 5]);
+        let mut delivery = delivery_with_message(Message::builder().value(true).build());
+
+        delivery.raw_payload = vec![b0.clone()];
+        let raw_payload = delivery.raw_payload();
+        assert_eq!(raw_payload, b0);
+        // The payload of a single transfer is not copied
+        assert_eq!(raw_payload.as_ptr(), b0.as_ptr());
+
+        delivery.raw_payload = vec![b0, b1];
+        assert_eq!(delivery.raw_payload(), Payload::from(vec![1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_delivery_footer() {
+        let delivery = delivery_with_message(Message::builder().value(true).build());
+        assert!(delivery.footer().is_none());
+
+        let footer = Footer::builder()
+            .insert("signature", Binary::from(vec![1u8, 2, 3]))
+            .build();
+        let message = Message::builder()
+            .value(true)
+            .footer(footer.clone())
+            .build();
+        let delivery = delivery_with_message(message);
+        assert_eq!(delivery.footer(), Some(&footer));
+    }
+
+    #[test]
+    fn test_delivery_is_redelivered() {
+        let delivery = delivery_with_message(Message::builder().value(true).build());
+        assert_eq!(delivery.delivery_count(), 0);
+        assert!(!delivery.first_acquirer());
+        assert!(!delivery.is_redelivered());
+
+        let header = Header::builder().first_acquirer(true).build();
+        let message = Message::builder().header(header).value(true).build();
+        assert!(!delivery_with_message(message).is_redelivered());
+
+        let header = Header::builder().delivery_count(2).build();
+        let message = Message::builder().header(header).value(true).build();
+        let delivery = delivery_with_message(message);
+        assert_eq!(delivery.delivery_count(), 2);
+        assert!(delivery.is_redelivered());
+    }
+
+    #[test]
+    fn test_delivery_expiry() {
+        let header = Header {
+            ttl: Some(1000),
+            ..Default::default()
+        };
+
+        // ttl without creation-time
+        let message = Message::builder()
+            .header(header.clone())
+            .value(true)
+            .build();
+        let delivery = delivery_with_message(message);
+        assert_eq!(delivery.ttl(), Some(Duration::from_millis(1000)));
+        assert_eq!(delivery.expires_at(), None);
+        assert!(!delivery.is_expired(UNIX_EPOCH + Duration::from_secs(1_000_000)));
+
+        // creation-time + ttl
+        let properties = Properties::builder()
+            .creation_time(Timestamp::from_milliseconds(5000))
+            .build();
+        let message = Message::builder()
+            .header(header.clone())
+            .properties(properties)
+            .value(true)
+            .build();
+        let delivery = delivery_with_message(message);
+        let expires_at = UNIX_EPOCH + Duration::from_millis(6000);
+        assert_eq!(delivery.expires_at(), Some(expires_at));
+        assert!(!delivery.is_expired(expires_at - Duration::from_millis(1)));
+        assert!(delivery.is_expired(expires_at));
+
+        // absolute-expiry-time takes precedence
+        let properties = Properties::builder()
+            .creation_time(Timestamp::from_milliseconds(5000))
+            .absolute_expiry_time(Timestamp::from_milliseconds(10_000))
+            .build();
+        let message = Message::builder()
+            .header(header)
+            .properties(properties)
+            .value(true)
+            .build();
+        let delivery = delivery_with_message(message);
+        assert_eq!(
+            delivery.expires_at(),
+            Some(UNIX_EPOCH + Duration::from_millis(10_000))
+        );
+    }
+
+    #[test]
+    fn test_sendable_scheduled_enqueue_time() {
+        use fe2o3_amqp_types::{messaging::annotations::AnnotationKey, primitives::Value};
+
+        let time = UNIX_EPOCH + Duration::from_millis(10_000);
+        let sendable = Sendable::builder()
+            .message("hello")
+            .scheduled_enqueue_time(time)
+            .scheduled_enqueue_time_with_key(super::DELIVERY_TIME, time)
+            .delivery_delay_with_key(super::DELAY, Duration::from_secs(5))
+            .build();
+
+        let annotations = sendable.message.message_annotations.unwrap();
+        let get = |key: &str| annotations.get(&key as &dyn AnnotationKey).cloned();
+        assert_eq!(
+            get(super::SCHEDULED_ENQUEUE_TIME),
+            Some(Value::Timestamp(Timestamp::from_milliseconds(10_000)))
+        );
+        assert_eq!(
+            get(super::DELIVERY_TIME),
+            Some(Value::Timestamp(Timestamp::from_milliseconds(10_000)))
+        );
+        assert_eq!(get(super::DELAY), Some(Value::Long(5_000)));
+    }
+
+    fn unsettled_delivery_fut(
+        settle_on_fut_drop: bool,
+    ) -> (
+        DeliveryFut<SendResult>,
+        mpsc::Receiver<SessionControl>,
+        ArcSenderUnsettledMap,
+    ) {
+        let delivery_tag = Binary::from(vec![1u8]);
+        let unsettled: ArcSenderUnsettledMap = Arc::new(RwLock::new(None));
+        let (session_tx, session_rx) = mpsc::channel(1);
+
+        // The outcome will never be resolved by the remote peer in this test
+        let (outcome_tx, outcome_rx) = oneshot::channel();
+        let message = UnsettledMessage::new(Payload::new(), None, 0, outcome_tx);
+        unsettled
+            .write()
+            .get_or_insert(OrderedMap::new())
+            .insert(delivery_tag.clone(), message);
+
+        let settle_on_drop = settle_on_fut_drop.then(|| SettleOnDrop {
+            session: session_tx,
+            input_handle: InputHandle(0),
+            unsettled: unsettled.clone(),
+        });
+        let fut = DeliveryFut::from(Settlement::Unsettled {
+            delivery_tag,
+            outcome: outcome_rx,
+        })
+        .with_settle_on_drop(settle_on_drop);
+        (fut, session_rx, unsettled)
+    }
+
+    #[test]
+    fn test_delivery_fut_settle_on_drop() {
+        let (fut, mut session_rx, unsettled) = unsettled_delivery_fut(true);
+        drop(fut);
+
+        assert!(unsettled.read().as_ref().unwrap().is_empty());
+        match session_rx.try_recv().unwrap() {
+            SessionControl::SettleOutgoingDelivery {
+                input_handle,
+                delivery_tag,
+                state,
+            } => {
+                assert_eq!(input_handle, InputHandle(0));
+                assert_eq!(delivery_tag, Binary::from(vec![1u8]));
+                assert!(matches!(state, DeliveryState::Released(_)));
+            }
+            control => panic!("Unexpected control {}", control),
+        }
+    }
+
+    #[test]
+    fn test_delivery_fut_settle_on_drop_after_session_stopped() {
+        let (fut, session_rx, unsettled) = unsettled_delivery_fut(true);
+        drop(session_rx);
+        drop(fut);
+
+        // The control message can't be queued, so the delivery must stay unsettled
+        assert_eq!(unsettled.read().as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_delivery_fut_drop_without_settling() {
+        let (fut, _session_rx, unsettled) = unsettled_delivery_fut(false);
+        drop(fut);
+
+        assert_eq!(unsettled.read().as_ref().unwrap().len(), 1);
+    }
 }
@@ -1,4 +1,10 @@
-use fe2o3_amqp_types::definitions::{self, AmqpError, ErrorCondition, SessionError};
+use fe2o3_amqp_types::{
+    definitions::{
+        self, AmqpError, ConnectionError, DeliveryNumber, DeliveryTag, ErrorCondition, LinkError,
+        SessionError,
+    },
+    messaging::Outcome,
+};
 use serde_amqp::primitives::Symbol;
 
 use crate::session::error::AllocLinkError;
@@ -51,6 +57,12 @@ pub enum SenderAttachError {
     #[error("Link name is not unique.")]
     DuplicatedLinkName,
 
+    /// The maximum number of links on the session is reached. The limit can be raised with
+    /// [`Session::builder().handle_max()`](crate::session::Builder::handle_max) if the remote
+    /// peer allows it
+    #[error("The maximum number of links on the session is reached.")]
+    HandleMaxReached,
+
     /// Illegal link state
     #[error("Illegal session state")]
     IllegalState,
@@ -134,6 +146,11 @@ pub enum SendError {
     /// Error serializing message
     #[error("Error encoding message")]
     MessageEncodeError,
+
+    /// A pre-settled message cannot be sent because the negotiated sender settle mode is
+    /// `SenderSettleMode::Unsettled`
+    #[error("Cannot send a pre-settled message when the sender settle mode is unsettled")]
+    UnsettledSenderSettleMode,
 }
 
 impl From<serde_amqp::Error> for SendError {
@@ -201,6 +218,12 @@ pub enum ReceiverAttachError {
     #[error("Link name is not unique.")]
     DuplicatedLinkName,
 
+    /// The maximum number of links on the session is reached. The limit can be raised with
+    /// [`Session::builder().handle_max()`](crate::session::Builder::handle_max) if the remote
+    /// peer allows it
+    #[error("The maximum number of links on the session is reached.")]
+    HandleMaxReached,
+
     /// Illegal link state
     #[error("Illegal session state")]
     IllegalState,
@@ -264,11 +287,65 @@ pub enum ReceiverAttachError {
     DesiredFilterNotSupported(#[from] DesiredFilterNotSupported),
 }
 
+/// Whether an error condition sent by the remote peer in response to an attach may go away if the
+/// attach is retried
+fn is_transient_condition(condition: &ErrorCondition) -> bool {
+    matches!(
+        condition,
+        ErrorCondition::AmqpError(AmqpError::InternalError)
+            | ErrorCondition::AmqpError(AmqpError::ResourceLimitExceeded)
+            | ErrorCondition::AmqpError(AmqpError::ResourceLocked)
+            | ErrorCondition::AmqpError(AmqpError::IllegalState)
+            | ErrorCondition::ConnectionError(ConnectionError::ConnectionForced)
+            | ErrorCondition::LinkError(LinkError::DetachForced)
+    )
+}
+
+impl SenderAttachError {
+    /// Whether the error is transient, ie. retrying the attach may succeed
+    ///
+    /// Only errors caused by the timing of the attach are transient, ie.
+    ///
+    /// - `IllegalState`, eg. a link with the same handle is still being detached
+    /// - `HandleMaxReached`, another link may be detached in the meantime
+    /// - `RemoteClosedWithError` with one of the conditions `amqp:internal-error`,
+    ///   `amqp:resource-limit-exceeded`, `amqp:resource-locked`, `amqp:illegal-state`,
+    ///   `amqp:connection:forced` or `amqp:link:detach-forced`
+    ///
+    /// Errors with the session, the configuration of the link or any other error condition from
+    /// the remote peer (eg. `amqp:unauthorized-access` or `amqp:not-found`) are permanent.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            SenderAttachError::HandleMaxReached | SenderAttachError::IllegalState => true,
+            SenderAttachError::RemoteClosedWithError(error) => {
+                is_transient_condition(&error.condition)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ReceiverAttachError {
+    /// Whether the error is transient, ie. retrying the attach may succeed
+    ///
+    /// See [`SenderAttachError::is_transient`] for which errors are considered transient
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ReceiverAttachError::HandleMaxReached | ReceiverAttachError::IllegalState => true,
+            ReceiverAttachError::RemoteClosedWithError(error) => {
+                is_transient_condition(&error.condition)
+            }
+            _ => false,
+        }
+    }
+}
+
 impl From<AllocLinkError> for ReceiverAttachError {
     fn from(value: AllocLinkError) -> Self {
         match value {
             AllocLinkError::IllegalSessionState => Self::IllegalSessionState,
             AllocLinkError::DuplicatedLinkName => Self::DuplicatedLinkName,
+            AllocLinkError::HandleMaxReached => Self::HandleMaxReached,
         }
     }
 }
@@ -306,6 +383,7 @@ impl From<AllocLinkError> for SenderAttachError {
         match value {
             AllocLinkError::IllegalSessionState => Self::IllegalSessionState,
             AllocLinkError::DuplicatedLinkName => Self::DuplicatedLinkName,
+            AllocLinkError::HandleMaxReached => Self::HandleMaxReached,
         }
     }
 }
@@ -482,6 +560,24 @@ pub(crate) enum ReceiverTransferError {
     /// Field is inconsisten in multi-frame delivery
     #[error("Field is inconsisten in multi-frame delivery")]
     InconsistentFieldInMultiFrameDelivery,
+
+    /// The delivery-id of a transfer is different from that of the multi-transfer delivery in
+    /// progress
+    #[error("Expecting delivery-id {expected} in multi-transfer delivery, found {found}")]
+    UnexpectedDeliveryId {
+        /// The delivery-id of the multi-transfer delivery in progress
+        expected: DeliveryNumber,
+        /// The delivery-id of the transfer
+        found: DeliveryNumber,
+    },
+
+    /// A transfer without a delivery-id is received while there is no multi-transfer delivery in
+    /// progress, ie. the preceding transfer did not have the `more` flag set
+    #[error("Continuation transfer (delivery-tag: {delivery_tag:?}) received without a preceding transfer with more=true")]
+    InconsistentMore {
+        /// The delivery-tag of the transfer
+        delivery_tag: Option<DeliveryTag>,
+    },
 }
 
 /// Error decoding message
@@ -534,9 +630,34 @@ pub enum RecvError {
     #[error("Field is inconsisten in multi-frame delivery")]
     InconsistentFieldInMultiFrameDelivery,
 
+    /// The delivery-id of a transfer is different from that of the multi-transfer delivery in
+    /// progress
+    #[error("Expecting delivery-id {expected} in multi-transfer delivery, found {found}")]
+    UnexpectedDeliveryId {
+        /// The delivery-id of the multi-transfer delivery in progress
+        expected: DeliveryNumber,
+        /// The delivery-id of the transfer
+        found: DeliveryNumber,
+    },
+
+    /// A transfer without a delivery-id is received while there is no multi-transfer delivery in
+    /// progress, ie. the preceding transfer did not have the `more` flag set
+    #[error("Continuation transfer (delivery-tag: {delivery_tag:?}) received without a preceding transfer with more=true")]
+    InconsistentMore {
+        /// The delivery-tag of the transfer
+        delivery_tag: Option<DeliveryTag>,
+    },
+
     /// Transactional acquision is not supported yet
     #[error("Transactional acquisition is not implemented")]
     TransactionalAcquisitionIsNotImeplemented,
+
+    /// The remote peer forcibly detached the link with the `amqp:link:detach-forced` condition
+    /// (eg. due to inactivity)
+    ///
+    /// Unlike other errors from the remote peer, the link may be attached again.
+    #[error("Link detached by broker: {}", .0)]
+    LinkDetachedByBroker(definitions::Error),
 }
 
 impl From<ReceiverTransferError> for RecvError {
@@ -552,6 +673,12 @@ impl From<ReceiverTransferError> for RecvError {
             ReceiverTransferError::InconsistentFieldInMultiFrameDelivery => {
                 RecvError::InconsistentFieldInMultiFrameDelivery
             }
+            ReceiverTransferError::UnexpectedDeliveryId { expected, found } => {
+                RecvError::UnexpectedDeliveryId { expected, found }
+            }
+            ReceiverTransferError::InconsistentMore { delivery_tag } => {
+                RecvError::InconsistentMore { delivery_tag }
+            }
             ReceiverTransferError::IllegalState => {
                 RecvError::LinkStateError(LinkStateError::IllegalState)
             }
@@ -577,6 +704,51 @@ pub enum IllegalLinkStateError {
     IllegalSessionState,
 }
 
+/// Errors associated with sending a Disposition without waiting for capacity in the outgoing
+/// channel
+#[derive(Debug, thiserror::Error)]
+pub enum TryDispositionError {
+    /// The outgoing channel to the session is full. The delivery is left untouched and the
+    /// disposition can be retried
+    #[error("The outgoing channel to the session is full")]
+    Full,
+
+    /// ILlegal link state
+    #[error("Illegal local state")]
+    IllegalState,
+
+    /// Session has dropped
+    #[error("Session has dropped")]
+    IllegalSessionState,
+}
+
+/// Errors associated with settling a range of delivery ids
+#[derive(Debug, thiserror::Error)]
+pub enum DispositionRangeError {
+    /// The first delivery id of the range is greater than the last one
+    #[error("The first delivery id is greater than the last delivery id")]
+    InvalidRange,
+
+    /// ILlegal link state
+    #[error("Illegal local state")]
+    IllegalState,
+
+    /// Session has dropped
+    #[error("Session has dropped")]
+    IllegalSessionState,
+}
+
+impl From<IllegalLinkStateError> for DispositionRangeError {
+    fn from(value: IllegalLinkStateError) -> Self {
+        match value {
+            IllegalLinkStateError::IllegalState => DispositionRangeError::IllegalState,
+            IllegalLinkStateError::IllegalSessionState => {
+                DispositionRangeError::IllegalSessionState
+            }
+        }
+    }
+}
+
 impl From<IllegalLinkStateError> for LinkStateError {
     fn from(value: IllegalLinkStateError) -> Self {
         match value {
@@ -629,7 +801,18 @@ where
     T: Into<LinkStateError>,
 {
     fn from(value: T) -> Self {
-        Self::LinkStateError(value.into())
+        match value.into() {
+            LinkStateError::RemoteDetachedWithError(error)
+            | LinkStateError::RemoteClosedWithError(error)
+                if matches!(
+                    error.condition,
+                    ErrorCondition::LinkError(LinkError::DetachForced)
+                ) =>
+            {
+                Self::LinkDetachedByBroker(error)
+            }
+            error => Self::LinkStateError(error),
+        }
     }
 }
 
@@ -782,6 +965,18 @@ pub enum DetachThenResumeSenderError {
     Resume(#[from] SenderResumeErrorKind),
 }
 
+/// Error with `Sender::redirect`
+#[derive(Debug, thiserror::Error)]
+pub enum SenderRedirectError {
+    /// Error with closing the sender
+    #[error(transparent)]
+    Detach(#[from] DetachError),
+
+    /// Error with attaching the sender to the new target
+    #[error(transparent)]
+    Attach(#[from] SenderAttachError),
+}
+
 /// Error with `Receiver::detach_then_resume_on_session`
 #[derive(Debug, thiserror::Error)]
 pub enum DetachThenResumeReceiverError {
@@ -793,3 +988,76 @@ pub enum DetachThenResumeReceiverError {
     #[error(transparent)]
     Resume(#[from] ReceiverResumeErrorKind),
 }
+
+/// Error with attaching a [`RequestReplyClient`](super::request_reply::RequestReplyClient)
+#[derive(Debug, thiserror::Error)]
+pub enum RequestReplyAttachError {
+    /// Error with attaching the sender
+    #[error(transparent)]
+    Sender(#[from] SenderAttachError),
+
+    /// Error with attaching the dynamic receiver
+    #[error(transparent)]
+    Receiver(#[from] ReceiverAttachError),
+
+    /// The remote peer didn't assign an address to the dynamic receiver
+    #[error("The remote peer didn't assign an address to the dynamic receiver")]
+    DynamicAddressNotAssigned,
+}
+
+/// Error with sending a request with a
+/// [`RequestReplyClient`](super::request_reply::RequestReplyClient)
+#[derive(Debug, thiserror::Error)]
+pub enum RequestReplyError {
+    /// Error with sending the request
+    #[error(transparent)]
+    Send(#[from] SendError),
+
+    /// The request is not accepted by the remote peer
+    #[error("The request is not accepted: {:?}", .0)]
+    NotAccepted(Outcome),
+
+    /// Error with receiving the reply
+    #[error(transparent)]
+    Recv(#[from] RecvError),
+
+    /// Error with accepting the reply
+    #[error(transparent)]
+    Disposition(#[from] DispositionError),
+}
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::definitions::{self, AmqpError, LinkError};
+
+    use super::{ReceiverAttachError, SenderAttachError};
+
+    #[test]
+    fn attach_error_is_transient() {
+        let resource_limit = definitions::Error::new(AmqpError::ResourceLimitExceeded, None, None);
+        let detach_forced = definitions::Error::new(LinkError::DetachForced, None, None);
+        assert!(SenderAttachError::IllegalState.is_transient());
+        assert!(SenderAttachError::HandleMaxReached.is_transient());
+        assert!(SenderAttachError::RemoteClosedWithError(resource_limit).is_transient());
+        assert!(ReceiverAttachError::IllegalState.is_transient());
+        assert!(ReceiverAttachError::RemoteClosedWithError(detach_forced).is_transient());
+    }
+
+    #[test]
+    fn attach_error_is_permanent() {
+        let unauthorized = definitions::Error::new(AmqpError::UnauthorizedAccess, None, None);
+        let not_found = definitions::Error::new(AmqpError::NotFound, None, None);
+        let custom = definitions::Error::new(
+            definitions::ErrorCondition::Custom("com.example:busy".into()),
+            None,
+            None,
+        );
+        assert!(!SenderAttachError::IllegalSessionState.is_transient());
+        assert!(!SenderAttachError::DuplicatedLinkName.is_transient());
+        assert!(!SenderAttachError::RemoteClosedWithError(unauthorized.clone()).is_transient());
+        assert!(!SenderAttachError::RemoteClosedWithError(custom).is_transient());
+        assert!(!ReceiverAttachError::RcvSettleModeNotSupported.is_transient());
+        assert!(!ReceiverAttachError::RemoteClosedWithError(unauthorized).is_transient());
+        assert!(!ReceiverAttachError::RemoteClosedWithError(not_found).is_transient());
+    }
+}
@@ -1,4 +1,5 @@
 use fe2o3_amqp_types::performatives::{Attach, Detach, Disposition, Transfer};
+use tokio::sync::oneshot;
 
 use crate::{
     endpoint::{InputHandle, LinkFlow},
@@ -25,6 +26,10 @@ pub(crate) enum LinkFrame {
     Disposition(Disposition),
     Detach(Detach),
 
+    /// Asking the session to notify once all the transfers sent before this frame and buffered by
+    /// the session are handed to the connection
+    Flush(oneshot::Sender<()>),
+
     #[cfg(feature = "transaction")]
     /// Indicating to the receiver that Txn controller side is requesting for
     /// a transactional acquisition
@@ -48,6 +53,7 @@ impl std::fmt::Debug for LinkFrame {
                 .finish(),
             Self::Disposition(arg0) => f.debug_tuple("Disposition").field(arg0).finish(),
             Self::Detach(arg0) => f.debug_tuple("Detach").field(arg0).finish(),
+            Self::Flush(_) => f.debug_tuple("Flush").finish(),
             #[cfg(feature = "transaction")]
             Self::Acquisition(arg0) => f.debug_tuple("Acquisition").field(arg0).finish(),
         }
@@ -0,0 +1,74 @@
+//! Identity of a link endpoint
+
+use std::fmt;
+
+use crate::{endpoint::OutputHandle, session::SessionId};
+
+/// Identity of a link endpoint that can be used as a key for logging and metrics
+///
+/// A link is identified by the container-id of the local connection, the local channel of the
+/// session, the local (output) handle and the name of the link. The handle is `None` if the link
+/// is currently detached.
+///
+/// The [`Display`](std::fmt::Display) implementation formats the identity as
+/// `<container-id>/<channel>/<handle>/<name>`, where a detached link has `-` as its handle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LinkId {
+    /// Container id of the local connection
+    pub container_id: String,
+
+    /// Local channel of the session
+    pub channel: u16,
+
+    /// Local handle of the link. This is `None` if the link is detached
+    pub handle: Option<u32>,
+
+    /// Name of the link
+    pub name: String,
+}
+
+impl LinkId {
+    pub(crate) fn new(session_id: &SessionId, handle: Option<&OutputHandle>, name: &str) -> Self {
+        Self {
+            container_id: session_id.container_id.clone(),
+            channel: session_id.channel.0,
+            handle: handle.map(|handle| handle.0),
+            name: name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for LinkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/", self.container_id, self.channel)?;
+        match self.handle {
+            Some(handle) => write!(f, "{}", handle)?,
+            None => write!(f, "-")?,
+        }
+        write!(f, "/{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        endpoint::{OutgoingChannel, OutputHandle},
+        session::SessionId,
+    };
+
+    use super::LinkId;
+
+    #[test]
+    fn test_link_id_display() {
+        let session_id = SessionId {
+            container_id: String::from("client"),
+            channel: OutgoingChannel(3),
+        };
+
+        let id = LinkId::new(&session_id, Some(&OutputHandle(7)), "sender-link");
+        assert_eq!(id.to_string(), "client/3/7/sender-link");
+
+        let id = LinkId::new(&session_id, None, "sender-link");
+        assert_eq!(id.to_string(), "client/3/-/sender-link");
+    }
+}
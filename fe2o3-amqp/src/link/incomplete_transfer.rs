@@ -42,13 +42,20 @@ impl IncompleteTransfer {
         Self {
             performative: transfer,
             buffer: vec![partial_payload], // TODO: handle payload split across re-attachment
-            section_number: Some(number),
+            // The section number is the zero-based index of the last section in the buffer
+            section_number: Some(number.saturating_sub(1)),
             section_offset: offset,
         }
     }
 
     /// Like `|=` operator but works on the field level
     pub fn or_assign(&mut self, other: Transfer) -> Result<(), ReceiverTransferError> {
+        if let (Some(expected), Some(found)) = (self.performative.delivery_id, other.delivery_id) {
+            if expected != found {
+                return Err(ReceiverTransferError::UnexpectedDeliveryId { expected, found });
+            }
+        }
+
         or_assign! {
             self, other,
             delivery_id,
@@ -163,3 +170,72 @@ impl IncompleteTransfer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use fe2o3_amqp_types::{definitions::Handle, performatives::Transfer};
+
+    use crate::link::ReceiverTransferError;
+
+    use super::IncompleteTransfer;
+
+    fn transfer(delivery_id: Option<u32>, more: bool) -> Transfer {
+        Transfer {
+            handle: Handle(0),
+            delivery_id,
+            delivery_tag: delivery_id.map(|id| id.to_be_bytes().to_vec().into()),
+            message_format: Some(0),
+            settled: Some(false),
+            more,
+            rcv_settle_mode: None,
+            state: None,
+            resume: false,
+            aborted: false,
+            batchable: true,
+        }
+    }
+
+    #[test]
+    fn test_or_assign_unexpected_delivery_id() {
+        let mut incomplete = IncompleteTransfer::new(transfer(Some(1), true), Bytes::new());
+
+        // Continuation transfers may omit or repeat the delivery-id
+        assert!(incomplete.or_assign(transfer(None, true)).is_ok());
+        assert!(incomplete.or_assign(transfer(Some(1), true)).is_ok());
+
+        let result = incomplete.or_assign(transfer(Some(2), false));
+        assert!(matches!(
+            result,
+            Err(ReceiverTransferError::UnexpectedDeliveryId {
+                expected: 1,
+                found: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_section_number_and_offset() {
+        use fe2o3_amqp_types::messaging::{message_to_vec, Message, Properties};
+
+        let properties = Properties::builder().message_id(1u64).build();
+        let message = Message::builder()
+            .properties(properties.clone())
+            .data(vec![1u8; 32])
+            .build();
+        let mut payload = Bytes::from(message_to_vec(&message).unwrap());
+        let properties_len = serde_amqp::to_vec(&properties).unwrap().len();
+
+        // The first transfer only carries part of the properties section
+        let first = payload.split_to(5);
+        let mut incomplete = IncompleteTransfer::new(transfer(Some(1), true), first);
+        assert_eq!(incomplete.section_number, Some(0));
+        assert_eq!(incomplete.section_offset, 5);
+
+        // The second transfer carries the rest of the message
+        let data_len = payload.len() - (properties_len - 5);
+        incomplete.append(payload);
+        assert_eq!(incomplete.section_number, Some(1));
+        assert_eq!(incomplete.section_offset, data_len as u64);
+    }
+}
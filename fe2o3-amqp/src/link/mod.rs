@@ -5,7 +5,7 @@ use std::{marker::PhantomData, sync::Arc};
 use bytes::{BufMut, BytesMut};
 use fe2o3_amqp_types::{
     definitions::{
-        self, DeliveryNumber, DeliveryTag, MessageFormat, ReceiverSettleMode, Role,
+        self, DeliveryNumber, DeliveryTag, Fields, MessageFormat, ReceiverSettleMode, Role,
         SenderSettleMode, SequenceNo, SessionError,
     },
     messaging::{DeliveryState, Received, Source, Target, TargetArchetype},
@@ -14,9 +14,14 @@ use fe2o3_amqp_types::{
 };
 
 pub use error::*;
+pub use id::LinkId;
+pub use properties::LinkProperties;
+pub use retry::RetryPolicy;
+pub use state::LinkFlowSnapshot;
 
 use parking_lot::RwLock;
 pub use receiver::Receiver;
+pub use request_reply::RequestReplyClient;
 pub use sender::Sender;
 use serde::Serialize;
 use serde_amqp::ser::Serializer;
@@ -25,7 +30,7 @@ use tokio::sync::{mpsc, oneshot};
 use crate::{
     control::SessionControl,
     endpoint::{self, InputHandle, LinkAttach, LinkDetach, LinkFlow, OutputHandle, Settlement},
-    link::delivery::UnsettledMessage,
+    link::delivery::{OnSettled, UnsettledMessage},
     util::{AsDeliveryState, Consumer, Produce, Producer},
     Payload,
 };
@@ -46,10 +51,14 @@ pub(crate) use frame::*;
 pub mod builder;
 pub mod delivery;
 mod error;
+mod id;
 mod incomplete_transfer;
+mod properties;
 pub mod receiver;
 mod receiver_link;
+pub mod request_reply;
 pub(crate) mod resumption;
+pub mod retry;
 pub mod sender;
 mod sender_link;
 pub(crate) mod shared_inner;
@@ -87,13 +96,13 @@ pub mod role {
     use fe2o3_amqp_types::definitions::Role;
 
     /// Type state for link::builder::Builder
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct SenderMarker {
         _private: (),
     }
 
     /// Type state for link::builder::Builder
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub struct ReceiverMarker {
         _private: (),
     }
@@ -205,12 +214,18 @@ pub(crate) struct Link<R, T, F, M> {
     pub(crate) offered_capabilities: Option<Vec<Symbol>>, // TODO: Add accessor fns
     pub(crate) desired_capabilities: Option<Vec<Symbol>>, // TODO: Add accessor fns
 
+    /// Properties carried by the remote peer's `Attach`
+    pub(crate) remote_properties: Option<Fields>,
+
     /// See Section 2.6.7 Flow Control
     pub(crate) flow_state: F,
     pub(crate) unsettled: ArcUnsettledMap<M>,
 
     pub(crate) verify_incoming_source: bool,
     pub(crate) verify_incoming_target: bool,
+
+    /// The maximum number of unsettled entries carried by an attach when resuming the link
+    pub(crate) max_unsettled_in_attach: Option<usize>,
 }
 
 impl<R, T, F, M> Link<R, T, F, M>
@@ -220,49 +235,37 @@ where
     F: AsRef<LinkFlowState<R>> + Send + Sync,
     M: AsDeliveryState + Send + Sync,
 {
+    /// Returns at most `max_len` entries of the unsettled map and whether any entry is left out
     fn get_unsettled_map(
         &self,
         is_reattaching: bool,
-        partial_unsettled: usize,
-    ) -> Option<OrderedMap<DeliveryTag, Option<DeliveryState>>> {
+        max_len: usize,
+    ) -> (Option<OrderedMap<DeliveryTag, Option<DeliveryState>>>, bool) {
         // When reattaching (as opposed to resuming), the unsettled map MUST be null.
         if is_reattaching {
-            return None;
+            return (None, false);
         }
 
         let guard = self.unsettled.read();
-        let map = guard.as_ref()?;
-        match (map.len(), partial_unsettled) {
-            (0, _) => None,
-            (_, 0..=1) => {
-                let v = map
-                    .iter()
-                    .map(|(key, val)| (key.clone(), val.as_delivery_state().clone()))
-                    .collect();
-                Some(v)
-            }
-            (total, denom) => {
-                let len = total / denom;
-                let v = (0..len)
-                    .zip(map.iter())
-                    .map(|(_, (key, val))| (key.clone(), val.as_delivery_state().clone()))
-                    .collect();
-                Some(v)
-            }
-        }
-    }
-
-    fn as_complete_attach(&self, handle: OutputHandle, is_reattaching: bool) -> Attach {
-        self.as_attach_inner(handle, is_reattaching, 1)
+        let map = match guard.as_ref() {
+            Some(map) if !map.is_empty() => map,
+            _ => return (None, false),
+        };
+        let v = map
+            .iter()
+            .take(max_len)
+            .map(|(key, val)| (key.clone(), val.as_delivery_state().clone()))
+            .collect();
+        (Some(v), max_len < map.len())
     }
 
     fn as_attach_inner(
         &self,
         handle: OutputHandle,
         is_reattaching: bool,
-        partial_unsettled: usize,
+        max_len: usize,
     ) -> Attach {
-        let unsettled = self.get_unsettled_map(is_reattaching, partial_unsettled);
+        let (unsettled, incomplete_unsettled) = self.get_unsettled_map(is_reattaching, max_len);
 
         let max_message_size = match self.max_message_size {
             0 => None,
@@ -270,7 +273,6 @@ where
         };
         let initial_delivery_count = Some(self.flow_state.as_ref().initial_delivery_count());
         let properties = self.flow_state.as_ref().properties();
-        let incomplete_unsettled = !matches!(partial_unsettled, 0..=1);
 
         Attach {
             name: self.name.clone(),
@@ -295,33 +297,33 @@ where
         }
     }
 
+    /// Halves the number of unsettled entries carried by the attach until it fits in
+    /// `max_frame_size`. The remaining entries are left out with `incomplete_unsettled` set
     fn as_maybe_incomplete_attach(
         &self,
         max_frame_size: usize,
         handle: OutputHandle,
         is_reattaching: bool,
+        unsettled_map_len: usize,
     ) -> Result<Attach, SendAttachErrorKind> {
-        let mut denominator = 1usize; // This is going to be the denominator
+        let mut max_len = self
+            .max_unsettled_in_attach
+            .map_or(unsettled_map_len, |max| max.min(unsettled_map_len));
         let mut buf = BytesMut::new();
 
-        let mut attach = self.as_attach_inner(handle.clone(), is_reattaching, denominator);
-        let mut serializer = Serializer::from((&mut buf).writer());
-        attach
-            .serialize(&mut serializer)
-            .map_err(|_| SendAttachErrorKind::IllegalState)?; // This should not happen
-
-        while buf.len() > max_frame_size {
-            buf.clear();
-            denominator *= 2;
-
-            attach = self.as_attach_inner(handle.clone(), is_reattaching, denominator);
+        loop {
+            let attach = self.as_attach_inner(handle.clone(), is_reattaching, max_len);
             let mut serializer = Serializer::from((&mut buf).writer());
             attach
                 .serialize(&mut serializer)
                 .map_err(|_| SendAttachErrorKind::IllegalState)?; // This should not happen
-        }
 
-        Ok(attach)
+            if buf.len() <= max_frame_size || max_len == 0 {
+                return Ok(attach);
+            }
+            buf.clear();
+            max_len /= 2;
+        }
     }
 
     /// # Cancel safety
@@ -353,10 +355,10 @@ where
         };
 
         let attach = match unsettled_map_len {
-            Some(0) | None => self.as_complete_attach(handle, is_reattaching),
-            Some(_) => {
+            Some(0) | None => self.as_attach_inner(handle, is_reattaching, 0),
+            Some(len) => {
                 let max_frame_size = get_max_frame_size(session).await?; // FIXME: cancel safe?
-                self.as_maybe_incomplete_attach(max_frame_size, handle, is_reattaching)?
+                self.as_maybe_incomplete_attach(max_frame_size, handle, is_reattaching, len)?
             }
         };
         let incomplete_unsettled = attach.incomplete_unsettled;
@@ -530,6 +532,7 @@ pub(crate) enum LinkRelay<O> {
         flow_state: SenderRelayFlowState,
         unsettled: ArcSenderUnsettledMap,
         receiver_settle_mode: ReceiverSettleMode,
+        on_settled: Option<OnSettled>,
     },
     Receiver {
         tx: mpsc::Sender<LinkIncomingItem>,
@@ -538,6 +541,7 @@ pub(crate) enum LinkRelay<O> {
         unsettled: ArcReceiverUnsettledMap,
         receiver_settle_mode: ReceiverSettleMode,
         more: bool,
+        on_settled: Option<OnSettled>,
     },
 }
 
@@ -546,6 +550,7 @@ impl LinkRelay<()> {
         tx: mpsc::Sender<LinkIncomingItem>,
         flow_state: SenderRelayFlowState,
         unsettled: ArcSenderUnsettledMap,
+        on_settled: Option<OnSettled>,
     ) -> Self {
         Self::Sender {
             tx,
@@ -553,6 +558,7 @@ impl LinkRelay<()> {
             flow_state,
             unsettled,
             receiver_settle_mode: Default::default(),
+            on_settled,
         }
     }
 
@@ -561,6 +567,7 @@ impl LinkRelay<()> {
         flow_state: ReceiverRelayFlowState,
         unsettled: ArcReceiverUnsettledMap,
         receiver_settle_mode: ReceiverSettleMode,
+        on_settled: Option<OnSettled>,
     ) -> Self {
         Self::Receiver {
             tx,
@@ -569,6 +576,7 @@ impl LinkRelay<()> {
             unsettled,
             receiver_settle_mode,
             more: false,
+            on_settled,
         }
     }
 
@@ -579,6 +587,7 @@ impl LinkRelay<()> {
                 flow_state,
                 unsettled,
                 receiver_settle_mode,
+                on_settled,
                 ..
             } => LinkRelay::Sender {
                 tx,
@@ -586,6 +595,7 @@ impl LinkRelay<()> {
                 flow_state,
                 unsettled,
                 receiver_settle_mode,
+                on_settled,
             },
             LinkRelay::Receiver {
                 tx,
@@ -593,6 +603,7 @@ impl LinkRelay<()> {
                 unsettled,
                 receiver_settle_mode,
                 more,
+                on_settled,
                 ..
             } => LinkRelay::Receiver {
                 tx,
@@ -601,12 +612,20 @@ impl LinkRelay<()> {
                 unsettled,
                 receiver_settle_mode,
                 more,
+                on_settled,
             },
         }
     }
 }
 
 impl LinkRelay<OutputHandle> {
+    pub(crate) fn output_handle(&self) -> &OutputHandle {
+        match self {
+            LinkRelay::Sender { output_handle, .. } => output_handle,
+            LinkRelay::Receiver { output_handle, .. } => output_handle,
+        }
+    }
+
     pub(crate) async fn send(
         &mut self,
         frame: LinkFrame,
@@ -672,6 +691,7 @@ impl LinkRelay<OutputHandle> {
             LinkRelay::Sender {
                 unsettled,
                 receiver_settle_mode,
+                on_settled,
                 ..
             } => {
                 let echo = if settled {
@@ -681,12 +701,12 @@ impl LinkRelay<OutputHandle> {
 
                     // Since we are settling (ie. forgetting) this message, we don't care whether the
                     // receiving end is alive or not
-                    {
+                    let removed = {
                         let mut guard = unsettled.write();
-                        guard
-                            .as_mut()
-                            .and_then(|m| m.swap_remove(&delivery_tag))
-                            .map(|msg| msg.settle_with_state(state));
+                        guard.as_mut().and_then(|m| m.swap_remove(&delivery_tag))
+                    };
+                    if let Some(msg) = removed {
+                        settle_unsettled_message(msg, delivery_tag, state, on_settled.as_ref());
                     }
                     false
                 } else {
@@ -694,19 +714,20 @@ impl LinkRelay<OutputHandle> {
                         Some(s) => s.is_terminal(),
                         None => false, // Probably should not assume the state is not specified
                     };
-                    {
+                    // Once the receiving application has finished processing the message,
+                    // it indicates to the link endpoint a **terminal delivery state** that
+                    // reflects the outcome of the application processing
+                    if is_terminal {
+                        let removed = {
+                            let mut guard = unsettled.write();
+                            guard.as_mut().and_then(|m| m.swap_remove(&delivery_tag))
+                        };
+                        if let Some(msg) = removed {
+                            settle_unsettled_message(msg, delivery_tag, state, on_settled.as_ref());
+                        }
+                    } else {
                         let mut guard = unsettled.write();
-                        // Once the receiving application has finished processing the message,
-                        // it indicates to the link endpoint a **terminal delivery state** that
-                        // reflects the outcome of the application processing
-                        if is_terminal {
-                            let _result = guard
-                                .as_mut()
-                                .and_then(|m| m.swap_remove(&delivery_tag))
-                                .map(|msg| msg.settle_with_state(state));
-                        } else if let Some(msg) =
-                            guard.as_mut().and_then(|m| m.get_mut(&delivery_tag))
-                        {
+                        if let Some(msg) = guard.as_mut().and_then(|m| m.get_mut(&delivery_tag)) {
                             msg.state = state;
                         }
                     }
@@ -731,11 +752,21 @@ impl LinkRelay<OutputHandle> {
 
                 echo
             }
-            LinkRelay::Receiver { unsettled, .. } => {
+            LinkRelay::Receiver {
+                unsettled,
+                on_settled,
+                ..
+            } => {
                 if settled {
-                    let mut guard = unsettled.write();
                     // let _state = remove_from_unsettled(unsettled, &delivery_tag).await;
-                    let _state = guard.as_mut().and_then(|m| m.swap_remove(&delivery_tag));
+                    let removed = {
+                        let mut guard = unsettled.write();
+                        guard.as_mut().and_then(|m| m.swap_remove(&delivery_tag))
+                    };
+                    // The lock is released before invoking the callback
+                    if let (Some(_), Some(on_settled)) = (removed, on_settled.as_ref()) {
+                        on_settled.call(delivery_tag, state);
+                    }
                 } else {
                     let mut guard = unsettled.write();
                     if let Some(msg_state) = guard.as_mut().and_then(|m| m.get_mut(&delivery_tag)) {
@@ -750,6 +781,18 @@ impl LinkRelay<OutputHandle> {
         }
     }
 
+    /// Removes a delivery that is settled by a disposition sent by the local receiver from the
+    /// unsettled map
+    ///
+    /// This is used for dispositions that only carry a range of delivery ids, which are only
+    /// known to the session
+    pub(crate) fn on_outgoing_settled_disposition(&mut self, delivery_tag: &DeliveryTag) {
+        if let LinkRelay::Receiver { unsettled, .. } = self {
+            let mut guard = unsettled.write();
+            guard.as_mut().and_then(|m| m.swap_remove(delivery_tag));
+        }
+    }
+
     /// LinkRelay operates in session's event loop
     ///
     /// The session needs a map of delivery_id and delivery_tag
@@ -760,12 +803,7 @@ impl LinkRelay<OutputHandle> {
     ) -> Result<Option<(DeliveryNumber, DeliveryTag)>, LinkRelayError> {
         match self {
             LinkRelay::Sender { .. } => Err(LinkRelayError::TransferFrameToSender),
-            LinkRelay::Receiver {
-                tx,
-                receiver_settle_mode,
-                more,
-                ..
-            } => {
+            LinkRelay::Receiver { tx, more, .. } => {
                 let settled = transfer.settled.unwrap_or(false);
                 let delivery_id = transfer.delivery_id;
                 let delivery_tag = transfer.delivery_tag.clone();
@@ -780,24 +818,29 @@ impl LinkRelay<OutputHandle> {
                 .map_err(|_| LinkRelayError::UnattachedHandle)?;
 
                 if !settled {
-                    if let ReceiverSettleMode::Second = receiver_settle_mode {
-                        // The delivery-id MUST be supplied on the first transfer of a
-                        // multi-transfer delivery.
-                        // And self.more should be false upon the first transfer
-                        if !(*more) {
-                            // The same delivery ID should be used for a multi-transfer delivery
-                            match (delivery_id, delivery_tag) {
-                                (Some(id), Some(tag)) => return Ok(Some((id, tag))),
-                                _ => {
-                                    // This should be an error, but it will be handled by
-                                    // the link instead of the session. So just return a None
-                                    return Ok(None);
-                                }
+                    // The session keeps the delivery tag in both receiver settle modes so that a
+                    // disposition that only carries a range of delivery ids can be mapped back
+                    // to the unsettled deliveries
+
+                    // The delivery-id MUST be supplied on the first transfer of a
+                    // multi-transfer delivery.
+                    // And self.more should be false upon the first transfer
+                    let is_first = !(*more);
+
+                    // The last transfer of multi-transfer delivery should have
+                    // `more` set to false
+                    *more = transfer_more;
+
+                    if is_first {
+                        // The same delivery ID should be used for a multi-transfer delivery
+                        match (delivery_id, delivery_tag) {
+                            (Some(id), Some(tag)) => return Ok(Some((id, tag))),
+                            _ => {
+                                // This should be an error, but it will be handled by
+                                // the link instead of the session. So just return a None
+                                return Ok(None);
                             }
                         }
-                        // The last transfer of multi-transfer delivery should have
-                        // `more` set to false
-                        *more = transfer_more;
                     }
                 }
                 Ok(None)
@@ -836,10 +879,100 @@ pub(crate) fn get_max_message_size(local: u64, remote: Option<u64>) -> u64 {
     }
 }
 
+/// Notifies the settlement callback (if any) outside of the unsettled map lock, and then
+/// resolves the corresponding `DeliveryFut`
+fn settle_unsettled_message(
+    msg: UnsettledMessage,
+    delivery_tag: DeliveryTag,
+    state: Option<DeliveryState>,
+    on_settled: Option<&OnSettled>,
+) {
+    if let Some(on_settled) = on_settled {
+        on_settled.call(delivery_tag, state.clone());
+    }
+    let _result = msg.settle_with_state(state);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::link::state::LinkFlowStateInner;
 
+    #[test]
+    fn test_max_unsettled_in_attach() {
+        use std::sync::Arc;
+
+        use fe2o3_amqp_types::messaging::Target;
+
+        use super::*;
+        use crate::endpoint::OutputHandle;
+
+        let mut unsettled = OrderedMap::new();
+        for i in 0..8u32 {
+            unsettled.insert(DeliveryTag::from(i.to_be_bytes().to_vec()), None);
+        }
+        let mut link: ReceiverLink<Target> = ReceiverLink {
+            role: PhantomData,
+            local_state: LinkState::Detached,
+            name: String::from("test-receiver"),
+            output_handle: Some(OutputHandle(0)),
+            input_handle: None,
+            snd_settle_mode: SenderSettleMode::Unsettled,
+            rcv_settle_mode: ReceiverSettleMode::First,
+            source: None,
+            target: None,
+            max_message_size: 0,
+            offered_capabilities: None,
+            desired_capabilities: None,
+            remote_properties: None,
+            flow_state: Arc::new(LinkFlowState::receiver(LinkFlowStateInner {
+                initial_delivery_count: 0,
+                delivery_count: 0,
+                link_credit: 0,
+                available: 0,
+                drain: false,
+                properties: None,
+            })),
+            unsettled: Arc::new(RwLock::new(Some(unsettled))),
+            verify_incoming_source: true,
+            verify_incoming_target: true,
+            max_unsettled_in_attach: None,
+        };
+        let unsettled_len = |attach: &Attach| attach.unsettled.as_ref().map(|map| map.len());
+        let as_attach = |link: &ReceiverLink<Target>, max_frame_size, is_reattaching| {
+            let result =
+                link.as_maybe_incomplete_attach(max_frame_size, OutputHandle(0), is_reattaching, 8);
+            match result {
+                Ok(attach) => attach,
+                Err(_) => panic!("Failed to create the attach"),
+            }
+        };
+
+        let attach = as_attach(&link, usize::MAX, false);
+        assert_eq!(unsettled_len(&attach), Some(8));
+        assert!(!attach.incomplete_unsettled);
+
+        // The unsettled map is halved until the attach fits in the frame
+        let full_size = serde_amqp::to_vec(&attach).unwrap().len();
+        let attach = as_attach(&link, full_size - 1, false);
+        assert_eq!(unsettled_len(&attach), Some(4));
+        assert!(attach.incomplete_unsettled);
+
+        link.max_unsettled_in_attach = Some(3);
+        let attach = as_attach(&link, usize::MAX, false);
+        assert_eq!(unsettled_len(&attach), Some(3));
+        assert!(attach.incomplete_unsettled);
+
+        link.max_unsettled_in_attach = Some(0);
+        let attach = as_attach(&link, usize::MAX, false);
+        assert_eq!(unsettled_len(&attach), Some(0));
+        assert!(attach.incomplete_unsettled);
+
+        // The unsettled map must be null when reattaching
+        let attach = as_attach(&link, usize::MAX, true);
+        assert_eq!(unsettled_len(&attach), None);
+        assert!(!attach.incomplete_unsettled);
+    }
+
     #[tokio::test]
     async fn test_producer_notify() {
         use std::sync::Arc;
@@ -869,4 +1002,244 @@ mod tests {
         notified.await;
         handle.await.unwrap();
     }
+
+    #[test]
+    fn test_on_settled_callback() {
+        use std::sync::{Arc, Mutex};
+
+        use fe2o3_amqp_types::messaging::{Accepted, Rejected};
+        use tokio::sync::Notify;
+
+        use super::*;
+        use crate::endpoint::OutputHandle;
+
+        let settled = Arc::new(Mutex::new(Vec::new()));
+        let settled_clone = settled.clone();
+        let on_settled = OnSettled::new(move |delivery_tag, state| {
+            settled_clone.lock().unwrap().push((delivery_tag, state));
+        });
+
+        let state = LinkFlowState::sender(LinkFlowStateInner {
+            initial_delivery_count: 0,
+            delivery_count: 0,
+            link_credit: 0,
+            available: 0,
+            drain: false,
+            properties: None,
+        });
+        let producer = Producer::new(Arc::new(Notify::new()), Arc::new(state));
+        let unsettled: ArcSenderUnsettledMap = Arc::new(RwLock::new(Some(UnsettledMap::new())));
+        let mut outcomes = Vec::new();
+        for tag in ["a", "b", "c"] {
+            let (tx, rx) = oneshot::channel();
+            let msg = UnsettledMessage::new(Payload::new(), None, 0, tx);
+            unsettled
+                .write()
+                .as_mut()
+                .unwrap()
+                .insert(DeliveryTag::from(tag), msg);
+            outcomes.push(rx);
+        }
+
+        let (tx, _rx) = mpsc::channel(1);
+        let mut relay = LinkRelay::new_sender(tx, producer, unsettled, Some(on_settled))
+            .with_output_handle(OutputHandle(0));
+
+        let accepted = DeliveryState::Accepted(Accepted {});
+        let rejected = DeliveryState::Rejected(Rejected { error: None });
+        let received = DeliveryState::Received(Received {
+            section_number: 0,
+            section_offset: 0,
+        });
+
+        // Settled by the receiver
+        relay.on_incoming_disposition(
+            Role::Receiver,
+            true,
+            Some(accepted.clone()),
+            DeliveryTag::from("a"),
+        );
+        // Unsettled terminal state
+        relay.on_incoming_disposition(
+            Role::Receiver,
+            false,
+            Some(rejected),
+            DeliveryTag::from("b"),
+        );
+        // Non-terminal state does not settle the delivery
+        relay.on_incoming_disposition(
+            Role::Receiver,
+            false,
+            Some(received),
+            DeliveryTag::from("c"),
+        );
+        // Unknown delivery tag
+        relay.on_incoming_disposition(Role::Receiver, true, Some(accepted), DeliveryTag::from("d"));
+
+        let settled = settled.lock().unwrap();
+        assert_eq!(settled.len(), 2);
+        assert_eq!(settled[0].0, DeliveryTag::from("a"));
+        assert!(matches!(settled[0].1, Some(DeliveryState::Accepted(_))));
+        assert_eq!(settled[1].0, DeliveryTag::from("b"));
+        assert!(matches!(settled[1].1, Some(DeliveryState::Rejected(_))));
+
+        let mut outcomes = outcomes.into_iter();
+        assert!(matches!(
+            outcomes.next().unwrap().try_recv(),
+            Ok(Some(DeliveryState::Accepted(_)))
+        ));
+        assert!(matches!(
+            outcomes.next().unwrap().try_recv(),
+            Ok(Some(DeliveryState::Rejected(_)))
+        ));
+        assert!(outcomes.next().unwrap().try_recv().is_err());
+    }
+
+    #[test]
+    fn test_receiver_on_settled_callback() {
+        use std::sync::{Arc, Mutex};
+
+        use fe2o3_amqp_types::messaging::Accepted;
+
+        use super::*;
+        use crate::endpoint::OutputHandle;
+
+        let settled = Arc::new(Mutex::new(Vec::new()));
+        let settled_clone = settled.clone();
+        let on_settled = OnSettled::new(move |delivery_tag, state| {
+            settled_clone.lock().unwrap().push((delivery_tag, state));
+        });
+
+        let flow_state = Arc::new(LinkFlowState::receiver(LinkFlowStateInner {
+            initial_delivery_count: 0,
+            delivery_count: 0,
+            link_credit: 0,
+            available: 0,
+            drain: false,
+            properties: None,
+        }));
+        let unsettled: ArcReceiverUnsettledMap = Arc::new(RwLock::new(Some(UnsettledMap::new())));
+        // The receiver has sent a non-settled disposition for "a"
+        let accepted = DeliveryState::Accepted(Accepted {});
+        unsettled
+            .write()
+            .as_mut()
+            .unwrap()
+            .insert(DeliveryTag::from("a"), Some(accepted.clone()));
+
+        let (tx, _rx) = mpsc::channel(1);
+        let mut relay = LinkRelay::new_receiver(
+            tx,
+            flow_state,
+            unsettled.clone(),
+            ReceiverSettleMode::Second,
+            Some(on_settled),
+        )
+        .with_output_handle(OutputHandle(0));
+
+        // Settled by the sender
+        relay.on_incoming_disposition(
+            Role::Sender,
+            true,
+            Some(accepted.clone()),
+            DeliveryTag::from("a"),
+        );
+        // Unknown delivery tag
+        relay.on_incoming_disposition(Role::Sender, true, Some(accepted), DeliveryTag::from("b"));
+
+        let settled = settled.lock().unwrap();
+        assert_eq!(settled.len(), 1);
+        assert_eq!(settled[0].0, DeliveryTag::from("a"));
+        assert!(matches!(settled[0].1, Some(DeliveryState::Accepted(_))));
+        assert!(unsettled.read().as_ref().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disposition_before_transfer_is_sent() {
+        use std::sync::Arc;
+
+        use fe2o3_amqp_types::{definitions::Handle, messaging::Accepted};
+        use tokio::sync::Notify;
+
+        use super::*;
+        use crate::endpoint::{OutputHandle, SenderLink as _};
+
+        let flow_state = Arc::new(LinkFlowState::sender(LinkFlowStateInner {
+            initial_delivery_count: 0,
+            delivery_count: 0,
+            link_credit: 0,
+            available: 0,
+            drain: false,
+            properties: None,
+        }));
+        let unsettled: ArcSenderUnsettledMap = Arc::new(RwLock::new(None));
+        let mut link: SenderLink<Target> = SenderLink {
+            role: PhantomData,
+            local_state: LinkState::Attached,
+            name: String::from("test-sender"),
+            output_handle: Some(OutputHandle(0)),
+            input_handle: Some(InputHandle(0)),
+            snd_settle_mode: SenderSettleMode::Unsettled,
+            rcv_settle_mode: ReceiverSettleMode::First,
+            source: None,
+            target: None,
+            // The payload is split into three transfers
+            max_message_size: 1,
+            offered_capabilities: None,
+            desired_capabilities: None,
+            remote_properties: None,
+            flow_state: Consumer::new(Arc::new(Notify::new()), flow_state.clone()),
+            unsettled: unsettled.clone(),
+            verify_incoming_source: true,
+            verify_incoming_target: true,
+            max_unsettled_in_attach: None,
+        };
+        let producer = Producer::new(Arc::new(Notify::new()), flow_state);
+        let (relay_tx, _relay_rx) = mpsc::channel(1);
+        let mut relay = LinkRelay::new_sender(relay_tx, producer, unsettled.clone(), None)
+            .with_output_handle(OutputHandle(0));
+
+        let delivery_tag = DeliveryTag::from("a");
+        let transfer = Transfer {
+            handle: Handle(0),
+            delivery_id: None,
+            delivery_tag: Some(delivery_tag.clone()),
+            message_format: Some(0),
+            settled: Some(false),
+            more: false,
+            rcv_settle_mode: None,
+            state: None,
+            resume: false,
+            aborted: false,
+            batchable: false,
+        };
+
+        // The writer only has room for the first transfer
+        let (writer, mut reader) = mpsc::channel(1);
+        let send = link.send_payload_with_transfer(&writer, 0, transfer, Payload::from("abc"));
+        tokio::pin!(send);
+        assert!(futures_util::poll!(&mut send).is_pending());
+        assert!(reader.recv().await.is_some());
+
+        // The receiver settles the delivery before the sender finishes sending it
+        relay.on_incoming_disposition(
+            Role::Receiver,
+            true,
+            Some(DeliveryState::Accepted(Accepted {})),
+            delivery_tag,
+        );
+
+        let (settlement, _) = tokio::join!(send, async {
+            reader.recv().await.unwrap();
+            reader.recv().await.unwrap();
+        });
+        match settlement.unwrap() {
+            Settlement::Unsettled { mut outcome, .. } => assert!(matches!(
+                outcome.try_recv(),
+                Ok(Some(DeliveryState::Accepted(_)))
+            )),
+            Settlement::Settled(_) => panic!("The delivery should not be settled on send"),
+        }
+        assert!(unsettled.read().as_ref().unwrap().is_empty());
+    }
 }
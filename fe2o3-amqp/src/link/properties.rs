@@ -0,0 +1,151 @@
+//! Typed access to link properties
+
+use fe2o3_amqp_types::{
+    definitions::Fields,
+    primitives::{Symbol, Timestamp, Value},
+};
+
+/// A read-only, typed view over the `properties` field of a link `Attach`
+///
+/// Link properties are a map of symbol keys to arbitrary AMQP values. Brokers commonly use them to
+/// carry link metadata (eg. sequence number ranges, lease information) back to the client. This
+/// type provides helpers to read the value of a key as a specific type. All getters return `None`
+/// if the key is absent or if the value is not of the requested type.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let sender = Sender::attach(&mut session, "sender", "q1").await.unwrap();
+/// if let Some(properties) = sender.remote_link_properties() {
+///     let seq_no = properties.get_i64("x-opt-sequence-number");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LinkProperties<'a> {
+    fields: &'a Fields,
+}
+
+impl<'a> From<&'a Fields> for LinkProperties<'a> {
+    fn from(fields: &'a Fields) -> Self {
+        Self { fields }
+    }
+}
+
+impl<'a> LinkProperties<'a> {
+    /// Creates a typed view over the link properties
+    pub fn new(fields: &'a Fields) -> Self {
+        Self { fields }
+    }
+
+    /// Get a reference to the underlying fields
+    pub fn fields(&self) -> &'a Fields {
+        self.fields
+    }
+
+    /// Returns `true` if the properties contain the key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.fields.get(key).is_some()
+    }
+
+    /// Get the raw value of a key
+    pub fn get(&self, key: &str) -> Option<&'a Value> {
+        self.fields.get(key)
+    }
+
+    /// Get the value of a key as a string slice. Both `string` and `symbol` values are accepted
+    pub fn get_str(&self, key: &str) -> Option<&'a str> {
+        match self.get(key)? {
+            Value::String(s) => Some(s.as_str()),
+            Value::Symbol(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a key as a symbol
+    pub fn get_symbol(&self, key: &str) -> Option<&'a Symbol> {
+        match self.get(key)? {
+            Value::Symbol(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a key as a boolean
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a key as an unsigned integer. Any unsigned integer type is accepted
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        match self.get(key)? {
+            Value::Ubyte(v) => Some(*v as u64),
+            Value::Ushort(v) => Some(*v as u64),
+            Value::Uint(v) => Some(*v as u64),
+            Value::Ulong(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a key as a signed integer. Any signed integer type is accepted
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        match self.get(key)? {
+            Value::Byte(v) => Some(*v as i64),
+            Value::Short(v) => Some(*v as i64),
+            Value::Int(v) => Some(*v as i64),
+            Value::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a key as a timestamp
+    pub fn get_timestamp(&self, key: &str) -> Option<&'a Timestamp> {
+        match self.get(key)? {
+            Value::Timestamp(t) => Some(t),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::{
+        definitions::Fields,
+        primitives::{Symbol, Timestamp, Value},
+    };
+
+    use super::LinkProperties;
+
+    #[test]
+    fn test_typed_link_properties() {
+        let mut fields = Fields::new();
+        fields.insert(Symbol::from("string"), Value::from("foo"));
+        fields.insert(Symbol::from("symbol"), Value::Symbol(Symbol::from("bar")));
+        fields.insert(Symbol::from("bool"), Value::Bool(true));
+        fields.insert(Symbol::from("uint"), Value::Uint(7));
+        fields.insert(Symbol::from("long"), Value::Long(-7));
+        fields.insert(
+            Symbol::from("timestamp"),
+            Value::Timestamp(Timestamp::from_milliseconds(1000)),
+        );
+
+        let properties = LinkProperties::from(&fields);
+        assert_eq!(properties.get_str("string"), Some("foo"));
+        assert_eq!(properties.get_str("symbol"), Some("bar"));
+        assert_eq!(properties.get_symbol("string"), None);
+        assert_eq!(properties.get_bool("bool"), Some(true));
+        assert_eq!(properties.get_u64("uint"), Some(7));
+        assert_eq!(properties.get_i64("uint"), None);
+        assert_eq!(properties.get_i64("long"), Some(-7));
+        assert_eq!(
+            properties
+                .get_timestamp("timestamp")
+                .map(|t| t.milliseconds()),
+            Some(1000)
+        );
+        assert!(properties.contains_key("bool"));
+        assert!(!properties.contains_key("missing"));
+        assert_eq!(properties.get_u64("missing"), None);
+    }
+}
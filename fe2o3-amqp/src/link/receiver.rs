@@ -3,13 +3,17 @@
 use std::sync::atomic::{AtomicU32, Ordering};
 
 use fe2o3_amqp_types::{
-    definitions::{self, DeliveryTag, Fields, SequenceNo},
+    definitions::{
+        self, AmqpError, DeliveryNumber, DeliveryTag, Fields, Handle, ReceiverSettleMode,
+        SenderSettleMode, SequenceNo,
+    },
     messaging::{
-        Accepted, Address, DeliveryState, FromBody, Modified, Rejected, Released, Source, Target,
+        Accepted, DeliveryState, FromBody, Modified, Outcome, Rejected, Released, Source, Target,
     },
     performatives::{Attach, Detach, Transfer},
+    primitives::{Array, Symbol},
 };
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 
 cfg_not_wasm32! {
     use std::time::Duration;
@@ -19,28 +23,26 @@ cfg_not_wasm32! {
 use crate::{
     control::SessionControl,
     endpoint::{self, LinkAttach, LinkDetach, LinkExt},
-    session::SessionHandle,
+    session::{SessionHandle, SessionId},
     Payload,
 };
 
 use super::{
     builder::{self, WithTarget, WithoutName, WithoutSource},
-    delivery::{Delivery, DeliveryInfo},
+    delivery::{Delivery, DeliveryInfo, OnSettled},
     error::DetachError,
     incomplete_transfer::IncompleteTransfer,
     receiver_link::count_number_of_sections_and_offset,
     role,
     shared_inner::{LinkEndpointInner, LinkEndpointInnerDetach, LinkEndpointInnerReattach},
-    ArcReceiverUnsettledMap, DetachThenResumeReceiverError, DispositionError, FlowError,
-    IllegalLinkStateError, LinkFrame, LinkRelay, LinkStateError, ReceiverAttachError,
-    ReceiverAttachExchange, ReceiverFlowState, ReceiverLink, ReceiverResumeError,
-    ReceiverResumeErrorKind, ReceiverTransferError, RecvError, DEFAULT_CREDIT,
+    state::LinkFlowSnapshot,
+    ArcReceiverUnsettledMap, DetachThenResumeReceiverError, DispositionError,
+    DispositionRangeError, FlowError, IllegalLinkStateError, LinkFrame, LinkId, LinkProperties,
+    LinkRelay, LinkStateError, MessageDecodeError, ReceiverAttachError, ReceiverAttachExchange,
+    ReceiverFlowState, ReceiverLink, ReceiverResumeError, ReceiverResumeErrorKind,
+    ReceiverTransferError, RecvError, RetryPolicy, TryDispositionError, DEFAULT_CREDIT,
 };
 
-cfg_transaction! {
-    use fe2o3_amqp_types::definitions::AmqpError;
-}
-
 #[cfg(docsrs)]
 use fe2o3_amqp_types::{
     messaging::{AmqpSequence, AmqpValue, Batch, Body},
@@ -65,6 +67,26 @@ impl Default for CreditMode {
     }
 }
 
+/// Action taken by the receiver when an incoming message cannot be decoded into the requested
+/// type
+#[derive(Debug, Clone, Default)]
+pub enum DecodeErrorAction {
+    /// Settle the delivery as `Rejected` with the `amqp:decode-error` condition and continue
+    /// receiving
+    Reject,
+
+    /// Settle the delivery as `Released` and continue receiving
+    Release,
+
+    /// Settle the delivery as the given `Modified` and continue receiving
+    Modify(Modified),
+
+    /// Return [`RecvError::MessageDecode`] to the caller, which is then responsible for settling
+    /// the delivery
+    #[default]
+    Propagate,
+}
+
 /// An AMQP1.0 receiver
 ///
 /// # Attach a new receiver with default configurations
@@ -129,12 +151,48 @@ impl Receiver {
         self.inner.link.name()
     }
 
+    /// Get the local handle of the link. This is `None` if the link is detached
+    pub fn handle(&self) -> Option<Handle> {
+        self.inner.link.output_handle.clone().map(Into::into)
+    }
+
+    /// Get the identity of the link, which can be used as a key for logging and metrics
+    pub fn link_id(&self) -> LinkId {
+        LinkId::new(
+            &self.inner.session_id,
+            self.inner.link.output_handle.as_ref(),
+            self.inner.link.name(),
+        )
+    }
+
     /// Returns the `max_message_size` of the link. A value of zero indicates that the link has no
     /// maximum message size, and thus a zero value is turned into a `None`
     pub fn max_message_size(&self) -> Option<u64> {
         self.inner.link.max_message_size()
     }
 
+    /// Returns a snapshot of the link flow state, which can be used to implement a custom credit
+    /// policy on top of `CreditMode::Manual`
+    pub fn flow_state_snapshot(&self) -> LinkFlowSnapshot {
+        self.inner.link.flow_state.snapshot()
+    }
+
+    /// Returns the receiver settle mode of the link
+    ///
+    /// A receiver attach is refused if the remote sender does not agree on the receiver settle
+    /// mode, so once attached this is the mode in effect on both ends of the link.
+    pub fn receiver_settle_mode(&self) -> &ReceiverSettleMode {
+        &self.inner.link.rcv_settle_mode
+    }
+
+    /// Returns the sender settle mode of the link
+    ///
+    /// Once attached, this is the mode chosen by the remote sender, which may differ from the mode
+    /// requested on the builder.
+    pub fn sender_settle_mode(&self) -> &SenderSettleMode {
+        &self.inner.link.snd_settle_mode
+    }
+
     /// Get the current credit of the link
     pub fn credit_mode(&self) -> &CreditMode {
         &self.inner.credit_mode
@@ -177,6 +235,53 @@ impl Receiver {
         &mut self.inner.link.target
     }
 
+    /// Returns the address of the node created by the remote peer if the receiver is attached with
+    /// a dynamic source, or `None` if the source is not dynamic
+    pub fn dynamic_address(&self) -> Option<&str> {
+        self.inner
+            .link
+            .source
+            .as_ref()
+            .filter(|source| source.dynamic)
+            .and_then(|source| source.address.as_deref())
+    }
+
+    /// Returns the `default-outcome` of the source, which is the outcome that the sender assumes
+    /// for the deliveries that are still unsettled when the link is closed
+    ///
+    /// Once the link is attached, the source is the one sent by the remote sender. This thus
+    /// returns the value chosen by the remote peer rather than the value set on the builder.
+    pub fn default_outcome(&self) -> Option<&Outcome> {
+        self.inner
+            .link
+            .source
+            .as_ref()
+            .and_then(|source| source.default_outcome.as_ref())
+    }
+
+    /// Returns the descriptors of the outcomes that the source supports (eg.
+    /// `"amqp:modified:list"`)
+    ///
+    /// Once the link is attached, this is the value sent by the remote sender.
+    pub fn outcomes(&self) -> Option<&Array<Symbol>> {
+        self.inner
+            .link
+            .source
+            .as_ref()
+            .and_then(|source| source.outcomes.as_ref())
+    }
+
+    /// Returns the capabilities of the source
+    ///
+    /// Once the link is attached, this is the value sent by the remote sender.
+    pub fn source_capabilities(&self) -> Option<&Array<Symbol>> {
+        self.inner
+            .link
+            .source
+            .as_ref()
+            .and_then(|source| source.capabilities.as_ref())
+    }
+
     /// Get a reference to the link's properties field in the op
     pub fn properties<F, O>(&self, op: F) -> O
     where
@@ -193,6 +298,19 @@ impl Receiver {
         self.inner.link.properties_mut(op)
     }
 
+    /// Get a reference to the `properties` field of the `Attach` received from the remote peer
+    ///
+    /// This is `None` if the link has not been attached yet or if the remote peer did not send
+    /// any properties
+    pub fn remote_properties(&self) -> Option<&Fields> {
+        self.inner.link.remote_properties.as_ref()
+    }
+
+    /// Get a typed view over the `properties` field of the `Attach` received from the remote peer
+    pub fn remote_link_properties(&self) -> Option<LinkProperties<'_>> {
+        self.remote_properties().map(LinkProperties::new)
+    }
+
     /// Attach the receiver link to a session with the default configuration
     /// with the `name` and `source` address set the specified value
     ///
@@ -223,14 +341,97 @@ impl Receiver {
     ///     "q1"                    // Source address
     /// ).await.unwrap();
     /// ```
+    ///
+    /// The `source` argument accepts anything that converts into a [`Source`], including an
+    /// address string and a [`SourceBuilder`](fe2o3_amqp_types::messaging::SourceBuilder).
     pub async fn attach<R>(
         session: &mut SessionHandle<R>,
         name: impl Into<String>,
-        addr: impl Into<Address>,
+        source: impl Into<Source>,
     ) -> Result<Receiver, ReceiverAttachError> {
         Self::builder()
             .name(name)
-            .source(addr)
+            .source(source)
+            .attach(session)
+            .await
+    }
+
+    /// Attach the receiver link to a session with the default configuration and retry the attach
+    /// with backoff if it fails with a transient error (see [`ReceiverAttachError::is_transient`])
+    ///
+    /// Transient errors include a link that is still being detached (`IllegalState`), the
+    /// maximum number of links being reached, or the remote peer closing the link with a condition
+    /// like `amqp:resource-limit-exceeded` or `amqp:internal-error`. Any other error, eg.
+    /// `amqp:unauthorized-access` or `amqp:not-found`, is returned immediately.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let receiver = Receiver::attach_with_retry(
+    ///     &mut session,
+    ///     "rust-receiver-link-1",
+    ///     "q1",
+    ///     RetryPolicy::default(),
+    /// ).await.unwrap();
+    /// ```
+    pub async fn attach_with_retry<R>(
+        session: &mut SessionHandle<R>,
+        name: impl Into<String>,
+        source: impl Into<Source>,
+        policy: RetryPolicy,
+    ) -> Result<Receiver, ReceiverAttachError> {
+        let name = name.into();
+        let source = source.into();
+        let mut attempt = 0;
+        loop {
+            match Self::attach(session, name.clone(), source.clone()).await {
+                Ok(receiver) => return Ok(receiver),
+                Err(error) if attempt < policy.max_retries && error.is_transient() => {
+                    crate::util::sleep(policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Attach the receiver link to a session with a hand-constructed [`Attach`] performative
+    ///
+    /// This is an escape hatch for testing the behavior of a remote peer and for fields that are
+    /// not covered by the [`builder`](Receiver::builder). Only the minimum required fields are
+    /// validated: the `role` must be `Role::Receiver` and the `source` must be set. Otherwise
+    /// `ReceiverAttachError::IllegalState` is returned without sending anything.
+    ///
+    /// The `handle` is allocated by the session and is thus ignored. The `unsettled` map is sent
+    /// as is and becomes the initial unsettled map of the receiver. The receiver uses the default
+    /// [`CreditMode`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let attach = Attach {
+    ///     name: "rust-receiver-link-1".into(),
+    ///     handle: 0.into(),
+    ///     role: Role::Receiver,
+    ///     snd_settle_mode: SenderSettleMode::Mixed,
+    ///     rcv_settle_mode: ReceiverSettleMode::First,
+    ///     source: Some(Box::new(Source::from("q1"))),
+    ///     target: Some(Box::new(Target::default().into())),
+    ///     unsettled: None,
+    ///     incomplete_unsettled: false,
+    ///     initial_delivery_count: None,
+    ///     max_message_size: None,
+    ///     offered_capabilities: None,
+    ///     desired_capabilities: None,
+    ///     properties: None,
+    /// };
+    /// let receiver = Receiver::attach_with_performative(&mut session, attach).await.unwrap();
+    /// ```
+    pub async fn attach_with_performative<R>(
+        session: &mut SessionHandle<R>,
+        attach: Attach,
+    ) -> Result<Receiver, ReceiverAttachError> {
+        builder::Builder::<role::ReceiverMarker, Target, _, _, _>::from_attach(attach)?
             .attach(session)
             .await
     }
@@ -297,6 +498,12 @@ impl Receiver {
     /// receiver.accept(&delivery).await.unwrap();
     /// ```
     ///
+    /// # Forced detach
+    ///
+    /// If the remote peer forcibly detaches the link with the `amqp:link:detach-forced` condition
+    /// (eg. due to inactivity), [`RecvError::LinkDetachedByBroker`] is returned so that the
+    /// application can decide whether to attach the link again.
+    ///
     /// # Cancel safety
     ///
     /// This function is cancel-safe. See [#22](https://github.com/minghuaw/fe2o3-amqp/issues/22)
@@ -308,6 +515,92 @@ impl Receiver {
         self.inner.recv().await
     }
 
+    cfg_not_wasm32! {
+        /// Receive up to `max` deliveries or until `duration` has elapsed, whichever comes first
+        ///
+        /// The deliveries that have arrived before the deadline are returned, which may be an
+        /// empty `Vec`. A delivery that is split over multiple transfers and that is only partially
+        /// received at the deadline is kept and completed by the next call to receive.
+        ///
+        /// An error is returned if it occurs before any delivery is received in this call.
+        /// Otherwise, the deliveries received so far are returned and the error is returned by the
+        /// next call to [`recv`](#method.recv) or this function.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// let deliveries = receiver
+        ///     .recv_batch_timeout::<Value>(100, Duration::from_millis(500))
+        ///     .await
+        ///     .unwrap();
+        /// receiver.accept_all(&deliveries).await.unwrap();
+        /// ```
+        ///
+        /// # Cancel safety
+        ///
+        /// This function is cancel-safe in the sense that a partially received multi-transfer
+        /// delivery is not lost. Complete deliveries received before the cancellation are, however,
+        /// dropped (and remain unsettled).
+        pub async fn recv_batch_timeout<T>(
+            &mut self,
+            max: usize,
+            duration: Duration,
+        ) -> Result<Vec<Delivery<T>>, RecvError>
+        where
+            for<'de> T: FromBody<'de> + Send,
+        {
+            self.inner.recv_batch_timeout(max, duration).await
+        }
+
+        /// Grant exactly one credit and wait up to `duration` for a delivery
+        ///
+        /// If no delivery arrives before `duration` has elapsed, the credit is withdrawn by
+        /// draining the link and `Ok(None)` is returned. A delivery that is already in flight when
+        /// the link is drained is still returned. The remote sender can therefore never have more
+        /// than one message outstanding on the link, which is useful for competing consumers that
+        /// take one message at a time.
+        ///
+        /// The receiver should be attached with [`CreditMode::Manual`] so that no credit is
+        /// granted other than by this method. With [`CreditMode::Auto`], settling a delivery
+        /// re-fills the credit.
+        ///
+        /// Waiting for the remote sender to respond to the drain is also bounded by `duration`.
+        ///
+        /// # Example
+        ///
+        /// ```rust,ignore
+        /// let mut receiver = Receiver::builder()
+        ///     .name("rust-receiver-link-1")
+        ///     .source("q1")
+        ///     .credit_mode(CreditMode::Manual)
+        ///     .attach(&mut session)
+        ///     .await
+        ///     .unwrap();
+        ///
+        /// if let Some(delivery) = receiver
+        ///     .pull::<Value>(Duration::from_secs(1))
+        ///     .await
+        ///     .unwrap()
+        /// {
+        ///     receiver.accept(&delivery).await.unwrap();
+        /// }
+        /// ```
+        ///
+        /// # Cancel safety
+        ///
+        /// This function is not cancel-safe. The credit is left outstanding if the future is
+        /// dropped before the credit is withdrawn.
+        pub async fn pull<T>(
+            &mut self,
+            duration: Duration,
+        ) -> Result<Option<Delivery<T>>, RecvError>
+        where
+            for<'de> T: FromBody<'de> + Send,
+        {
+            self.inner.pull(duration).await
+        }
+    }
+
     /// Set the link credit. This will stop draining if the link is in a draining cycle
     pub async fn set_credit(&mut self, credit: SequenceNo) -> Result<(), IllegalLinkStateError> {
         self.inner.set_credit(credit).await
@@ -326,6 +619,28 @@ impl Receiver {
         self.inner.send_properties().await
     }
 
+    /// Synchronize the flow state with the remote sender.
+    ///
+    /// This sends a `Flow` performative with the `echo` field set to true, which asks the remote
+    /// sender to respond with its flow state, and resolves when the next `Flow` from the remote
+    /// sender arrives. The `available` and `delivery_count` of
+    /// [`flow_state_snapshot`](Self::flow_state_snapshot) are then up-to-date with the remote
+    /// sender.
+    ///
+    /// This does not time out if the remote peer does not respond, and it returns
+    /// `IllegalSessionState` if the session is dropped while waiting. Consider wrapping it with a
+    /// timeout.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// receiver.sync_flow().await.unwrap();
+    /// let available = receiver.flow_state_snapshot().available;
+    /// ```
+    pub async fn sync_flow(&mut self) -> Result<(), FlowError> {
+        self.inner.sync_flow().await
+    }
+
     /// Detach the link.
     ///
     /// This will send a `Detach` performative with the `closed` field set to false. If the remote
@@ -386,6 +701,7 @@ impl Receiver {
 
         // re-attach the link
         self.inner.session = new_session.control.clone();
+        self.inner.session_id = new_session.id.clone();
         self.inner.outgoing = new_session.outgoing.clone();
         let exchange_result = self
             .inner
@@ -462,6 +778,36 @@ impl Receiver {
         self.dispose_all(deliveries, state).await
     }
 
+    /// Accept and settle all unsettled deliveries of this receiver with a delivery id in
+    /// `first..=last` by sending disposition(s) with the `delivery_state` field set to `Accept`
+    ///
+    /// Delivery ids are assigned by the session and may be interleaved with the deliveries of
+    /// other links on the same session, which are left untouched. One disposition is sent for
+    /// each run of consecutive delivery ids, and no disposition is sent if none of them is found
+    /// in the local unsettled map. Deliveries in the range are settled regardless of the
+    /// negotiated receiver settle mode.
+    ///
+    /// An error is returned if `first` is greater than `last`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let first: Delivery<Value> = receiver.recv().await.unwrap();
+    /// let last: Delivery<Value> = receiver.recv().await.unwrap();
+    /// receiver
+    ///     .accept_range(*first.delivery_id(), *last.delivery_id())
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub async fn accept_range(
+        &self,
+        first: DeliveryNumber,
+        last: DeliveryNumber,
+    ) -> Result<(), DispositionRangeError> {
+        let state = TerminalDeliveryState::Accepted(Accepted {});
+        self.inner.dispose_range(first, last, state.into()).await
+    }
+
     /// Reject the message by sending a disposition with the `delivery_state` field set
     /// to `Reject`
     ///
@@ -529,6 +875,41 @@ impl Receiver {
         self.dispose(delivery_info, state).await
     }
 
+    /// Modify the message by sending a disposition with the `delivery_state` field set to
+    /// `Modify` that carries the `message_annotations` to merge into the message
+    ///
+    /// The broker merges the annotations into the existing message annotations of the message
+    /// and keeps them for redelivery, which allows eg. a custom retry counter to be incremented
+    /// on the broker.
+    ///
+    /// This will not send disposition if the delivery is not found in the local unsettled map.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let delivery: Delivery<String> = receiver.recv().await.unwrap();
+    /// let mut annotations = Fields::new();
+    /// annotations.insert(Symbol::from("x-opt-retry-count"), Value::from(1u32));
+    /// receiver
+    ///     .modify_with_annotations(&delivery, true, false, annotations)
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub async fn modify_with_annotations(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+        delivery_failed: bool,
+        undeliverable_here: bool,
+        annotations: Fields,
+    ) -> Result<(), DispositionError> {
+        let modified = Modified {
+            delivery_failed: Some(delivery_failed),
+            undeliverable_here: Some(undeliverable_here),
+            message_annotations: Some(annotations),
+        };
+        self.modify(delivery_info, modified).await
+    }
+
     /// Modify the message by sending one or more disposition(s) with the `delivery_state` field set
     /// to `Modify`
     ///
@@ -554,6 +935,84 @@ impl Receiver {
         self.inner.dispose(delivery_info, None, state.into()).await
     }
 
+    /// Accept the message by enqueuing a disposition with the `delivery_state` field set to
+    /// `Accept` without waiting for capacity in the outgoing channel to the session
+    ///
+    /// This returns [`TryDispositionError::Full`] if the channel is full, in which case the
+    /// delivery is left untouched and the disposition can be retried later. This will not send
+    /// disposition if the delivery is not found in the local unsettled map.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let delivery: Delivery<Value> = receiver.recv().await.unwrap();
+    /// match receiver.try_accept(&delivery) {
+    ///     Ok(()) => {}
+    ///     Err(TryDispositionError::Full) => receiver.accept(&delivery).await.unwrap(),
+    ///     Err(err) => panic!("{}", err),
+    /// }
+    /// ```
+    pub fn try_accept(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+    ) -> Result<(), TryDispositionError> {
+        let state = TerminalDeliveryState::Accepted(Accepted {});
+        self.try_dispose(delivery_info, state)
+    }
+
+    /// Reject the message by enqueuing a disposition with the `delivery_state` field set to
+    /// `Reject` without waiting for capacity in the outgoing channel to the session
+    ///
+    /// See [`Receiver::try_accept`] for the behavior when the channel is full.
+    pub fn try_reject(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+        error: impl Into<Option<definitions::Error>>,
+    ) -> Result<(), TryDispositionError> {
+        let state = TerminalDeliveryState::Rejected(Rejected {
+            error: error.into(),
+        });
+        self.try_dispose(delivery_info, state)
+    }
+
+    /// Release the message by enqueuing a disposition with the `delivery_state` field set to
+    /// `Release` without waiting for capacity in the outgoing channel to the session
+    ///
+    /// See [`Receiver::try_accept`] for the behavior when the channel is full.
+    pub fn try_release(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+    ) -> Result<(), TryDispositionError> {
+        let state = TerminalDeliveryState::Released(Released {});
+        self.try_dispose(delivery_info, state)
+    }
+
+    /// Modify the message by enqueuing a disposition with the `delivery_state` field set to
+    /// `Modify` without waiting for capacity in the outgoing channel to the session
+    ///
+    /// See [`Receiver::try_accept`] for the behavior when the channel is full.
+    pub fn try_modify(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+        modified: Modified,
+    ) -> Result<(), TryDispositionError> {
+        let state = TerminalDeliveryState::Modified(modified);
+        self.try_dispose(delivery_info, state)
+    }
+
+    /// Dispose the message by enqueuing a disposition with the provided state without waiting for
+    /// capacity in the outgoing channel to the session
+    ///
+    /// See [`Receiver::try_accept`] for the behavior when the channel is full.
+    pub fn try_dispose(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+        state: impl Into<TerminalDeliveryState>,
+    ) -> Result<(), TryDispositionError> {
+        let state: TerminalDeliveryState = state.into();
+        self.inner.try_dispose(delivery_info, None, state.into())
+    }
+
     /// Dispose the message by sending one or more disposition(s) with the provided state
     ///
     /// Only deliveries that are found in the local unsettled map will be included in the disposition frame(s).
@@ -642,16 +1101,27 @@ pub(crate) struct ReceiverInner<L: endpoint::ReceiverLink> {
     pub(crate) credit_mode: CreditMode,
     pub(crate) processed: AtomicU32, // SequenceNo,
     pub(crate) auto_accept: bool,
+    pub(crate) on_decode_error: DecodeErrorAction,
 
     // Control sender to the session
     pub(crate) session: mpsc::Sender<SessionControl>,
 
+    // Identity of the session that the link is attached to
+    pub(crate) session_id: SessionId,
+
     // Outgoing mpsc channel to send the Link Frames
     pub(crate) outgoing: mpsc::Sender<LinkFrame>,
     pub(crate) incoming: mpsc::Receiver<LinkFrame>,
 
     // Wrap in a box to avoid clippy warning large_enum_variant on link acceptor's output
     pub(crate) incomplete_transfer: Option<Box<IncompleteTransfer>>,
+
+    // An error that occurred after a partial batch was received. This is returned by the next
+    // call to receive
+    pub(crate) deferred_error: Option<RecvError>,
+
+    // Invoked when the sender settles a delivery that is still unsettled on the receiver
+    pub(crate) on_settled: Option<OnSettled>,
 }
 
 impl<L: endpoint::ReceiverLink> Drop for ReceiverInner<L> {
@@ -703,6 +1173,7 @@ where
             // This only controls whether a multi-transfer delivery id
             // will be added to sessions map
             more: false,
+            on_settled: self.on_settled.clone(),
         }
     }
 
@@ -787,6 +1258,10 @@ where
     where
         for<'de> T: FromBody<'de> + Send,
     {
+        if let Some(error) = self.deferred_error.take() {
+            return Err(error);
+        }
+
         loop {
             match self.recv_inner().await? // FIXME: cancel safe? if oneshot channel is cancel safe
             {
@@ -796,6 +1271,102 @@ where
         }
     }
 
+    cfg_not_wasm32! {
+        /// # Cancel safety
+        ///
+        /// A partially received multi-transfer delivery is kept in `incomplete_transfer` because
+        /// `recv_inner` is cancel safe. The deliveries collected so far are dropped upon cancellation
+        pub(crate) async fn recv_batch_timeout<T>(
+            &mut self,
+            max: usize,
+            duration: Duration,
+        ) -> Result<Vec<Delivery<T>>, RecvError>
+        where
+            for<'de> T: FromBody<'de> + Send,
+        {
+            if let Some(error) = self.deferred_error.take() {
+                return Err(error);
+            }
+
+            let deadline = tokio::time::Instant::now() + duration;
+            let mut deliveries = Vec::new();
+            while deliveries.len() < max {
+                // A partially received multi-transfer delivery stays in `incomplete_transfer`
+                // if the deadline is reached
+                match tokio::time::timeout_at(deadline, self.recv_inner()).await {
+                    Ok(Ok(Some(delivery))) => deliveries.push(delivery),
+                    Ok(Ok(None)) => continue,
+                    Ok(Err(error)) => match deliveries.is_empty() {
+                        true => return Err(error),
+                        false => {
+                            self.deferred_error = Some(error);
+                            break;
+                        }
+                    },
+                    Err(_) => break,
+                }
+            }
+            Ok(deliveries)
+        }
+
+        /// # Cancel safety
+        ///
+        /// This is not cancel safe. The credit is left outstanding if the future is dropped
+        /// before the credit is drained
+        pub(crate) async fn pull<T>(
+            &mut self,
+            duration: Duration,
+        ) -> Result<Option<Delivery<T>>, RecvError>
+        where
+            for<'de> T: FromBody<'de> + Send,
+        {
+            if let Some(error) = self.deferred_error.take() {
+                return Err(error);
+            }
+
+            // Grant exactly one credit. This also stops a previous draining cycle
+            self.processed = AtomicU32::new(0);
+            self.link
+                .send_flow(&self.outgoing, Some(1), Some(false), false, false)
+                .await?;
+
+            let deadline = tokio::time::Instant::now() + duration;
+            loop {
+                match tokio::time::timeout_at(deadline, self.recv_inner()).await {
+                    Ok(Ok(Some(delivery))) => return Ok(Some(delivery)),
+                    Ok(Ok(None)) => continue,
+                    Ok(Err(error)) => return Err(error),
+                    Err(_) => break,
+                }
+            }
+
+            // Withdraw the credit by draining the link. The remote sender either uses the credit
+            // for a delivery that is already in flight or responds with a flow that consumes the
+            // credit. The future must be created before sending the flow so that a response
+            // arriving before the future is polled is not missed
+            let flow_state = self.link.flow_state().clone();
+            let flow_received = flow_state.flow_received();
+            tokio::pin!(flow_received);
+            self.link
+                .send_flow(&self.outgoing, None, Some(true), false, false)
+                .await?;
+
+            let deadline = tokio::time::Instant::now() + duration;
+            loop {
+                tokio::select! {
+                    // A transfer sent before the flow is already queued when the flow arrives
+                    biased;
+                    result = self.recv_inner() => match result? {
+                        Some(delivery) => return Ok(Some(delivery)),
+                        None => continue,
+                    },
+                    _ = &mut flow_received => return Ok(None),
+                    _ = tokio::time::sleep_until(deadline) => return Ok(None),
+                }
+            }
+        }
+    }
+
     /// # Cancel safety
     ///
     /// This should be cancel safe if oneshot channel is cancel safe
@@ -826,11 +1397,14 @@ where
                 input_handle: _,
                 performative,
                 payload,
-            } => self.on_incoming_transfer(performative, payload).await, // cancel safe
+            } => match self.on_incoming_transfer(performative, payload).await {
+                Err(RecvError::MessageDecode(error)) => self.on_decode_error(error).await, // cancel safe
+                result => result, // cancel safe
+            },
             LinkFrame::Attach(_) => Err(LinkStateError::IllegalState.into()),
-            LinkFrame::Flow(_) | LinkFrame::Disposition(_) => {
+            LinkFrame::Flow(_) | LinkFrame::Disposition(_) | LinkFrame::Flush(_) => {
                 // Flow and Disposition are handled by LinkRelay which runs
-                // in the session loop
+                // in the session loop, and Flush is only sent to the session
                 unreachable!()
             }
             #[cfg(feature = "transaction")]
@@ -998,6 +1572,29 @@ where
         Ok(Some(delivery))
     }
 
+    /// Settles the delivery that failed to decode according to `on_decode_error`
+    ///
+    /// # Cancel safety
+    ///
+    /// This is cancel safe because all internal `.await` point(s) are cancel safe
+    async fn on_decode_error<T>(
+        &mut self,
+        error: MessageDecodeError,
+    ) -> Result<Option<Delivery<T>>, RecvError> {
+        let state: DeliveryState = match &self.on_decode_error {
+            DecodeErrorAction::Propagate => return Err(error.into()),
+            DecodeErrorAction::Reject => {
+                let error =
+                    definitions::Error::new(AmqpError::DecodeError, error.source.to_string(), None);
+                Rejected { error: Some(error) }.into()
+            }
+            DecodeErrorAction::Release => Released {}.into(),
+            DecodeErrorAction::Modify(modified) => modified.clone().into(),
+        };
+        self.dispose(error.info, None, state).await?; // cancel safe
+        Ok(None)
+    }
+
     /// # Cancel safety
     ///
     /// This is cancel safe because all internal `.await` point(s) are cancel safe
@@ -1014,10 +1611,41 @@ where
         // within the frame carrying the performative MUST be ignored). An aborted
         // message is implicitly settled
         if transfer.aborted {
-            let _ = self.incomplete_transfer.take();
+            let incomplete = self.incomplete_transfer.take();
+            let expected = incomplete.as_ref().and_then(|i| i.performative.delivery_id);
+            if let (Some(expected), Some(found)) = (expected, transfer.delivery_id) {
+                if expected != found {
+                    let error = ReceiverTransferError::UnexpectedDeliveryId { expected, found };
+                    return Err(error.into());
+                }
+            }
+
+            let _delivery_id = expected.or(transfer.delivery_id);
+            let _buffered = incomplete.as_ref().map(|i| i.buffer.len()).unwrap_or(0);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                delivery_id = ?_delivery_id,
+                buffered_transfers = _buffered,
+                "Discarding aborted delivery"
+            );
+            #[cfg(feature = "log")]
+            log::debug!(
+                "Discarding aborted delivery (delivery_id: {:?}, buffered_transfers: {})",
+                _delivery_id,
+                _buffered
+            );
             return Ok(None);
         }
 
+        // Only the continuation transfers of a multi-transfer delivery may omit the delivery-id
+        if transfer.delivery_id.is_none() && !transfer.resume && self.incomplete_transfer.is_none()
+        {
+            return Err(ReceiverTransferError::InconsistentMore {
+                delivery_tag: transfer.delivery_tag,
+            }
+            .into());
+        }
+
         if let Some(state) = transfer.state.clone() {
             // Setting the state
             // on the transfer can be thought of as being equivalent to sending a disposition immediately before
@@ -1109,6 +1737,44 @@ where
         Ok(())
     }
 
+    pub(crate) fn try_dispose(
+        &self,
+        delivery_info: impl Into<DeliveryInfo>,
+        settled: Option<bool>,
+        state: DeliveryState,
+    ) -> Result<(), TryDispositionError> {
+        let delivery_info = delivery_info.into();
+        self.link
+            .try_dispose(&self.outgoing, delivery_info, settled, state, false)?;
+
+        let prev = self.processed.fetch_add(1, Ordering::Release);
+        self.try_update_credit_if_auto(prev + 1)
+    }
+
+    fn try_update_credit_if_auto(&self, processed: u32) -> Result<(), TryDispositionError> {
+        if let CreditMode::Auto(max_credit) = self.credit_mode {
+            if processed >= max_credit / 2 {
+                match self.link.try_send_flow(
+                    &self.outgoing,
+                    Some(max_credit),
+                    Some(false),
+                    false,
+                    false,
+                ) {
+                    // Reset link credit
+                    Ok(()) => {
+                        self.processed.swap(0, Ordering::Release);
+                    }
+                    // The disposition has already been sent. The processed count is not reset so
+                    // that the flow is sent again by the next disposition
+                    Err(TryDispositionError::Full) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Drain the link.
     ///
     /// This will send a `Flow` performative with the `drain` field set to true.
@@ -1135,9 +1801,61 @@ where
             .send_flow(&self.outgoing, None, None, false, true)
             .await
     }
+
+    pub(crate) async fn sync_flow(&self) -> Result<(), FlowError> {
+        // The future must be created before sending the flow so that a response arriving before
+        // the future is polled is not missed
+        let flow_received = self.link.flow_state().flow_received();
+        self.link
+            .send_flow(&self.outgoing, None, None, true, false)
+            .await?;
+
+        tokio::select! {
+            _ = flow_received => Ok(()),
+            _ = self.outgoing.closed() => Err(FlowError::IllegalSessionState),
+        }
+    }
 }
 
 impl ReceiverInner<ReceiverLink<Target>> {
+    /// The session looks up the delivery ids as they are allocated by the session and may be
+    /// shared with other links on the same session
+    pub(crate) async fn dispose_range(
+        &self,
+        first: DeliveryNumber,
+        last: DeliveryNumber,
+        state: DeliveryState,
+    ) -> Result<(), DispositionRangeError> {
+        if first > last {
+            return Err(DispositionRangeError::InvalidRange);
+        }
+        let input_handle = self
+            .link
+            .input_handle
+            .clone()
+            .ok_or(DispositionRangeError::IllegalState)?;
+
+        let (responder, settled) = oneshot::channel();
+        let control = SessionControl::SettleIncomingRange {
+            input_handle,
+            first,
+            last,
+            state,
+            responder,
+        };
+        self.session
+            .send(control)
+            .await
+            .map_err(|_| DispositionRangeError::IllegalSessionState)?;
+        let total = settled
+            .await
+            .map_err(|_| DispositionRangeError::IllegalSessionState)?;
+
+        let prev = self.processed.fetch_add(total, Ordering::Release);
+        self.update_credit_if_auto(prev + total).await?;
+        Ok(())
+    }
+
     pub(crate) async fn resume_incoming_attach(
         &mut self,
         mut initial_remote_attach: Option<Attach>,
@@ -1176,6 +1894,19 @@ impl ReceiverInner<ReceiverLink<Target>> {
 /// let detached = receiver.detach().await.unwrap();
 /// let resuming_receiver = detached.resume().await.unwrap();
 /// ```
+///
+/// Link migration
+///
+/// The detached receiver keeps the link's unsettled deliveries and can be held until a new
+/// session is available, ie. after re-connecting, and then resumed on that session.
+///
+/// ```rust,ignore
+/// let detached = receiver.detach().await.unwrap();
+///
+/// let mut connection = Connection::open("connection-2", "amqp://localhost:5672").await.unwrap();
+/// let mut session = Session::begin(&mut connection).await.unwrap();
+/// let resuming_receiver = detached.resume_on_session(&session).await.unwrap();
+/// ```
 #[derive(Debug)]
 pub struct DetachedReceiver {
     inner: ReceiverInner<ReceiverLink<Target>>,
@@ -1393,6 +2124,7 @@ impl DetachedReceiver {
         let is_reattaching = !self.inner.session.same_channel(&session.control);
 
         self.inner.session = session.control.clone();
+        self.inner.session_id = session.id.clone();
         self.inner.outgoing = session.outgoing.clone();
 
         self.resume_inner(is_reattaching).await
@@ -1435,6 +2167,7 @@ impl DetachedReceiver {
         let is_reattaching = !self.inner.session.same_channel(&session.control);
 
         self.inner.session = session.control.clone();
+        self.inner.session_id = session.id.clone();
         self.inner.outgoing = session.outgoing.clone();
 
         let exchange = try_as_recver!(
@@ -1466,6 +2199,7 @@ impl DetachedReceiver {
         ) -> Result<ResumingReceiver, ReceiverResumeError> {
             let is_reattaching = !self.inner.session.same_channel(&session.control);
             self.inner.session = session.control.clone();
+            self.inner.session_id = session.id.clone();
             self.inner.outgoing = session.outgoing.clone();
             self.resume_with_timeout_inner(duration, is_reattaching).await
         }
@@ -1520,6 +2254,7 @@ impl DetachedReceiver {
             let is_reattaching = !self.inner.session.same_channel(&session.control);
 
             self.inner.session = session.control.clone();
+            self.inner.session_id = session.id.clone();
             self.inner.outgoing = session.outgoing.clone();
 
             let fut = self.inner.resume_incoming_attach(Some(remote_attach), is_reattaching);
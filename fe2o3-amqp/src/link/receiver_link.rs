@@ -6,7 +6,7 @@ use serde_amqp::format_code::EncodingCodes;
 
 use crate::{
     endpoint::LinkExt,
-    util::{is_consecutive, AsByteIterator, IntoReader, Sealed},
+    util::{is_consecutive, log_window_transition, AsByteIterator, IntoReader, Sealed, ToPayloads},
 };
 
 use super::{delivery::DeliveryInfo, *};
@@ -133,7 +133,7 @@ where
     ) -> Result<Delivery<T>, Self::TransferError>
     where
         for<'de> T: FromBody<'de> + Send,
-        P: IntoReader<'a> + AsByteIterator + Send + 'a,
+        P: IntoReader<'a> + AsByteIterator + ToPayloads + Send + 'a,
     {
         match self.local_state {
             LinkState::Attached | LinkState::IncompleteAttachExchanged => {}
@@ -154,6 +154,16 @@ where
             .delivery_tag
             .ok_or(Self::TransferError::DeliveryTagIsNone)?;
         let message_format = transfer.message_format;
+        #[cfg(feature = "transaction")]
+        let txn_id = match transfer.state {
+            Some(DeliveryState::TransactionalState(state)) => Some(state.txn_id),
+            _ => None,
+        };
+
+        // The raw payload is retained so that the exact bytes received remain available after
+        // the message is decoded. The payloads of a multi-frame delivery are only concatenated
+        // when the raw payload is requested
+        let raw_payload = payload.to_payloads();
 
         let (result, mode) = if settled_by_sender {
             // If the message is pre-settled, there is no need to
@@ -228,7 +238,10 @@ where
             delivery_tag,
             message_format,
             rcv_settle_mode: mode,
+            #[cfg(feature = "transaction")]
+            txn_id,
             message,
+            raw_payload,
         };
 
         Ok(delivery)
@@ -243,51 +256,10 @@ where
         state: DeliveryState,
         batchable: bool,
     ) -> Result<(), Self::DispositionError> {
-        let settled = settled.unwrap_or({
-            match delivery_info
-                .rcv_settle_mode
-                .as_ref()
-                .unwrap_or(&self.rcv_settle_mode)
-            {
-                ReceiverSettleMode::First => {
-                    // If first, this indicates that the receiver MUST settle
-                    // the delivery once it has arrived without waiting
-                    // for the sender to settle first.
-
-                    // The delivery is not inserted into unsettled map if in First mode
-                    true
-                }
-                ReceiverSettleMode::Second => {
-                    // If second, this indicates that the receiver MUST NOT settle until sending
-                    // its disposition to the sender and receiving a settled disposition from
-                    // the sender.
-                    false
-                }
-            }
-        });
-
-        let unsettled_state = if settled {
-            let mut lock = self.unsettled.write();
-            lock.as_mut()
-                .and_then(|map| map.swap_remove(&delivery_info.delivery_tag))
-        } else {
-            let mut lock = self.unsettled.write();
-            // If the key is present in the map, the old value will be returned, which
-            // we don't really need
-            lock.get_or_insert(OrderedMap::new())
-                .insert(delivery_info.delivery_tag.clone(), Some(state.clone()))
-        };
-
         // Only dispose if message is found in unsettled map
-        if unsettled_state.is_some() {
-            let disposition = Disposition {
-                role: Role::Receiver,
-                first: delivery_info.delivery_id,
-                last: None,
-                settled,
-                state: Some(state),
-                batchable,
-            };
+        if let Some(disposition) =
+            self.update_unsettled_on_dispose(delivery_info, settled, state, batchable)
+        {
             let frame = LinkFrame::Disposition(disposition);
             writer
                 .send(frame)
@@ -298,6 +270,53 @@ where
         Ok(())
     }
 
+    fn try_dispose(
+        &self,
+        writer: &mpsc::Sender<LinkFrame>,
+        delivery_info: DeliveryInfo,
+        settled: Option<bool>,
+        state: DeliveryState,
+        batchable: bool,
+    ) -> Result<(), TryDispositionError> {
+        // Reserve the capacity first so that the unsettled map is left untouched if the channel
+        // is full
+        let permit = writer.try_reserve().map_err(|err| match err {
+            mpsc::error::TrySendError::Full(_) => TryDispositionError::Full,
+            mpsc::error::TrySendError::Closed(_) => TryDispositionError::IllegalSessionState,
+        })?;
+
+        if let Some(disposition) =
+            self.update_unsettled_on_dispose(delivery_info, settled, state, batchable)
+        {
+            permit.send(LinkFrame::Disposition(disposition));
+        }
+
+        Ok(())
+    }
+
+    fn try_send_flow(
+        &self,
+        writer: &mpsc::Sender<LinkFrame>,
+        link_credit: Option<u32>,
+        drain: Option<bool>,
+        echo: bool,
+        include_properties: bool,
+    ) -> Result<(), TryDispositionError> {
+        let handle = self
+            .output_handle
+            .clone()
+            .ok_or(TryDispositionError::IllegalState)?
+            .into();
+
+        let flow = self.get_link_flow(handle, link_credit, drain, echo, include_properties);
+        writer
+            .try_send(LinkFrame::Flow(flow))
+            .map_err(|err| match err {
+                mpsc::error::TrySendError::Full(_) => TryDispositionError::Full,
+                mpsc::error::TrySendError::Closed(_) => TryDispositionError::IllegalSessionState,
+            })
+    }
+
     /// This is cancel safe because all internal `.await` points are cancel safe
     async fn dispose_all(
         &self,
@@ -426,6 +445,60 @@ impl ReceiverLink<Target> {
 }
 
 impl<T> ReceiverLink<T> {
+    /// Updates the unsettled map with the disposed delivery and returns the disposition that
+    /// should be sent. This returns `None` if the delivery is not found in the unsettled map.
+    fn update_unsettled_on_dispose(
+        &self,
+        delivery_info: DeliveryInfo,
+        settled: Option<bool>,
+        state: DeliveryState,
+        batchable: bool,
+    ) -> Option<Disposition> {
+        let settled = settled.unwrap_or({
+            match delivery_info
+                .rcv_settle_mode
+                .as_ref()
+                .unwrap_or(&self.rcv_settle_mode)
+            {
+                ReceiverSettleMode::First => {
+                    // If first, this indicates that the receiver MUST settle
+                    // the delivery once it has arrived without waiting
+                    // for the sender to settle first.
+
+                    // The delivery is not inserted into unsettled map if in First mode
+                    true
+                }
+                ReceiverSettleMode::Second => {
+                    // If second, this indicates that the receiver MUST NOT settle until sending
+                    // its disposition to the sender and receiving a settled disposition from
+                    // the sender.
+                    false
+                }
+            }
+        });
+
+        let unsettled_state = if settled {
+            let mut lock = self.unsettled.write();
+            lock.as_mut()
+                .and_then(|map| map.swap_remove(&delivery_info.delivery_tag))
+        } else {
+            let mut lock = self.unsettled.write();
+            // If the key is present in the map, the old value will be returned, which
+            // we don't really need
+            lock.get_or_insert(OrderedMap::new())
+                .insert(delivery_info.delivery_tag.clone(), Some(state.clone()))
+        };
+
+        unsettled_state.map(|_| Disposition {
+            role: Role::Receiver,
+            first: delivery_info.delivery_id,
+            last: None,
+            settled,
+            state: Some(state),
+            batchable,
+        })
+    }
+
     fn handle_unsettled_in_attach(
         &mut self,
         remote_unsettled: Option<OrderedMap<DeliveryTag, Option<DeliveryState>>>,
@@ -514,6 +587,7 @@ impl<T> ReceiverLink<T> {
         match (link_credit, drain) {
             (Some(link_credit), Some(drain)) => {
                 let mut guard = self.flow_state.lock.write();
+                log_window_transition("link-credit", guard.link_credit, link_credit);
                 guard.link_credit = link_credit;
                 guard.drain = drain;
 
@@ -540,6 +614,7 @@ impl<T> ReceiverLink<T> {
             }
             (Some(link_credit), None) => {
                 let mut guard = self.flow_state.lock.write();
+                log_window_transition("link-credit", guard.link_credit, link_credit);
                 guard.link_credit = link_credit;
 
                 let properties = if include_properties {
@@ -716,6 +791,7 @@ where
             .as_ref()
             .delivery_count_mut(|_| initial_delivery_count);
 
+        self.remote_properties = remote_attach.properties.clone();
         if let Some(remote_properties) = remote_attach.properties {
             self.properties_mut(|local_properties| {
                 local_properties
@@ -850,6 +926,7 @@ where
         match attach_error {
             // Errors that indicate failed attachment
             ReceiverAttachError::IllegalSessionState
+            | ReceiverAttachError::HandleMaxReached
             | ReceiverAttachError::IllegalState
             | ReceiverAttachError::NonAttachFrameReceived
             | ReceiverAttachError::ExpectImmediateDetach
@@ -925,7 +1002,7 @@ where
 mod tests {
     use fe2o3_amqp_types::{
         messaging::{
-            message::{Body, __private::Serializable},
+            message::{__private::Serializable, Body},
             AmqpValue, DeliveryAnnotations, Header, Message, MessageAnnotations,
         },
         primitives::{OrderedMap, Value},
@@ -988,4 +1065,104 @@ mod tests {
         let final_slice = &vals[prev_ind..];
         assert_eq!(final_slice, expected.last().unwrap())
     }
+
+    #[test]
+    fn test_try_dispose() {
+        use std::{marker::PhantomData, sync::Arc};
+
+        use fe2o3_amqp_types::{
+            definitions::{DeliveryTag, ReceiverSettleMode, SenderSettleMode},
+            messaging::{Accepted, DeliveryState, Target},
+        };
+        use parking_lot::RwLock;
+        use tokio::sync::mpsc;
+
+        use crate::{
+            endpoint::{OutputHandle, ReceiverLink as _},
+            link::{
+                delivery::DeliveryInfo,
+                state::{LinkFlowState, LinkFlowStateInner, LinkState},
+                LinkFrame, ReceiverLink, TryDispositionError,
+            },
+            util::Sealed,
+        };
+
+        let mut unsettled = OrderedMap::new();
+        unsettled.insert(DeliveryTag::from("a"), None);
+        unsettled.insert(DeliveryTag::from("b"), None);
+        let link: ReceiverLink<Target> = ReceiverLink {
+            role: PhantomData,
+            local_state: LinkState::Attached,
+            name: String::from("test-receiver"),
+            output_handle: Some(OutputHandle(0)),
+            input_handle: None,
+            snd_settle_mode: SenderSettleMode::Unsettled,
+            rcv_settle_mode: ReceiverSettleMode::First,
+            source: None,
+            target: None,
+            max_message_size: 0,
+            offered_capabilities: None,
+            desired_capabilities: None,
+            remote_properties: None,
+            flow_state: Arc::new(LinkFlowState::receiver(LinkFlowStateInner {
+                initial_delivery_count: 0,
+                delivery_count: 0,
+                link_credit: 0,
+                available: 0,
+                drain: false,
+                properties: None,
+            })),
+            unsettled: Arc::new(RwLock::new(Some(unsettled))),
+            verify_incoming_source: true,
+            verify_incoming_target: true,
+            max_unsettled_in_attach: None,
+        };
+        let info = |id: u32, tag: &str| DeliveryInfo {
+            delivery_id: id,
+            delivery_tag: DeliveryTag::from(tag),
+            rcv_settle_mode: None,
+            _sealed: Sealed {},
+        };
+        let accepted = DeliveryState::Accepted(Accepted {});
+
+        let (writer, mut reader) = mpsc::channel(1);
+        link.try_dispose(&writer, info(0, "a"), None, accepted.clone(), false)
+            .unwrap();
+
+        // The channel is full and the delivery is left in the unsettled map
+        let result = link.try_dispose(&writer, info(1, "b"), None, accepted.clone(), false);
+        assert!(matches!(result, Err(TryDispositionError::Full)));
+        let contains_b = |link: &ReceiverLink<Target>| {
+            link.unsettled
+                .read()
+                .as_ref()
+                .unwrap()
+                .contains_key(&DeliveryTag::from("b"))
+        };
+        assert!(contains_b(&link));
+
+        match reader.try_recv().unwrap() {
+            LinkFrame::Disposition(disposition) => {
+                assert_eq!(disposition.first, 0);
+                assert!(disposition.settled);
+            }
+            _ => panic!("Expecting a disposition"),
+        }
+
+        // Retry once there is capacity
+        link.try_dispose(&writer, info(1, "b"), None, accepted.clone(), false)
+            .unwrap();
+        assert!(!contains_b(&link));
+        assert!(matches!(
+            reader.try_recv().unwrap(),
+            LinkFrame::Disposition(_)
+        ));
+
+        drop(reader);
+        let result = link.try_dispose(&writer, info(2, "c"), None, accepted, false);
+        assert!(matches!(
+            result,
+            Err(TryDispositionError::IllegalSessionState)
+        ));
+    }
 }
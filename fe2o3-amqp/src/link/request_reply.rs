@@ -0,0 +1,188 @@
+//! A request/reply client built on a sender and a dynamic receiver
+
+use fe2o3_amqp_types::messaging::{
+    FromBody, Message, MessageId, Outcome, Properties, SerializableBody, Source, Target,
+};
+
+use crate::{session::SessionHandle, Delivery};
+
+use super::{DetachError, Receiver, RequestReplyAttachError, RequestReplyError, Sender};
+
+/// A client that sends requests with a sender and receives the replies on a dynamic receiver
+///
+/// Each request is sent with the "reply-to" property set to the address of the dynamic receiver
+/// and with a generated id. The id is set as the "message-id" (unless the request already has
+/// one) and as the "correlation-id" of the request, and a reply is matched if its
+/// "correlation-id" is equal to the generated id. This covers both the responders that copy the
+/// "message-id" and those that copy the "correlation-id" of the request into the reply.
+///
+/// Only one request can be outstanding at a time. A reply that doesn't match the current request
+/// (eg. the reply of a request whose future was dropped) is accepted and discarded.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut client = RequestReplyClient::attach(&mut session, "rpc-client", "rpc-queue").await?;
+/// let reply: Delivery<String> = client.request(Message::from("ping")).await?;
+/// client.close().await?;
+/// ```
+#[derive(Debug)]
+pub struct RequestReplyClient {
+    next_id: u64,
+    reply_to: String,
+    sender: Sender,
+    receiver: Receiver,
+}
+
+impl RequestReplyClient {
+    /// Attaches a sender to `target` and a dynamic receiver to the session
+    ///
+    /// The links are named `"<name>-sender"` and `"<name>-receiver"`.
+    pub async fn attach<R>(
+        session: &mut SessionHandle<R>,
+        name: impl Into<String>,
+        target: impl Into<Target>,
+    ) -> Result<Self, RequestReplyAttachError> {
+        let name = name.into();
+        let sender = Sender::attach(session, format!("{}-sender", name), target).await?;
+
+        let source = Source::builder().dynamic(true).build();
+        let receiver = Receiver::builder()
+            .name(format!("{}-receiver", name))
+            .source(source)
+            .attach(session)
+            .await?;
+
+        let reply_to = match receiver.dynamic_address() {
+            Some(reply_to) => reply_to.to_string(),
+            None => {
+                let _ = sender.close().await;
+                let _ = receiver.close().await;
+                return Err(RequestReplyAttachError::DynamicAddressNotAssigned);
+            }
+        };
+
+        Ok(Self {
+            next_id: 0,
+            reply_to,
+            sender,
+            receiver,
+        })
+    }
+
+    /// The address assigned to the dynamic receiver, which is set as the "reply-to" of the
+    /// requests
+    pub fn reply_to(&self) -> &str {
+        &self.reply_to
+    }
+
+    /// Sends a request and waits for the matching reply
+    ///
+    /// The reply is accepted before it is returned.
+    ///
+    /// # Cancel safety
+    ///
+    /// If the returned future is dropped after the request is sent, the reply will be discarded
+    /// by the next call to `request`.
+    pub async fn request<T, U>(
+        &mut self,
+        message: impl Into<Message<T>>,
+    ) -> Result<Delivery<U>, RequestReplyError>
+    where
+        T: SerializableBody,
+        for<'de> U: FromBody<'de> + Send,
+    {
+        let id = MessageId::from(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        let message = prepare_request(message.into(), &id, &self.reply_to);
+
+        match self.sender.send(message).await? {
+            Outcome::Accepted(_) => {}
+            outcome => return Err(RequestReplyError::NotAccepted(outcome)),
+        }
+
+        loop {
+            let delivery: Delivery<U> = self.receiver.recv().await?;
+            self.receiver.accept(&delivery).await?;
+            if is_reply_to(&delivery.message().properties, &id) {
+                return Ok(delivery);
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(request_id = ?id, "Discarding unmatched reply");
+            #[cfg(feature = "log")]
+            log::debug!("Discarding reply that doesn't match request {:?}", id);
+        }
+    }
+
+    /// Closes both the sender and the receiver
+    pub async fn close(self) -> Result<(), DetachError> {
+        self.sender.close().await?;
+        self.receiver.close().await?;
+        Ok(())
+    }
+}
+
+/// Sets the "reply-to", "correlation-id" and, if not set, the "message-id" of the request
+fn prepare_request<T>(mut message: Message<T>, id: &MessageId, reply_to: &str) -> Message<T> {
+    let properties = message.properties.get_or_insert_with(Properties::default);
+    properties.message_id.get_or_insert_with(|| id.clone());
+    properties.correlation_id = Some(id.clone());
+    properties.reply_to = Some(reply_to.to_string());
+    message
+}
+
+fn is_reply_to(properties: &Option<Properties>, id: &MessageId) -> bool {
+    properties
+        .as_ref()
+        .and_then(|properties| properties.correlation_id.as_ref())
+        .map(|correlation_id| correlation_id == id)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use fe2o3_amqp_types::messaging::{Message, MessageId, Properties};
+
+    use super::{is_reply_to, prepare_request};
+
+    #[test]
+    fn test_prepare_request() {
+        let id = MessageId::from(7u64);
+        let message = prepare_request(Message::from("request"), &id, "reply-address");
+        let properties = message.properties.unwrap();
+        assert_eq!(properties.message_id, Some(id.clone()));
+        assert_eq!(properties.correlation_id, Some(id.clone()));
+        assert_eq!(properties.reply_to.as_deref(), Some("reply-address"));
+
+        // A message-id that is already set is kept
+        let message = Message::builder()
+            .properties(
+                Properties::builder()
+                    .message_id(String::from("request-1"))
+                    .build(),
+            )
+            .value("request")
+            .build();
+        let message = prepare_request(message, &id, "reply-address");
+        let properties = message.properties.unwrap();
+        assert_eq!(
+            properties.message_id,
+            Some(MessageId::from(String::from("request-1")))
+        );
+        assert_eq!(properties.correlation_id, Some(id));
+    }
+
+    #[test]
+    fn test_is_reply_to() {
+        let id = MessageId::from(7u64);
+        assert!(!is_reply_to(&None, &id));
+        assert!(!is_reply_to(&Some(Properties::default()), &id));
+
+        let properties = Properties::builder().correlation_id(8u64).build();
+        assert!(!is_reply_to(&Some(properties), &id));
+
+        let properties = Properties::builder().correlation_id(7u64).build();
+        assert!(is_reply_to(&Some(properties), &id));
+    }
+}
@@ -14,7 +14,11 @@ pub(crate) enum ResumingDelivery {
         sender: Option<oneshot::Sender<Option<DeliveryState>>>,
     },
     Resend(UnsettledMessage),
-    Resume(UnsettledMessage),
+    Resume {
+        message: UnsettledMessage,
+        /// The part of the payload that has not been received by the remote peer
+        remaining: Payload,
+    },
     RestateOutcome {
         payload: Payload,
         local_state: DeliveryState,
@@ -24,7 +28,7 @@ pub(crate) enum ResumingDelivery {
 }
 
 pub(crate) fn resume_delivery(
-    mut local: UnsettledMessage,
+    local: UnsettledMessage,
     remote: Option<Option<DeliveryState>>,
 ) -> Option<ResumingDelivery> {
     // The outer None indicates absence of entry
@@ -63,16 +67,11 @@ pub(crate) fn resume_delivery(
                 section_number,
                 section_offset,
             })),
-        ) => {
-            let remaining = split_off_at_section_and_offset(
-                &local.payload,
-                *section_number as usize,
-                *section_offset as usize,
-            )
-            .unwrap_or(local.payload);
-            local.payload = remaining;
-            Some(ResumingDelivery::Resume(local))
-        }
+        ) => Some(resume_from_section_and_offset(
+            local,
+            *section_number,
+            *section_offset,
+        )),
 
         // delivery-tag 3 example
         (None, Some(DeliveryState::Accepted(_)))
@@ -100,14 +99,11 @@ pub(crate) fn resume_delivery(
         ) => {
             if local_recved <= remote_recved {
                 // delivery-tag 6 case
-                let remaining = split_off_at_section_and_offset(
-                    &local.payload,
-                    remote_recved.section_number as usize,
-                    remote_recved.section_offset as usize,
-                )
-                .unwrap_or(local.payload);
-                local.payload = remaining;
-                Some(ResumingDelivery::Resume(local))
+                Some(resume_from_section_and_offset(
+                    local,
+                    remote_recved.section_number,
+                    remote_recved.section_offset,
+                ))
             } else {
                 // delivery-tag 7 and 9 case
                 //
@@ -200,38 +196,86 @@ pub(crate) fn resume_delivery(
     }
 }
 
+/// Resumes the delivery from the position recorded in the remote `Received` state.
+///
+/// The delivery is aborted if the position cannot be found in the payload, because resending
+/// the whole payload with the resume flag set would duplicate the data the receiver already has.
+fn resume_from_section_and_offset(
+    local: UnsettledMessage,
+    section_number: u32,
+    section_offset: u64,
+) -> ResumingDelivery {
+    match split_off_at_section_and_offset(&local.payload, section_number, section_offset) {
+        Some(remaining) => ResumingDelivery::Resume {
+            message: local,
+            remaining,
+        },
+        None => ResumingDelivery::Abort {
+            message_format: local.message_format,
+            sender: Some(local.sender),
+        },
+    }
+}
+
+/// Splits off the part of the payload starting at `offset` bytes from the beginning of the
+/// section with the zero-based index `section`, which is how the receiver records the position
+/// of a partially received delivery.
+///
+/// The returned payload is empty if the receiver has received the whole payload.
 fn split_off_at_section_and_offset(
     payload: &Payload,
-    section: usize,
-    offset: usize,
+    section: u32,
+    offset: u64,
 ) -> Option<Payload> {
-    let b0 = payload.iter();
-    let b1 = payload.iter().skip(1);
-    let b2 = payload.iter().skip(2);
-    let zip = b0.zip(b1.zip(b2));
-
-    let mut section_counter = None;
-    let mut last_section_index = 0;
-
-    for (i, (&b0, (&b1, &b2))) in zip.enumerate() {
-        if is_section_header(b0, b1, b2) {
-            match &mut section_counter {
-                Some(value) => *value += 1,
-                None => section_counter = Some(0),
-            }
-            last_section_index = i;
-        }
-
-        if section_counter == Some(section) && i - last_section_index == offset {
-            return Some(payload.slice(i..));
-        }
+    let section_start = (0..payload.len().saturating_sub(2))
+        .filter(|&i| is_section_header(payload[i], payload[i + 1], payload[i + 2]))
+        .nth(section as usize)?;
+    let index = usize::try_from(offset)
+        .ok()
+        .and_then(|offset| section_start.checked_add(offset))?;
+    match index <= payload.len() {
+        true => Some(payload.slice(index..)),
+        false => None,
     }
-
-    None
 }
 
 #[cfg(test)]
 mod tests {
+    use fe2o3_amqp_types::messaging::{message_to_vec, Message, Properties};
+
+    use crate::Payload;
+
+    use super::split_off_at_section_and_offset;
+
+    #[test]
+    fn test_split_off_at_section_and_offset() {
+        let properties = Properties::builder().message_id(1u64).build();
+        let message = Message::builder()
+            .properties(properties.clone())
+            .data(vec![1u8; 32])
+            .build();
+        let payload = Payload::from(message_to_vec(&message).unwrap());
+        // The data section starts right after the properties section
+        let data_start = serde_amqp::to_vec(&properties).unwrap().len();
+        let data_len = payload.len() - data_start;
+
+        assert_eq!(
+            split_off_at_section_and_offset(&payload, 0, 0),
+            Some(payload.clone())
+        );
+        assert_eq!(
+            split_off_at_section_and_offset(&payload, 1, 5),
+            Some(payload.slice(data_start + 5..))
+        );
+
+        // The whole payload has been received
+        let remaining = split_off_at_section_and_offset(&payload, 1, data_len as u64).unwrap();
+        assert!(remaining.is_empty());
+
+        // The position is not in the payload
+        assert!(split_off_at_section_and_offset(&payload, 1, data_len as u64 + 1).is_none());
+        assert!(split_off_at_section_and_offset(&payload, 2, 0).is_none());
+    }
 
     #[test]
     fn test_zipped_iter() {
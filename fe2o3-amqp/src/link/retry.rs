@@ -0,0 +1,90 @@
+//! Retrying link attach on transient errors
+
+use std::time::Duration;
+
+/// Default number of retries after the first attempt
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Default upper bound of the delay between two retries
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Policy for retrying an attach that failed with a transient error
+///
+/// The delay before each retry starts at `initial_backoff` and is doubled after every retry until
+/// it reaches `max_backoff`.
+///
+/// # Default configuration
+///
+/// | Field | Default Value |
+/// |-------|---------------|
+/// |`max_retries`| [`DEFAULT_MAX_RETRIES`] |
+/// |`initial_backoff`| [`DEFAULT_INITIAL_BACKOFF`] |
+/// |`max_backoff`| [`DEFAULT_MAX_BACKOFF`] |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt
+    pub max_retries: u32,
+
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+
+    /// Upper bound of the delay between two retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a new policy
+    pub fn new(max_retries: u32, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_backoff,
+            max_backoff,
+        }
+    }
+
+    /// A policy that never retries
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before the retry following the zero-based `attempt`
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    #[test]
+    fn backoff_doubles_until_max_backoff() {
+        let policy = RetryPolicy::new(10, Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+    }
+}
@@ -9,10 +9,11 @@ cfg_not_wasm32! {
 }
 
 use fe2o3_amqp_types::{
-    definitions::{self, DeliveryTag, Fields, MessageFormat, SenderSettleMode},
+    definitions::{
+        self, DeliveryTag, Fields, Handle, MessageFormat, ReceiverSettleMode, SenderSettleMode,
+    },
     messaging::{
-        message::__private::Serializable, Address, DeliveryState, Outcome, SerializableBody,
-        Source, Target,
+        message::__private::Serializable, DeliveryState, Outcome, SerializableBody, Source, Target,
     },
     performatives::{Attach, Detach, Transfer},
     primitives::OrderedMap,
@@ -21,22 +22,26 @@ use fe2o3_amqp_types::{
 use crate::{
     control::SessionControl,
     endpoint::{self, LinkAttach, LinkDetach, LinkExt, Settlement},
-    session::SessionHandle,
+    session::{SessionHandle, SessionId},
     Payload,
 };
 
 use super::{
     builder::{self, WithSource, WithoutName, WithoutTarget},
-    delivery::{DeliveryFut, Sendable, UnsettledMessage},
+    delivery::{
+        DeliveryFut, FromDeliveryState, OnSettled, SendResult, Sendable, SettleOnDrop,
+        UnsettledMessage,
+    },
     error::DetachError,
     resumption::ResumingDelivery,
     role,
     shared_inner::{
         recv_remote_detach, LinkEndpointInner, LinkEndpointInnerDetach, LinkEndpointInnerReattach,
     },
-    ArcSenderUnsettledMap, DetachThenResumeSenderError, LinkFrame, LinkRelay, LinkStateError,
-    SendError, SenderAttachError, SenderAttachExchange, SenderFlowState, SenderLink,
-    SenderResumeError, SenderResumeErrorKind,
+    state::LinkFlowSnapshot,
+    ArcSenderUnsettledMap, DetachThenResumeSenderError, LinkFrame, LinkId, LinkProperties,
+    LinkRelay, LinkStateError, RetryPolicy, SendError, SenderAttachError, SenderAttachExchange,
+    SenderFlowState, SenderLink, SenderRedirectError, SenderResumeError, SenderResumeErrorKind,
 };
 
 #[cfg(docsrs)]
@@ -117,12 +122,58 @@ impl Sender {
         self.inner.link.name()
     }
 
+    /// Get the local handle of the link. This is `None` if the link is detached
+    pub fn handle(&self) -> Option<Handle> {
+        self.inner.link.output_handle.clone().map(Into::into)
+    }
+
+    /// Get the identity of the link, which can be used as a key for logging and metrics
+    pub fn link_id(&self) -> LinkId {
+        LinkId::new(
+            &self.inner.session_id,
+            self.inner.link.output_handle.as_ref(),
+            self.inner.link.name(),
+        )
+    }
+
     /// Returns the `max_message_size` of the link. A value of zero indicates that the link has no
     /// maximum message size, and thus a zero value is turned into a `None`
     pub fn max_message_size(&self) -> Option<u64> {
         self.inner.link.max_message_size()
     }
 
+    /// Returns a snapshot of the link flow state, which reflects the credit granted by the remote
+    /// receiver
+    pub fn flow_state_snapshot(&self) -> LinkFlowSnapshot {
+        self.inner.link.flow_state.state().snapshot()
+    }
+
+    /// Returns the link credit that is left from the last flow received from the remote receiver
+    ///
+    /// Each message sent consumes one credit, and [`send()`](#method.send) and
+    /// [`send_batchable()`](#method.send_batchable) wait until the remote receiver grants more
+    /// credit once it is exhausted. Publishers can check this beforehand to pace themselves to the
+    /// demand of the consumers instead of waiting on a send.
+    pub fn credit(&self) -> u32 {
+        self.inner.link.flow_state.state().link_credit()
+    }
+
+    /// Returns the sender settle mode of the link
+    ///
+    /// A sender attach is refused if the remote receiver does not agree on the settle modes, so
+    /// once attached this is the mode in effect on both ends of the link.
+    pub fn sender_settle_mode(&self) -> &SenderSettleMode {
+        &self.inner.link.snd_settle_mode
+    }
+
+    /// Returns the receiver settle mode of the link
+    ///
+    /// This is the mode that the remote receiver uses to settle the deliveries, which decides
+    /// whether an outcome is reported before or after the receiver settles.
+    pub fn receiver_settle_mode(&self) -> &ReceiverSettleMode {
+        &self.inner.link.rcv_settle_mode
+    }
+
     /// Get a reference to the link's source field
     pub fn source(&self) -> &Option<Source> {
         &self.inner.link.source
@@ -143,6 +194,17 @@ impl Sender {
         &mut self.inner.link.target
     }
 
+    /// Returns the address of the node created by the remote peer if the sender is attached with
+    /// a dynamic target, or `None` if the target is not dynamic
+    pub fn dynamic_address(&self) -> Option<&str> {
+        self.inner
+            .link
+            .target
+            .as_ref()
+            .filter(|target| target.dynamic)
+            .and_then(|target| target.address.as_deref())
+    }
+
     /// Get a reference to the link's properties field in the op
     pub fn properties<F, O>(&self, op: F) -> O
     where
@@ -159,6 +221,19 @@ impl Sender {
         self.inner.link.properties_mut(op)
     }
 
+    /// Get a reference to the `properties` field of the `Attach` received from the remote peer
+    ///
+    /// This is `None` if the link has not been attached yet or if the remote peer did not send
+    /// any properties
+    pub fn remote_properties(&self) -> Option<&Fields> {
+        self.inner.link.remote_properties.as_ref()
+    }
+
+    /// Get a typed view over the `properties` field of the `Attach` received from the remote peer
+    pub fn remote_link_properties(&self) -> Option<LinkProperties<'_>> {
+        self.remote_properties().map(LinkProperties::new)
+    }
+
     /// Attach the sender link to a session with default configuration
     /// with the `name` and `target` address set to the specified values
     ///
@@ -189,14 +264,96 @@ impl Sender {
     /// ).await.unwrap();
     /// ```
     ///
+    /// The `target` argument accepts anything that converts into a [`Target`], including an
+    /// address string and a [`TargetBuilder`](fe2o3_amqp_types::messaging::TargetBuilder).
     pub async fn attach<R>(
         session: &mut SessionHandle<R>,
         name: impl Into<String>,
-        addr: impl Into<Address>,
+        target: impl Into<Target>,
     ) -> Result<Sender, SenderAttachError> {
         Self::builder()
             .name(name)
-            .target(addr)
+            .target(target)
+            .attach(session)
+            .await
+    }
+
+    /// Attach the sender link to a session with the default configuration and retry the attach
+    /// with backoff if it fails with a transient error (see [`SenderAttachError::is_transient`])
+    ///
+    /// Transient errors include a link that is still being detached (`IllegalState`), the
+    /// maximum number of links being reached, or the remote peer closing the link with a condition
+    /// like `amqp:resource-limit-exceeded` or `amqp:internal-error`. Any other error, eg.
+    /// `amqp:unauthorized-access` or `amqp:not-found`, is returned immediately.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let sender = Sender::attach_with_retry(
+    ///     &mut session,
+    ///     "rust-sender-link-1",
+    ///     "q1",
+    ///     RetryPolicy::default(),
+    /// ).await.unwrap();
+    /// ```
+    pub async fn attach_with_retry<R>(
+        session: &mut SessionHandle<R>,
+        name: impl Into<String>,
+        target: impl Into<Target>,
+        policy: RetryPolicy,
+    ) -> Result<Sender, SenderAttachError> {
+        let name = name.into();
+        let target = target.into();
+        let mut attempt = 0;
+        loop {
+            match Self::attach(session, name.clone(), target.clone()).await {
+                Ok(sender) => return Ok(sender),
+                Err(error) if attempt < policy.max_retries && error.is_transient() => {
+                    crate::util::sleep(policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Attach the sender link to a session with a hand-constructed [`Attach`] performative
+    ///
+    /// This is an escape hatch for testing the behavior of a remote peer and for fields that are
+    /// not covered by the [`builder`](Sender::builder). Only the minimum required fields are
+    /// validated: the `role` must be `Role::Sender`, and both the `target` and the
+    /// `initial_delivery_count` must be set. Otherwise `SenderAttachError::IllegalState` is
+    /// returned without sending anything.
+    ///
+    /// The `handle` is allocated by the session and is thus ignored. The `unsettled` map is also
+    /// ignored because the sender can only resume deliveries whose payload it still holds.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let attach = Attach {
+    ///     name: "rust-sender-link-1".into(),
+    ///     handle: 0.into(),
+    ///     role: Role::Sender,
+    ///     snd_settle_mode: SenderSettleMode::Mixed,
+    ///     rcv_settle_mode: ReceiverSettleMode::First,
+    ///     source: Some(Box::new(Source::default())),
+    ///     target: Some(Box::new(Target::from("q1").into())),
+    ///     unsettled: None,
+    ///     incomplete_unsettled: false,
+    ///     initial_delivery_count: Some(0),
+    ///     max_message_size: None,
+    ///     offered_capabilities: None,
+    ///     desired_capabilities: Some(vec![Symbol::from("my-capability")].into()),
+    ///     properties: None,
+    /// };
+    /// let sender = Sender::attach_with_performative(&mut session, attach).await.unwrap();
+    /// ```
+    pub async fn attach_with_performative<R>(
+        session: &mut SessionHandle<R>,
+        attach: Attach,
+    ) -> Result<Sender, SenderAttachError> {
+        builder::Builder::<role::SenderMarker, Target, _, _, _>::from_attach(attach)?
             .attach(session)
             .await
     }
@@ -257,6 +414,7 @@ impl Sender {
 
         // Re-attach the link
         self.inner.session = new_session.control.clone();
+        self.inner.session_id = new_session.id.clone();
         self.inner.outgoing = new_session.outgoing.clone();
         let attach_result = self
             .inner
@@ -270,6 +428,51 @@ impl Sender {
         }
     }
 
+    /// Close the link and attach a new link with the same name to a new target
+    ///
+    /// The new link keeps the name, the source and the settle modes of this link. All the other
+    /// configurations of the new link (eg. `max_message_size` and capabilities) are the defaults
+    /// of [`Sender::builder`]. This is useful when the remote peer responds with an
+    /// `amqp:link:redirect` error that points to a different node.
+    ///
+    /// Unsettled deliveries of this link are not carried over. This will still attempt to attach
+    /// the new link even if closing this link fails. `SenderRedirectError::Attach` will be
+    /// returned if the close succeeds but the attach fails. `SenderRedirectError::Detach` will be
+    /// returned if both the close and the attach fail.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let sender = Sender::attach(&mut session, "rust-sender-link-1", "q1").await.unwrap();
+    /// let sender = sender.redirect(&mut session, "q2").await.unwrap();
+    /// ```
+    pub async fn redirect<R>(
+        mut self,
+        session: &mut SessionHandle<R>,
+        new_target: impl Into<Target>,
+    ) -> Result<Sender, SenderRedirectError> {
+        let name = self.inner.link.name.clone();
+        let source = self.inner.link.source.clone().unwrap_or_default();
+        let snd_settle_mode = self.inner.link.snd_settle_mode.clone();
+        let rcv_settle_mode = self.inner.link.rcv_settle_mode.clone();
+
+        let close_result = self.inner.close_with_error(None).await;
+        let attach_result = Self::builder()
+            .name(name)
+            .source(source)
+            .target(new_target)
+            .sender_settle_mode(snd_settle_mode)
+            .receiver_settle_mode(rcv_settle_mode)
+            .attach(session)
+            .await;
+
+        match (close_result, attach_result) {
+            (_, Ok(sender)) => Ok(sender),
+            (Ok(()), Err(e)) => Err(SenderRedirectError::Attach(e)),
+            (Err(e), Err(_)) => Err(SenderRedirectError::Detach(e)),
+        }
+    }
+
     /// Close the link.
     ///
     /// This will set the `closed` field in the Detach performative to true
@@ -381,6 +584,11 @@ impl Sender {
     /// let outcome = sender.send(sendable).await.unwrap():
     /// ```
     ///
+    /// # Flow control
+    ///
+    /// This waits until the remote receiver grants link credit if there is none left. The
+    /// remaining credit can be checked with [`credit()`](#method.credit).
+    ///
     /// # Cancel safety
     ///
     /// This function is cancel-safe. See [#22](https://github.com/minghuaw/fe2o3-amqp/issues/22)
@@ -413,6 +621,78 @@ impl Sender {
         fut.await
     }
 
+    /// Send a message with explicit control over the `settled` and initial `state` fields of the
+    /// `Transfer` performative and wait for acknowledgement (disposition)
+    ///
+    /// The `settled` argument overrides the `settled` field of the [`Sendable`] and, like
+    /// [`send()`](#method.send), will be ***ignored*** if the negotiated `SenderSettleMode` is
+    /// not `SenderSettleMode::Mixed`. If the message is sent pre-settled and a `state` is given,
+    /// the [`Outcome`] corresponding to `state` is returned without waiting for the remote peer.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let state = DeliveryState::Released(Released {});
+    /// let outcome = sender.send_with("hello AMQP", true, Some(state)).await.unwrap();
+    /// ```
+    pub async fn send_with<T: SerializableBody>(
+        &mut self,
+        sendable: impl Into<Sendable<T>>,
+        settled: bool,
+        state: Option<DeliveryState>,
+    ) -> Result<Outcome, SendError> {
+        let mut sendable = sendable.into();
+        sendable.settled = Some(settled);
+        let settlement = self
+            .inner
+            .send_with_state::<T, SendError>(sendable, state.clone(), false)
+            .await?;
+        match (settlement, state) {
+            (Settlement::Settled(_), Some(state)) => SendResult::from_delivery_state(state),
+            (settlement, _) => DeliveryFut::from(settlement).await,
+        }
+    }
+
+    /// Send a message pre-settled without waiting for any acknowledgement
+    ///
+    /// This is the fire-and-forget path for senders whose negotiated `SenderSettleMode` is
+    /// `SenderSettleMode::Settled` or `SenderSettleMode::Mixed`, in which case the message is
+    /// always sent with the `settled` field set to true. This returns as soon as the transfer is
+    /// handed to the session, and there is no outcome to wait for.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SendError::UnsettledSenderSettleMode` without sending the message if the
+    /// negotiated `SenderSettleMode` is `SenderSettleMode::Unsettled`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut sender = Sender::builder()
+    ///     .name("rust-sender-link-1")
+    ///     .target("q1")
+    ///     .sender_settle_mode(SenderSettleMode::Settled)
+    ///     .attach(&mut session)
+    ///     .await
+    ///     .unwrap();
+    /// sender.send_settled("hello AMQP").await.unwrap();
+    /// ```
+    pub async fn send_settled<T: SerializableBody>(
+        &mut self,
+        sendable: impl Into<Sendable<T>>,
+    ) -> Result<(), SendError> {
+        if let SenderSettleMode::Unsettled = self.inner.link.snd_settle_mode {
+            return Err(SendError::UnsettledSenderSettleMode);
+        }
+
+        let mut sendable = sendable.into();
+        sendable.settled = Some(true);
+        self.inner
+            .send_with_state::<T, SendError>(sendable, None, false)
+            .await?;
+        Ok(())
+    }
+
     cfg_not_wasm32! {
         /// Send a message and wait for acknowledgement (disposition) with a timeout.
         ///
@@ -437,14 +717,27 @@ impl Sender {
     /// let fut = sender.send_batchable("HELLO AMQP").await.unwrap();
     /// let result = fut.await;
     /// ```
+    ///
+    /// # Dropping the returned future
+    ///
+    /// By default, dropping the returned [`DeliveryFut`] only discards the outcome, and the
+    /// delivery remains unsettled until the remote receiver settles it. If the sender is built
+    /// with `settle_on_fut_drop(true)`, dropping an unresolved [`DeliveryFut`] settles the
+    /// delivery locally with the `Released` outcome.
+    ///
+    /// # Flow control
+    ///
+    /// Like [`send()`](#method.send), this waits until the remote receiver grants link credit if
+    /// there is none left.
     pub async fn send_batchable<T: SerializableBody>(
         &mut self,
         sendable: impl Into<Sendable<T>>,
     ) -> Result<DeliveryFut<Result<Outcome, SendError>>, SendError> {
+        let settle_on_drop = self.inner.settle_on_drop();
         self.inner
             .send_with_state(sendable.into(), None, true)
             .await
-            .map(DeliveryFut::from)
+            .map(|settlement| DeliveryFut::from(settlement).with_settle_on_drop(settle_on_drop))
     }
 
     /// Like [`send_batchable()`](#method.send_batchable) but this only takes a reference.
@@ -455,30 +748,44 @@ impl Sender {
         &mut self,
         sendable: &Sendable<T>,
     ) -> Result<DeliveryFut<Result<Outcome, SendError>>, SendError> {
+        let settle_on_drop = self.inner.settle_on_drop();
         self.inner
             .send_ref_with_state(sendable, None, true)
             .await
-            .map(DeliveryFut::from)
+            .map(|settlement| DeliveryFut::from(settlement).with_settle_on_drop(settle_on_drop))
+    }
+
+    /// Waits until the transfers sent by this sender are no longer buffered by the session and
+    /// have been handed to the connection to be written to the wire
+    ///
+    /// The session may hold transfers back while the remote incoming-window is exhausted. This
+    /// does not wait for the outcomes of the deliveries, which are still reported by the
+    /// [`DeliveryFut`]s returned by [`send_batchable()`](#method.send_batchable).
+    ///
+    /// # Errors
+    ///
+    /// Returns `SendError::LinkStateError(LinkStateError::IllegalSessionState)` if the session
+    /// stops before the buffered transfers are sent.
+    pub async fn flush(&mut self) -> Result<(), SendError> {
+        let (tx, rx) = oneshot::channel();
+        self.inner
+            .outgoing
+            .send(LinkFrame::Flush(tx))
+            .await
+            .map_err(|_| LinkStateError::IllegalSessionState)?;
+        rx.await
+            .map_err(|_| LinkStateError::IllegalSessionState.into())
     }
 
     /// Returns when the remote peer detach/close the link
-    pub async fn on_detach(&mut self) -> DetachError {
-        match recv_remote_detach(&mut self.inner).await {
-            Ok(detach) => {
-                let closed = detach.closed;
-                match self.inner.link.on_incoming_detach(detach) {
-                    Ok(_) => {
-                        if closed {
-                            DetachError::ClosedByRemote
-                        } else {
-                            DetachError::DetachedByRemote
-                        }
-                    }
-                    Err(err) => err,
-                }
-            }
-            Err(err) => err,
-        }
+    ///
+    /// `Ok(())` is returned if the remote peer detached or closed the link without an error. The
+    /// error sent by the remote peer is returned as [`DetachError::RemoteDetachedWithError`] or
+    /// [`DetachError::RemoteClosedWithError`], which allows telling a graceful detach apart from
+    /// one that is caused by an error.
+    pub async fn on_detach(&mut self) -> Result<(), DetachError> {
+        let detach = recv_remote_detach(&mut self.inner).await?;
+        self.inner.link.on_incoming_detach(detach)
     }
 }
 
@@ -493,9 +800,18 @@ pub(crate) struct SenderInner<L: endpoint::SenderLink> {
     // Control sender to the session
     pub(crate) session: mpsc::Sender<SessionControl>,
 
+    // Identity of the session that the link is attached to
+    pub(crate) session_id: SessionId,
+
     // Outgoing mpsc channel to send the Link frames
     pub(crate) outgoing: mpsc::Sender<LinkFrame>,
     pub(crate) incoming: mpsc::Receiver<LinkFrame>,
+
+    // Carried over to the new link relay when re-attaching
+    pub(crate) on_settled: Option<OnSettled>,
+
+    // Whether dropping an unresolved `DeliveryFut` settles the delivery as released
+    pub(crate) settle_on_fut_drop: bool,
 }
 
 impl<L: endpoint::SenderLink> Drop for SenderInner<L> {
@@ -545,6 +861,7 @@ where
             // TODO: what else to do during re-attaching
             unsettled: self.link.unsettled().clone(),
             receiver_settle_mode: self.link.rcv_settle_mode().clone(),
+            on_settled: self.on_settled.clone(),
         }
     }
 
@@ -639,6 +956,7 @@ where
             message,
             message_format,
             settled,
+            rcv_settle_mode,
         } = sendable;
 
         // serialize message
@@ -647,8 +965,15 @@ where
         Serializable(message).serialize(&mut serializer)?;
         let payload = payload.freeze();
 
-        self.send_payload(payload, message_format, settled, state, batchable)
-            .await
+        self.send_payload(
+            payload,
+            message_format,
+            settled,
+            rcv_settle_mode,
+            state,
+            batchable,
+        )
+        .await
     }
 
     pub(crate) async fn send_ref_with_state<T, E>(
@@ -669,6 +994,7 @@ where
             message,
             message_format,
             settled,
+            rcv_settle_mode,
         } = sendable;
 
         // serialize message
@@ -677,8 +1003,15 @@ where
         Serializable(message).serialize(&mut serializer)?;
         let payload = payload.freeze();
 
-        self.send_payload(payload, *message_format, *settled, state, batchable)
-            .await
+        self.send_payload(
+            payload,
+            *message_format,
+            *settled,
+            rcv_settle_mode.clone(),
+            state,
+            batchable,
+        )
+        .await
     }
 
     pub(crate) async fn send_payload<E>(
@@ -686,6 +1019,7 @@ where
         payload: Payload,
         message_format: MessageFormat,
         settled: Option<bool>,
+        rcv_settle_mode: Option<ReceiverSettleMode>,
         state: Option<DeliveryState>,
         batchable: bool,
     ) -> Result<Settlement, E>
@@ -702,6 +1036,7 @@ where
                 payload,
                 message_format,
                 settled,
+                rcv_settle_mode,
                 state,
                 batchable,
             )
@@ -711,6 +1046,18 @@ where
 }
 
 impl SenderInner<SenderLink<Target>> {
+    /// Returns the handle used to settle an unresolved `DeliveryFut` on drop if enabled
+    pub(crate) fn settle_on_drop(&self) -> Option<SettleOnDrop> {
+        if !self.settle_on_fut_drop {
+            return None;
+        }
+        Some(SettleOnDrop {
+            session: self.session.clone(),
+            input_handle: self.link.input_handle.clone()?,
+            unsettled: self.link.unsettled.clone(),
+        })
+    }
+
     /// Resumes a delivery with the given state and payload.
     ///
     /// The resume operation should not replace the unsettled map entry.
@@ -730,8 +1077,8 @@ impl SenderInner<SenderLink<Target>> {
                 sender,
             } => self.abort(delivery_tag, message_format, sender).await?,
             ResumingDelivery::Resend(unsettled_message) => resend_buf.push(unsettled_message),
-            ResumingDelivery::Resume(unsettled_message) => {
-                self.resume(delivery_tag, unsettled_message).await?
+            ResumingDelivery::Resume { message, remaining } => {
+                self.resume(delivery_tag, message, remaining).await?
             }
             ResumingDelivery::RestateOutcome {
                 message_format,
@@ -807,6 +1154,7 @@ impl SenderInner<SenderLink<Target>> {
         &mut self,
         delivery_tag: DeliveryTag,
         unsettled_message: UnsettledMessage,
+        remaining: Payload,
     ) -> Result<(), SendError> {
         let handle = self
             .link
@@ -835,11 +1183,7 @@ impl SenderInner<SenderLink<Target>> {
 
         let settled = self
             .link
-            .send_transfer_without_modifying_unsettled_map(
-                &self.outgoing,
-                transfer,
-                unsettled_message.payload.clone(),
-            )
+            .send_transfer_without_modifying_unsettled_map(&self.outgoing, transfer, remaining)
             .await?;
 
         match settled {
@@ -921,6 +1265,7 @@ impl SenderInner<SenderLink<Target>> {
             unsettled_message.message_format,
             None,
             None,
+            None,
             false,
         )?;
 
@@ -1008,6 +1353,23 @@ impl SenderInner<SenderLink<Target>> {
 /// let detached = sender.detach().await.unwrap();
 /// let sender = detached.resume().await.unwrap():
 /// ```
+///
+/// Link migration
+///
+/// The detached sender keeps the link's unsettled deliveries and can be held until a new session
+/// is available, ie. after re-connecting, and then resumed on that session. The unsettled
+/// deliveries are exchanged in the re-attach so that they can be resumed on the new link.
+///
+/// ```rust,ignore
+/// let detached = sender.detach().await.unwrap();
+///
+/// let mut connection = Connection::open("connection-2", "amqp://localhost:5672").await.unwrap();
+/// let mut session = Session::begin(&mut connection).await.unwrap();
+/// let sender = detached.resume_on_session(&session).await.unwrap();
+/// ```
+///
+/// The detached sender is not serializable because the unsettled deliveries hold the channels
+/// that are used to notify the pending [`send`](Sender::send) calls of the outcome.
 #[derive(Debug)]
 pub struct DetachedSender {
     inner: SenderInner<SenderLink<Target>>,
@@ -1156,6 +1518,7 @@ impl DetachedSender {
     ) -> Result<Sender, SenderResumeError> {
         let is_reattaching = !self.inner.session.same_channel(&session.control);
         self.inner.session = session.control.clone();
+        self.inner.session_id = session.id.clone();
         self.inner.outgoing = session.outgoing.clone();
         self.resume_inner(is_reattaching).await
     }
@@ -1168,6 +1531,7 @@ impl DetachedSender {
     ) -> Result<Sender, SenderResumeError> {
         let is_reattaching = !self.inner.session.same_channel(&session.control);
         self.inner.session = session.control.clone();
+        self.inner.session_id = session.id.clone();
         self.inner.outgoing = session.outgoing.clone();
 
         try_as_sender!(
@@ -1188,6 +1552,7 @@ impl DetachedSender {
         ) -> Result<Sender, SenderResumeError> {
             let is_reattaching = !self.inner.session.same_channel(&session.control);
             self.inner.session = session.control.clone();
+            self.inner.session_id = session.id.clone();
             self.inner.outgoing = session.outgoing.clone();
             self.resume_with_timeout_inner(duration, is_reattaching).await
         }
@@ -1201,6 +1566,7 @@ impl DetachedSender {
         ) -> Result<Sender, SenderResumeError> {
             let is_reattaching = !self.inner.session.same_channel(&session.control);
             self.inner.session = session.control.clone();
+            self.inner.session_id = session.id.clone();
             self.inner.outgoing = session.outgoing.clone();
             self.resume_incoming_attach_with_timeout_inner(remote_attach, duration, is_reattaching)
                 .await
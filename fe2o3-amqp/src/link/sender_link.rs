@@ -23,11 +23,7 @@ where
         mut transfer: Transfer,
         mut payload: Payload,
     ) -> Result<bool, LinkStateError> {
-        let settled = transfer.settled.unwrap_or(match self.snd_settle_mode {
-            SenderSettleMode::Settled => true,
-            SenderSettleMode::Unsettled => false,
-            SenderSettleMode::Mixed => false,
-        });
+        let settled = self.is_settled(&transfer);
         let input_handle = self
             .input_handle
             .clone()
@@ -67,6 +63,14 @@ where
         Ok(settled)
     }
 
+    fn is_settled(&self, transfer: &Transfer) -> bool {
+        transfer.settled.unwrap_or(match self.snd_settle_mode {
+            SenderSettleMode::Settled => true,
+            SenderSettleMode::Unsettled => false,
+            SenderSettleMode::Mixed => false,
+        })
+    }
+
     pub(crate) async fn get_delivery_tag_or_detached<Fut>(
         &mut self,
         writer: &mpsc::Sender<LinkFrame>,
@@ -126,6 +130,7 @@ where
         delivery_tag: DeliveryTag,
         message_format: MessageFormat,
         settled: Option<bool>,
+        rcv_settle_mode: Option<ReceiverSettleMode>,
         state: Option<DeliveryState>,
         batchable: bool,
     ) -> Result<Transfer, LinkStateError> {
@@ -156,8 +161,9 @@ where
             more: false, // This will be changed later
 
             // If not set, this value is defaulted to the value negotiated
-            // on link attach.
-            rcv_settle_mode: None,
+            // on link attach. If the message is being sent settled by the sender,
+            // the value of this field is ignored.
+            rcv_settle_mode: if settled { None } else { rcv_settle_mode },
             state,
             resume,
             aborted: false,
@@ -187,6 +193,7 @@ where
         payload: Payload,
         message_format: MessageFormat,
         settled: Option<bool>,
+        rcv_settle_mode: Option<ReceiverSettleMode>,
         state: Option<DeliveryState>,
         batchable: bool,
     ) -> Result<Settlement, Self::TransferError>
@@ -201,6 +208,7 @@ where
             delivery_tag,
             message_format,
             settled,
+            rcv_settle_mode,
             state,
             batchable,
         )?;
@@ -226,14 +234,17 @@ where
             .delivery_tag
             .clone()
             .ok_or(LinkStateError::IllegalState)?;
-        let settled = self
-            .send_transfer_without_modifying_unsettled_map(writer, transfer, payload)
-            .await?;
-        match settled {
-            true => Ok(Settlement::Settled(delivery_tag)),
+        match self.is_settled(&transfer) {
+            true => {
+                self.send_transfer_without_modifying_unsettled_map(writer, transfer, payload)
+                    .await?;
+                Ok(Settlement::Settled(delivery_tag))
+            }
             // If not set on the first (or only) transfer for a (multi-transfer)
             // delivery, then the settled flag MUST be interpreted as being false.
             false => {
+                // The message is added to the unsettled map before the transfer is sent because
+                // the disposition may arrive before this function returns
                 let (tx, rx) = oneshot::channel();
                 let unsettled = UnsettledMessage::new(payload_copy, None, message_format, tx);
                 {
@@ -242,6 +253,13 @@ where
                         .get_or_insert(OrderedMap::new())
                         .insert(delivery_tag.clone(), unsettled);
                 }
+                let unsent = UnsentDelivery {
+                    unsettled: self.unsettled.clone(),
+                    delivery_tag: Some(delivery_tag.clone()),
+                };
+                self.send_transfer_without_modifying_unsettled_map(writer, transfer, payload)
+                    .await?;
+                unsent.sent();
 
                 Ok(Settlement::Unsettled {
                     delivery_tag,
@@ -355,6 +373,29 @@ where
     }
 }
 
+/// Removes an unsettled message from the unsettled map when dropped before the transfer is
+/// sent, which keeps sending the transfer cancel safe
+struct UnsentDelivery {
+    unsettled: ArcSenderUnsettledMap,
+    delivery_tag: Option<DeliveryTag>,
+}
+
+impl UnsentDelivery {
+    fn sent(mut self) {
+        self.delivery_tag = None;
+    }
+}
+
+impl Drop for UnsentDelivery {
+    fn drop(&mut self) {
+        if let Some(delivery_tag) = self.delivery_tag.take() {
+            if let Some(map) = self.unsettled.write().as_mut() {
+                map.swap_remove(&delivery_tag);
+            }
+        }
+    }
+}
+
 /// # Cancel safety
 ///
 /// This is cancel safe because it only involves `.await` on sending over `tokio::mpsc::Sender`
@@ -570,6 +611,7 @@ where
         self.max_message_size =
             get_max_message_size(self.max_message_size, remote_attach.max_message_size);
 
+        self.remote_properties = remote_attach.properties.clone();
         if let Some(remote_properties) = remote_attach.properties {
             self.properties_mut(|local_properties| {
                 local_properties
@@ -697,6 +739,7 @@ where
     ) -> SenderAttachError {
         match attach_error {
             SenderAttachError::IllegalSessionState
+            | SenderAttachError::HandleMaxReached
             | SenderAttachError::IllegalState
             | SenderAttachError::NonAttachFrameReceived
             | SenderAttachError::ExpectImmediateDetach
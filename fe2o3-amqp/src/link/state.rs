@@ -4,10 +4,11 @@ use std::{marker::PhantomData, sync::Arc};
 
 use fe2o3_amqp_types::definitions::{Fields, SequenceNo};
 use parking_lot::RwLock;
+use tokio::sync::{futures::Notified, Notify};
 
 use crate::{
     endpoint::{LinkFlow, OutputHandle},
-    util::{Consume, ProducerState},
+    util::{log_window_transition, Consume, ProducerState},
 };
 
 use super::{role, ReceiverTransferError, SenderFlowState};
@@ -57,6 +58,25 @@ pub enum LinkState {
     Closed,
 }
 
+/// A read-only snapshot of the flow state of a link
+///
+/// The values may change as soon as the snapshot is taken because the flow state is updated by
+/// both the link and the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkFlowSnapshot {
+    /// The current link credit
+    pub credit: u32,
+
+    /// The current delivery count
+    pub delivery_count: SequenceNo,
+
+    /// The number of messages awaiting credit at the sender as last known by the link endpoint
+    pub available: u32,
+
+    /// The drain flag
+    pub drain: bool,
+}
+
 #[derive(Debug)]
 pub(crate) struct LinkFlowStateInner {
     pub initial_delivery_count: SequenceNo,
@@ -96,6 +116,9 @@ impl LinkFlowStateInner {
 #[derive(Debug)]
 pub(crate) struct LinkFlowState<R> {
     pub(crate) lock: RwLock<LinkFlowStateInner>,
+
+    // Notifies the receiver endpoint that a Flow has been received from the remote sender
+    flow_received: Notify,
     role: PhantomData<R>,
 }
 
@@ -103,6 +126,7 @@ impl<R> LinkFlowState<R> {
     pub(crate) fn new(inner: LinkFlowStateInner) -> Self {
         Self {
             lock: RwLock::new(inner),
+            flow_received: Notify::new(),
             role: PhantomData,
         }
     }
@@ -155,9 +179,13 @@ impl LinkFlowState<role::SenderMarker> {
         );
 
         if let Some(link_credit_rcv) = flow.link_credit {
-            let link_credit = delivery_count_rcv
-                .saturating_add(link_credit_rcv)
-                .saturating_sub(state.delivery_count);
+            // The delivery-count is a sequence number which wraps around, so the number of
+            // deliveries that are not yet known to the receiver is computed with serial number
+            // arithmetic
+            let in_flight = state.delivery_count.wrapping_sub(delivery_count_rcv);
+            let in_flight = if (in_flight as i32) < 0 { 0 } else { in_flight };
+            let link_credit = link_credit_rcv.saturating_sub(in_flight);
+            log_window_transition("link-credit", state.link_credit, link_credit);
             state.link_credit = link_credit;
         }
 
@@ -176,6 +204,7 @@ impl LinkFlowState<role::SenderMarker> {
         state.drain = flow.drain;
         if flow.drain {
             state.delivery_count = state.delivery_count.wrapping_add(state.link_credit);
+            log_window_transition("link-credit", state.link_credit, 0);
             state.link_credit = 0;
 
             return Some(state.as_link_flow(output_handle, false, false));
@@ -234,10 +263,20 @@ impl LinkFlowState<role::ReceiverMarker> {
         // receiver can independently modify this field. The sender’s value is always the
         // last known value indicated by the receiver.
 
-        match flow.echo {
+        let echo = match flow.echo {
             true => Some(state.as_link_flow(output_handle, false, false)),
             false => None,
-        }
+        };
+        drop(state);
+        self.flow_received.notify_waiters();
+        echo
+    }
+
+    /// Returns a future that resolves when the next Flow is received from the remote sender
+    ///
+    /// Only flows received after this is called will resolve the future
+    pub(crate) fn flow_received(&self) -> Notified<'_> {
+        self.flow_received.notified()
     }
 }
 
@@ -270,6 +309,16 @@ impl<R> LinkFlowState<R> {
     pub fn properties(&self) -> Option<Fields> {
         self.lock.read().properties.clone()
     }
+
+    pub fn snapshot(&self) -> LinkFlowSnapshot {
+        let guard = self.lock.read();
+        LinkFlowSnapshot {
+            credit: guard.link_credit,
+            delivery_count: guard.delivery_count,
+            available: guard.available,
+            drain: guard.drain,
+        }
+    }
 }
 
 impl LinkFlowState<role::ReceiverMarker> {
@@ -281,7 +330,9 @@ impl LinkFlowState<role::ReceiverMarker> {
             Err(ReceiverTransferError::TransferLimitExceeded)
         } else {
             state.delivery_count = state.delivery_count.wrapping_add(count);
+            let old = state.link_credit;
             state.link_credit = state.link_credit.saturating_sub(count);
+            log_window_transition("link-credit", old, state.link_credit);
             Ok(())
         }
     }
@@ -337,7 +388,9 @@ cfg_transaction! {
             } else {
                 let tag = state.delivery_count.to_be_bytes();
                 state.delivery_count = state.delivery_count.wrapping_add(item);
+                let old = state.link_credit;
                 state.link_credit = state.link_credit.saturating_sub(item);
+                log_window_transition("link-credit", old, state.link_credit);
                 Ok(tag)
             }
         }
@@ -355,7 +408,9 @@ fn consume_link_credit(
     } else {
         let tag = state.delivery_count.to_be_bytes();
         state.delivery_count = state.delivery_count.wrapping_add(count);
+        let old = state.link_credit;
         state.link_credit = state.link_credit.saturating_sub(count);
+        log_window_transition("link-credit", old, state.link_credit);
         Ok(tag)
     }
 }
@@ -371,7 +426,7 @@ mod tests {
         endpoint::{LinkFlow, OutputHandle},
         link::{
             role,
-            state::{LinkFlowState, LinkFlowStateInner},
+            state::{LinkFlowSnapshot, LinkFlowState, LinkFlowStateInner},
             SenderFlowState,
         },
         util::{Consume, Consumer, Produce, Producer},
@@ -510,4 +565,28 @@ mod tests {
         // All credits have been consumed already
         assert_pending!(consumer.consume(1));
     }
+
+    #[test]
+    fn test_flow_state_snapshot() {
+        let flow_state = LinkFlowState::receiver(LinkFlowStateInner {
+            initial_delivery_count: 0,
+            delivery_count: 3,
+            link_credit: 10,
+            available: 0,
+            drain: false,
+            properties: None,
+        });
+        flow_state.consume(2).unwrap();
+
+        let snapshot = flow_state.snapshot();
+        assert_eq!(
+            snapshot,
+            LinkFlowSnapshot {
+                credit: 8,
+                delivery_count: 5,
+                available: 0,
+                drain: false,
+            }
+        );
+    }
 }
@@ -81,3 +81,13 @@ macro_rules! cfg_scram {
         )*
     }
 }
+
+macro_rules! cfg_test_util {
+    ($($item:item)*) => {
+        $(
+            #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+            #[cfg(feature = "test-util")]
+            $item
+        )*
+    }
+}
@@ -51,6 +51,17 @@ pub enum SaslProfile {
         password: String,
     },
 
+    /// SASL profile for PLAIN mechanism with an authorization identity (authzid) that is
+    /// different from the authentication identity (username)
+    PlainWithAuthzid {
+        /// Authorization identity
+        authzid: String,
+        /// Username
+        username: String,
+        /// Password
+        password: String,
+    },
+
     /// SASL-SCRAM-SHA-1
     #[cfg_attr(docsrs, doc(cfg(feature = "scram")))]
     #[cfg(feature = "scram")]
@@ -109,7 +120,8 @@ impl SaslProfile {
             SaslProfile::Plain {
                 username: _,
                 password: _,
-            } => PLAIN,
+            }
+            | SaslProfile::PlainWithAuthzid { .. } => PLAIN,
             #[cfg(feature = "scram")]
             SaslProfile::ScramSha1(_) => SCRAM_SHA_1,
             #[cfg(feature = "scram")]
@@ -124,15 +136,13 @@ impl SaslProfile {
         match self {
             SaslProfile::Anonymous => None,
             SaslProfile::Plain { username, password } => {
-                let username = username.as_bytes();
-                let password = password.as_bytes();
-                let mut buf = Vec::with_capacity(username.len() + password.len() + 2);
-                buf.put_u8(0);
-                buf.put_slice(username);
-                buf.put_u8(0);
-                buf.put_slice(password);
-                Some(Binary::from(buf))
+                Some(plain_initial_response("", username, password))
             }
+            SaslProfile::PlainWithAuthzid {
+                authzid,
+                username,
+                password,
+            } => Some(plain_initial_response(authzid, username, password)),
             #[cfg(feature = "scram")]
             SaslProfile::ScramSha1(scram_sha1) => Some(Binary::from(
                 scram_sha1.client.compute_client_first_message().to_vec(),
@@ -175,9 +185,11 @@ impl SaslProfile {
                 }
             }
             Frame::Challenge(challenge) => match self {
-                SaslProfile::Anonymous | SaslProfile::Plain { .. } => Err(Error::NotImplemented(
-                    Some("SASL Challenge is not implemented for ANONYMOUS or PLAIN.".to_string()),
-                )),
+                SaslProfile::Anonymous
+                | SaslProfile::Plain { .. }
+                | SaslProfile::PlainWithAuthzid { .. } => Err(Error::NotImplemented(Some(
+                    "SASL Challenge is not implemented for ANONYMOUS or PLAIN.".to_string(),
+                ))),
                 #[cfg(feature = "scram")]
                 SaslProfile::ScramSha1(SaslScramSha1 { client })
                 | SaslProfile::ScramSha256(SaslScramSha256 { client })
@@ -194,7 +206,9 @@ impl SaslProfile {
             },
             Frame::Outcome(outcome) => {
                 match self {
-                    SaslProfile::Anonymous | SaslProfile::Plain { .. } => {}
+                    SaslProfile::Anonymous
+                    | SaslProfile::Plain { .. }
+                    | SaslProfile::PlainWithAuthzid { .. } => {}
                     #[cfg(feature = "scram")]
                     SaslProfile::ScramSha1(SaslScramSha1 { client })
                     | SaslProfile::ScramSha256(SaslScramSha256 { client })
@@ -218,6 +232,20 @@ impl SaslProfile {
     }
 }
 
+/// `[authzid] UTF8NUL authcid UTF8NUL passwd` as defined in RFC 4616
+fn plain_initial_response(authzid: &str, username: &str, password: &str) -> Binary {
+    let authzid = authzid.as_bytes();
+    let username = username.as_bytes();
+    let password = password.as_bytes();
+    let mut buf = Vec::with_capacity(authzid.len() + username.len() + password.len() + 2);
+    buf.put_slice(authzid);
+    buf.put_u8(0);
+    buf.put_slice(username);
+    buf.put_u8(0);
+    buf.put_slice(password);
+    Binary::from(buf)
+}
+
 #[cfg(test)]
 mod tests {
     use url::Url;
@@ -253,6 +281,19 @@ mod tests {
             username: String::from("user"),
             password: String::from("example"),
         };
-        let _response = profile.initial_response();
+        let response = profile.initial_response().unwrap();
+        assert_eq!(&response[..], b"\0user\0example");
+    }
+
+    #[test]
+    fn test_plain_with_authzid_initial_response() {
+        let mut profile = SaslProfile::PlainWithAuthzid {
+            authzid: String::from("admin"),
+            username: String::from("user"),
+            password: String::from("example"),
+        };
+        assert_eq!(profile.mechanism(), super::PLAIN.into());
+        let response = profile.initial_response().unwrap();
+        assert_eq!(&response[..], b"admin\0user\0example");
     }
 }
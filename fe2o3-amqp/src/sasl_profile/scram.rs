@@ -33,6 +33,12 @@ impl SaslScramSha1 {
         let client = ScramClient::new(username, password, ScramVersion::Sha1);
         Self { client }
     }
+
+    /// Sets the authorization identity (authzid) that is sent in the GS2 header
+    pub fn with_authzid(mut self, authzid: impl Into<String>) -> Self {
+        self.client.set_authzid(authzid);
+        self
+    }
 }
 
 impl From<SaslScramSha1> for SaslProfile {
@@ -66,6 +72,12 @@ impl SaslScramSha256 {
         let client = ScramClient::new(username, password, ScramVersion::Sha256);
         Self { client }
     }
+
+    /// Sets the authorization identity (authzid) that is sent in the GS2 header
+    pub fn with_authzid(mut self, authzid: impl Into<String>) -> Self {
+        self.client.set_authzid(authzid);
+        self
+    }
 }
 
 impl From<SaslScramSha256> for SaslProfile {
@@ -99,6 +111,12 @@ impl SaslScramSha512 {
         let client = ScramClient::new(username, password, ScramVersion::Sha512);
         Self { client }
     }
+
+    /// Sets the authorization identity (authzid) that is sent in the GS2 header
+    pub fn with_authzid(mut self, authzid: impl Into<String>) -> Self {
+        self.client.set_authzid(authzid);
+        self
+    }
 }
 
 impl From<SaslScramSha512> for SaslProfile {
@@ -2,8 +2,10 @@
 
 use std::collections::{HashMap, VecDeque};
 
-use fe2o3_amqp_types::definitions::{Fields, Handle, TransferNumber};
-use serde_amqp::primitives::Symbol;
+use fe2o3_amqp_types::definitions::{
+    Fields, Handle, ReceiverSettleMode, SenderSettleMode, SequenceNo, TransferNumber,
+};
+use serde_amqp::{primitives::Symbol, Value};
 use slab::Slab;
 use tokio::sync::mpsc;
 
@@ -11,12 +13,13 @@ use crate::{
     connection::{AllocSessionError, ConnectionHandle},
     control::SessionControl,
     endpoint::OutgoingChannel,
+    link::builder::LinkDefaults,
     session::{engine::SessionEngine, SessionState},
     util::Constant,
     Session,
 };
 
-use super::{error::BeginError, SessionHandle, DEFAULT_WINDOW};
+use super::{error::BeginError, SessionHandle, SessionId, WindowPolicy, DEFAULT_WINDOW};
 
 pub(crate) const DEFAULT_SESSION_CONTROL_BUFFER_SIZE: usize = 128;
 pub(crate) const DEFAULT_SESSION_MUX_BUFFER_SIZE: usize = u16::MAX as usize;
@@ -36,6 +39,9 @@ pub struct Builder {
     /// The maximum handle value that can be used on the session
     pub handle_max: Handle,
 
+    /// How the incoming-window is advertised to the remote peer
+    pub incoming_window_policy: WindowPolicy,
+
     /// The extension capabilities the sender supports
     pub offered_capabilities: Option<Vec<Symbol>>,
 
@@ -49,10 +55,30 @@ pub struct Builder {
     /// that are used by links attached to the session
     pub buffer_size: usize,
 
+    /// Maximum number of locally initiated links that can wait for the remote attach at the same
+    /// time. Further links wait until one of the in-flight attach exchanges completes. There is no
+    /// limit if this is `None`. This only applies to sessions that are begun locally
+    pub max_concurrent_attaches: Option<usize>,
+
+    /// Default settings for the links attached to the session. Values that are not set are
+    /// inherited from the connection
+    pub(crate) link_defaults: LinkDefaults,
+
+    /// Transaction capabilities that are desired on the coordinator when a
+    /// [`Controller`](crate::transaction::Controller) is attached with
+    /// [`Controller::attach`](crate::transaction::Controller::attach)
+    #[cfg(feature = "transaction")]
+    pub(crate) txn_capabilities: Option<Vec<TxnCapability>>,
+
     /// Acceptor for incoming transaction control links
     #[cfg(not(target_arch = "wasm32"))]
     #[cfg(all(feature = "transaction", feature = "acceptor"))]
     pub(crate) control_link_acceptor: Option<ControlLinkAcceptor>,
+
+    /// How a listener session handles an incoming attach with a link name that is already in use
+    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(feature = "acceptor")]
+    pub(crate) duplicate_link_name_policy: crate::acceptor::session::DuplicateLinkNamePolicy,
 }
 
 impl Default for Builder {
@@ -62,19 +88,67 @@ impl Default for Builder {
             incoming_window: DEFAULT_WINDOW,
             outgoing_window: DEFAULT_WINDOW,
             handle_max: Default::default(),
+            incoming_window_policy: WindowPolicy::Fixed,
             offered_capabilities: None,
             desired_capabilities: None,
             properties: None,
             buffer_size: DEFAULT_SESSION_MUX_BUFFER_SIZE,
+            max_concurrent_attaches: None,
+            link_defaults: LinkDefaults::default(),
+
+            #[cfg(feature = "transaction")]
+            txn_capabilities: None,
 
             #[cfg(not(target_arch = "wasm32"))]
             #[cfg(all(feature = "transaction", feature = "acceptor"))]
             control_link_acceptor: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            #[cfg(feature = "acceptor")]
+            duplicate_link_name_policy: Default::default(),
         }
     }
 }
 
 cfg_transaction! {
+    use fe2o3_amqp_types::transaction::TxnCapability;
+
+    impl Builder {
+        /// Enable transactions on the session
+        ///
+        /// Transaction capabilities are not negotiated in `Begin` but with the coordinator target
+        /// of the control link. A [`Controller`](crate::transaction::Controller) attached to the
+        /// session with [`Controller::attach`](crate::transaction::Controller::attach) will
+        /// desire the `amqp:local-transactions` capability on the coordinator, and the attach
+        /// fails with [`SenderAttachError::DesireTxnCapabilitiesNotSupported`] if the remote
+        /// coordinator doesn't support it.
+        ///
+        /// [`SenderAttachError::DesireTxnCapabilitiesNotSupported`]: crate::link::SenderAttachError::DesireTxnCapabilitiesNotSupported
+        ///
+        /// # Example
+        ///
+        /// ```rust, ignore
+        /// let mut session = Session::builder()
+        ///     .enable_transactions()
+        ///     .begin(&mut connection)
+        ///     .await.unwrap();
+        /// let controller = Controller::attach(&mut session, "controller").await.unwrap();
+        /// ```
+        pub fn enable_transactions(self) -> Self {
+            self.set_txn_capabilities(vec![TxnCapability::LocalTransactions])
+        }
+
+        /// Set the transaction capabilities that are desired on the coordinator when a
+        /// [`Controller`](crate::transaction::Controller) is attached to the session with
+        /// [`Controller::attach`](crate::transaction::Controller::attach)
+        ///
+        /// See [`enable_transactions`](#method.enable_transactions) for more details.
+        pub fn set_txn_capabilities(mut self, capabilities: Vec<TxnCapability>) -> Self {
+            self.txn_capabilities = Some(capabilities);
+            self
+        }
+    }
+
     cfg_acceptor! {
         use crate::transaction::{
             coordinator::ControlLinkAcceptor, manager::TransactionManager, session::TxnSession,
@@ -86,6 +160,7 @@ cfg_transaction! {
                 control: mpsc::Sender<SessionControl>,
                 outgoing: mpsc::Sender<crate::link::LinkFrame>,
                 outgoing_channel: OutgoingChannel,
+                container_id: String,
                 control_link_acceptor: ControlLinkAcceptor,
                 local_state: SessionState,
             ) -> TxnSession<Session> {
@@ -99,11 +174,14 @@ cfg_transaction! {
                     incoming_window: self.incoming_window,
                     outgoing_window: self.outgoing_window,
                     handle_max: self.handle_max,
+                    incoming_window_policy: self.incoming_window_policy,
+                    incoming_window_used: 0,
                     incoming_channel: None,
                     next_incoming_id: 0,
                     remote_incoming_window: 0,
                     remote_incoming_window_exhausted_buffer: VecDeque::new(),
                     remote_outgoing_window: 0,
+                    remote_handle_max: Handle::default(),
                     offered_capabilities: self.offered_capabilities,
                     desired_capabilities: self.desired_capabilities,
                     properties: self.properties,
@@ -118,6 +196,10 @@ cfg_transaction! {
                     control,
                     session,
                     txn_manager,
+                    id: SessionId {
+                        container_id,
+                        channel: outgoing_channel,
+                    },
                 }
             }
         }
@@ -144,11 +226,14 @@ impl Builder {
             incoming_window: self.incoming_window,
             outgoing_window: self.outgoing_window,
             handle_max: self.handle_max,
+            incoming_window_policy: self.incoming_window_policy,
+            incoming_window_used: 0,
             incoming_channel: None,
             next_incoming_id: 0,
             remote_incoming_window: 0,
             remote_incoming_window_exhausted_buffer: VecDeque::new(),
             remote_outgoing_window: 0,
+            remote_handle_max: Handle::default(),
             offered_capabilities: self.offered_capabilities,
             desired_capabilities: self.desired_capabilities,
             properties: self.properties,
@@ -172,6 +257,16 @@ impl Builder {
         self
     }
 
+    /// How the incoming-window is advertised to the remote peer
+    ///
+    /// With [`WindowPolicy::AutoReplenish`], a session flow that re-advertises the full
+    /// incoming-window is sent once half of the window has been used. The default is
+    /// [`WindowPolicy::Fixed`]
+    pub fn incoming_window_policy(mut self, policy: WindowPolicy) -> Self {
+        self.incoming_window_policy = policy;
+        self
+    }
+
     /// The initial outgoing-window of the sender
     pub fn outgoing_window(mut self, value: TransferNumber) -> Self {
         self.outgoing_window = value;
@@ -179,6 +274,10 @@ impl Builder {
     }
 
     /// The maximum handle value that can be used on the session
+    ///
+    /// Attaching a link fails with `HandleMaxReached` once all handles up to the smaller of this
+    /// value and the remote peer's `handle-max` are in use, so this needs to be raised if the
+    /// session has many links
     pub fn handle_max(mut self, value: impl Into<Handle>) -> Self {
         self.handle_max = value.into();
         self
@@ -220,6 +319,15 @@ impl Builder {
         self
     }
 
+    /// Insert a single session property. Any property previously inserted with the same key is
+    /// replaced
+    pub fn property(mut self, key: impl Into<Symbol>, value: impl Into<Value>) -> Self {
+        self.properties
+            .get_or_insert_with(Fields::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
     /// Buffer size of the underlying [`tokio::sync::mpsc::channel`]
     /// that are used by links attached to the session
     pub fn buffer_size(mut self, buffer_size: usize) -> Self {
@@ -227,6 +335,43 @@ impl Builder {
         self
     }
 
+    /// Limits the number of locally initiated links (including links that are resumed on the
+    /// session) that can wait for the remote attach at the same time. This smooths out bursts
+    /// of attaches, eg. when hundreds of links are resumed at once after reconnecting.
+    ///
+    /// A value of zero is treated as one
+    pub fn max_concurrent_attaches(mut self, max: usize) -> Self {
+        self.max_concurrent_attaches = Some(max);
+        self
+    }
+
+    /// Default credit of the receivers attached to the session, which will use
+    /// `CreditMode::Auto` with the given credit unless `credit_mode` is set on the link builder.
+    ///
+    /// This overrides the default set on the connection
+    pub fn default_link_credit(mut self, credit: SequenceNo) -> Self {
+        self.link_defaults.credit = Some(credit);
+        self
+    }
+
+    /// Default settlement policy for the senders attached to the session unless
+    /// `sender_settle_mode` is set on the link builder.
+    ///
+    /// This overrides the default set on the connection
+    pub fn default_sender_settle_mode(mut self, mode: SenderSettleMode) -> Self {
+        self.link_defaults.snd_settle_mode = Some(mode);
+        self
+    }
+
+    /// Default settlement policy of the receivers attached to the session unless
+    /// `receiver_settle_mode` is set on the link builder.
+    ///
+    /// This overrides the default set on the connection
+    pub fn default_receiver_settle_mode(mut self, mode: ReceiverSettleMode) -> Self {
+        self.link_defaults.rcv_settle_mode = Some(mode);
+        self
+    }
+
     // TODO
     // /// Enable handling remotely initiated control link and transaction by setting the
     // /// `control_link_acceptor` field
@@ -256,6 +401,10 @@ impl Builder {
             connection: &mut ConnectionHandle<()>,
         ) -> Result<SessionHandle<()>, BeginError> {
             let local_state = SessionState::Unmapped;
+            let link_defaults = self.link_defaults.clone().or(&connection.link_defaults);
+            #[cfg(feature = "transaction")]
+            let txn_capabilities = self.txn_capabilities.clone();
+            let max_concurrent_attaches = self.max_concurrent_attaches;
             let (session_control_tx, session_control_rx) =
                 mpsc::channel::<SessionControl>(DEFAULT_SESSION_CONTROL_BUFFER_SIZE);
             let (incoming_tx, incoming_rx) = mpsc::channel(self.buffer_size);
@@ -270,6 +419,9 @@ impl Builder {
                         // Locally initiating session exceeded channel max
                         return Err(BeginError::LocalChannelMaxReached);
                     }
+                    AllocSessionError::SessionLimitReached => {
+                        return Err(BeginError::SessionLimitReached)
+                    }
                 },
             };
 
@@ -283,6 +435,7 @@ impl Builder {
                     incoming_rx,
                     connection.outgoing.clone(),
                     outgoing_rx,
+                    max_concurrent_attaches,
                 )
                 .await?;
                 engine.spawn()
@@ -297,6 +450,7 @@ impl Builder {
                             session_control_tx.clone(),
                             outgoing_tx.clone(),
                             outgoing_channel,
+                            connection.container_id.clone(),
                             control_link_acceptor,
                             local_state,
                         );
@@ -307,6 +461,7 @@ impl Builder {
                             incoming_rx,
                             connection.outgoing.clone(),
                             outgoing_rx,
+                            max_concurrent_attaches,
                         )
                         .await?;
                         engine.spawn()
@@ -320,6 +475,7 @@ impl Builder {
                             incoming_rx,
                             connection.outgoing.clone(),
                             outgoing_rx,
+                            max_concurrent_attaches,
                         )
                         .await?;
                         engine.spawn()
@@ -334,6 +490,13 @@ impl Builder {
                 outcome,
                 outgoing: outgoing_tx,
                 link_listener: (),
+                link_defaults,
+                #[cfg(feature = "transaction")]
+                txn_capabilities,
+                id: SessionId {
+                    container_id: connection.container_id.clone(),
+                    channel: outgoing_channel,
+                },
             };
             Ok(handle)
         }
@@ -357,6 +520,10 @@ impl Builder {
             local_set: &tokio::task::LocalSet,
         ) -> Result<SessionHandle<()>, BeginError> {
             let local_state = SessionState::Unmapped;
+            let link_defaults = self.link_defaults.clone().or(&connection.link_defaults);
+            #[cfg(feature = "transaction")]
+            let txn_capabilities = self.txn_capabilities.clone();
+            let max_concurrent_attaches = self.max_concurrent_attaches;
             let (session_control_tx, session_control_rx) =
                 mpsc::channel::<SessionControl>(DEFAULT_SESSION_CONTROL_BUFFER_SIZE);
             let (incoming_tx, incoming_rx) = mpsc::channel(self.buffer_size);
@@ -371,6 +538,9 @@ impl Builder {
                         // Locally initiating session exceeded channel max
                         return Err(BeginError::LocalChannelMaxReached);
                     }
+                    AllocSessionError::SessionLimitReached => {
+                        return Err(BeginError::SessionLimitReached)
+                    }
                 },
             };
 
@@ -383,6 +553,7 @@ impl Builder {
                     incoming_rx,
                     connection.outgoing.clone(),
                     outgoing_rx,
+                    max_concurrent_attaches,
                 )
                 .await?;
                 engine.spawn_on_local_set(local_set)
@@ -395,6 +566,13 @@ impl Builder {
                 outcome,
                 outgoing: outgoing_tx,
                 link_listener: (),
+                link_defaults,
+                #[cfg(feature = "transaction")]
+                txn_capabilities,
+                id: SessionId {
+                    container_id: connection.container_id.clone(),
+                    channel: outgoing_channel,
+                },
             };
             Ok(handle)
         }
@@ -417,6 +595,10 @@ impl Builder {
             connection: &mut ConnectionHandle<()>,
         ) -> Result<SessionHandle<()>, BeginError> {
             let local_state = SessionState::Unmapped;
+            let link_defaults = self.link_defaults.clone().or(&connection.link_defaults);
+            #[cfg(feature = "transaction")]
+            let txn_capabilities = self.txn_capabilities.clone();
+            let max_concurrent_attaches = self.max_concurrent_attaches;
             let (session_control_tx, session_control_rx) =
                 mpsc::channel::<SessionControl>(DEFAULT_SESSION_CONTROL_BUFFER_SIZE);
             let (incoming_tx, incoming_rx) = mpsc::channel(self.buffer_size);
@@ -431,6 +613,9 @@ impl Builder {
                         // Locally initiating session exceeded channel max
                         return Err(BeginError::LocalChannelMaxReached);
                     }
+                    AllocSessionError::SessionLimitReached => {
+                        return Err(BeginError::SessionLimitReached)
+                    }
                 },
             };
 
@@ -443,6 +628,7 @@ impl Builder {
                     incoming_rx,
                     connection.outgoing.clone(),
                     outgoing_rx,
+                    max_concurrent_attaches,
                 )
                 .await?;
                 engine.spawn_local()
@@ -455,6 +641,13 @@ impl Builder {
                 outcome,
                 outgoing: outgoing_tx,
                 link_listener: (),
+                link_defaults,
+                #[cfg(feature = "transaction")]
+                txn_capabilities,
+                id: SessionId {
+                    container_id: connection.container_id.clone(),
+                    channel: outgoing_channel,
+                },
             };
             Ok(handle)
         }
@@ -1,3 +1,5 @@
+use std::collections::{HashMap, VecDeque};
+
 use fe2o3_amqp_types::{
     definitions::{self, AmqpError, SessionError},
     performatives::End,
@@ -10,8 +12,8 @@ use tokio::{
 use crate::{
     connection::{self},
     control::{ConnectionControl, SessionControl},
-    endpoint::{self, IncomingChannel, Session},
-    link::LinkFrame,
+    endpoint::{self, IncomingChannel, OutputHandle, Session},
+    link::{LinkFrame, LinkRelay},
     util::Running,
     SendBound,
 };
@@ -57,6 +59,24 @@ pub(crate) struct SessionEngine<S: Session> {
     pub outgoing: mpsc::Sender<SessionFrame>,
 
     pub outgoing_link_frames: mpsc::Receiver<LinkFrame>,
+
+    // Notified once there is no buffered outgoing transfer
+    pub pending_flushes: Vec<oneshot::Sender<()>>,
+
+    // Maximum number of locally initiated links that can wait for the remote attach at once
+    pub max_concurrent_attaches: Option<usize>,
+
+    // Locally initiated links that are waiting for the remote attach
+    pub attaching: HashMap<String, OutputHandle>,
+
+    // Link allocations that are deferred until an in-flight attach exchange completes
+    pub pending_allocations: VecDeque<PendingAllocation>,
+}
+
+pub(crate) struct PendingAllocation {
+    link_name: String,
+    link_relay: LinkRelay<()>,
+    responder: oneshot::Sender<Result<OutputHandle, AllocLinkError>>,
 }
 
 impl<S> SessionEngine<S>
@@ -71,6 +91,7 @@ where
         incoming: mpsc::Receiver<SessionIncomingItem>,
         outgoing: mpsc::Sender<SessionFrame>,
         outgoing_link_frames: mpsc::Receiver<LinkFrame>,
+        max_concurrent_attaches: Option<usize>,
     ) -> Result<Self, BeginError> {
         let mut engine = Self {
             conn_control,
@@ -79,6 +100,11 @@ where
             incoming,
             outgoing,
             outgoing_link_frames,
+            pending_flushes: Vec::new(),
+            // A limit of zero would never allow any link to attach
+            max_concurrent_attaches: max_concurrent_attaches.map(|max| max.max(1)),
+            attaching: HashMap::new(),
+            pending_allocations: VecDeque::new(),
         };
 
         // send a begin
@@ -161,7 +187,11 @@ where
                 self.session.on_incoming_begin(channel, begin)?;
             }
             SessionFrameBody::Attach(attach) => {
+                let in_flight = self.attaching.remove(&attach.name).is_some();
                 self.session.on_incoming_attach(attach).await?;
+                if in_flight {
+                    self.allocate_pending_links()?;
+                }
             }
             SessionFrameBody::Flow(flow) => {
                 if let Some(outgoing_item) = self.session.on_incoming_flow(flow).await? {
@@ -175,6 +205,14 @@ where
                 self.session
                     .on_incoming_transfer(performative, payload)
                     .await?;
+                if let Some(flow) = self.session.replenish_incoming_window() {
+                    self.outgoing
+                        .send(flow)
+                        .await
+                        // The receiving half must have dropped, and thus the `Connection`
+                        // event loop has stopped. It should be treated as an io error
+                        .map_err(|_| SessionInnerError::IllegalConnectionState)?;
+                }
             }
             SessionFrameBody::Disposition(disposition) => {
                 if let Some(dispositions) = self.session.on_incoming_disposition(disposition)? {
@@ -235,11 +273,28 @@ where
                 link_relay,
                 responder,
             } => {
-                let result = self.session.allocate_link(link_name, Some(link_relay));
-                responder
-                    .send(result.map_err(Into::into))
-                    // The receiving end (ie. link) must have been stopped
-                    .map_err(|_| SessionInnerError::UnattachedHandle)?;
+                // Process the link frames queued before the allocation request so that the detach
+                // of a previous link with the same name releases the name first
+                for _ in 0..self.outgoing_link_frames.len() {
+                    match self.outgoing_link_frames.try_recv() {
+                        Ok(frame) => {
+                            self.on_outgoing_link_frames(frame).await?;
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                let allocation = PendingAllocation {
+                    link_name,
+                    link_relay,
+                    responder,
+                };
+                match self.max_concurrent_attaches {
+                    Some(max) if self.attaching.len() >= max => {
+                        self.pending_allocations.push_back(allocation);
+                    }
+                    _ => self.allocate_link(allocation)?,
+                }
             }
             SessionControl::AllocateIncomingLink {
                 link_name,
@@ -255,8 +310,13 @@ where
                     // The receiving end (ie. link) must have been stopped
                     .map_err(|_| SessionInnerError::UnattachedHandle)?;
             }
-            SessionControl::DeallocateLink(link_name) => {
-                self.session.deallocate_link(link_name);
+            SessionControl::DeallocateLink(output_handle) => {
+                let len = self.attaching.len();
+                self.attaching.retain(|_, handle| *handle != output_handle);
+                self.session.deallocate_link(output_handle);
+                if self.attaching.len() < len {
+                    self.allocate_pending_links()?;
+                }
             }
             SessionControl::Disposition(disposition) => {
                 let disposition = self.session.on_outgoing_disposition(disposition)?;
@@ -267,6 +327,45 @@ where
                     // event loop has stopped. It should be treated as an io error
                     .map_err(|_| SessionInnerError::IllegalConnectionState)?;
             }
+            SessionControl::SettleOutgoingDelivery {
+                input_handle,
+                delivery_tag,
+                state,
+            } => {
+                if let Some(disposition) =
+                    self.session
+                        .settle_outgoing_delivery(input_handle, delivery_tag, state)
+                {
+                    let disposition = self.session.on_outgoing_disposition(disposition)?;
+                    self.outgoing
+                        .send(disposition)
+                        .await
+                        .map_err(|_| SessionInnerError::IllegalConnectionState)?;
+                }
+            }
+            SessionControl::SettleIncomingRange {
+                input_handle,
+                first,
+                last,
+                state,
+                responder,
+            } => {
+                let dispositions =
+                    self.session
+                        .settle_incoming_range(input_handle, first, last, state);
+                let mut settled = 0u32;
+                for disposition in dispositions {
+                    let run_last = disposition.last.unwrap_or(disposition.first);
+                    settled += run_last.wrapping_sub(disposition.first) + 1;
+                    let disposition = self.session.on_outgoing_disposition(disposition)?;
+                    self.outgoing
+                        .send(disposition)
+                        .await
+                        .map_err(|_| SessionInnerError::IllegalConnectionState)?;
+                }
+                // The receiver may have been dropped
+                let _ = responder.send(settled);
+            }
             SessionControl::CloseConnectionWithError((condition, description)) => {
                 let error = definitions::Error::new(condition, description, None);
                 let control = ConnectionControl::Close(Some(error));
@@ -312,11 +411,55 @@ where
         }
     }
 
+    fn allocate_link(&mut self, allocation: PendingAllocation) -> Result<(), SessionInnerError> {
+        let PendingAllocation {
+            link_name,
+            link_relay,
+            responder,
+        } = allocation;
+        let result = self
+            .session
+            .allocate_link(link_name.clone(), Some(link_relay))
+            .map_err(Into::into);
+        if let (Some(_), Ok(handle)) = (self.max_concurrent_attaches, &result) {
+            self.attaching.insert(link_name, handle.clone());
+        }
+        responder
+            .send(result)
+            // The receiving end (ie. link) must have been stopped
+            .map_err(|_| SessionInnerError::UnattachedHandle)
+    }
+
+    /// Allocates the deferred links until the limit of concurrent attaches is reached again
+    fn allocate_pending_links(&mut self) -> Result<(), SessionInnerError> {
+        while let Some(max) = self.max_concurrent_attaches {
+            if self.attaching.len() >= max {
+                break;
+            }
+            match self.pending_allocations.pop_front() {
+                // The link has stopped waiting for the allocation
+                Some(allocation) if allocation.responder.is_closed() => continue,
+                Some(allocation) => self.allocate_link(allocation)?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     async fn on_outgoing_link_frames(
         &mut self,
         frame: LinkFrame,
     ) -> Result<Running, SessionInnerError> {
+        let frame = match frame {
+            LinkFrame::Flush(resp) => {
+                // Notified in the event loop once the buffered transfers are sent
+                self.pending_flushes.push(resp);
+                return Ok(Running::Continue);
+            }
+            frame => frame,
+        };
+
         match self.session.local_state() {
             SessionState::Mapped => {}
             _ => return Err(SessionInnerError::IllegalState), // End session with illegal state
@@ -348,6 +491,7 @@ where
             LinkFrame::Detach(detach) => Some(SessionOutgoingItem::SingleFrame(
                 self.session.on_outgoing_detach(detach),
             )),
+            LinkFrame::Flush(_) => unreachable!("LinkFrame::Flush is handled above"),
 
             #[cfg(feature = "transaction")]
             LinkFrame::Acquisition(_) => {
@@ -484,6 +628,14 @@ where
         }
     }
 
+    fn notify_pending_flushes(&mut self) {
+        if !self.session.has_buffered_outgoing_transfers() {
+            for resp in self.pending_flushes.drain(..) {
+                let _ = resp.send(());
+            }
+        }
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(name = "Session::event_loop", skip(self), fields(outgoing_channel = %self.session.outgoing_channel().0)))]
     async fn event_loop(mut self, tx: oneshot::Sender<Result<(), Error>>) {
         let mut outcome = Ok(());
@@ -579,6 +731,10 @@ where
                 }
             };
 
+            if !self.pending_flushes.is_empty() {
+                self.notify_pending_flushes();
+            }
+
             match running {
                 Running::Continue => {}
                 Running::Stop => break,
@@ -46,6 +46,11 @@ pub enum BeginError {
     /// Channel max reached
     #[error("Local channel-max reached")]
     LocalChannelMaxReached,
+
+    /// The number of sessions on the connection has reached the limit set with
+    /// `Connection::builder().max_sessions()`
+    #[error("Local session limit reached")]
+    SessionLimitReached,
 }
 
 impl From<SessionStateError> for BeginError {
@@ -212,6 +217,9 @@ pub(crate) enum AllocLinkError {
 
     #[error("Link name must be unique")]
     DuplicatedLinkName,
+
+    #[error("The maximum number of links on the session is reached")]
+    HandleMaxReached,
 }
 
 /// Error with attempting to end a session
@@ -1,11 +1,15 @@
 //! Implements AMQP1.0 Session
 
-use std::collections::{HashMap, VecDeque};
+use std::{
+    cmp::min,
+    collections::{HashMap, VecDeque},
+};
 
 use fe2o3_amqp_types::{
     definitions::{
         self, DeliveryNumber, DeliveryTag, Fields, Handle, Role, SequenceNo, TransferNumber,
     },
+    messaging::{DeliveryState, Outcome},
     performatives::{Attach, Begin, Detach, Disposition, End, Flow, Transfer},
     primitives::{Symbol, Uint},
     states::SessionState,
@@ -22,13 +26,19 @@ use tokio::{
 use crate::{
     control::SessionControl,
     endpoint::{self, IncomingChannel, InputHandle, LinkFlow, OutgoingChannel, OutputHandle},
-    link::{LinkFrame, LinkRelay},
-    util::{is_consecutive, Constant},
+    link::{
+        builder::LinkDefaults, delivery::DeliveryFut, DetachError, LinkFrame, LinkRelay, SendError,
+        Sender,
+    },
+    util::{is_consecutive, log_window_transition, Constant},
     Payload,
 };
 
 cfg_transaction! {
-    use fe2o3_amqp_types::{messaging::Accepted, transaction::TransactionError};
+    use fe2o3_amqp_types::{
+        messaging::Accepted,
+        transaction::{TransactionError, TxnCapability},
+    };
 
     use crate::{
         endpoint::{HandleDeclare, HandleDischarge},
@@ -51,6 +61,19 @@ use self::frame::{SessionFrame, SessionFrameBody, SessionOutgoingItem};
 /// Default incoming_window and outgoing_window
 pub const DEFAULT_WINDOW: Uint = 2048;
 
+/// Policy that decides how the local incoming-window is advertised to the remote peer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WindowPolicy {
+    /// The incoming-window is only re-advertised along with the flows of the links. The window
+    /// seen by the remote peer shrinks with every incoming transfer until a link sends a flow
+    #[default]
+    Fixed,
+
+    /// A session flow that re-advertises the full incoming-window is sent proactively once half
+    /// of the window has been used, so that the remote peer never runs out of incoming-window
+    AutoReplenish,
+}
+
 /// A handle to the [`Session`] event loop
 ///
 /// Dropping the handle will also stop the [`Session`] event loop
@@ -69,6 +92,24 @@ pub struct SessionHandle<R> {
     // outgoing for Link
     pub(crate) outgoing: mpsc::Sender<LinkFrame>,
     pub(crate) link_listener: R,
+
+    // Default settings for the links attached to the session
+    pub(crate) link_defaults: LinkDefaults,
+
+    // Transaction capabilities desired on the coordinator when a controller is attached with
+    // `Controller::attach`
+    #[cfg(feature = "transaction")]
+    pub(crate) txn_capabilities: Option<Vec<TxnCapability>>,
+
+    // Identity of the session that is carried by the links attached to the session
+    pub(crate) id: SessionId,
+}
+
+/// Identity of a session that is used to identify the links attached to the session
+#[derive(Debug, Clone)]
+pub(crate) struct SessionId {
+    pub container_id: String,
+    pub channel: OutgoingChannel,
 }
 
 impl<R> std::fmt::Debug for SessionHandle<R> {
@@ -84,6 +125,11 @@ impl<R> Drop for SessionHandle<R> {
 }
 
 impl<R> SessionHandle<R> {
+    /// The local channel of the session
+    pub fn channel(&self) -> u16 {
+        self.id.channel.0
+    }
+
     /// Checks if the underlying event loop has stopped
     pub fn is_ended(&self) -> bool {
         match self.is_ended {
@@ -92,6 +138,77 @@ impl<R> SessionHandle<R> {
         }
     }
 
+    /// Closes `sender` once the given outstanding deliveries are settled while keeping all the
+    /// other links on the session (eg. the receiver of a request/reply pair) attached
+    ///
+    /// The outcomes of `pending` are returned in the same order. The session keeps running and
+    /// the receivers can continue to receive after this returns.
+    ///
+    /// # Safe sequence
+    ///
+    /// 1. Wait for the outcome of every outstanding delivery of the sender, which is what this
+    ///    method does with `pending`. Dispositions that arrive after the sender is detached can no
+    ///    longer be delivered to the sender and their outcomes would be lost.
+    /// 2. Close the sender with a closing detach. Replies travel on a different link and are not
+    ///    affected by the detach of the sender.
+    /// 3. Keep receiving on the receiver until all the replies are received, then close the
+    ///    receiver.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DetachError::IllegalState` without closing the sender if `sender` is not attached
+    /// to this session.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut pending = Vec::new();
+    /// for request in requests {
+    ///     pending.push(sender.send_batchable(request).await?);
+    /// }
+    /// let outcomes = session.half_close_sender(sender, pending).await?;
+    /// while let Ok(reply) = receiver.recv::<String>().await {
+    ///     receiver.accept(&reply).await?;
+    /// }
+    /// ```
+    pub async fn half_close_sender<I>(
+        &self,
+        sender: Sender,
+        pending: I,
+    ) -> Result<Vec<Result<Outcome, SendError>>, DetachError>
+    where
+        I: IntoIterator<Item = DeliveryFut<Result<Outcome, SendError>>>,
+    {
+        if !sender.inner.session.same_channel(&self.control) {
+            return Err(DetachError::IllegalState);
+        }
+
+        let mut outcomes = Vec::new();
+        for fut in pending {
+            outcomes.push(fut.await);
+        }
+        sender.close().await?;
+        Ok(outcomes)
+    }
+
+    /// Waits until the transfers that are buffered by the session because the remote
+    /// incoming-window is exhausted are all handed to the connection to be written to the wire
+    ///
+    /// Transfers sent by the links after this is called are not awaited. This is useful before
+    /// ending the session to make sure no transfer is left in the session.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::IllegalState` if the session stops before the buffered transfers are sent.
+    pub async fn flush(&self) -> Result<(), Error> {
+        let (tx, rx) = oneshot::channel();
+        self.outgoing
+            .send(LinkFrame::Flush(tx))
+            .await
+            .map_err(|_| Error::IllegalState)?;
+        rx.await.map_err(|_| Error::IllegalState)
+    }
+
     /// Tries to end the session
     ///
     /// # Returns
@@ -267,6 +384,9 @@ pub struct Session {
     pub(crate) incoming_window: TransferNumber,
     pub(crate) outgoing_window: TransferNumber,
     pub(crate) handle_max: Handle,
+    pub(crate) incoming_window_policy: WindowPolicy,
+    // Number of incoming transfers since the incoming-window was last advertised
+    pub(crate) incoming_window_used: TransferNumber,
 
     // remote amqp states
     pub(crate) incoming_channel: Option<IncomingChannel>,
@@ -283,6 +403,7 @@ pub struct Session {
     // indication of outstanding transfers. Settling outstanding transfers can cause the window
     // to grow.
     pub(crate) remote_outgoing_window: SequenceNo,
+    pub(crate) remote_handle_max: Handle,
 
     // capabilities
     pub(crate) offered_capabilities: Option<Vec<Symbol>>,
@@ -378,7 +499,9 @@ impl Session {
         // incoming-window. This value MUST be decremented after every transfer
         // frame is sent, and recomputed when informed of the remote session
         // endpoint state.
+        let old = self.remote_incoming_window;
         self.remote_incoming_window = self.remote_incoming_window.saturating_sub(1);
+        log_window_transition("remote-incoming-window", old, self.remote_incoming_window);
 
         let body = SessionFrameBody::Transfer {
             performative: transfer,
@@ -399,28 +522,27 @@ impl Session {
         // and it MUST update the remote-outgoing- window directly from the
         // outgoing-window of the frame.
         self.next_incoming_id = flow.next_outgoing_id;
+        let old = self.remote_outgoing_window;
         self.remote_outgoing_window = flow.outgoing_window;
+        log_window_transition("remote-outgoing-window", old, self.remote_outgoing_window);
 
-        match &flow.next_incoming_id {
-            Some(flow_next_incoming_id) => {
-                // The remote-incoming-window is computed as follows:
-                // next-incoming-id_flow + incoming-window_flow - next-outgoing-id_endpoint
-                self.remote_incoming_window = flow_next_incoming_id
-                    .saturating_add(flow.incoming_window)
-                    .saturating_sub(self.next_outgoing_id);
-            }
-            None => {
-                // If the next-incoming-id field of the flow frame is not set,
-                // then remote-incoming-window is computed as follows:
-                // initial-outgoing-id_endpoint + incoming-window_flow -
-                // next-outgoing-id_endpoint
-                self.remote_incoming_window = self
-                    .initial_outgoing_id
-                    .value()
-                    .saturating_add(flow.incoming_window)
-                    .saturating_sub(self.next_outgoing_id);
-            }
-        }
+        let old = self.remote_incoming_window;
+        // The remote-incoming-window is computed as follows:
+        // next-incoming-id_flow + incoming-window_flow - next-outgoing-id_endpoint
+        //
+        // If the next-incoming-id field of the flow frame is not set,
+        // then remote-incoming-window is computed as follows:
+        // initial-outgoing-id_endpoint + incoming-window_flow -
+        // next-outgoing-id_endpoint
+        let flow_next_incoming_id = flow
+            .next_incoming_id
+            .unwrap_or_else(|| *self.initial_outgoing_id.value());
+        self.remote_incoming_window = remote_incoming_window(
+            flow_next_incoming_id,
+            flow.incoming_window,
+            self.next_outgoing_id,
+        );
+        log_window_transition("remote-incoming-window", old, self.remote_incoming_window);
 
         // Handle link flow control
         if let Ok(link_flow) = LinkFlow::try_from(flow) {
@@ -518,7 +640,13 @@ impl endpoint::Session for Session {
 
         // get a new entry index
         let entry = self.link_name_by_output_handle.vacant_entry();
-        let handle = OutputHandle(entry.key() as u32);
+
+        // the handle MUST NOT exceed the handle-max of either endpoint
+        let handle_max = min(self.handle_max.0, self.remote_handle_max.0);
+        let handle = match u32::try_from(entry.key()) {
+            Ok(handle) if handle <= handle_max => OutputHandle(handle),
+            _ => return Err(AllocLinkError::HandleMaxReached),
+        };
 
         entry.insert(link_name.clone());
         let value = link_relay.map(|val| val.with_output_handle(handle.clone()));
@@ -567,6 +695,7 @@ impl endpoint::Session for Session {
         self.next_incoming_id = begin.next_outgoing_id;
         self.remote_incoming_window = begin.incoming_window;
         self.remote_outgoing_window = begin.outgoing_window;
+        self.remote_handle_max = begin.handle_max;
 
         Ok(())
     }
@@ -643,9 +772,14 @@ impl endpoint::Session for Session {
         // match the implicit transfer-id of the incoming transfer plus one, as well as decrementing the
         // remote-outgoing-window, and MAY (depending on policy) decrement its incoming-window.
         self.next_incoming_id = self.next_incoming_id.wrapping_add(1);
+        let old = self.remote_outgoing_window;
         self.remote_outgoing_window = self.remote_outgoing_window.saturating_sub(1);
+        log_window_transition("remote-outgoing-window", old, self.remote_outgoing_window);
 
-        // TODO: allow user to define whether the incoming window should be decremented
+        // The incoming-window itself is never decremented. Instead, the number of transfers
+        // received since the window was last advertised is tracked so that the window can be
+        // replenished according to the `WindowPolicy`
+        self.incoming_window_used = self.incoming_window_used.wrapping_add(1);
 
         let input_handle = InputHandle::from(transfer.handle.clone());
         match self.link_by_input_handle.get_mut(&input_handle) {
@@ -858,7 +992,36 @@ impl endpoint::Session for Session {
         Ok(frame)
     }
 
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame> {
+        match self.incoming_window_policy {
+            WindowPolicy::Fixed => None,
+            WindowPolicy::AutoReplenish => {
+                if self.incoming_window_used < (self.incoming_window / 2).max(1) {
+                    return None;
+                }
+                self.incoming_window_used = 0;
+
+                let flow = Flow {
+                    next_incoming_id: Some(self.next_incoming_id),
+                    incoming_window: self.incoming_window,
+                    next_outgoing_id: self.next_outgoing_id,
+                    outgoing_window: self.outgoing_window,
+                    handle: None,
+                    delivery_count: None,
+                    link_credit: None,
+                    available: None,
+                    drain: false,
+                    echo: false,
+                    properties: None,
+                };
+                let body = SessionFrameBody::Flow(flow);
+                Some(SessionFrame::new(self.outgoing_channel, body))
+            }
+        }
+    }
+
     fn on_outgoing_flow(&mut self, flow: LinkFlow) -> Result<SessionFrame, Self::Error> {
+        self.incoming_window_used = 0;
         let flow = Flow {
             // Session flow states
             next_incoming_id: Some(self.next_incoming_id),
@@ -923,6 +1086,32 @@ impl endpoint::Session for Session {
         // Currently the sender cannot actively dispose any message
         // because the sender doesn't have access to the delivery_id
 
+        // A settled disposition from a local receiver only covers consecutive delivery ids of the
+        // same link, which may not have been removed from the link's unsettled map yet
+        if disposition.settled && disposition.role == Role::Receiver {
+            let first = disposition.first;
+            let span = disposition.last.unwrap_or(first).wrapping_sub(first);
+            // Avoid walking a range that is much wider than the number of tracked deliveries
+            let tracked = self.delivery_tag_by_id.len();
+            let delivery_ids: Vec<DeliveryNumber> = if (span as usize) < tracked {
+                (0..=span).map(|i| first.wrapping_add(i)).collect()
+            } else {
+                self.delivery_tag_by_id
+                    .keys()
+                    .filter(|(role, id)| *role == Role::Sender && id.wrapping_sub(first) <= span)
+                    .map(|(_, id)| *id)
+                    .collect()
+            };
+            for delivery_id in delivery_ids {
+                let key = (Role::Sender, delivery_id);
+                if let Some((handle, delivery_tag)) = self.delivery_tag_by_id.remove(&key) {
+                    if let Some(link_handle) = self.link_by_input_handle.get_mut(&handle) {
+                        link_handle.on_outgoing_settled_disposition(&delivery_tag);
+                    }
+                }
+            }
+        }
+
         // The remote-outgoing-window reflects the maximum number of incoming transfers that MAY
         // arrive without exceeding the remote endpoint’s outgoing-window. This value MUST be
         // decremented after every incoming transfer frame is received, and recomputed when in-
@@ -936,7 +1125,9 @@ impl endpoint::Session for Session {
             .unwrap_or(false)
         {
             let count = num_messages_settled_by_disposition(disposition.first, disposition.last);
+            let old = self.remote_outgoing_window;
             self.remote_outgoing_window = self.remote_outgoing_window.saturating_add(count);
+            log_window_transition("remote-outgoing-window", old, self.remote_outgoing_window);
         }
 
         let body = SessionFrameBody::Disposition(disposition);
@@ -944,11 +1135,104 @@ impl endpoint::Session for Session {
         Ok(frame)
     }
 
+    fn settle_outgoing_delivery(
+        &mut self,
+        input_handle: InputHandle,
+        delivery_tag: DeliveryTag,
+        state: DeliveryState,
+    ) -> Option<Disposition> {
+        // Unsettled outgoing transfers are stored with the remote peer's role (ie. receiver)
+        let key = self
+            .delivery_tag_by_id
+            .iter()
+            .find(|((role, _), (handle, tag))| {
+                *role == Role::Receiver && *handle == input_handle && *tag == delivery_tag
+            })
+            .map(|(key, _)| key.clone())?;
+        self.delivery_tag_by_id.remove(&key);
+
+        Some(Disposition {
+            role: Role::Sender,
+            first: key.1,
+            last: None,
+            settled: true,
+            state: Some(state),
+            batchable: false,
+        })
+    }
+
+    fn settle_incoming_range(
+        &mut self,
+        input_handle: InputHandle,
+        first: DeliveryNumber,
+        last: DeliveryNumber,
+        state: DeliveryState,
+    ) -> Vec<Disposition> {
+        // Delivery ids are allocated by the session, so the range may include deliveries of other
+        // links, which must be left untouched. Unsettled incoming transfers are stored with the
+        // remote peer's role (ie. sender)
+        let mut delivery_ids: Vec<DeliveryNumber> = self
+            .delivery_tag_by_id
+            .iter()
+            .filter(|((role, id), (handle, _))| {
+                *role == Role::Sender && *handle == input_handle && (first..=last).contains(id)
+            })
+            .map(|((_, id), _)| *id)
+            .collect();
+        delivery_ids.sort_unstable();
+
+        let mut dispositions: Vec<Disposition> = Vec::new();
+        for delivery_id in delivery_ids {
+            if let Some((handle, delivery_tag)) =
+                self.delivery_tag_by_id.remove(&(Role::Sender, delivery_id))
+            {
+                if let Some(link_handle) = self.link_by_input_handle.get_mut(&handle) {
+                    link_handle.on_outgoing_settled_disposition(&delivery_tag);
+                }
+            }
+
+            match dispositions.last_mut() {
+                Some(Disposition {
+                    last: Some(run_last),
+                    ..
+                }) if run_last.wrapping_add(1) == delivery_id => *run_last = delivery_id,
+                _ => dispositions.push(Disposition {
+                    role: Role::Receiver,
+                    first: delivery_id,
+                    last: Some(delivery_id),
+                    settled: true,
+                    state: Some(state.clone()),
+                    batchable: false,
+                }),
+            }
+        }
+        dispositions
+    }
+
     fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame {
         self.deallocate_link(detach.handle.clone().into());
         let body = SessionFrameBody::Detach(detach);
         SessionFrame::new(self.outgoing_channel, body)
     }
+
+    fn has_buffered_outgoing_transfers(&self) -> bool {
+        !self.remote_incoming_window_exhausted_buffer.is_empty()
+    }
+}
+
+/// Computes the remote-incoming-window from the next-incoming-id and incoming-window carried by a
+/// flow frame
+///
+/// Transfer ids are serial numbers that may wrap around, so the number of transfers that are sent
+/// but not yet known to the remote peer is the wrapping distance between the two ids. The window
+/// is zero if the peer claims to have received transfers that have not been sent.
+fn remote_incoming_window(
+    flow_next_incoming_id: TransferNumber,
+    flow_incoming_window: SequenceNo,
+    next_outgoing_id: TransferNumber,
+) -> SequenceNo {
+    let in_flight = next_outgoing_id.wrapping_sub(flow_next_incoming_id);
+    flow_incoming_window.saturating_sub(in_flight)
 }
 
 fn num_messages_settled_by_disposition(first: u32, last: Option<u32>) -> u32 {
@@ -1002,7 +1286,13 @@ fn consecutive_chunk_indices(delivery_ids: &[DeliveryNumber]) -> Vec<usize> {
 
 #[cfg(test)]
 mod tests {
-    use super::num_messages_settled_by_disposition;
+    use fe2o3_amqp_types::{definitions::Handle, states::SessionState};
+
+    use crate::endpoint::{OutgoingChannel, Session as _};
+
+    use super::{
+        error::AllocLinkError, num_messages_settled_by_disposition, remote_incoming_window, Builder,
+    };
 
     #[test]
     fn number_of_message_settled_by_disposition() {
@@ -1022,4 +1312,521 @@ mod tests {
         let count = num_messages_settled_by_disposition(first, last);
         assert_eq!(count, 1);
     }
+
+    #[test]
+    fn remote_incoming_window_on_transfer_id_wraparound() {
+        // 2 transfers are in flight
+        assert_eq!(remote_incoming_window(8, 10, 10), 8);
+
+        // next-outgoing-id has wrapped around while the peer has not seen the last 4 transfers
+        assert_eq!(remote_incoming_window(u32::MAX - 1, 10, 2), 6);
+
+        // the flow frame has wrapped around with the next-outgoing-id
+        assert_eq!(remote_incoming_window(1, 10, 2), 9);
+
+        // more transfers in flight than the window allows
+        assert_eq!(remote_incoming_window(u32::MAX, 2, 5), 0);
+    }
+
+    #[test]
+    fn allocate_link_respects_handle_max() {
+        let mut session = Builder::new()
+            .handle_max(1u32)
+            .into_session(OutgoingChannel(0), SessionState::Mapped);
+
+        assert!(session.allocate_link(String::from("link-0"), None).is_ok());
+        assert!(session.allocate_link(String::from("link-1"), None).is_ok());
+        assert!(matches!(
+            session.allocate_link(String::from("link-2"), None),
+            Err(AllocLinkError::HandleMaxReached)
+        ));
+
+        // The remote handle-max also limits the handles
+        let mut session = Builder::new().into_session(OutgoingChannel(0), SessionState::Mapped);
+        session.remote_handle_max = Handle(0);
+        assert!(session.allocate_link(String::from("link-0"), None).is_ok());
+        assert!(matches!(
+            session.allocate_link(String::from("link-1"), None),
+            Err(AllocLinkError::HandleMaxReached)
+        ));
+    }
+
+    #[test]
+    fn buffered_outgoing_transfers_are_reported_until_sent() {
+        use bytes::Bytes;
+        use fe2o3_amqp_types::performatives::Transfer;
+
+        use crate::endpoint::InputHandle;
+
+        let transfer = |tag: u8| Transfer {
+            handle: Handle(0),
+            delivery_id: None,
+            delivery_tag: Some(vec![tag].into()),
+            message_format: Some(0),
+            settled: Some(true),
+            more: false,
+            rcv_settle_mode: None,
+            state: None,
+            resume: false,
+            aborted: false,
+            batchable: true,
+        };
+
+        let mut session = Builder::new().into_session(OutgoingChannel(0), SessionState::Mapped);
+        session.remote_incoming_window = 0;
+        assert!(!session.has_buffered_outgoing_transfers());
+
+        let item = session
+            .on_outgoing_transfer(InputHandle(0), transfer(0), Bytes::new())
+            .unwrap();
+        assert!(item.is_none());
+        assert!(session.has_buffered_outgoing_transfers());
+
+        session.remote_incoming_window = 2;
+        let item = session
+            .on_outgoing_transfer(InputHandle(0), transfer(1), Bytes::new())
+            .unwrap();
+        assert!(item.is_some());
+        assert!(!session.has_buffered_outgoing_transfers());
+    }
+
+    /// Deterministic harness for the session flow control
+    ///
+    /// A [`WindowModel`] tracks the window state of section 2.5.6. The harness feeds the same
+    /// synthetic frames to a `Session` and to the model and compares their state after every step.
+    /// The number of transfers in flight is counted by the harness itself rather than derived from
+    /// the transfer ids, so that the serial number arithmetic of the session is checked against
+    /// plain counters.
+    mod window {
+        use bytes::Bytes;
+        use fe2o3_amqp_types::{
+            definitions::{Handle, ReceiverSettleMode, Role},
+            messaging::{Accepted, DeliveryState},
+            performatives::{Begin, Disposition, Flow, Transfer},
+            primitives::OrderedMap,
+            states::SessionState,
+        };
+        use parking_lot::RwLock;
+        use std::sync::Arc;
+        use tokio::sync::mpsc;
+
+        use crate::{
+            endpoint::{
+                IncomingChannel, InputHandle, LinkFlow, OutgoingChannel, OutputHandle, Session as _,
+            },
+            link::{
+                state::{LinkFlowState, LinkFlowStateInner},
+                LinkFrame, LinkRelay,
+            },
+            session::{
+                frame::{SessionFrame, SessionFrameBody, SessionOutgoingItem},
+                Builder, Session, WindowPolicy,
+            },
+        };
+
+        #[derive(Debug, Default, Clone, PartialEq, Eq)]
+        struct WindowModel {
+            initial_outgoing_id: u32,
+            next_outgoing_id: u32,
+            next_incoming_id: u32,
+            remote_incoming_window: u32,
+            remote_outgoing_window: u32,
+            buffered: usize,
+        }
+
+        impl WindowModel {
+            fn snapshot(session: &Session) -> Self {
+                Self {
+                    initial_outgoing_id: *session.initial_outgoing_id.value(),
+                    next_outgoing_id: session.next_outgoing_id,
+                    next_incoming_id: session.next_incoming_id,
+                    remote_incoming_window: session.remote_incoming_window,
+                    remote_outgoing_window: session.remote_outgoing_window,
+                    buffered: session.remote_incoming_window_exhausted_buffer.len(),
+                }
+            }
+
+            /// Sends as many transfers as the remote-incoming-window allows and returns the number
+            /// of transfers that are sent
+            fn drain(&mut self, pending: usize) -> usize {
+                let sent = pending.min(self.remote_incoming_window as usize);
+                self.next_outgoing_id = self.next_outgoing_id.wrapping_add(sent as u32);
+                self.remote_incoming_window -= sent as u32;
+                self.buffered = pending - sent;
+                sent
+            }
+
+            fn on_outgoing_transfer(&mut self) -> usize {
+                self.drain(self.buffered + 1)
+            }
+
+            fn on_incoming_transfer(&mut self) {
+                self.next_incoming_id = self.next_incoming_id.wrapping_add(1);
+                self.remote_outgoing_window = self.remote_outgoing_window.saturating_sub(1);
+            }
+
+            /// `in_flight` is the number of transfers that are sent but not yet received by the
+            /// peer as known by the harness
+            fn on_incoming_flow(&mut self, flow: &Flow, in_flight: u32) -> usize {
+                self.next_incoming_id = flow.next_outgoing_id;
+                self.remote_outgoing_window = flow.outgoing_window;
+                self.remote_incoming_window = flow.incoming_window.saturating_sub(in_flight);
+                self.drain(self.buffered)
+            }
+
+            fn on_outgoing_disposition(&mut self, count: u32) {
+                self.remote_outgoing_window = self.remote_outgoing_window.saturating_add(count);
+            }
+        }
+
+        /// A small xorshift generator so that the sequence of frames is reproducible
+        struct Rng(u64);
+
+        impl Rng {
+            fn next(&mut self) -> u32 {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                (self.0 >> 32) as u32
+            }
+
+            fn below(&mut self, n: u32) -> u32 {
+                self.next() % n
+            }
+        }
+
+        fn begin(next_outgoing_id: u32, incoming_window: u32, outgoing_window: u32) -> Begin {
+            Begin {
+                remote_channel: Some(0),
+                next_outgoing_id,
+                incoming_window,
+                outgoing_window,
+                handle_max: Handle::default(),
+                offered_capabilities: None,
+                desired_capabilities: None,
+                properties: None,
+            }
+        }
+
+        fn flow(
+            next_incoming_id: Option<u32>,
+            incoming_window: u32,
+            next_outgoing_id: u32,
+            outgoing_window: u32,
+        ) -> Flow {
+            Flow {
+                next_incoming_id,
+                incoming_window,
+                next_outgoing_id,
+                outgoing_window,
+                handle: None,
+                delivery_count: None,
+                link_credit: None,
+                available: None,
+                drain: false,
+                echo: false,
+                properties: None,
+            }
+        }
+
+        fn transfer(delivery_id: Option<u32>, tag: u32) -> Transfer {
+            Transfer {
+                handle: Handle(0),
+                delivery_id,
+                delivery_tag: Some(tag.to_be_bytes().to_vec().into()),
+                message_format: Some(0),
+                settled: Some(true),
+                more: false,
+                rcv_settle_mode: None,
+                state: None,
+                resume: false,
+                aborted: false,
+                batchable: false,
+            }
+        }
+
+        fn accepted(first: u32, last: u32) -> Disposition {
+            Disposition {
+                role: Role::Receiver,
+                first,
+                last: Some(last),
+                settled: true,
+                state: Some(DeliveryState::Accepted(Accepted {})),
+                batchable: false,
+            }
+        }
+
+        /// Creates a session that has exchanged `Begin` with the remote peer and has a receiver
+        /// relay attached on input handle 0
+        fn mapped_session(
+            next_outgoing_id: u32,
+            remote: Begin,
+        ) -> (Session, mpsc::Receiver<LinkFrame>) {
+            let mut session = Builder::new()
+                .next_outgoing_id(next_outgoing_id)
+                .into_session(OutgoingChannel(0), SessionState::BeginSent);
+            session
+                .on_incoming_begin(IncomingChannel(0), remote)
+                .unwrap();
+
+            let flow_state = Arc::new(LinkFlowState::receiver(LinkFlowStateInner {
+                initial_delivery_count: 0,
+                delivery_count: 0,
+                link_credit: 0,
+                available: 0,
+                drain: false,
+                properties: None,
+            }));
+            let (tx, rx) = mpsc::channel(1024);
+            let relay = LinkRelay::new_receiver(
+                tx,
+                flow_state,
+                Arc::new(RwLock::new(Some(OrderedMap::new()))),
+                ReceiverSettleMode::First,
+                None,
+            )
+            .with_output_handle(OutputHandle(0));
+            session.link_by_input_handle.insert(InputHandle(0), relay);
+            (session, rx)
+        }
+
+        fn count_transfers(item: Option<SessionOutgoingItem>) -> usize {
+            match item {
+                None => 0,
+                Some(SessionOutgoingItem::SingleFrame(_)) => 1,
+                Some(SessionOutgoingItem::MultipleFrames(frames)) => frames.len(),
+            }
+        }
+
+        #[tokio::test]
+        async fn outgoing_transfers_are_buffered_until_flow_reopens_window() {
+            let (mut session, _rx) = mapped_session(0, begin(0, 2, 2048));
+            let mut model = WindowModel::snapshot(&session);
+
+            for tag in 0..5 {
+                let item = session
+                    .on_outgoing_transfer(InputHandle(0), transfer(None, tag), Bytes::new())
+                    .unwrap();
+                assert_eq!(count_transfers(item), model.on_outgoing_transfer());
+                assert_eq!(WindowModel::snapshot(&session), model);
+            }
+            assert_eq!(session.remote_incoming_window, 0);
+            assert_eq!(session.remote_incoming_window_exhausted_buffer.len(), 3);
+
+            // The peer has received both transfers and grants room for two more
+            let frame = flow(Some(2), 2, 0, 2048);
+            let item = session.on_incoming_flow(frame.clone()).await.unwrap();
+            assert_eq!(count_transfers(item), 2);
+            assert_eq!(model.on_incoming_flow(&frame, 0), 2);
+            assert_eq!(WindowModel::snapshot(&session), model);
+            assert_eq!(session.next_outgoing_id, 4);
+            assert_eq!(session.remote_incoming_window, 0);
+            assert_eq!(session.remote_incoming_window_exhausted_buffer.len(), 1);
+
+            // A flow that doesn't cover the two transfers in flight keeps the window closed
+            let frame = flow(Some(2), 2, 0, 2048);
+            let item = session.on_incoming_flow(frame.clone()).await.unwrap();
+            assert_eq!(count_transfers(item), 0);
+            assert_eq!(model.on_incoming_flow(&frame, 2), 0);
+            assert_eq!(WindowModel::snapshot(&session), model);
+            assert_eq!(session.remote_incoming_window, 0);
+
+            let frame = flow(Some(4), 10, 0, 2048);
+            let item = session.on_incoming_flow(frame.clone()).await.unwrap();
+            assert_eq!(count_transfers(item), 1);
+            assert_eq!(model.on_incoming_flow(&frame, 0), 1);
+            assert_eq!(WindowModel::snapshot(&session), model);
+            assert_eq!(session.next_outgoing_id, 5);
+            assert_eq!(session.remote_incoming_window, 9);
+            assert!(session.remote_incoming_window_exhausted_buffer.is_empty());
+        }
+
+        #[tokio::test]
+        async fn remote_incoming_window_with_wrapping_transfer_ids() {
+            let (mut session, _rx) = mapped_session(u32::MAX - 1, begin(0, 4, 2048));
+
+            for tag in 0..3 {
+                let item = session
+                    .on_outgoing_transfer(InputHandle(0), transfer(None, tag), Bytes::new())
+                    .unwrap();
+                assert_eq!(count_transfers(item), 1);
+            }
+            assert_eq!(session.next_outgoing_id, 1);
+            assert_eq!(session.remote_incoming_window, 1);
+
+            // The peer has only received the first transfer
+            let item = session
+                .on_incoming_flow(flow(Some(u32::MAX), 4, 0, 2048))
+                .await
+                .unwrap();
+            assert!(item.is_none());
+            assert_eq!(session.remote_incoming_window, 2);
+
+            // Without next-incoming-id, the window is relative to the initial-outgoing-id
+            let item = session
+                .on_incoming_flow(flow(None, 4, 0, 2048))
+                .await
+                .unwrap();
+            assert!(item.is_none());
+            assert_eq!(session.remote_incoming_window, 1);
+
+            // The peer claims to have received a transfer that was never sent
+            let item = session
+                .on_incoming_flow(flow(Some(2), 4, 0, 2048))
+                .await
+                .unwrap();
+            assert!(item.is_none());
+            assert_eq!(session.remote_incoming_window, 0);
+        }
+
+        #[tokio::test]
+        async fn remote_outgoing_window_with_wrapping_transfer_ids() {
+            let (mut session, _rx) = mapped_session(0, begin(u32::MAX - 1, 2048, 3));
+            let mut model = WindowModel::snapshot(&session);
+
+            for delivery_id in [u32::MAX - 1, u32::MAX, 0, 1] {
+                let _ = session
+                    .on_incoming_transfer(transfer(Some(delivery_id), delivery_id), Bytes::new())
+                    .await
+                    .unwrap();
+                model.on_incoming_transfer();
+                assert_eq!(WindowModel::snapshot(&session), model);
+            }
+            assert_eq!(session.next_incoming_id, 2);
+            assert_eq!(session.remote_outgoing_window, 0);
+
+            let _ = session.on_outgoing_disposition(accepted(0, 1)).unwrap();
+            model.on_outgoing_disposition(2);
+            assert_eq!(WindowModel::snapshot(&session), model);
+            assert_eq!(session.remote_outgoing_window, 2);
+        }
+
+        async fn receive(session: &mut Session, delivery_id: u32) -> Option<SessionFrame> {
+            let _ = session
+                .on_incoming_transfer(transfer(Some(delivery_id), delivery_id), Bytes::new())
+                .await
+                .unwrap();
+            session.replenish_incoming_window()
+        }
+
+        #[tokio::test]
+        async fn incoming_window_policy() {
+            // The default policy never sends a session flow on its own
+            let (mut session, _rx) = mapped_session(0, begin(0, 2048, 2048));
+            session.incoming_window = 4;
+            for delivery_id in 0..8 {
+                assert!(receive(&mut session, delivery_id).await.is_none());
+            }
+
+            let (mut session, _rx) = mapped_session(0, begin(0, 2048, 2048));
+            session.incoming_window = 4;
+            session.incoming_window_policy = WindowPolicy::AutoReplenish;
+            assert!(receive(&mut session, 0).await.is_none());
+            let frame = receive(&mut session, 1)
+                .await
+                .expect("half of the window is used");
+            match frame.body {
+                SessionFrameBody::Flow(flow) => {
+                    assert!(flow.handle.is_none());
+                    assert_eq!(flow.next_incoming_id, Some(2));
+                    assert_eq!(flow.incoming_window, 4);
+                }
+                _ => panic!("expecting a session flow"),
+            }
+
+            // A link flow also re-advertises the window
+            assert!(receive(&mut session, 2).await.is_none());
+            let _ = session.on_outgoing_flow(LinkFlow::default()).unwrap();
+            assert!(receive(&mut session, 3).await.is_none());
+            assert!(receive(&mut session, 4).await.is_some());
+        }
+
+        #[tokio::test]
+        async fn random_sequences_match_model() {
+            for seed in 1..=16u64 {
+                let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+                let initial = u32::MAX - rng.below(64);
+                let remote_initial = u32::MAX - rng.below(64);
+                let (mut session, mut rx) =
+                    mapped_session(initial, begin(remote_initial, rng.below(8), 2048));
+                let mut model = WindowModel::snapshot(&session);
+                // Next transfer id the synthetic peer will assign
+                let mut peer_next_outgoing_id = remote_initial;
+                // Number of transfers sent by the session and received by the peer, counted
+                // without wrapping
+                let mut sent = 0u32;
+                let mut received = 0u32;
+                let mut tag = 0;
+
+                for step in 0..256 {
+                    match rng.below(4) {
+                        0 => {
+                            let item = session
+                                .on_outgoing_transfer(
+                                    InputHandle(0),
+                                    transfer(None, tag),
+                                    Bytes::new(),
+                                )
+                                .unwrap();
+                            tag += 1;
+                            let count = count_transfers(item);
+                            assert_eq!(count, model.on_outgoing_transfer());
+                            sent += count as u32;
+                        }
+                        1 => {
+                            let _ = session
+                                .on_incoming_transfer(
+                                    transfer(Some(peer_next_outgoing_id), tag),
+                                    Bytes::new(),
+                                )
+                                .await
+                                .unwrap();
+                            tag += 1;
+                            peer_next_outgoing_id = peer_next_outgoing_id.wrapping_add(1);
+                            model.on_incoming_transfer();
+                            while rx.try_recv().is_ok() {}
+                        }
+                        2 => {
+                            // The peer has received some of the transfers that are in flight
+                            received += rng.below(sent - received + 1);
+                            // Without next-incoming-id, every transfer sent since the
+                            // initial-outgoing-id is in flight
+                            let (next_incoming_id, in_flight) = match rng.below(4) {
+                                0 => (None, sent),
+                                _ => (Some(initial.wrapping_add(received)), sent - received),
+                            };
+                            let frame = flow(
+                                next_incoming_id,
+                                rng.below(8),
+                                peer_next_outgoing_id,
+                                rng.below(8),
+                            );
+                            let item = session.on_incoming_flow(frame.clone()).await.unwrap();
+                            let count = count_transfers(item);
+                            assert_eq!(count, model.on_incoming_flow(&frame, in_flight));
+                            sent += count as u32;
+                        }
+                        _ => {
+                            let last = session.next_incoming_id.wrapping_sub(1);
+                            let count = rng.below(3);
+                            let first = last.wrapping_sub(count);
+                            let _ = session
+                                .on_outgoing_disposition(accepted(first, last))
+                                .unwrap();
+                            // A range that wraps around is counted as a single delivery
+                            let settled = if first <= last { count + 1 } else { 1 };
+                            model.on_outgoing_disposition(settled);
+                        }
+                    }
+                    assert_eq!(
+                        WindowModel::snapshot(&session),
+                        model,
+                        "seed {} step {}",
+                        seed,
+                        step
+                    );
+                }
+            }
+        }
+    }
 }
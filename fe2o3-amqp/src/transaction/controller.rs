@@ -3,6 +3,7 @@ use fe2o3_amqp_types::{
     messaging::{Accepted, DeliveryState, Message, SerializableBody},
     transaction::{Coordinator, Declare, Declared, Discharge, TransactionId},
 };
+use serde_amqp::primitives::Array;
 use tokio::sync::{oneshot, Mutex};
 
 use crate::{
@@ -12,7 +13,8 @@ use crate::{
         builder::{WithSource, WithoutName, WithoutTarget},
         role,
         sender::SenderInner,
-        shared_inner::LinkEndpointInnerDetach,
+        shared_inner::{LinkEndpointInnerDetach, LinkEndpointInnerReattach},
+        state::LinkState,
         LinkStateError, SendError, SenderAttachError, SenderLink,
     },
     session::SessionHandle,
@@ -96,11 +98,17 @@ impl Controller {
     }
 
     /// Attach the controller with the default [`Coordinator`]
+    ///
+    /// The coordinator desires the transaction capabilities that are set on the session with
+    /// [`Session::builder().enable_transactions()`](crate::session::Builder::enable_transactions),
+    /// and the attach fails with [`SenderAttachError::DesireTxnCapabilitiesNotSupported`] if the
+    /// remote coordinator doesn't support them.
     pub async fn attach<R>(
         session: &mut SessionHandle<R>,
         name: impl Into<String>,
     ) -> Result<Self, SenderAttachError> {
-        Self::attach_with_coordinator(session, name, Coordinator::default()).await
+        let coordinator = Coordinator::new(session.txn_capabilities.clone().map(Array::from));
+        Self::attach_with_coordinator(session, name, coordinator).await
     }
 
     /// Attach the controller with a customized [`Coordinator`]
@@ -117,6 +125,39 @@ impl Controller {
             .await
     }
 
+    /// Re-establish the control link on `session`
+    ///
+    /// This can be used to recover from the loss of the control link (eg. after the connection or
+    /// session is re-established). The control link is detached if it is still attached, and any
+    /// error from detaching is ignored since the link is most likely already gone. A new control
+    /// link with the same name and coordinator is then attached to `session`.
+    ///
+    /// # In-flight transactions
+    ///
+    /// The control link is re-attached without any unsettled state, so pending declares and
+    /// discharges will fail. Brokers typically roll back the transactions that are associated
+    /// with a lost control link, so transactions declared before the re-attach should be treated
+    /// as rolled back and must be declared again.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut session = Session::begin(&mut connection).await.unwrap();
+    /// controller.reattach(&mut session).await.unwrap();
+    /// let txn = Transaction::declare(&controller, None).await.unwrap();
+    /// ```
+    pub async fn reattach<R>(&self, session: &SessionHandle<R>) -> Result<(), SenderAttachError> {
+        let mut inner = self.inner.lock().await;
+        let _ = inner.detach_with_error(None).await;
+
+        // The link may still be considered attached if the session has stopped
+        inner.link.local_state = LinkState::Detached;
+        inner.session = session.control.clone();
+        inner.session_id = session.id.clone();
+        inner.outgoing = session.outgoing.clone();
+        inner.reattach_inner().await.map(|_| ())
+    }
+
     pub(crate) async fn declare_inner(
         &self,
         global_id: Option<TransactionId>,
@@ -21,6 +21,7 @@ use crate::{
         shared_inner::{LinkEndpointInner, LinkEndpointInnerDetach},
         IllegalLinkStateError, LinkFrame, ReceiverAttachError, ReceiverLink, RecvError,
     },
+    session::SessionId,
     util::{Initialized, Running},
     Delivery,
 };
@@ -71,9 +72,16 @@ impl ControlLinkAcceptor {
         remote_attach: Attach,
         control: mpsc::Sender<SessionControl>,
         outgoing: mpsc::Sender<LinkFrame>,
+        session_id: SessionId,
     ) -> Result<TxnCoordinator, ReceiverAttachError> {
         self.inner
-            .accept_incoming_attach_inner(&self.shared, remote_attach, control, outgoing)
+            .accept_incoming_attach_inner(
+                &self.shared,
+                remote_attach,
+                control,
+                outgoing,
+                session_id,
+            )
             .await
             .map(|inner| TxnCoordinator {
                 inner,
@@ -211,6 +219,18 @@ impl TxnCoordinator {
                     Running::Stop
                 }
             },
+            RecvError::LinkDetachedByBroker(_) => {
+                self.inner
+                    .close_with_error(None)
+                    .await
+                    .unwrap_or_else(|_err| {
+                        #[cfg(feature = "tracing")]
+                        tracing::error!(detach_error = ?_err);
+                        #[cfg(feature = "log")]
+                        log::error!("detach_error = {:?}", _err);
+                    });
+                Running::Stop
+            }
             RecvError::TransferLimitExceeded => {
                 #[cfg(feature = "tracing")]
                 tracing::error!(?error);
@@ -226,6 +246,8 @@ impl TxnCoordinator {
             | RecvError::MessageDecode(_)
             | RecvError::IllegalRcvSettleModeInTransfer
             | RecvError::InconsistentFieldInMultiFrameDelivery
+            | RecvError::UnexpectedDeliveryId { .. }
+            | RecvError::InconsistentMore { .. }
             | RecvError::TransactionalAcquisitionIsNotImeplemented => {
                 #[cfg(feature = "tracing")]
                 tracing::error!(?error);
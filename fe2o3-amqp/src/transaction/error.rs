@@ -124,6 +124,8 @@ impl From<SendError> for ControllerSendError {
             SendError::NonTerminalDeliveryState => Self::NonTerminalDeliveryState,
             SendError::IllegalDeliveryState => Self::IllegalDeliveryState,
             SendError::MessageEncodeError => Self::MessageEncodeError,
+            // The controller never sends pre-settled messages
+            SendError::UnsettledSenderSettleMode => Self::IllegalDeliveryState,
         }
     }
 }
@@ -2,7 +2,7 @@
 
 
 use fe2o3_amqp_types::{
-    definitions::{self},
+    definitions::{self, DeliveryNumber, DeliveryTag},
     messaging::{Accepted, DeliveryState},
     performatives::{Attach, Begin, Detach, Disposition, End, Flow, Transfer},
     transaction::{TransactionError, TransactionId},
@@ -17,6 +17,7 @@ use crate::{
     session::{
         self,
         frame::{SessionFrame, SessionOutgoingItem},
+        SessionId,
     },
     Payload,
 };
@@ -82,6 +83,7 @@ where
     pub(crate) control: mpsc::Sender<SessionControl>,
     pub(crate) session: S,
     pub(crate) txn_manager: TransactionManager,
+    pub(crate) id: SessionId,
 }
 
 impl<S> TxnSession<S> where
@@ -102,11 +104,12 @@ where
         let acceptor = self.txn_manager.control_link_acceptor.clone();
         let control = self.control.clone();
         let outgoing = self.txn_manager.control_link_outgoing.clone();
+        let session_id = self.id.clone();
 
         tokio::spawn(async move {
             // Error accepting new control link is handled by acceptor
             if let Ok(coordinator) = acceptor
-                .accept_incoming_attach(remote_attach, control, outgoing)
+                .accept_incoming_attach(remote_attach, control, outgoing, session_id)
                 .await
             {
                 coordinator.event_loop().await
@@ -380,6 +383,35 @@ where
         self.session.on_outgoing_disposition(disposition)
     }
 
+    fn settle_outgoing_delivery(
+        &mut self,
+        input_handle: InputHandle,
+        delivery_tag: DeliveryTag,
+        state: DeliveryState,
+    ) -> Option<Disposition> {
+        self.session
+            .settle_outgoing_delivery(input_handle, delivery_tag, state)
+    }
+
+    fn settle_incoming_range(
+        &mut self,
+        input_handle: InputHandle,
+        first: DeliveryNumber,
+        last: DeliveryNumber,
+        state: DeliveryState,
+    ) -> Vec<Disposition> {
+        self.session
+            .settle_incoming_range(input_handle, first, last, state)
+    }
+
+    fn replenish_incoming_window(&mut self) -> Option<SessionFrame> {
+        self.session.replenish_incoming_window()
+    }
+
+    fn has_buffered_outgoing_transfers(&self) -> bool {
+        self.session.has_buffered_outgoing_transfers()
+    }
+
     fn on_outgoing_detach(&mut self, detach: Detach) -> SessionFrame {
         self.session.on_outgoing_detach(detach)
     }
@@ -33,6 +33,99 @@ pub enum Error {
     FramingError,
 }
 
+/// Classification of a transport [`Error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The connection was reset or aborted by the remote peer
+    ConnectionReset,
+
+    /// Writing to a connection that is already closed by the remote peer
+    BrokenPipe,
+
+    /// The connection timed out, either at the socket level or because the remote peer has not
+    /// sent any frame within the idle time-out
+    TimedOut,
+
+    /// The remote peer closed the connection in the middle of a frame (ie. "early eof")
+    UnexpectedEof,
+
+    /// Error reported by the TLS implementation, eg. a TLS alert received from the remote peer
+    Tls,
+
+    /// The remote peer violated the protocol, eg. a frame that cannot be decoded
+    Protocol,
+
+    /// Any other error
+    Other,
+}
+
+impl Error {
+    /// Classifies the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Io(err) => io_error_kind(err),
+            Error::IdleTimeoutElapsed => ErrorKind::TimedOut,
+            Error::DecodeError(_) | Error::NotImplemented(_) | Error::FramingError => {
+                ErrorKind::Protocol
+            }
+        }
+    }
+
+    /// Whether the connection to the remote peer is lost, ie. the connection was reset or the
+    /// stream ended unexpectedly
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(
+            self.kind(),
+            ErrorKind::ConnectionReset | ErrorKind::BrokenPipe | ErrorKind::UnexpectedEof
+        )
+    }
+
+    /// Whether reconnecting may succeed
+    ///
+    /// This is true if the connection is lost (see [`is_connection_lost`](Self::is_connection_lost))
+    /// or has timed out. TLS errors, protocol violations and other IO errors are not retryable as
+    /// they are likely to happen again on a new connection.
+    pub fn is_retryable(&self) -> bool {
+        self.is_connection_lost() || matches!(self.kind(), ErrorKind::TimedOut)
+    }
+}
+
+pub(crate) fn io_error_kind(err: &io::Error) -> ErrorKind {
+    if is_tls_error(err) {
+        return ErrorKind::Tls;
+    }
+
+    match err.kind() {
+        io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted => {
+            ErrorKind::ConnectionReset
+        }
+        io::ErrorKind::BrokenPipe => ErrorKind::BrokenPipe,
+        io::ErrorKind::TimedOut => ErrorKind::TimedOut,
+        io::ErrorKind::UnexpectedEof => ErrorKind::UnexpectedEof,
+        _ => ErrorKind::Other,
+    }
+}
+
+/// The TLS stream wraps the error of the TLS implementation in an `io::Error`
+fn is_tls_error(err: &io::Error) -> bool {
+    let _inner = match err.get_ref() {
+        Some(inner) => inner,
+        None => return false,
+    };
+
+    #[cfg(feature = "rustls")]
+    if _inner.is::<librustls::Error>() {
+        return true;
+    }
+
+    #[cfg(feature = "native-tls")]
+    if _inner.is::<libnative_tls::Error>() {
+        return true;
+    }
+
+    false
+}
+
 impl From<serde_amqp::Error> for Error {
     fn from(err: serde_amqp::Error) -> Self {
         match err {
@@ -63,6 +156,9 @@ pub enum NegotiationError {
     #[error("Invalid domain")]
     InvalidDomain,
 
+    #[error("TLS negotiation failed: {0}")]
+    Tls(#[source] Box<dyn std::error::Error + Send + Sync>),
+
     #[error("Decode error")]
     DecodeError(String),
 
@@ -106,3 +202,51 @@ impl From<sasl_profile::Error> for NegotiationError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{Error, ErrorKind};
+
+    #[test]
+    fn test_error_kind() {
+        let io_error = |kind| Error::Io(io::Error::from(kind));
+
+        let error = io_error(io::ErrorKind::ConnectionReset);
+        assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+        assert!(error.is_connection_lost());
+        assert!(error.is_retryable());
+
+        let error = io_error(io::ErrorKind::BrokenPipe);
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+        assert!(error.is_connection_lost());
+
+        let error = io_error(io::ErrorKind::UnexpectedEof);
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        assert!(error.is_connection_lost());
+
+        let error = Error::IdleTimeoutElapsed;
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+        assert!(!error.is_connection_lost());
+        assert!(error.is_retryable());
+
+        let error = Error::FramingError;
+        assert_eq!(error.kind(), ErrorKind::Protocol);
+        assert!(!error.is_retryable());
+
+        let error = io_error(io::ErrorKind::PermissionDenied);
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert!(!error.is_retryable());
+    }
+
+    #[cfg(feature = "rustls")]
+    #[test]
+    fn test_tls_error_kind() {
+        let tls_error =
+            librustls::Error::AlertReceived(librustls::AlertDescription::HandshakeFailure);
+        let error = Error::Io(io::Error::new(io::ErrorKind::InvalidData, tls_error));
+        assert_eq!(error.kind(), ErrorKind::Tls);
+        assert!(!error.is_retryable());
+    }
+}
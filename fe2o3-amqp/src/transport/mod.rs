@@ -32,7 +32,7 @@ use protocol_header::ProtocolHeader;
 use self::{error::NegotiationError, protocol_header::ProtocolHeaderCodec};
 
 pub(crate) mod error;
-pub use error::Error;
+pub use error::{Error, ErrorKind};
 pub mod protocol_header;
 
 pin_project! {
@@ -118,7 +118,9 @@ where
 
             if !alt_tls {
                 send_tls_proto_header(&mut stream).await?;
-                let incoming_header = recv_tls_proto_header(&mut stream).await?;
+                let incoming_header = recv_tls_proto_header(&mut stream)
+                    .await
+                    .map_err(map_tls_proto_header_eof)?;
 
                 if !incoming_header.is_tls() {
                     return Err(NegotiationError::ProtocolHeaderMismatch(
@@ -129,7 +131,11 @@ where
 
             // TLS negotiation
             let domain = ServerName::try_from(domain).map_err(|_| NegotiationError::InvalidDomain)?.to_owned();
-            let tls = connector.connect(domain, stream).await?;
+            // tokio-rustls wraps the rustls error (eg. a received alert) in an `io::Error`
+            let tls = connector
+                .connect(domain, stream)
+                .await
+                .map_err(|e| NegotiationError::Tls(e.into()))?;
             Ok(tls)
         }
     }
@@ -145,7 +151,9 @@ where
             ) -> Result<tokio_native_tls::TlsStream<Io>, NegotiationError> {
                 if !alt_tls {
                     send_tls_proto_header(&mut stream).await?;
-                    let incoming_header = recv_tls_proto_header(&mut stream).await?;
+                    let incoming_header = recv_tls_proto_header(&mut stream)
+                        .await
+                        .map_err(map_tls_proto_header_eof)?;
 
                     if !incoming_header.is_tls() {
                         return Err(NegotiationError::ProtocolHeaderMismatch(
@@ -154,9 +162,10 @@ where
                     }
                 }
 
-                connector.connect(domain, stream).await.map_err(|e| {
-                    NegotiationError::Io(io::Error::new(io::ErrorKind::Other, format!("{:?}", e)))
-                })
+                connector
+                    .connect(domain, stream)
+                    .await
+                    .map_err(|e| NegotiationError::Tls(e.into()))
             }
         }
     }
@@ -256,6 +265,12 @@ where
         self.framed_write.encoder().max_frame_length()
     }
 
+    /// Get the number of bytes of outgoing frames that are buffered but not yet written to the
+    /// underlying IO
+    pub fn write_buffer_len(&self) -> usize {
+        self.framed_write.write_buffer().len()
+    }
+
     /// Change the max_frame_size for the transport length delimited decoder
     pub fn set_encoder_max_frame_size(&mut self, max_frame_size: usize) -> &mut Self {
         let max_frame_size = std::cmp::max(MIN_MAX_FRAME_SIZE, max_frame_size);
@@ -278,7 +293,7 @@ where
 }
 
 /// Creates a LengthDelimitedCodec that can handle the AMQP and SASL frames
-fn length_delimited_encoder(max_frame_size: usize) -> LengthDelimitedCodec {
+pub(crate) fn length_delimited_encoder(max_frame_size: usize) -> LengthDelimitedCodec {
     LengthDelimitedCodec::builder()
         .big_endian()
         .length_field_length(4)
@@ -289,7 +304,7 @@ fn length_delimited_encoder(max_frame_size: usize) -> LengthDelimitedCodec {
         .new_codec()
 }
 
-fn length_delimited_decoder(max_frame_size: usize) -> LengthDelimitedCodec {
+pub(crate) fn length_delimited_decoder(max_frame_size: usize) -> LengthDelimitedCodec {
     LengthDelimitedCodec::builder()
         .big_endian()
         .length_field_length(4)
@@ -298,6 +313,56 @@ fn length_delimited_decoder(max_frame_size: usize) -> LengthDelimitedCodec {
         .new_codec()
 }
 
+/// Exchanges the protocol header with the remote peer over an arbitrary stream
+///
+/// The `expected` header is sent first, and the header sent by the remote peer must be identical.
+/// This allows a custom transport to perform the `TLS`, `SASL` or `AMQP` protocol header exchange
+/// itself, eg. a TLS protocol header exchange before establishing TLS over an exotic transport and
+/// then handing the stream to `open_with_stream()` with `alt_tls_establishment(true)`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use fe2o3_amqp::transport::{negotiate_protocol_header, protocol_header::ProtocolHeader};
+///
+/// negotiate_protocol_header(&mut stream, ProtocolHeader::tls()).await?;
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub async fn negotiate_protocol_header<Io>(
+    stream: &mut Io,
+    expected: ProtocolHeader,
+) -> Result<(), Error>
+where
+    Io: AsyncRead + AsyncWrite + Unpin,
+{
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(proto_header = ?expected, "SEND");
+    #[cfg(feature = "log")]
+    log::trace!("SEND proto_header = {:?}", expected);
+    let buf: [u8; 8] = expected.clone().into();
+    stream.write_all(&buf).await?;
+    stream.flush().await?;
+
+    let mut buf = [0u8; 8];
+    stream.read_exact(&mut buf).await?;
+    let incoming_header = ProtocolHeader::try_from(buf)
+        .map_err(|buf| Error::DecodeError(format!("Invalid protocol header {:?}", buf)))?;
+    #[cfg(feature = "tracing")]
+    tracing::trace!(proto_header = ?incoming_header, "RECV");
+    #[cfg(feature = "log")]
+    log::trace!("RECV proto_header = {:?}", incoming_header);
+
+    if incoming_header != expected {
+        return Err(Error::NotImplemented(Some(format!(
+            "Expecting {:?}, found {:?}",
+            expected, incoming_header
+        ))));
+    }
+    Ok(())
+}
+
 #[cfg_attr(feature = "tracing", tracing::instrument(name = "SEND", skip_all))]
 pub(crate) async fn send_amqp_proto_header<W>(
     framed_write: &mut FramedWrite<W, ProtocolHeaderCodec>,
@@ -375,6 +440,23 @@ where
 #[allow(unused)]
 #[cfg(any(feature = "rustls", feature = "native-tls"))]
 #[cfg_attr(feature = "tracing", tracing::instrument(name = "RECV", skip_all))]
+/// Peers that expect the TLS handshake right away often close the stream upon receiving the TLS
+/// protocol header, which would otherwise surface as an `UnexpectedEof` IO error
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+fn map_tls_proto_header_eof(err: NegotiationError) -> NegotiationError {
+    match err {
+        NegotiationError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+            NegotiationError::Tls(
+                "remote peer closed the stream during the TLS protocol header exchange. The peer \
+                may expect the TLS handshake without the protocol header (see \
+                `Builder::alt_tls_establishment`)"
+                    .into(),
+            )
+        }
+        err => err,
+    }
+}
+
 pub(crate) async fn recv_tls_proto_header<Io>(
     stream: &mut Io,
 ) -> Result<ProtocolHeader, NegotiationError>
@@ -618,8 +700,9 @@ mod tests {
 
     use super::{
         amqp::{Frame, FrameBody},
-        protocol_header::ProtocolHeaderCodec,
-        Transport,
+        negotiate_protocol_header,
+        protocol_header::{ProtocolHeader, ProtocolHeaderCodec},
+        Error, Transport,
     };
 
     #[tokio::test]
@@ -692,6 +775,33 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_negotiate_protocol_header() {
+        let mut mock = Builder::new()
+            .write(&[b'A', b'M', b'Q', b'P', 2, 1, 0, 0])
+            .read(&[b'A', b'M', b'Q', b'P', 2, 1, 0, 0])
+            .build();
+        negotiate_protocol_header(&mut mock, ProtocolHeader::tls())
+            .await
+            .unwrap();
+
+        // The remote peer only supports SASL
+        let mut mock = Builder::new()
+            .write(&[b'A', b'M', b'Q', b'P', 0, 1, 0, 0])
+            .read(&[b'A', b'M', b'Q', b'P', 3, 1, 0, 0])
+            .build();
+        let result = negotiate_protocol_header(&mut mock, ProtocolHeader::amqp()).await;
+        assert!(matches!(result, Err(Error::NotImplemented(Some(_)))));
+
+        // Not an AMQP protocol header
+        let mut mock = Builder::new()
+            .write(&[b'A', b'M', b'Q', b'P', 3, 1, 0, 0])
+            .read(b"HTTP/1.1")
+            .build();
+        let result = negotiate_protocol_header(&mut mock, ProtocolHeader::sasl()).await;
+        assert!(matches!(result, Err(Error::DecodeError(_))));
+    }
+
     #[tokio::test]
     async fn test_empty_frame_with_length_delimited_codec() {
         let mock = Builder::new()
@@ -739,4 +849,75 @@ mod tests {
 
         transport.send(frame).await.unwrap();
     }
+
+    #[cfg(feature = "rustls")]
+    fn rustls_connector() -> tokio_rustls::TlsConnector {
+        use librustls::{ClientConfig, RootCertStore};
+        use std::sync::Arc;
+
+        let config = ClientConfig::builder()
+            .with_root_certificates(RootCertStore::empty())
+            .with_no_client_auth();
+        tokio_rustls::TlsConnector::from(Arc::new(config))
+    }
+
+    #[cfg(feature = "rustls")]
+    #[tokio::test]
+    async fn test_tls_alert_is_surfaced_as_tls_error() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        use super::error::NegotiationError;
+
+        let (client, mut server) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            // Read the ClientHello and reply with a fatal handshake_failure alert
+            let mut buf = [0u8; 4096];
+            let _ = server.read(&mut buf).await.unwrap();
+            server
+                .write_all(&[0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 0x28])
+                .await
+                .unwrap();
+            server
+        });
+
+        let result = Transport::<_, ()>::connect_tls_with_rustls(
+            client,
+            "localhost",
+            &rustls_connector(),
+            true,
+        )
+        .await;
+        let _server = server.await.unwrap();
+        match result {
+            Err(NegotiationError::Tls(err)) => {
+                assert!(err.to_string().contains("HandshakeFailure"))
+            }
+            _ => panic!("expecting a TLS error"),
+        }
+    }
+
+    #[cfg(feature = "rustls")]
+    #[tokio::test]
+    async fn test_eof_during_tls_proto_header_exchange_is_surfaced_as_tls_error() {
+        use tokio::io::AsyncReadExt;
+
+        use super::error::NegotiationError;
+
+        let (client, mut server) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            // Read the TLS protocol header and close the stream
+            let mut buf = [0u8; 8];
+            server.read_exact(&mut buf).await.unwrap();
+        });
+
+        let result = Transport::<_, ()>::connect_tls_with_rustls(
+            client,
+            "localhost",
+            &rustls_connector(),
+            false,
+        )
+        .await;
+        server.await.unwrap();
+        assert!(matches!(result, Err(NegotiationError::Tls(_))));
+    }
 }
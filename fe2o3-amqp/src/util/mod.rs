@@ -23,6 +23,25 @@ pub(crate) enum Running {
     Stop,
 }
 
+/// Logs when a session window or the link credit is exhausted or replenished
+///
+/// This only logs the transitions to and from zero so that the events remain useful when
+/// diagnosing stalls caused by an exhausted window.
+#[inline]
+pub(crate) fn log_window_transition(_name: &'static str, old: u32, new: u32) {
+    let transition = match (old, new) {
+        (1.., 0) => Some("exhausted"),
+        (0, 1..) => Some("replenished"),
+        _ => None,
+    };
+    if let Some(_transition) = transition {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(window = _name, old, new, "window {}", _transition);
+        #[cfg(feature = "log")]
+        log::debug!("{} {} (old: {}, new: {})", _name, _transition, old, new);
+    }
+}
+
 cfg_not_wasm32! {
     use tokio::time::{Instant, Sleep};
 
@@ -112,6 +131,57 @@ impl Future for IdleTimeout {
     }
 }
 
+/// Timer that delays the flush of the outgoing frames that are held back by the transport
+///
+/// The timer never completes unless it is started.
+#[derive(Debug)]
+pub(crate) struct FlushTimer {
+    delay: Option<InnerDelay>,
+    duration: Duration,
+}
+
+impl FlushTimer {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            delay: None,
+            duration,
+        }
+    }
+
+    /// Start the timer if it is not already running
+    pub fn start(&mut self) {
+        if self.delay.is_none() {
+            self.delay = Some(InnerDelay::new(self.duration));
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.delay = None;
+    }
+}
+
+impl Future for FlushTimer {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        match self.delay.as_mut() {
+            Some(delay) => match Pin::new(delay).poll(cx) {
+                Poll::Ready(_) => {
+                    self.delay = None;
+                    Poll::Ready(())
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Sleep for the given duration on both native and wasm32 targets
+pub(crate) async fn sleep(duration: Duration) {
+    let _ = InnerDelay::new(duration).await;
+}
+
 /// An custom type to make a field immutable to
 /// prevent accidental mutations
 #[derive(Debug)]
@@ -234,6 +304,24 @@ impl AsByteIterator for Vec<Payload> {
     }
 }
 
+/// Collects the payload of each frame of a (possibly multi-frame) transfer without copying the
+/// bytes
+pub(crate) trait ToPayloads {
+    fn to_payloads(&self) -> Vec<Payload>;
+}
+
+impl ToPayloads for &Payload {
+    fn to_payloads(&self) -> Vec<Payload> {
+        vec![Payload::clone(self)]
+    }
+}
+
+impl ToPayloads for Vec<Payload> {
+    fn to_payloads(&self) -> Vec<Payload> {
+        self.clone()
+    }
+}
+
 pub(crate) struct ByteReader<T> {
     inner: Vec<T>,
 }
@@ -21,6 +21,8 @@ cfg_not_wasm32! {
     async fn test_send_receive_compat() {
         activemq_artemis_send_receive().await;
         activemq_artemis_send_receive_large_content().await;
+        activemq_artemis_half_close_sender().await;
+        activemq_artemis_dynamic_receiver_address().await;
         rabbitmq_amqp10_send_receive().await;
         rabbitmq_amqp10_send_receive_large_content().await;
     }
@@ -79,6 +81,72 @@ cfg_not_wasm32! {
         connection.close().await.unwrap();
     }
 
+    async fn activemq_artemis_half_close_sender() {
+        let (_node, port) = common::setup_activemq_artemis(None, None).await;
+
+        let url = format!("amqp://localhost:{}", port);
+        let mut connection = Connection::open("test-connection", &url[..]).await.unwrap();
+        let mut session = Session::begin(&mut connection).await.unwrap();
+        let mut sender = Sender::attach(&mut session, "test-sender", "test-queue")
+            .await
+            .unwrap();
+        let mut receiver = Receiver::attach(&mut session, "test-receiver", "test-queue")
+            .await
+            .unwrap();
+
+        let count = 10;
+        let mut pending = Vec::with_capacity(count);
+        for i in 0..count {
+            let message = Message::from(format!("test-message-{}", i));
+            pending.push(sender.send_batchable(message).await.unwrap());
+        }
+
+        // Close the sender before receiving any message
+        let outcomes = session.half_close_sender(sender, pending).await.unwrap();
+        assert_eq!(outcomes.len(), count);
+        for outcome in outcomes {
+            outcome.unwrap().accepted_or("Not accepted").unwrap();
+        }
+
+        // No message is lost after the sender is closed
+        for i in 0..count {
+            let received = receiver.recv::<String>().await.unwrap();
+            receiver.accept(&received).await.unwrap();
+            assert_eq!(received.body(), &format!("test-message-{}", i));
+        }
+
+        receiver.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
+    async fn activemq_artemis_dynamic_receiver_address() {
+        use fe2o3_amqp_types::messaging::Source;
+
+        let (_node, port) = common::setup_activemq_artemis(None, None).await;
+
+        let url = format!("amqp://localhost:{}", port);
+        let mut connection = Connection::open("test-connection", &url[..]).await.unwrap();
+        let mut session = Session::begin(&mut connection).await.unwrap();
+        let receiver = Receiver::attach(&mut session, "test-receiver", "test-queue")
+            .await
+            .unwrap();
+        assert_eq!(receiver.dynamic_address(), None);
+
+        let dynamic_receiver = Receiver::builder()
+            .name("test-dynamic-receiver")
+            .source(Source::builder().dynamic(true).build())
+            .attach(&mut session)
+            .await
+            .unwrap();
+        assert!(dynamic_receiver.dynamic_address().is_some());
+
+        receiver.close().await.unwrap();
+        dynamic_receiver.close().await.unwrap();
+        session.close().await.unwrap();
+        connection.close().await.unwrap();
+    }
+
     async fn rabbitmq_amqp10_send_receive() {
         let (_node, port) = common::setup_rabbitmq_amqp10(None, None).await;
 
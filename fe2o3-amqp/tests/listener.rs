@@ -1 +1,1681 @@
+//! Tests the listener side (acceptors) against the client side of the crate
 
+#![cfg(all(feature = "acceptor", not(target_arch = "wasm32")))]
+
+use std::time::Duration;
+
+use fe2o3_amqp::{
+    acceptor::{
+        ConnectionAcceptor, LinkAcceptor, LinkEndpoint, ListenerConnectionHandle, SaslIdentity,
+        SaslPlainAuthenticator, SaslRejection, SessionAcceptor,
+    },
+    connection::{ConnectionHandle, OpenError},
+    link::SenderAttachError,
+    sasl_profile::SaslProfile,
+    Connection, Receiver, Sender, Session,
+};
+use fe2o3_amqp_types::{
+    definitions::{self, AmqpError},
+    messaging::Target,
+};
+use futures_util::StreamExt;
+use tokio::net::{TcpListener, TcpStream};
+
+/// Opens a connection to `acceptor` over an in-memory stream and returns the client side and the
+/// listener side of the connection
+async fn connect(
+    acceptor: &ConnectionAcceptor<(), ()>,
+) -> (ConnectionHandle<()>, ListenerConnectionHandle) {
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    let client = Connection::builder()
+        .container_id("test-connection")
+        .open_with_stream(client_io);
+    let (client, listener) = tokio::join!(client, acceptor.accept(server_io));
+    (client.unwrap(), listener.unwrap())
+}
+
+#[tokio::test]
+async fn test_sasl_identity() {
+    let sasl_acceptor =
+        SaslPlainAuthenticator::new(|authzid: Option<&str>, authcid: &str, passwd: &str| {
+            match (authcid, passwd) {
+                ("guest", "guest") => Ok(SaslIdentity {
+                    authcid: authcid.to_string(),
+                    authzid: authzid.map(String::from),
+                }),
+                _ => Err(SaslRejection::auth()),
+            }
+        });
+    let acceptor = ConnectionAcceptor::builder()
+        .container_id("test-listener")
+        .sasl_acceptor(sasl_acceptor)
+        .build();
+
+    let (client_io, server_io) = tokio::io::duplex(1024);
+    let client = tokio::spawn(async move {
+        Connection::builder()
+            .container_id("test-connection")
+            .sasl_profile(SaslProfile::PlainWithAuthzid {
+                authzid: "admin".to_string(),
+                username: "guest".to_string(),
+                password: "guest".to_string(),
+            })
+            .open_with_stream(client_io)
+            .await
+            .unwrap()
+    });
+    let mut server = acceptor.accept(server_io).await.unwrap();
+    let mut client = client.await.unwrap();
+
+    let identity = server.sasl_identity().unwrap();
+    assert_eq!(identity.authcid, "guest");
+    assert_eq!(identity.authzid.as_deref(), Some("admin"));
+
+    client.close().await.unwrap();
+    let _ = server.on_close().await;
+}
+
+#[tokio::test]
+async fn test_attach_with_the_name_of_a_refused_link() {
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    let server = tokio::spawn(async move {
+        let acceptor = ConnectionAcceptor::new("test-listener");
+        let mut connection = acceptor.accept(server_io).await.unwrap();
+        let mut session = SessionAcceptor::new()
+            .accept(&mut connection)
+            .await
+            .unwrap();
+        let link_acceptor = LinkAcceptor::new();
+
+        // Asking for a dynamic target makes the acceptor respond with a null target, which is
+        // followed by the closing detach that refuses the link
+        let mut attach = session.next_incoming_attach().await.unwrap();
+        attach.target = Some(Box::new(Target::builder().dynamic(true).build().into()));
+        let refused = match link_acceptor
+            .accept_incoming_attach(attach, &mut session)
+            .await
+            .unwrap()
+        {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let error = definitions::Error::new(AmqpError::NotAllowed, None, None);
+        let _ = refused.close_with_error(error).await;
+
+        let receiver = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let _ = receiver.close().await;
+        let _ = session.on_end().await;
+        let _ = connection.on_close().await;
+    });
+
+    let mut connection = Connection::builder()
+        .container_id("test-connection")
+        .open_with_stream(client_io)
+        .await
+        .unwrap();
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let result = Sender::attach(&mut session, "test-sender", "q1").await;
+    assert!(matches!(
+        result,
+        Err(SenderAttachError::RemoteClosedWithError(_))
+    ));
+
+    // The detach of the refused link is queued before the new link is allocated, so the name is
+    // released by then
+    let sender = Sender::attach(&mut session, "test-sender", "q1")
+        .await
+        .unwrap();
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_max_connections() {
+    let acceptor = ConnectionAcceptor::builder()
+        .container_id("test-listener")
+        .max_connections(1)
+        .build();
+    assert_eq!(acceptor.available_connections(), Some(1));
+
+    let (mut client, mut server) = connect(&acceptor).await;
+    assert_eq!(acceptor.available_connections(), Some(0));
+
+    // The limit is reached, so the next connection is rejected and its stream is dropped
+    let (mut client_io, server_io) = tokio::io::duplex(1024);
+    let result = acceptor.accept(server_io).await;
+    assert!(matches!(result, Err(OpenError::ConnectionLimitReached)));
+    let mut buf = [0u8; 8];
+    let n = tokio::io::AsyncReadExt::read(&mut client_io, &mut buf)
+        .await
+        .unwrap();
+    assert_eq!(n, 0);
+
+    // Closing the connection releases the permit
+    client.close().await.unwrap();
+    let _ = server.on_close().await;
+    assert_eq!(acceptor.available_connections(), Some(1));
+}
+
+#[tokio::test]
+async fn test_ping() {
+    let (mut client, mut server) = connect(&ConnectionAcceptor::new("test-listener")).await;
+
+    client.ping().await.unwrap();
+
+    // The client waits for any frame from the server, which keeps sending empty frames
+    {
+        let mut wait = std::pin::pin!(client.ping_and_wait());
+        let result = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                tokio::select! {
+                    result = &mut wait => break result,
+                    _ = tokio::time::sleep(Duration::from_millis(10)) => {
+                        server.ping().await.unwrap()
+                    }
+                }
+            }
+        })
+        .await;
+        assert!(matches!(result, Ok(Ok(()))));
+    }
+
+    client.close().await.unwrap();
+    let _ = server.on_close().await;
+    assert!(client.ping().await.is_err());
+}
+
+#[tokio::test]
+async fn test_incoming_streams() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = tokio::spawn(async move {
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut connection = Connection::builder()
+            .container_id("test-connection")
+            .open_with_stream(stream)
+            .await
+            .unwrap();
+        let mut session = Session::begin(&mut connection).await.unwrap();
+        let sender = Sender::attach(&mut session, "test-sender", "q1")
+            .await
+            .unwrap();
+        sender.close().await.unwrap();
+        session.end().await.unwrap();
+        connection.close().await.unwrap();
+    });
+
+    let connection_acceptor = ConnectionAcceptor::new("test-listener");
+    let mut connection = {
+        let mut connections = std::pin::pin!(connection_acceptor.incoming(listener));
+        connections.next().await.unwrap().unwrap()
+    };
+
+    let session_acceptor = SessionAcceptor::new();
+    let link_acceptor = LinkAcceptor::new();
+    {
+        let mut sessions = std::pin::pin!(session_acceptor.incoming(&mut connection));
+        let mut session = sessions.next().await.unwrap().unwrap();
+        {
+            let mut links = std::pin::pin!(link_acceptor.incoming(&mut session));
+            match links.next().await.unwrap().unwrap() {
+                LinkEndpoint::Receiver(receiver) => receiver.close().await.unwrap(),
+                LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+            }
+
+            // The stream ends once the session is ended by the client
+            assert!(links.next().await.is_none());
+        }
+        let _ = session.on_end().await;
+
+        // The stream ends once the connection is closed by the client
+        assert!(sessions.next().await.is_none());
+    }
+    let _ = connection.on_close().await;
+    client.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_incoming_with_shutdown() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let client = tokio::spawn(async move {
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut connection = Connection::builder()
+            .container_id("test-connection")
+            .open_with_stream(stream)
+            .await
+            .unwrap();
+        connection.close().await.unwrap();
+    });
+
+    let acceptor = ConnectionAcceptor::new("test-listener");
+    let shutdown = async move {
+        let _ = shutdown_rx.await;
+    };
+    let mut connections = std::pin::pin!(acceptor.incoming_with_shutdown(listener, shutdown));
+    let mut connection = connections.next().await.unwrap().unwrap();
+    let _ = connection.on_close().await;
+    client.await.unwrap();
+
+    // The stream ends once shutdown completes
+    shutdown_tx.send(()).unwrap();
+    let next = tokio::time::timeout(Duration::from_secs(1), connections.next()).await;
+    assert!(matches!(next, Ok(None)));
+}
+
+#[tokio::test]
+async fn test_max_concurrent_attaches() {
+    use futures_util::poll;
+    use tokio::sync::oneshot;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let (first_attach_tx, first_attach_rx) = oneshot::channel::<()>();
+    let (check_tx, check_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        let session_acceptor = SessionAcceptor::new();
+        let link_acceptor = LinkAcceptor::new();
+
+        // Both links are detached from the first session
+        let mut session1 = session_acceptor.accept(&mut listener).await.unwrap();
+        let mut receivers = Vec::new();
+        for _ in 0..2 {
+            match link_acceptor.accept(&mut session1).await.unwrap() {
+                LinkEndpoint::Receiver(receiver) => receivers.push(receiver),
+                LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+            }
+        }
+        for mut receiver in receivers {
+            assert!(receiver.recv::<String>().await.is_err());
+        }
+
+        let mut session2 = session_acceptor.accept(&mut listener).await.unwrap();
+        let mut sender = match link_acceptor.accept(&mut session2).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+
+        // The first attach is left unanswered until the client has checked the second one
+        let first_attach = session2.next_incoming_attach().await.unwrap();
+        first_attach_tx.send(()).unwrap();
+        check_rx.await.unwrap();
+        assert!(poll!(std::pin::pin!(session2.next_incoming_attach())).is_pending());
+
+        let first = link_acceptor
+            .accept_incoming_attach(first_attach, &mut session2)
+            .await
+            .unwrap();
+        let second = link_acceptor.accept(&mut session2).await.unwrap();
+        for link in [first, second] {
+            match link {
+                LinkEndpoint::Receiver(mut receiver) => {
+                    assert!(receiver.recv::<String>().await.is_err())
+                }
+                LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+            }
+        }
+        sender.on_detach().await.unwrap();
+        drop(sender);
+
+        let _ = session2.on_end().await;
+        let _ = session1.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session1 = Session::begin(&mut connection).await.unwrap();
+    let first = Sender::attach(&mut session1, "test-sender-0", "q1")
+        .await
+        .unwrap();
+    let second = Sender::attach(&mut session1, "test-sender-1", "q2")
+        .await
+        .unwrap();
+    let first = first.detach().await.unwrap();
+    let second = second.detach().await.unwrap();
+
+    let mut session2 = Session::builder()
+        .max_concurrent_attaches(1)
+        .begin(&mut connection)
+        .await
+        .unwrap();
+    let mut receiver = Receiver::attach(&mut session2, "test-receiver", "q0")
+        .await
+        .unwrap();
+
+    // Resuming on a new session attaches the links again
+    let (first, second) = {
+        let mut first = std::pin::pin!(first.resume_on_session(&session2));
+        let mut second = std::pin::pin!(second.resume_on_session(&session2));
+        tokio::select! {
+            _ = &mut first => panic!("The first attach is not answered yet"),
+            _ = first_attach_rx => {}
+        }
+
+        // Queues the allocation of the second link. The session control is processed in order,
+        // so the allocation has been handled once the range is settled.
+        assert!(poll!(&mut second).is_pending());
+        receiver.accept_range(0, 0).await.unwrap();
+        assert!(poll!(&mut second).is_pending());
+        // An attach sent by the second link would reach the remote peer before the echoed flow
+        receiver.sync_flow().await.unwrap();
+        check_tx.send(()).unwrap();
+
+        tokio::join!(first, second)
+    };
+    first.unwrap().close().await.unwrap();
+    second.unwrap().close().await.unwrap();
+    receiver.close().await.unwrap();
+    session2.end().await.unwrap();
+    session1.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_send_settled() {
+    use fe2o3_amqp_types::definitions::SenderSettleMode;
+
+    use fe2o3_amqp::link::SendError;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::new();
+        let mut receiver = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        // The pre-settled delivery is received without sending a disposition
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), "hello");
+
+        let unsettled = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        unsettled.close().await.unwrap();
+        receiver.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::builder()
+        .name("test-sender")
+        .target("q1")
+        .sender_settle_mode(SenderSettleMode::Settled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(1), sender.send_settled("hello"))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut unsettled = Sender::builder()
+        .name("test-unsettled-sender")
+        .target("q1")
+        .sender_settle_mode(SenderSettleMode::Unsettled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    let result = unsettled.send_settled("hello").await;
+    assert!(matches!(result, Err(SendError::UnsettledSenderSettleMode)));
+
+    unsettled.close().await.unwrap();
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_recv_batch_timeout() {
+    let (close_tx, close_rx) = tokio::sync::oneshot::channel::<()>();
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        let mut outcomes = Vec::new();
+        for body in ["foo", "bar", "baz"] {
+            outcomes.push(sender.send_batchable(body).await.unwrap());
+        }
+        for outcome in outcomes {
+            outcome.await.unwrap();
+        }
+
+        close_rx.await.unwrap();
+        sender.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "test-receiver", "q1")
+        .await
+        .unwrap();
+
+    // Returns once `max` deliveries are received
+    let deliveries = receiver
+        .recv_batch_timeout::<String>(2, Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert_eq!(deliveries.len(), 2);
+    receiver.accept_all(&deliveries).await.unwrap();
+
+    // Returns whatever arrived before the deadline
+    let deliveries = receiver
+        .recv_batch_timeout::<String>(10, Duration::from_millis(200))
+        .await
+        .unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].body(), "baz");
+    receiver.accept_all(&deliveries).await.unwrap();
+
+    let deliveries = receiver
+        .recv_batch_timeout::<String>(10, Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert!(deliveries.is_empty());
+
+    close_tx.send(()).unwrap();
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remote_properties() {
+    use fe2o3_amqp_types::{
+        definitions::Fields,
+        primitives::{Symbol, Value},
+    };
+
+    let mut broker_properties = Fields::new();
+    broker_properties.insert(Symbol::from("product"), Value::from("test-broker"));
+    let acceptor = ConnectionAcceptor::builder()
+        .container_id("test-listener")
+        .properties(broker_properties)
+        .build();
+
+    let (mut client, mut server) = connect(&acceptor).await;
+
+    let product = client
+        .remote_properties()
+        .and_then(|properties| properties.get(&Symbol::from("product")));
+    assert_eq!(product, Some(&Value::from("test-broker")));
+    assert!(server.remote_properties().is_none());
+
+    client.close().await.unwrap();
+    let _ = server.on_close().await;
+}
+
+#[tokio::test]
+async fn test_detached_sender_resume_on_new_session() {
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let session_acceptor = SessionAcceptor::new();
+        let link_acceptor = LinkAcceptor::new();
+
+        let mut session1 = session_acceptor.accept(&mut listener).await.unwrap();
+        let mut receiver = match link_acceptor.accept(&mut session1).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), "foo");
+        receiver.accept(&delivery).await.unwrap();
+        // The client detaches the link without closing it
+        assert!(receiver.recv::<String>().await.is_err());
+        drop(receiver);
+
+        // The link is then re-attached on a new session
+        let mut session2 = session_acceptor.accept(&mut listener).await.unwrap();
+        let mut receiver = match link_acceptor.accept(&mut session2).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let delivery = receiver.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), "bar");
+        receiver.accept(&delivery).await.unwrap();
+        assert!(receiver.recv::<String>().await.is_err());
+
+        let _ = session2.on_end().await;
+        let _ = session1.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session1 = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::attach(&mut session1, "test-sender", "q1")
+        .await
+        .unwrap();
+    let outcome = sender.send("foo").await.unwrap();
+    assert!(outcome.is_accepted());
+
+    let detached = sender.detach().await.unwrap();
+    let mut session2 = Session::begin(&mut connection).await.unwrap();
+    let mut sender = detached.resume_on_session(&session2).await.unwrap();
+    let outcome = sender.send("bar").await.unwrap();
+    assert!(outcome.is_accepted());
+
+    sender.close().await.unwrap();
+    session2.end().await.unwrap();
+    session1.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_link_id() {
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        assert_eq!(listener.remote_container_id(), Some("test-connection"));
+        let session_acceptor = SessionAcceptor::new();
+        let link_acceptor = LinkAcceptor::new();
+
+        let mut session = session_acceptor.accept(&mut listener).await.unwrap();
+        let mut receiver = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let link_id = receiver.link_id();
+        assert_eq!(link_id.container_id, "test-listener");
+        assert_eq!(link_id.channel, session.channel());
+        assert_eq!(link_id.handle, receiver.handle().map(|handle| handle.0));
+        assert_eq!(link_id.name, "test-sender");
+
+        assert!(receiver.recv::<String>().await.is_err());
+        drop(receiver);
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    assert_eq!(connection.remote_container_id(), Some("test-listener"));
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let sender = Sender::attach(&mut session, "test-sender", "q1")
+        .await
+        .unwrap();
+
+    let link_id = sender.link_id();
+    assert_eq!(link_id.container_id, "test-connection");
+    assert_eq!(link_id.channel, session.channel());
+    assert!(link_id.handle.is_some());
+    assert_eq!(link_id.handle, sender.handle().map(|handle| handle.0));
+    assert_eq!(link_id.name, "test-sender");
+    assert_eq!(
+        link_id.to_string(),
+        format!(
+            "test-connection/{}/{}/test-sender",
+            link_id.channel,
+            link_id.handle.unwrap()
+        )
+    );
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_sender_initial_delivery_count() {
+    const INITIAL_DELIVERY_COUNT: u32 = u32::MAX - 1;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut receiver = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        // The receiver takes the delivery-count from the initial-delivery-count of the
+        // incoming Attach
+        assert_eq!(
+            receiver.flow_state_snapshot().delivery_count,
+            INITIAL_DELIVERY_COUNT
+        );
+
+        for expected in [INITIAL_DELIVERY_COUNT, u32::MAX, 0] {
+            let delivery = receiver.recv::<String>().await.unwrap();
+            assert_eq!(&delivery.delivery_tag()[..], &expected.to_be_bytes()[..]);
+            receiver.accept(&delivery).await.unwrap();
+        }
+
+        assert!(receiver.recv::<String>().await.is_err());
+        drop(receiver);
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::builder()
+        .name("test-sender")
+        .target("q1")
+        .initial_delivery_count(INITIAL_DELIVERY_COUNT)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert_eq!(
+        sender.flow_state_snapshot().delivery_count,
+        INITIAL_DELIVERY_COUNT
+    );
+
+    for expected in [u32::MAX, 0, 1] {
+        let outcome = sender.send("foo").await.unwrap();
+        assert!(outcome.is_accepted());
+        assert_eq!(sender.flow_state_snapshot().delivery_count, expected);
+    }
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_receiver_sync_flow() {
+    let (close_tx, close_rx) = tokio::sync::oneshot::channel::<()>();
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        for body in ["foo", "bar"] {
+            let outcome = sender.send(body).await.unwrap();
+            assert!(outcome.is_accepted());
+        }
+
+        close_rx.await.unwrap();
+        sender.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "test-receiver", "q1")
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        receiver.accept(&delivery).await.unwrap();
+    }
+
+    // Resolves once the remote sender responds to the echo
+    tokio::time::timeout(Duration::from_secs(1), receiver.sync_flow())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receiver.flow_state_snapshot().delivery_count, 2);
+
+    close_tx.send(()).unwrap();
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_receiver_rejects_on_decode_error() {
+    use fe2o3_amqp_types::{definitions::AmqpError, messaging::Outcome};
+
+    use fe2o3_amqp::link::receiver::DecodeErrorAction;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        // Cannot be decoded as a String
+        match sender.send(42u32).await.unwrap() {
+            Outcome::Rejected(rejected) => {
+                let error = rejected.error.unwrap();
+                assert_eq!(error.condition, AmqpError::DecodeError.into());
+            }
+            outcome => panic!("Expecting Rejected, found {:?}", outcome),
+        }
+        let outcome = sender.send("foo").await.unwrap();
+        assert!(outcome.is_accepted());
+
+        sender.on_detach().await.unwrap();
+        drop(sender);
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::builder()
+        .name("test-receiver")
+        .source("q1")
+        .on_decode_error(DecodeErrorAction::Reject)
+        .attach(&mut session)
+        .await
+        .unwrap();
+
+    // The first delivery is rejected and skipped
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.body(), "foo");
+    receiver.accept(&delivery).await.unwrap();
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_receiver_modify_with_annotations() {
+    use fe2o3_amqp_types::{
+        definitions::Fields,
+        messaging::Outcome,
+        primitives::{Symbol, Value},
+    };
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        match sender.send("foo").await.unwrap() {
+            Outcome::Modified(modified) => {
+                assert_eq!(modified.delivery_failed, Some(true));
+                assert_eq!(modified.undeliverable_here, Some(false));
+                let annotations = modified.message_annotations.unwrap();
+                assert_eq!(
+                    annotations.get(&Symbol::from("x-opt-retry-count")),
+                    Some(&Value::from(1u32))
+                );
+            }
+            outcome => panic!("Expecting Modified, found {:?}", outcome),
+        }
+
+        sender.on_detach().await.unwrap();
+        drop(sender);
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "test-receiver", "q1")
+        .await
+        .unwrap();
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    let mut annotations = Fields::new();
+    annotations.insert(Symbol::from("x-opt-retry-count"), Value::from(1u32));
+    receiver
+        .modify_with_annotations(&delivery, true, false, annotations)
+        .await
+        .unwrap();
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[cfg(feature = "transaction")]
+async fn attach_controller_with_enabled_transactions(
+    target_capabilities: Option<Vec<fe2o3_amqp_types::transaction::TxnCapability>>,
+) -> Result<fe2o3_amqp::transaction::Controller, fe2o3_amqp::link::SenderAttachError> {
+    use fe2o3_amqp::transaction::{coordinator::ControlLinkAcceptor, Controller};
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let control_link_acceptor = ControlLinkAcceptor::builder()
+            .target_capabilities(target_capabilities)
+            .build();
+        let mut session = SessionAcceptor::builder()
+            .control_link_acceptor(control_link_acceptor)
+            .build()
+            .accept(&mut listener)
+            .await
+            .unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::builder()
+        .enable_transactions()
+        .begin(&mut connection)
+        .await
+        .unwrap();
+    let result = Controller::attach(&mut session, "test-controller").await;
+
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+    result
+}
+
+#[cfg(feature = "transaction")]
+#[tokio::test]
+async fn test_enable_transactions_with_supporting_coordinator() {
+    use fe2o3_amqp_types::transaction::TxnCapability;
+
+    let result =
+        attach_controller_with_enabled_transactions(Some(vec![TxnCapability::LocalTransactions]))
+            .await;
+    assert!(result.is_ok());
+}
+
+#[cfg(feature = "transaction")]
+#[tokio::test]
+async fn test_enable_transactions_with_unsupporting_coordinator() {
+    use fe2o3_amqp::link::SenderAttachError;
+
+    let result = attach_controller_with_enabled_transactions(None).await;
+    assert!(matches!(
+        result,
+        Err(SenderAttachError::DesireTxnCapabilitiesNotSupported)
+    ));
+}
+
+#[cfg(feature = "transaction")]
+#[tokio::test]
+async fn test_delivery_transactional_state() {
+    use fe2o3_amqp_types::{
+        messaging::{DeliveryState, Outcome},
+        transaction::{TransactionId, TransactionalState},
+    };
+
+    let txn_id = TransactionId::from(vec![1u8, 2, 3]);
+    let server_txn_id = txn_id.clone();
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        let state = DeliveryState::TransactionalState(TransactionalState {
+            txn_id: server_txn_id,
+            outcome: None,
+        });
+        let outcome = sender.send_with("foo", false, Some(state)).await.unwrap();
+        assert!(matches!(outcome, Outcome::Accepted(_)));
+        sender.send("bar").await.unwrap();
+
+        sender.on_detach().await.unwrap();
+        drop(sender);
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "test-receiver", "q1")
+        .await
+        .unwrap();
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.transactional_state(), Some(&txn_id));
+    receiver.accept(&delivery).await.unwrap();
+
+    let delivery = receiver.recv::<String>().await.unwrap();
+    assert_eq!(delivery.transactional_state(), None);
+    receiver.accept(&delivery).await.unwrap();
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[cfg(feature = "transaction")]
+#[tokio::test]
+async fn test_controller_reattach() {
+    use fe2o3_amqp::transaction::{
+        coordinator::ControlLinkAcceptor, Controller, Transaction, TransactionDischarge,
+    };
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let session_acceptor = SessionAcceptor::builder()
+            .control_link_acceptor(ControlLinkAcceptor::default())
+            .build();
+        let mut first = session_acceptor.accept(&mut listener).await.unwrap();
+        let mut second = session_acceptor.accept(&mut listener).await.unwrap();
+        let _ = first.on_end().await;
+        let _ = second.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut first = Session::begin(&mut connection).await.unwrap();
+    let controller = Controller::attach(&mut first, "test-controller")
+        .await
+        .unwrap();
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    txn.commit().await.unwrap();
+
+    // The control link is lost with the session
+    first.end().await.unwrap();
+    let mut second = Session::begin(&mut connection).await.unwrap();
+    controller.reattach(&second).await.unwrap();
+
+    let txn = Transaction::declare(&controller, None).await.unwrap();
+    txn.commit().await.unwrap();
+
+    controller.close().await.unwrap();
+    second.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_recv_link_detached_by_broker() {
+    use fe2o3_amqp_types::definitions::{self, LinkError};
+
+    use fe2o3_amqp::link::RecvError;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        let error = definitions::Error::new(LinkError::DetachForced, None, None);
+        let _ = sender.detach_with_error(error).await;
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::attach(&mut session, "test-receiver", "q1")
+        .await
+        .unwrap();
+
+    match receiver.recv::<String>().await {
+        Err(RecvError::LinkDetachedByBroker(error)) => {
+            assert_eq!(
+                error.condition,
+                definitions::ErrorCondition::LinkError(LinkError::DetachForced)
+            );
+        }
+        result => panic!("Expecting LinkDetachedByBroker, found {:?}", result),
+    }
+
+    drop(receiver);
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_accept_range() {
+    use fe2o3_amqp_types::messaging::Outcome;
+
+    use fe2o3_amqp::link::DispositionRangeError;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::new();
+        let mut sender_a = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        let mut sender_b = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+
+        // The delivery ids of the two links are interleaved
+        let a1 = sender_a.send_batchable("a1").await.unwrap();
+        let b1 = sender_b.send_batchable("b1").await.unwrap();
+        let a2 = sender_a.send_batchable("a2").await.unwrap();
+        assert!(matches!(a1.await.unwrap(), Outcome::Accepted(_)));
+        assert!(matches!(a2.await.unwrap(), Outcome::Accepted(_)));
+        assert!(matches!(b1.await.unwrap(), Outcome::Rejected(_)));
+
+        sender_a.close().await.unwrap();
+        sender_b.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver_a = Receiver::attach(&mut session, "test-receiver-a", "q1")
+        .await
+        .unwrap();
+    let mut receiver_b = Receiver::attach(&mut session, "test-receiver-b", "q2")
+        .await
+        .unwrap();
+
+    let a1 = receiver_a.recv::<String>().await.unwrap();
+    let b1 = receiver_b.recv::<String>().await.unwrap();
+    let a2 = receiver_a.recv::<String>().await.unwrap();
+    assert!(*a1.delivery_id() < *b1.delivery_id());
+    assert!(*b1.delivery_id() < *a2.delivery_id());
+
+    let result = receiver_a
+        .accept_range(*a2.delivery_id(), *a1.delivery_id())
+        .await;
+    assert!(matches!(result, Err(DispositionRangeError::InvalidRange)));
+
+    // The range covers the delivery of the other link, which must be left unsettled
+    receiver_a
+        .accept_range(*a1.delivery_id(), *a2.delivery_id())
+        .await
+        .unwrap();
+    receiver_b.reject(&b1, None).await.unwrap();
+
+    receiver_a.close().await.unwrap();
+    receiver_b.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_sender_redirect() {
+    use fe2o3_amqp::link::SendError;
+    use fe2o3_amqp_types::definitions::SenderSettleMode;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::new();
+
+        let mut first = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        assert_eq!(
+            first.target().as_ref().unwrap().address.as_deref(),
+            Some("q1")
+        );
+        let delivery = first.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), "foo");
+        first.accept(&delivery).await.unwrap();
+        // The closing detach of the redirect
+        assert!(first.recv::<String>().await.is_err());
+        let _ = first.close().await;
+
+        let mut second = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        assert_eq!(second.name(), "test-sender");
+        assert_eq!(
+            second.target().as_ref().unwrap().address.as_deref(),
+            Some("q2")
+        );
+        let delivery = second.recv::<String>().await.unwrap();
+        assert_eq!(delivery.body(), "bar");
+        second.accept(&delivery).await.unwrap();
+        let _ = second.close().await;
+
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::builder()
+        .name("test-sender")
+        .target("q1")
+        .sender_settle_mode(SenderSettleMode::Unsettled)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert!(sender.send("foo").await.unwrap().is_accepted());
+
+    let mut sender = sender.redirect(&mut session, "q2").await.unwrap();
+    assert_eq!(sender.name(), "test-sender");
+    assert_eq!(
+        sender.target().as_ref().unwrap().address.as_deref(),
+        Some("q2")
+    );
+    // The settle mode is carried over to the redirected link
+    let result = sender.send_settled("bar").await;
+    assert!(matches!(result, Err(SendError::UnsettledSenderSettleMode)));
+    assert!(sender.send("bar").await.unwrap().is_accepted());
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_sender_follows_link_redirect() {
+    use fe2o3_amqp_types::{
+        definitions::{self, Fields, LinkError},
+        messaging::Target,
+        primitives::{Symbol, Value},
+    };
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::new();
+
+        // Asking for a dynamic target makes the acceptor respond with a null target, which is
+        // followed by the closing detach that carries the redirect
+        let mut attach = session.next_incoming_attach().await.unwrap();
+        attach.target = Some(Box::new(Target::builder().dynamic(true).build().into()));
+        let first = match link_acceptor
+            .accept_incoming_attach(attach, &mut session)
+            .await
+            .unwrap()
+        {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let mut info = Fields::new();
+        info.insert(Symbol::from("address"), Value::from("q2"));
+        let error = definitions::Error::new(LinkError::Redirect, None, info);
+        let _ = first.close_with_error(error).await;
+
+        let mut second = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        assert_eq!(
+            second.target().as_ref().unwrap().address.as_deref(),
+            Some("q2")
+        );
+        let delivery = second.recv::<String>().await.unwrap();
+        second.accept(&delivery).await.unwrap();
+        let _ = second.close().await;
+
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::builder()
+        .name("test-sender")
+        .target("q1")
+        .follow_redirects(true)
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert_eq!(
+        sender.target().as_ref().unwrap().address.as_deref(),
+        Some("q2")
+    );
+    sender.send("foo").await.unwrap();
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_coalesce_writes() {
+    use fe2o3_amqp_types::messaging::Outcome;
+
+    let acceptor = ConnectionAcceptor::new("test-listener");
+    let (client_io, server_io) = tokio::io::duplex(4096);
+    let client = Connection::builder()
+        .container_id("test-connection")
+        .coalesce_writes(true)
+        .open_with_stream(client_io);
+    let (connection, listener) = tokio::join!(client, acceptor.accept(server_io));
+    let (mut connection, mut listener) = (connection.unwrap(), listener.unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut receiver = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        for i in 0..21 {
+            let delivery = receiver.recv::<String>().await.unwrap();
+            assert_eq!(delivery.body(), &format!("message {}", i));
+            receiver.accept(&delivery).await.unwrap();
+        }
+        receiver.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::attach(&mut session, "test-sender", "q1")
+        .await
+        .unwrap();
+
+    let mut outcomes = Vec::new();
+    for i in 0..20 {
+        let fut = sender
+            .send_batchable(format!("message {}", i))
+            .await
+            .unwrap();
+        outcomes.push(fut);
+    }
+    // The transfers held back must be written once the session stops sending frames
+    for fut in outcomes {
+        assert!(matches!(fut.await.unwrap(), Outcome::Accepted(_)));
+    }
+    let outcome = sender.send("message 20").await.unwrap();
+    assert!(matches!(outcome, Outcome::Accepted(_)));
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_receiver_remote_source_outcomes() {
+    use fe2o3_amqp_types::{
+        messaging::{Modified, Outcome, Source},
+        primitives::{Array, Symbol},
+    };
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::builder()
+            .source_capabilities(vec![Symbol::from("shared")])
+            .build();
+        let sender = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        sender.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let modified = Modified {
+        delivery_failed: Some(true),
+        undeliverable_here: Some(true),
+        message_annotations: None,
+    };
+    let outcomes = Array::from(vec![
+        Symbol::from("amqp:accepted:list"),
+        Symbol::from("amqp:modified:list"),
+        Symbol::from("amqp:rejected:list"),
+    ]);
+    let source = Source::builder()
+        .address("q1")
+        .default_outcome(Outcome::Modified(modified))
+        .outcomes(outcomes.clone())
+        .build();
+    let receiver = Receiver::builder()
+        .name("test-receiver")
+        .source(source)
+        .attach(&mut session)
+        .await
+        .unwrap();
+
+    // The source echoed by the listener replaces the one set on the builder
+    assert!(matches!(
+        receiver.default_outcome(),
+        Some(Outcome::Modified(Modified {
+            delivery_failed: Some(true),
+            undeliverable_here: Some(true),
+            message_annotations: None,
+        }))
+    ));
+    assert_eq!(receiver.outcomes(), Some(&outcomes));
+    assert_eq!(
+        receiver.source_capabilities(),
+        Some(&Array::from(vec![Symbol::from("shared")]))
+    );
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_sender_on_detach_with_error() {
+    use fe2o3_amqp::link::DetachError;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        match sender.on_detach().await {
+            Err(DetachError::RemoteClosedWithError(error)) => {
+                assert_eq!(error.condition, AmqpError::NotAllowed.into());
+            }
+            other => panic!("Expecting RemoteClosedWithError, found {:?}", other),
+        }
+        sender.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let receiver = Receiver::attach(&mut session, "test-receiver", "q1")
+        .await
+        .unwrap();
+    let error = definitions::Error::new(AmqpError::NotAllowed, None, None);
+    let _ = receiver.close_with_error(error).await;
+
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_negotiated_settle_modes() {
+    use fe2o3_amqp::acceptor::SupportedSenderSettleModes;
+    use fe2o3_amqp_types::definitions::{ReceiverSettleMode, SenderSettleMode};
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::builder()
+            .supported_sender_settle_modes(SupportedSenderSettleModes::Settled)
+            .fallback_sender_settle_mode(SenderSettleMode::Settled)
+            .build();
+        let sender = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+        assert_eq!(sender.sender_settle_mode(), &SenderSettleMode::Settled);
+        assert_eq!(sender.receiver_settle_mode(), &ReceiverSettleMode::First);
+        sender.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let receiver = Receiver::builder()
+        .name("test-receiver")
+        .source("q1")
+        .sender_settle_mode(SenderSettleMode::Mixed)
+        .attach(&mut session)
+        .await
+        .unwrap();
+
+    // The receiver takes the sender settle mode chosen by the listener
+    assert_eq!(receiver.sender_settle_mode(), &SenderSettleMode::Settled);
+    assert_eq!(receiver.receiver_settle_mode(), &ReceiverSettleMode::First);
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_sender_credit() {
+    use fe2o3_amqp::link::receiver::CreditMode;
+    use fe2o3_amqp_types::messaging::Outcome;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut receiver = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        receiver.set_credit_mode(CreditMode::Manual);
+        receiver.set_credit(5).await.unwrap();
+        let delivery = receiver.recv::<String>().await.unwrap();
+        receiver.accept(&delivery).await.unwrap();
+        receiver.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::attach(&mut session, "test-sender", "q1")
+        .await
+        .unwrap();
+    let outcome = sender.send("hello").await.unwrap();
+    assert!(matches!(outcome, Outcome::Accepted(_)));
+    // The flow granting the credit is received before the disposition
+    assert_eq!(sender.credit(), 4);
+    assert_eq!(sender.flow_state_snapshot().credit, 4);
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_delivery_raw_payload() {
+    use fe2o3_amqp_types::messaging::{message_to_vec, Message};
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut receiver = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let delivery = receiver.recv::<String>().await.unwrap();
+        receiver.accept(&delivery).await.unwrap();
+
+        // The raw payload is the message exactly as it is encoded by the remote sender
+        let expected = message_to_vec(&Message::from("hello")).unwrap();
+        assert_eq!(delivery.body(), "hello");
+        assert_eq!(&delivery.raw_payload()[..], &expected[..]);
+
+        receiver.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::attach(&mut session, "test-sender", "q1")
+        .await
+        .unwrap();
+    let outcome = sender.send("hello").await.unwrap();
+    assert!(outcome.is_accepted());
+
+    sender.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_receiver_pull() {
+    use fe2o3_amqp::link::receiver::CreditMode;
+
+    let (pulled_tx, pulled_rx) = tokio::sync::oneshot::channel::<()>();
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let mut sender = match LinkAcceptor::new().accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a receiver"),
+        };
+        let outcome = sender.send("foo").await.unwrap();
+        assert!(outcome.is_accepted());
+
+        // The credit granted by a pull that timed out is withdrawn
+        pulled_rx.await.unwrap();
+        assert_eq!(sender.credit(), 0);
+
+        sender.close().await.unwrap();
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut receiver = Receiver::builder()
+        .name("test-receiver")
+        .source("q1")
+        .credit_mode(CreditMode::Manual)
+        .attach(&mut session)
+        .await
+        .unwrap();
+
+    let delivery = receiver
+        .pull::<String>(Duration::from_secs(1))
+        .await
+        .unwrap()
+        .expect("Expecting a delivery");
+    assert_eq!(delivery.body(), "foo");
+    receiver.accept(&delivery).await.unwrap();
+
+    let delivery = receiver
+        .pull::<String>(Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert!(delivery.is_none());
+    pulled_tx.send(()).unwrap();
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_half_close_sender() {
+    use fe2o3_amqp::link::{LinkStateError, RecvError};
+
+    let count = 10;
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::new();
+        let mut requests = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Receiver(receiver) => receiver,
+            LinkEndpoint::Sender(_) => panic!("Expecting a receiver"),
+        };
+        let mut replies = match link_acceptor.accept(&mut session).await.unwrap() {
+            LinkEndpoint::Sender(sender) => sender,
+            LinkEndpoint::Receiver(_) => panic!("Expecting a sender"),
+        };
+
+        for i in 0..count {
+            let delivery = requests.recv::<String>().await.unwrap();
+            requests.accept(&delivery).await.unwrap();
+            assert_eq!(delivery.body(), &format!("request-{}", i));
+        }
+        // The requests link is closed once the outcomes of all requests are received
+        assert!(matches!(
+            requests.recv::<String>().await,
+            Err(RecvError::LinkStateError(LinkStateError::RemoteClosed))
+        ));
+
+        // Replies are still sent after the requests link is closed
+        for i in 0..count {
+            let outcome = replies.send(format!("reply-{}", i)).await.unwrap();
+            assert!(outcome.is_accepted());
+        }
+        replies.on_detach().await.unwrap();
+        drop(replies);
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let mut sender = Sender::attach(&mut session, "test-sender", "requests")
+        .await
+        .unwrap();
+    let mut receiver = Receiver::attach(&mut session, "test-receiver", "replies")
+        .await
+        .unwrap();
+
+    let mut pending = Vec::with_capacity(count);
+    for i in 0..count {
+        pending.push(
+            sender
+                .send_batchable(format!("request-{}", i))
+                .await
+                .unwrap(),
+        );
+    }
+    let outcomes = session.half_close_sender(sender, pending).await.unwrap();
+    assert_eq!(outcomes.len(), count);
+    for outcome in outcomes {
+        assert!(outcome.unwrap().is_accepted());
+    }
+
+    for i in 0..count {
+        let delivery = receiver.recv::<String>().await.unwrap();
+        receiver.accept(&delivery).await.unwrap();
+        assert_eq!(delivery.body(), &format!("reply-{}", i));
+    }
+
+    receiver.close().await.unwrap();
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dynamic_address() {
+    use fe2o3_amqp_types::messaging::Source;
+
+    let (mut connection, mut listener) = connect(&ConnectionAcceptor::new("test-listener")).await;
+    let server = tokio::spawn(async move {
+        let mut session = SessionAcceptor::new().accept(&mut listener).await.unwrap();
+        let link_acceptor = LinkAcceptor::builder()
+            .on_dynamic_source(|mut source| {
+                source.address = Some(String::from("dynamic-source"));
+                Some(source)
+            })
+            .on_dynamic_target(|mut target| {
+                target.address = Some(String::from("dynamic-target"));
+                Some(target)
+            })
+            .build();
+
+        for _ in 0..3 {
+            match link_acceptor.accept(&mut session).await.unwrap() {
+                LinkEndpoint::Sender(mut sender) => {
+                    sender.on_detach().await.unwrap();
+                }
+                LinkEndpoint::Receiver(mut receiver) => {
+                    assert!(receiver.recv::<String>().await.is_err());
+                }
+            }
+        }
+        let _ = session.on_end().await;
+        let _ = listener.on_close().await;
+    });
+
+    let mut session = Session::begin(&mut connection).await.unwrap();
+    let receiver = Receiver::builder()
+        .name("test-dynamic-receiver")
+        .source(Source::builder().dynamic(true).build())
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert_eq!(receiver.dynamic_address(), Some("dynamic-source"));
+    receiver.close().await.unwrap();
+
+    let sender = Sender::builder()
+        .name("test-dynamic-sender")
+        .target(Target::builder().dynamic(true).build())
+        .attach(&mut session)
+        .await
+        .unwrap();
+    assert_eq!(sender.dynamic_address(), Some("dynamic-target"));
+    sender.close().await.unwrap();
+
+    // The address of a link that is not dynamic is not a dynamic address
+    let sender = Sender::attach(&mut session, "test-sender", "q1")
+        .await
+        .unwrap();
+    assert_eq!(sender.dynamic_address(), None);
+    sender.close().await.unwrap();
+
+    session.end().await.unwrap();
+    connection.close().await.unwrap();
+    server.await.unwrap();
+}
@@ -0,0 +1,83 @@
+#![allow(clippy::all)]
+
+//! Benchmarks for encoding described lists. The types below mirror the `Header` and `Properties`
+//! sections defined in `fe2o3-amqp-types`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_amqp::{
+    primitives::{Binary, Symbol, Timestamp},
+    SerializeComposite,
+};
+
+#[derive(SerializeComposite)]
+#[amqp_contract(
+    name = "amqp:header:list",
+    code = "0x0000_0000:0x0000_0070",
+    encoding = "list",
+    rename_all = "kebab-case"
+)]
+struct Header {
+    durable: bool,
+    priority: u8,
+    ttl: Option<u32>,
+    first_acquirer: bool,
+    delivery_count: u32,
+}
+
+#[derive(SerializeComposite)]
+#[amqp_contract(
+    name = "amqp:properties:list",
+    code = "0x0000_0000:0x0000_0073",
+    encoding = "list",
+    rename_all = "kebab-case"
+)]
+struct Properties {
+    message_id: Option<u64>,
+    user_id: Option<Binary>,
+    to: Option<String>,
+    subject: Option<String>,
+    reply_to: Option<String>,
+    correlation_id: Option<u64>,
+    content_type: Option<Symbol>,
+    content_encoding: Option<Symbol>,
+    absolute_expiry_time: Option<Timestamp>,
+    creation_time: Option<Timestamp>,
+    group_id: Option<String>,
+    group_sequence: Option<u32>,
+    reply_to_group_id: Option<String>,
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let value = Header {
+        durable: true,
+        priority: 4,
+        ttl: Some(30_000),
+        first_acquirer: false,
+        delivery_count: 1,
+    };
+    c.bench_function("serialize Header", |b| {
+        b.iter(|| serde_amqp::to_vec(black_box(&value)).unwrap())
+    });
+
+    let value = Properties {
+        message_id: Some(rand::random::<u64>()),
+        user_id: Some(Binary::from(b"guest".to_vec())),
+        to: Some(String::from("q1")),
+        subject: Some(String::from("subject")),
+        reply_to: Some(String::from("reply-q1")),
+        correlation_id: Some(rand::random::<u64>()),
+        content_type: Some(Symbol::from("application/json")),
+        content_encoding: None,
+        absolute_expiry_time: None,
+        creation_time: Some(Timestamp::from_milliseconds(1_700_000_000_000)),
+        group_id: None,
+        group_sequence: Some(7),
+        reply_to_group_id: None,
+    };
+    c.bench_function("serialize Properties", |b| {
+        b.iter(|| serde_amqp::to_vec(black_box(&value)).unwrap())
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -4,7 +4,7 @@ use serde::{
     de::{self},
     Deserialize,
 };
-use std::convert::TryInto;
+use std::{convert::TryInto, sync::Arc};
 
 use crate::{
     __constants::{
@@ -18,7 +18,7 @@ use crate::{
         OFFSET_ARRAY32, OFFSET_ARRAY8, OFFSET_LIST32, OFFSET_LIST8, OFFSET_MAP32, OFFSET_MAP8,
     },
     format_code::EncodingCodes,
-    read::{IoReader, Read, SliceReader},
+    read::{read_primitive_bytes_or_else, IoReader, Read, SliceReader, TransientSliceReader},
     util::{EnumType, NonNativeType, PeekTypeCode, SequenceType, StructEncoding},
 };
 
@@ -36,6 +36,33 @@ pub fn from_slice<'de, T: de::Deserialize<'de>>(slice: &'de [u8]) -> Result<T, E
     T::deserialize(&mut de)
 }
 
+/// Deserializes an AMQP `null` into the default value of `T`
+///
+/// This is a helper for plain serde structs and is meant to be used with
+/// `#[serde(deserialize_with = "serde_amqp::de::null_as_default")]` on a non-`Option` field that
+/// has a default value. Some implementations encode such fields with an explicit `null` instead of
+/// omitting them. Composite types derived with `DeserializeComposite` do not need this, as their
+/// `#[amqp_contract(default)]` fields already decode a `null` as the default value.
+///
+/// # Example
+///
+/// ```rust
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Foo {
+///     #[serde(deserialize_with = "serde_amqp::de::null_as_default")]
+///     durable: bool,
+/// }
+/// ```
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
 /// A structure that deserializes AMQP1.0 binary encoded values into rust types
 #[derive(Debug)]
 pub struct Deserializer<R> {
@@ -45,6 +72,39 @@ pub struct Deserializer<R> {
     enum_type: EnumType,
     struct_encoding: StructEncoding,
     elem_format_code: Option<EncodingCodes>,
+    elem_descriptors: ElemDescriptors,
+}
+
+/// Encoded descriptors (including the leading `0x00`) that are factored into the constructor of
+/// an array of described types
+///
+/// The descriptors are shared by all elements of the array without being copied, and `next` is
+/// the index of the first descriptor that is not yet consumed by the current element
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ElemDescriptors {
+    encoded: Option<Arc<[Vec<u8>]>>,
+    next: usize,
+}
+
+impl ElemDescriptors {
+    fn new(encoded: Vec<Vec<u8>>) -> Self {
+        let encoded = match encoded.is_empty() {
+            true => None,
+            false => Some(encoded.into()),
+        };
+        Self { encoded, next: 0 }
+    }
+
+    fn front(&self) -> Option<&[u8]> {
+        self.encoded
+            .as_ref()
+            .and_then(|encoded| encoded.get(self.next))
+            .map(Vec::as_slice)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.front().is_none()
+    }
 }
 
 impl<'de, R: Read<'de>> Deserializer<R> {
@@ -57,6 +117,7 @@ impl<'de, R: Read<'de>> Deserializer<R> {
             enum_type: Default::default(),
             struct_encoding: StructEncoding::None,
             elem_format_code: None,
+            elem_descriptors: ElemDescriptors::default(),
         }
     }
 
@@ -76,12 +137,47 @@ impl<'de, R: Read<'de>> Deserializer<R> {
     }
 
     fn get_elem_code_or_peek_byte(&mut self) -> Option<Result<u8, Error>> {
+        if !self.elem_descriptors.is_empty() {
+            return Some(Ok(EncodingCodes::DescribedType as u8));
+        }
         match &self.elem_format_code {
             Some(c) => Some(Ok(c.clone() as u8)),
             None => self.reader.peek().map(Ok),
         }
     }
 
+    /// Reads the constructor shared by all elements of an array
+    ///
+    /// If the elements are described types, the descriptors are returned separately from the
+    /// format code of the described value
+    fn read_array_elem_constructor(&mut self) -> Result<(EncodingCodes, ElemDescriptors), Error> {
+        let mut descriptors = Vec::new();
+        loop {
+            let format_code = self
+                .read_format_code()
+                .ok_or_else(|| Error::unexpected_eof("Expecting format code"))??;
+            match format_code {
+                EncodingCodes::DescribedType => {
+                    let mut descriptor = vec![EncodingCodes::DescribedType as u8];
+                    let mut bytes = read_primitive_bytes_or_else(&mut self.reader, |_| {
+                        Err(Error::InvalidFormatCode)
+                    })?;
+                    descriptor.append(&mut bytes);
+                    descriptors.push(descriptor);
+                }
+                // A zero width constructor would allow an arbitrary number of elements without
+                // consuming any input, and all elements would have to be identical anyway
+                EncodingCodes::Null
+                | EncodingCodes::BooleanTrue
+                | EncodingCodes::BooleanFalse
+                | EncodingCodes::Uint0
+                | EncodingCodes::Ulong0
+                | EncodingCodes::List0 => return Err(Error::InvalidFormatCode),
+                _ => return Ok((format_code, ElemDescriptors::new(descriptors))),
+            }
+        }
+    }
+
     #[inline]
     fn parse_bool(&mut self) -> Result<bool, Error> {
         match self
@@ -371,6 +467,13 @@ impl<'de, R: Read<'de>> Deserializer<R> {
         }
     }
 
+    /// 128-bit integers are encoded as a 16 bytes `binary`
+    #[inline]
+    fn parse_128bit_binary(&mut self) -> Result<[u8; 16], Error> {
+        let bytes = self.parse_binary()?;
+        <[u8; 16]>::try_from(bytes.as_slice()).map_err(|_| Error::InvalidLength)
+    }
+
     #[inline]
     fn parse_decimal<V>(&mut self, visitor: V) -> Result<V::Value, Error>
     where
@@ -439,6 +542,12 @@ impl<'de, R: Read<'de>> Deserializer<R> {
     where
         V: de::Visitor<'de>,
     {
+        if let Some(bytes) = self.elem_descriptors.front() {
+            // The descriptor is factored into the constructor of an array
+            let reader = TransientSliceReader::new(bytes);
+            return Deserializer::new(reader).parse_described_identifier(visitor);
+        }
+
         // [0] is 0x00,
         // [1] is format code
         let buf = self
@@ -511,6 +620,27 @@ impl<'de, R: Read<'de>> Deserializer<R> {
     }
 }
 
+/// Removes the `offset` from the encoded size of an array
+///
+/// The size is read from the input and returns an error instead of underflowing if it is
+/// smaller than the offset
+fn size_without_offset(size: usize, offset: usize) -> Result<usize, Error> {
+    size.checked_sub(offset).ok_or(Error::InvalidLength)
+}
+
+/// Removes the `offset` from the encoded size of a list, map or array
+///
+/// Every element of a list or map takes at least one byte, so a `count` that is larger than the
+/// remaining size is rejected before any element is read. The same bound applies to arrays, whose
+/// elements are encoded with fixed width format codes that take at least one byte
+fn compound_size_without_offset(size: usize, offset: usize, count: usize) -> Result<usize, Error> {
+    let size = size_without_offset(size, offset)?;
+    match count > size {
+        true => Err(Error::InvalidLength),
+        false => Ok(size),
+    }
+}
+
 impl<'de, R> de::Deserializer<'de> for &mut Deserializer<R>
 where
     R: Read<'de>,
@@ -655,6 +785,24 @@ where
         visitor.visit_u64(self.parse_u64()?)
     }
 
+    /// `i128` is expected to be encoded as a 16 bytes `binary` in big-endian
+    #[inline]
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_i128(i128::from_be_bytes(self.parse_128bit_binary()?))
+    }
+
+    /// `u128` is expected to be encoded as a 16 bytes `binary` in big-endian
+    #[inline]
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_u128(u128::from_be_bytes(self.parse_128bit_binary()?))
+    }
+
     #[inline]
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
@@ -874,16 +1022,16 @@ where
                 match count {
                     0 => visitor.visit_seq(ArrayAccess::new(self, len, count)),
                     _ => {
-                        let format_code = self
-                            .read_format_code()
-                            .ok_or_else(|| Error::unexpected_eof("Expecting format code"))??;
-                        self.elem_format_code = Some(format_code);
+                        let (format_code, descriptors) = self.read_array_elem_constructor()?;
 
                         // Account for offset
-                        let len = len - OFFSET_ARRAY8;
+                        let len = compound_size_without_offset(len, OFFSET_ARRAY8, count)?;
                         // let buf = self.reader.read_bytes(len)?;
 
-                        visitor.visit_seq(ArrayAccess::new(self, len, count))
+                        visitor.visit_seq(
+                            ArrayAccess::new(self, len, count)
+                                .elem_constructor(format_code, descriptors),
+                        )
                     }
                 }
             }
@@ -899,16 +1047,16 @@ where
                 match count {
                     0 => visitor.visit_seq(ArrayAccess::new(self, len, count)),
                     _ => {
-                        let format_code = self
-                            .read_format_code()
-                            .ok_or_else(|| Error::unexpected_eof("Expecting format code"))??;
-                        self.elem_format_code = Some(format_code);
+                        let (format_code, descriptors) = self.read_array_elem_constructor()?;
 
                         // Account for offset
-                        let len = len - OFFSET_ARRAY32;
+                        let len = compound_size_without_offset(len, OFFSET_ARRAY32, count)?;
                         // let buf = self.reader.read_bytes(len)?;
 
-                        visitor.visit_seq(ArrayAccess::new(self, len, count))
+                        visitor.visit_seq(
+                            ArrayAccess::new(self, len, count)
+                                .elem_constructor(format_code, descriptors),
+                        )
                     }
                 }
             }
@@ -930,7 +1078,7 @@ where
                     as usize;
 
                 // Account for offset
-                let len = len - OFFSET_LIST8;
+                let len = compound_size_without_offset(len, OFFSET_LIST8, count)?;
 
                 // Make sure there is no other element format code
                 self.elem_format_code = None;
@@ -943,7 +1091,7 @@ where
                 let count = u32::from_be_bytes(count_bytes) as usize;
 
                 // Account for offset
-                let len = len - OFFSET_LIST32;
+                let len = compound_size_without_offset(len, OFFSET_LIST32, count)?;
 
                 // Make sure there is no other element format code
                 self.elem_format_code = None;
@@ -981,7 +1129,7 @@ where
                     as usize;
 
                 // Account for offset
-                let size = size - OFFSET_LIST8;
+                let size = compound_size_without_offset(size, OFFSET_LIST8, count)?;
 
                 // Make sure there is no other element format code
                 self.elem_format_code = None;
@@ -994,7 +1142,7 @@ where
                 let count = u32::from_be_bytes(count_bytes) as usize;
 
                 // Account for offset
-                let size = size - OFFSET_LIST32;
+                let size = compound_size_without_offset(size, OFFSET_LIST32, count)?;
 
                 // Make sure there is no other element format code
                 self.elem_format_code = None;
@@ -1031,7 +1179,7 @@ where
                     as usize;
 
                 // Account for offset
-                let size = size - OFFSET_MAP8;
+                let size = compound_size_without_offset(size, OFFSET_MAP8, count)?;
 
                 (size, count)
             }
@@ -1043,13 +1191,21 @@ where
                 let count = u32::from_be_bytes(count_bytes) as usize;
 
                 // Account for offset
-                let size = size - OFFSET_MAP32;
+                let size = compound_size_without_offset(size, OFFSET_MAP32, count)?;
 
                 (size, count)
             }
             _ => return Err(Error::InvalidFormatCode),
         };
 
+        // AMQP map count includes both key and value
+        if count % 2 != 0 {
+            return Err(Error::InvalidLength);
+        }
+
+        // Make sure there is no other element format code
+        self.elem_format_code = None;
+
         // // AMQP map count includes both key and value, should be halfed
         // let count = count / 2;
         visitor.visit_map(MapAccess::new(self, size, count))
@@ -1139,8 +1295,21 @@ where
             self.enum_type = EnumType::Value;
             visitor.visit_enum(VariantAccess::new(self))
         } else if name == DESCRIPTOR {
-            self.enum_type = EnumType::Descriptor;
-            visitor.visit_enum(VariantAccess::new(self))
+            // Cloning only increments the reference count of the cached descriptors
+            let descriptors = self.elem_descriptors.clone();
+            match descriptors.front() {
+                // The descriptor is factored into the constructor of an array
+                Some(bytes) => {
+                    self.elem_descriptors.next += 1;
+                    let mut de = Deserializer::new(TransientSliceReader::new(bytes));
+                    de.enum_type = EnumType::Descriptor;
+                    visitor.visit_enum(VariantAccess::new(&mut de))
+                }
+                None => {
+                    self.enum_type = EnumType::Descriptor;
+                    visitor.visit_enum(VariantAccess::new(self))
+                }
+            }
         } else if name == UNTAGGED_ENUM {
             visitor.visit_enum(VariantAccess::new(self))
         } else if name == ARRAY {
@@ -1299,6 +1468,8 @@ pub struct ArrayAccess<'a, R> {
     de: &'a mut Deserializer<R>,
     _size: usize,
     count: usize,
+    elem_format_code: Option<EncodingCodes>,
+    elem_descriptors: ElemDescriptors,
 }
 
 impl<'a, R> ArrayAccess<'a, R> {
@@ -1307,8 +1478,21 @@ impl<'a, R> ArrayAccess<'a, R> {
             de,
             _size: size,
             count,
+            elem_format_code: None,
+            elem_descriptors: ElemDescriptors::default(),
         }
     }
+
+    /// Sets the constructor that is shared by all elements
+    pub(crate) fn elem_constructor(
+        mut self,
+        format_code: EncodingCodes,
+        descriptors: ElemDescriptors,
+    ) -> Self {
+        self.elem_format_code = Some(format_code);
+        self.elem_descriptors = descriptors;
+        self
+    }
 }
 
 impl<R> AsMut<Deserializer<R>> for ArrayAccess<'_, R> {
@@ -1327,10 +1511,14 @@ impl<'de, R: Read<'de>> de::SeqAccess<'de> for ArrayAccess<'_, R> {
         match self.count {
             0 => {
                 self.de.elem_format_code = None;
+                self.de.elem_descriptors = ElemDescriptors::default();
                 Ok(None)
             }
             _ => {
                 self.count -= 1;
+                // Nested compound values may have reset the element constructor
+                self.de.elem_format_code = self.elem_format_code.clone();
+                self.de.elem_descriptors = self.elem_descriptors.clone();
                 seed.deserialize(self.as_mut()).map(Some)
             }
         }
@@ -1606,11 +1794,14 @@ impl<'a, 'de, R: Read<'de>> DescribedAccess<'a, R> {
 
     pub(crate) fn consume_list_header(&mut self) -> Result<u32, Error> {
         // consume the list headers if
-        match self
+        let code = self
             .as_mut()
             .get_elem_code_or_read_format_code()
-            .ok_or_else(|| Error::unexpected_eof("Expecting format code"))??
-        {
+            .ok_or_else(|| Error::unexpected_eof("Expecting format code"))??;
+
+        // Make sure the fields are not read with the array element format code
+        self.as_mut().elem_format_code = None;
+        match code {
             EncodingCodes::List0 => Ok(0),
             EncodingCodes::List8 => {
                 let _size = self
@@ -1638,11 +1829,14 @@ impl<'a, 'de, R: Read<'de>> DescribedAccess<'a, R> {
 
     pub(crate) fn consume_map_header(&mut self) -> Result<u32, Error> {
         // consume the list headers if
-        match self
+        let code = self
             .as_mut()
             .get_elem_code_or_read_format_code()
-            .ok_or_else(|| Error::unexpected_eof("Expecting format code"))??
-        {
+            .ok_or_else(|| Error::unexpected_eof("Expecting format code"))??;
+
+        // Make sure the fields are not read with the array element format code
+        self.as_mut().elem_format_code = None;
+        match code {
             EncodingCodes::Map8 => {
                 let _size = self
                     .as_mut()
@@ -1684,8 +1878,8 @@ impl<'de, R: Read<'de>> de::SeqAccess<'de> for DescribedAccess<'_, R> {
         if self.counter >= self.field_count {
             return Ok(None);
         }
-        let byte = match self.de.reader.peek() {
-            Some(b) => b,
+        let byte = match self.de.get_elem_code_or_peek_byte() {
+            Some(b) => b?,
             None => return Ok(None),
         };
         let code = byte.try_into()?;
@@ -1722,8 +1916,8 @@ impl<'de, R: Read<'de>> de::MapAccess<'de> for DescribedAccess<'_, R> {
         if self.counter >= self.field_count {
             return Ok(None);
         }
-        let byte = match self.de.reader.peek() {
-            Some(b) => b,
+        let byte = match self.de.get_elem_code_or_peek_byte() {
+            Some(b) => b?,
             None => return Ok(None),
         };
         let code = byte.try_into()?;
@@ -1773,8 +1967,8 @@ impl<'de, R: Read<'de>> de::MapAccess<'de> for DescribedAccess<'_, R> {
         if self.counter >= self.field_count {
             return Ok(None);
         }
-        let byte = match self.de.reader.peek() {
-            Some(b) => b,
+        let byte = match self.de.get_elem_code_or_peek_byte() {
+            Some(b) => b?,
             None => return Ok(None),
         };
         let code = byte.try_into()?;
@@ -1799,7 +1993,7 @@ mod tests {
 
     use crate::format_code::EncodingCodes;
 
-    use super::{from_reader, from_slice};
+    use super::{from_reader, from_slice, null_as_default};
 
     fn assert_eq_from_reader_vs_expected<T>(buf: &[u8], expected: T)
     where
@@ -1817,6 +2011,31 @@ mod tests {
         assert_eq!(deserialized, expected)
     }
 
+    #[test]
+    fn test_null_as_default() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Foo {
+            #[serde(deserialize_with = "null_as_default")]
+            a: u32,
+            #[serde(deserialize_with = "null_as_default")]
+            b: bool,
+        }
+
+        let null = EncodingCodes::Null as u8;
+        let buf = &[EncodingCodes::List8 as u8, 3, 2, null, null];
+        assert_eq_from_slice_vs_expected(buf, Foo { a: 0, b: false });
+
+        let buf = &[
+            EncodingCodes::List8 as u8,
+            4,
+            2,
+            EncodingCodes::SmallUint as u8,
+            7,
+            EncodingCodes::BooleanTrue as u8,
+        ];
+        assert_eq_from_slice_vs_expected(buf, Foo { a: 7, b: true });
+    }
+
     #[test]
     fn test_deserialize_bool() {
         let buf = &[EncodingCodes::BooleanFalse as u8];
@@ -1924,6 +2143,20 @@ mod tests {
         "The quick brown fox jumps over the lazy dog. 
         "The quick brown fox jumps over the lazy dog."#;
 
+    #[test]
+    fn test_deserialize_char() {
+        let buf = &[EncodingCodes::Char as u8, 0x00, 0x00, 0x00, 0x63];
+        assert_eq_from_reader_vs_expected(buf, 'c');
+
+        let buf = &[EncodingCodes::Char as u8, 0x00, 0x01, 0xF6, 0x00];
+        assert_eq_from_reader_vs_expected(buf, '\u{1F600}');
+
+        // Surrogates are not valid Unicode scalar values
+        let buf = &[EncodingCodes::Char as u8, 0x00, 0x00, 0xD8, 0x00];
+        let result: Result<char, _> = from_slice(buf);
+        assert!(matches!(result, Err(crate::Error::InvalidValue)));
+    }
+
     #[test]
     fn test_deserialize_str() {
         // str8
@@ -2143,6 +2376,91 @@ mod tests {
         assert_eq_from_reader_vs_expected(&buf, expected);
     }
 
+    #[test]
+    fn test_deserialize_array_of_described() {
+        use crate::described::Described;
+        use crate::descriptor::Descriptor;
+        use crate::primitives::{Array, Symbol};
+        use crate::ser::to_vec;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Foo {
+            bar: u32,
+            name: String,
+        }
+
+        let descriptor = Descriptor::Name(Symbol::from("foo"));
+        let expected = Array::from(vec![
+            Described {
+                descriptor: descriptor.clone(),
+                value: Foo {
+                    bar: 1,
+                    name: "a".to_string(),
+                },
+            },
+            Described {
+                descriptor: descriptor.clone(),
+                value: Foo {
+                    bar: 300,
+                    name: "amqp".to_string(),
+                },
+            },
+        ]);
+        let buf = to_vec(&expected).unwrap();
+
+        // The descriptor is only encoded once in the element constructor
+        assert_eq!(buf[0], EncodingCodes::Array8 as u8);
+        assert_eq!(buf[2], 2); // count
+        assert_eq!(
+            &buf[3..9],
+            &[
+                EncodingCodes::DescribedType as u8,
+                EncodingCodes::Sym8 as u8,
+                3,
+                b'f',
+                b'o',
+                b'o'
+            ]
+        );
+        assert_eq!(buf[9], EncodingCodes::List8 as u8);
+
+        assert_eq_from_reader_vs_expected(&buf, expected);
+    }
+
+    #[cfg(feature = "serde_amqp_derive")]
+    #[test]
+    fn test_deserialize_array_of_composite() {
+        use crate as serde_amqp;
+        use crate::macros::{DeserializeComposite, SerializeComposite};
+        use crate::primitives::Array;
+        use crate::ser::to_vec;
+
+        #[derive(Debug, SerializeComposite, DeserializeComposite, PartialEq)]
+        #[amqp_contract(code = "0x00:0x13", encoding = "list")]
+        struct Foo {
+            is_fool: bool,
+            a: i32,
+        }
+
+        let expected = Array::from(vec![
+            Foo {
+                is_fool: true,
+                a: 9,
+            },
+            Foo {
+                is_fool: false,
+                a: 13,
+            },
+            Foo {
+                is_fool: true,
+                a: -1,
+            },
+        ]);
+        let buf = to_vec(&expected).unwrap();
+        assert_eq_from_reader_vs_expected(&buf, expected);
+    }
+
     #[test]
     fn test_deserialize_empty_list() {
         // List0
@@ -2623,4 +2941,96 @@ mod tests {
         let buf = to_vec(&expected).unwrap();
         assert_eq_from_reader_vs_expected(&buf, expected);
     }
+
+    #[test]
+    fn test_serde_128bit_integers() {
+        use crate::{
+            from_value, primitives::Uuid, ser::to_vec, size_ser::serialized_size, to_value,
+            value::Value,
+        };
+
+        let value = 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10_u128;
+        let buf = to_vec(&value).unwrap();
+        let mut expected = vec![EncodingCodes::Vbin8 as u8, 16];
+        expected.extend_from_slice(&value.to_be_bytes());
+        assert_eq!(buf, expected);
+        assert_eq!(serialized_size(&value).unwrap(), buf.len());
+        assert_eq_from_slice_vs_expected(&buf, value);
+        assert_eq_from_reader_vs_expected(&buf, value);
+
+        let value = -2_i128;
+        let buf = to_vec(&value).unwrap();
+        assert_eq_from_slice_vs_expected(&buf, value);
+        assert_eq!(from_slice::<u128>(&buf).unwrap(), u128::MAX - 1);
+
+        // Only a 16 bytes binary is accepted
+        let buf = to_vec(&serde_bytes::ByteBuf::from(vec![1u8; 15])).unwrap();
+        assert!(from_slice::<u128>(&buf).is_err());
+        let buf = to_vec(&1u64).unwrap();
+        assert!(from_slice::<i128>(&buf).is_err());
+
+        let value = u128::MAX / 3;
+        let converted = Value::from(value);
+        assert_eq!(to_value(&value).unwrap(), converted);
+        assert_eq!(from_value::<u128>(converted.clone()).unwrap(), value);
+        assert_eq!(u128::try_from(converted).unwrap(), value);
+        assert_eq!(i128::try_from(Value::from(i128::MIN)).unwrap(), i128::MIN);
+        assert!(u128::try_from(Value::Ulong(1)).is_err());
+
+        let uuid = Uuid::from(value);
+        assert_eq!(uuid.as_inner(), &value.to_be_bytes());
+        assert_eq!(u128::from(uuid), value);
+    }
+
+    #[test]
+    fn test_deserialize_invalid_lengths() {
+        use crate::Value;
+
+        let inputs: &[&[u8]] = &[
+            // vbin32 claiming u32::MAX bytes
+            &[0xb0, 0xff, 0xff, 0xff, 0xff, 0x01],
+            // str32 claiming u32::MAX bytes
+            &[0xb1, 0xff, 0xff, 0xff, 0xff, b'a'],
+            // list8 with a size smaller than the count byte
+            &[0xc0, 0x00, 0x00],
+            // list32 with a size smaller than the count bytes
+            &[0xd0, 0, 0, 0, 0x02, 0, 0, 0, 0],
+            // list32 claiming more elements than its size
+            &[0xd0, 0, 0, 0, 0x05, 0xff, 0xff, 0xff, 0xff, 0x40],
+            // map32 claiming a huge size and count
+            &[0xd1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe],
+            // map8 with an odd count
+            &[0xc1, 0x03, 0x01, 0x40],
+            // array8 with a size smaller than the header
+            &[0xe0, 0x00, 0x01, 0x40],
+            // array32 of zero width elements claiming a huge count
+            &[0xf0, 0, 0, 0, 0x06, 0x1a, 0x22, 0x94, 0x2f, 0x43],
+        ];
+
+        for input in inputs {
+            assert!(from_slice::<Value>(input).is_err());
+            assert!(from_reader::<Value>(*input).is_err());
+        }
+    }
+
+    #[test]
+    fn test_deserialize_arbitrary_bytes_does_not_panic() {
+        use crate::Value;
+
+        // xorshift so that the inputs are the same on every run
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..10_000 {
+            let len = (next() % 32) as usize;
+            let buf: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            let _ = from_slice::<Value>(&buf);
+            let _ = from_reader::<Value>(&buf[..]);
+        }
+    }
 }
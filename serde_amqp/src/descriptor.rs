@@ -27,6 +27,28 @@ pub enum Descriptor {
     Code(u64),
 }
 
+/// A type that is serialized with a known descriptor
+///
+/// This is implemented by `#[derive(SerializeComposite)]` with the `name` and `code` (or
+/// `descriptor`) given in `#[amqp_contract]`, which allows looking up the descriptor of a custom
+/// composite type without re-declaring it.
+pub trait DescribedType {
+    /// The symbolic name of the descriptor
+    const DESCRIPTOR_NAME: &'static str;
+
+    /// The numeric code of the descriptor if one is defined
+    const DESCRIPTOR_CODE: Option<u64>;
+
+    /// The descriptor that is used when the type is serialized. The numeric code is preferred
+    /// over the symbolic name if both are defined
+    fn descriptor() -> Descriptor {
+        match Self::DESCRIPTOR_CODE {
+            Some(code) => Descriptor::Code(code),
+            None => Descriptor::Name(Symbol::from(Self::DESCRIPTOR_NAME)),
+        }
+    }
+}
+
 use std::convert::TryInto;
 
 use serde::de::{self, VariantAccess};
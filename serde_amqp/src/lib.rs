@@ -25,6 +25,11 @@
 //!
 //! All primitive types defined in AMQP1.0 protocol can be found in mod [primitives].
 //!
+//! AMQP1.0 doesn't define a 128-bit integer type. `u128` and `i128` are encoded as a 16 bytes
+//! `binary` holding the big-endian representation of the value (the same byte order as a
+//! [`primitives::Uuid`] created with `Uuid::from(u128)`), and can only be decoded from a 16 bytes
+//! `binary`.
+//!
 //! # Described types
 //!
 //! AMQP1.0 specification allows annotating any AMQP type with a [`descriptor::Descriptor`], thus
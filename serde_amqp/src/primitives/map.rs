@@ -9,6 +9,14 @@ pub use indexmap::map::{Drain, IntoKeys, IntoValues, Iter, IterMut, Keys, Values
 /// [`PartialOrd`], [`Ord`], [`Hash`], [`Serialize`], and [`Deserialize`].
 ///
 /// Only a selected list of methods are re-exported for convenience.
+///
+/// # Serialization order
+///
+/// The entries are always serialized in insertion order, so the same map (or a clone of it)
+/// yields identical bytes every time it is serialized. Updating the value of an existing key with
+/// [`insert`](OrderedMap::insert) keeps the original position of the key. Deserialization keeps
+/// the order found on the wire, so a map that is decoded and then encoded again produces the same
+/// bytes. This is relied upon when the unsettled maps are exchanged during link resumption.
 #[derive(Debug, Clone, Default)]
 pub struct OrderedMap<K, V>(IndexMap<K, V>);
 
@@ -124,6 +132,9 @@ where
 {
     /// Insert a key-value pair in the map.
     ///
+    /// If the key already exists, the value is replaced and the key keeps its position in the
+    /// map. Otherwise the new pair is appended to the end.
+    ///
     /// Calls [`IndexMap::insert`] internally
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
         self.0.insert(key, value)
@@ -361,3 +372,60 @@ where
         Self(index_map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, Rng};
+
+    use crate::{from_slice, to_vec};
+
+    use super::OrderedMap;
+
+    #[test]
+    fn serialization_is_deterministic() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let len = rng.gen_range(0..64);
+            let mut keys: Vec<u32> = (0..len).collect();
+            keys.shuffle(&mut rng);
+
+            let map: OrderedMap<u32, String> = keys
+                .iter()
+                .map(|key| (*key, format!("value-{}", rng.gen::<u64>())))
+                .collect();
+
+            let first = to_vec(&map).unwrap();
+            let second = to_vec(&map).unwrap();
+            let cloned = to_vec(&map.clone()).unwrap();
+            assert_eq!(first, second);
+            assert_eq!(first, cloned);
+
+            // Decoding keeps the order on the wire
+            let decoded: OrderedMap<u32, String> = from_slice(&first).unwrap();
+            assert!(decoded.keys().eq(keys.iter()));
+            assert_eq!(to_vec(&decoded).unwrap(), first);
+        }
+    }
+
+    #[test]
+    fn serialization_follows_insertion_order() {
+        let mut map = OrderedMap::new();
+        map.insert("b", 1u8);
+        map.insert("a", 2u8);
+        map.insert("c", 3u8);
+        // Replacing the value keeps the position of the key
+        map.insert("b", 4u8);
+
+        let mut expected = OrderedMap::new();
+        expected.insert("b", 4u8);
+        expected.insert("a", 2u8);
+        expected.insert("c", 3u8);
+        assert_eq!(to_vec(&map).unwrap(), to_vec(&expected).unwrap());
+
+        let mut reversed = OrderedMap::new();
+        reversed.insert("c", 3u8);
+        reversed.insert("a", 2u8);
+        reversed.insert("b", 4u8);
+        assert_ne!(to_vec(&map).unwrap(), to_vec(&reversed).unwrap());
+    }
+}
@@ -137,6 +137,12 @@ pub type Double = f64;
 /// encoding name = "utf32", encoding code = 0x73
 /// category = fixed, width = 4,
 /// label = "a UTF-32BE encoded Unicode character"
+///
+/// A Rust `char` is a Unicode scalar value, which is exactly the set of code points that can be
+/// encoded as UTF-32. It is therefore always serialized with the format code `0x73` followed by
+/// the 4 bytes of the code point in network byte order, including code points outside of the
+/// Basic Multilingual Plane (eg. emojis). Deserializing a code point that is not a valid `char`
+/// (eg. a surrogate) returns [`Error::InvalidValue`](crate::Error::InvalidValue).
 pub type Char = char;
 
 /// A sequence of octets.
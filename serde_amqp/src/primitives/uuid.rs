@@ -73,6 +73,20 @@ impl From<Uuid> for [u8; UUID_WIDTH] {
     }
 }
 
+/// The bytes of the `Uuid` are the big-endian representation of the `u128`
+impl From<u128> for Uuid {
+    fn from(val: u128) -> Self {
+        Self(val.to_be_bytes())
+    }
+}
+
+/// The bytes of the `Uuid` are the big-endian representation of the `u128`
+impl From<Uuid> for u128 {
+    fn from(val: Uuid) -> Self {
+        u128::from_be_bytes(val.0)
+    }
+}
+
 impl TryFrom<&[u8]> for Uuid {
     type Error = Error;
 
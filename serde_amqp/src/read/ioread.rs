@@ -33,12 +33,15 @@ impl<R: io::Read> IoReader<R> {
     pub fn fill_buffer(&mut self, len: usize) -> Result<(), io::Error> {
         let l = self.buf.len();
         if l < len {
-            self.buf.resize(len, 0);
-            self.reader.read_exact(&mut self.buf[l..])?;
-            Ok(())
-        } else {
-            Ok(())
+            // Only grow the buffer with the bytes that are actually read so that a length decoded
+            // from the input cannot cause a huge allocation before the bytes are available
+            let mut reader = io::Read::take(&mut self.reader, (len - l) as u64);
+            let n = io::Read::read_to_end(&mut reader, &mut self.buf)?;
+            if l + n < len {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, ""));
+            }
         }
+        Ok(())
     }
 }
 
@@ -82,6 +85,11 @@ impl<'de, R: io::Read + 'de> Read<'de> for IoReader<R> {
         }
     }
 
+    fn read_bytes(&mut self, n: usize) -> Result<Vec<u8>, io::Error> {
+        self.fill_buffer(n)?;
+        Ok(self.buf.drain(..n).collect())
+    }
+
     fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
         let n = buf.len();
         let l = self.buf.len();
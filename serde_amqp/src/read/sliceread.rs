@@ -49,6 +49,11 @@ impl<'s> Read<'s> for SliceReader<'s> {
         }
     }
 
+    fn read_bytes(&mut self, n: usize) -> Result<Vec<u8>, io::Error> {
+        // Check the remaining length before allocating
+        self.get_byte_slice(n).map(<[u8]>::to_vec)
+    }
+
     fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
         std::io::Read::read_exact(&mut self.slice, buf)
     }
@@ -77,6 +82,68 @@ impl<'s> Read<'s> for SliceReader<'s> {
     }
 }
 
+/// A reader for a slice of bytes that doesn't live as long as the deserialized value
+///
+/// Bytes and strings are copied when they are forwarded to the visitor. This is used to read the
+/// descriptor of an array of described types, which is cached by the deserializer and read once
+/// for every element.
+#[derive(Debug)]
+pub(crate) struct TransientSliceReader<'s> {
+    inner: SliceReader<'s>,
+}
+
+impl<'s> TransientSliceReader<'s> {
+    /// Creates a new transient slice reader
+    pub(crate) fn new(slice: &'s [u8]) -> Self {
+        Self {
+            inner: SliceReader::new(slice),
+        }
+    }
+}
+
+impl private::Sealed for TransientSliceReader<'_> {}
+
+impl<'de> Read<'de> for TransientSliceReader<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        self.inner.peek()
+    }
+
+    fn peek_bytes(&mut self, n: usize) -> Result<Option<&[u8]>, io::Error> {
+        self.inner.peek_bytes(n)
+    }
+
+    fn next(&mut self) -> Result<Option<u8>, io::Error> {
+        self.inner.next()
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+        self.inner.read_exact(buf)
+    }
+
+    fn forward_read_bytes_with_hint<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_bytes(self.inner.get_byte_slice(len)?)
+    }
+
+    fn forward_read_byte_buf<V>(&mut self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let buf = read_primitive_bytes_or_else(self, read_described_bytes)?;
+        visitor.visit_byte_buf(buf)
+    }
+
+    fn forward_read_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let str_slice = std::str::from_utf8(self.inner.get_byte_slice(len)?)?;
+        visitor.visit_str(str_slice)
+    }
+}
+
 #[allow(clippy::all)]
 #[cfg(test)]
 mod tests {
@@ -17,14 +17,23 @@ use crate::{
     format::{OFFSET_LIST32, OFFSET_LIST8, OFFSET_MAP32, OFFSET_MAP8},
     format_code::EncodingCodes,
     serialized_size,
-    util::{FieldRole, IsArrayElement, NonNativeType, SequenceType, StructEncoding},
+    util::{
+        FieldRole, IsArrayElement, NonNativeType, SequenceType, StructEncoding, StructEncodingStack,
+    },
 };
 
 pub(crate) const U8_MAX: usize = u8::MAX as usize;
 
+/// Estimated average encoded size of a struct field, which is used to reserve the buffer of a
+/// struct
+const ESTIMATED_FIELD_SIZE: usize = 8;
+
 // Variable type will spend a byte on size
 pub(crate) const U8_MAX_MINUS_1: usize = u8::MAX as usize - 1;
 
+// Smallest length that doesn't fit in a one byte width
+pub(crate) const U8_MAX_PLUS_1: usize = u8::MAX as usize + 1;
+
 // Variable type will spend 4 bytes on size
 pub(crate) const U32_MAX_MINUS_4: usize = u32::MAX as usize - 4;
 
@@ -43,6 +52,27 @@ where
 // created, the internal buffer (or all the nested serializers) will be created in the arena
 
 /// A struct for serializing Rust structs/values into AMQP1.0 wire format
+///
+/// # Encoding
+///
+/// Outside of arrays, the serializer always chooses the smallest encoding that can represent a
+/// value, ie.
+///
+/// | Type | Encodings (in order of preference) |
+/// |------|-----------|
+/// | `bool` | `true`/`false` (zero width) |
+/// | `u32` | `uint0`, `smalluint` (1..=255), `uint` |
+/// | `u64` | `ulong0`, `smallulong` (1..=255), `ulong` |
+/// | `i32` | `smallint` (-128..=127), `int` |
+/// | `i64` | `smalllong` (-128..=127), `long` |
+/// | `String`/`Symbol`/`Binary` | `str8`/`sym8`/`vbin8` (up to 255 bytes), `str32`/`sym32`/`vbin32` |
+/// | list | `list0` (empty), `list8`, `list32` |
+/// | map | `map8`, `map32` |
+/// | array | `array8`, `array32` |
+///
+/// Because all elements of an array share the same constructor, array elements are always
+/// encoded with the fixed width format codes (eg. `uint` instead of `smalluint`). The constructor
+/// of lists and maps in an array is chosen by the size of the first element.
 #[derive(Debug)]
 pub struct Serializer<W> {
     /// The output of serialized data
@@ -55,7 +85,7 @@ pub struct Serializer<W> {
     seq_type: Option<SequenceType>,
 
     /// How a struct should be encoded
-    struct_encoding: Vec<StructEncoding>,
+    struct_encoding: StructEncodingStack,
 
     /// Whether we are serializing an array
     /// NOTE: This should only be changed by `SeqSerializer`
@@ -75,7 +105,7 @@ impl<W: Write> Serializer<W> {
             writer,
             non_native_type: None,
             seq_type: None,
-            struct_encoding: Vec::new(),
+            struct_encoding: StructEncodingStack::default(),
             is_array_elem: IsArrayElement::False,
         }
     }
@@ -102,7 +132,7 @@ impl<W: Write> Serializer<W> {
             writer,
             non_native_type: None,
             seq_type: None,
-            struct_encoding: vec![StructEncoding::DescribedList],
+            struct_encoding: StructEncodingStack::new(StructEncoding::DescribedList),
             is_array_elem: IsArrayElement::False,
         }
     }
@@ -113,7 +143,7 @@ impl<W: Write> Serializer<W> {
             writer,
             non_native_type: None,
             seq_type: None,
-            struct_encoding: vec![StructEncoding::DescribedMap],
+            struct_encoding: StructEncodingStack::new(StructEncoding::DescribedMap),
             is_array_elem: IsArrayElement::False,
         }
     }
@@ -124,7 +154,7 @@ impl<W: Write> Serializer<W> {
             writer,
             non_native_type: None,
             seq_type: None,
-            struct_encoding: vec![StructEncoding::DescribedBasic],
+            struct_encoding: StructEncodingStack::new(StructEncoding::DescribedBasic),
             is_array_elem: IsArrayElement::False,
         }
     }
@@ -373,6 +403,20 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
         Ok(())
     }
 
+    /// AMQP 1.0 doesn't have a 128-bit integer type, so `i128` is encoded as a 16 bytes `binary`
+    /// holding the big-endian two's complement representation of the value
+    #[inline]
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bytes(&v.to_be_bytes())
+    }
+
+    /// AMQP 1.0 doesn't have a 128-bit integer type, so `u128` is encoded as a 16 bytes `binary`
+    /// holding the big-endian representation of the value
+    #[inline]
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bytes(&v.to_be_bytes())
+    }
+
     #[inline]
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         if let IsArrayElement::False | IsArrayElement::FirstElement = self.is_array_elem {
@@ -422,11 +466,11 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
 
                         match l {
                             // sym8
-                            0..=U8_MAX_MINUS_1 => {
+                            0..=U8_MAX => {
                                 let code = [EncodingCodes::Sym8 as u8, l as u8];
                                 self.writer.write_all(&code)?;
                             }
-                            U8_MAX..=U32_MAX_MINUS_4 => {
+                            U8_MAX_PLUS_1..=U32_MAX_MINUS_4 => {
                                 let code = [EncodingCodes::Sym32 as u8];
                                 let width = (l as u32).to_be_bytes();
                                 self.writer.write_all(&code)?;
@@ -442,14 +486,14 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
                         let l = v.len();
                         match l {
                             // str8-utf8
-                            0..=U8_MAX_MINUS_1 => {
+                            0..=U8_MAX => {
                                 let code = [EncodingCodes::Str8 as u8, l as u8];
                                 // let width: [u8; 1] = (l as u8).to_be_bytes();
                                 self.writer.write_all(&code)?;
                                 // self.writer.write_all(&width)?;
                             }
                             // str32-utf8
-                            U8_MAX..=U32_MAX_MINUS_4 => {
+                            U8_MAX_PLUS_1..=U32_MAX_MINUS_4 => {
                                 let code = [EncodingCodes::Str32 as u8];
                                 let width: [u8; 4] = (l as u32).to_be_bytes();
                                 self.writer.write_all(&code)?;
@@ -523,14 +567,14 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
                     IsArrayElement::False => {
                         match l {
                             // vbin8
-                            0..=U8_MAX_MINUS_1 => {
+                            0..=U8_MAX => {
                                 let code = [EncodingCodes::Vbin8 as u8];
                                 let width: [u8; 1] = (l as u8).to_be_bytes();
                                 self.writer.write_all(&code)?;
                                 self.writer.write_all(&width)?;
                             }
                             // vbin32
-                            U8_MAX..=U32_MAX_MINUS_4 => {
+                            U8_MAX_PLUS_1..=U32_MAX_MINUS_4 => {
                                 let code = [EncodingCodes::Vbin32 as u8];
                                 let width: [u8; 4] = (l as u32).to_be_bytes();
                                 self.writer.write_all(&code)?;
@@ -685,9 +729,23 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
         if name == DESCRIPTOR
         // || name == VALUE || name == AMQP_ERROR || name == CONNECTION_ERROR || name == SESSION_ERROR || name == LINK_ERROR
         {
-            let code = [EncodingCodes::DescribedType as u8];
-            self.writer.write_all(&code)?;
-            value.serialize(self)
+            match self.is_array_elem {
+                IsArrayElement::False => {
+                    let code = [EncodingCodes::DescribedType as u8];
+                    self.writer.write_all(&code)?;
+                    value.serialize(self)
+                }
+                // The descriptor is part of the array element constructor and is encoded
+                // like a regular value
+                IsArrayElement::FirstElement => {
+                    let code = [EncodingCodes::DescribedType as u8];
+                    self.writer.write_all(&code)?;
+                    let mut serializer = Serializer::new(&mut self.writer);
+                    value.serialize(&mut serializer)
+                }
+                // The descriptor is only written once in the array element constructor
+                IsArrayElement::OtherElement => Ok(()),
+            }
         } else {
             let mut state = self.serialize_map(Some(1))?;
             state.serialize_entry(&variant_index, value)?;
@@ -758,20 +816,21 @@ impl<'a, W: Write + 'a> ser::Serializer for &'a mut Serializer<W> {
     fn serialize_struct(
         self,
         name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
         // The name should override the parent struct encoding
         if name == DESCRIBED_LIST {
             self.struct_encoding.push(StructEncoding::DescribedList);
-            Ok(StructSerializer::new(self))
+            Ok(StructSerializer::with_capacity(self, len))
         } else if name == DESCRIBED_MAP {
             self.struct_encoding.push(StructEncoding::DescribedMap);
-            Ok(StructSerializer::new(self))
+            Ok(StructSerializer::with_capacity(self, len))
         } else if name == DESCRIBED_BASIC {
+            // The value is written directly into the writer
             self.struct_encoding.push(StructEncoding::DescribedBasic);
             Ok(StructSerializer::new(self))
         } else {
-            Ok(StructSerializer::new(self))
+            Ok(StructSerializer::with_capacity(self, len))
         }
     }
 
@@ -1352,7 +1411,6 @@ impl<'a, W: Write + 'a> ser::SerializeTupleStruct for TupleStructSerializer<'a,
                     StructEncoding::None => {
                         // serialize regualr tuple struct as a list like in tuple
                         let mut serializer = Serializer::new(&mut self.buf);
-                        serializer.is_array_elem = self.se.is_array_elem.clone();
                         value.serialize(&mut serializer)
                     }
                     StructEncoding::DescribedBasic => {
@@ -1380,7 +1438,7 @@ impl<'a, W: Write + 'a> ser::SerializeTupleStruct for TupleStructSerializer<'a,
                     &mut self.se.writer,
                     self.count,
                     &self.buf,
-                    &IsArrayElement::False,
+                    &self.se.is_array_elem,
                 )
             }
             StructEncoding::DescribedBasic => {
@@ -1395,7 +1453,7 @@ impl<'a, W: Write + 'a> ser::SerializeTupleStruct for TupleStructSerializer<'a,
                     &mut self.se.writer,
                     self.count,
                     &self.buf,
-                    &IsArrayElement::False,
+                    &self.se.is_array_elem,
                 )
             }
             StructEncoding::DescribedMap => {
@@ -1422,6 +1480,16 @@ impl<'a, W: 'a> StructSerializer<'a, W> {
             buf: vec![],
         }
     }
+
+    /// Reserves the buffer assuming an average encoded size of [`ESTIMATED_FIELD_SIZE`] per
+    /// field so that encoding small fields does not grow the buffer repeatedly
+    fn with_capacity(se: &'a mut Serializer<W>, len: usize) -> Self {
+        Self {
+            se,
+            count: 0,
+            buf: Vec::with_capacity(len * ESTIMATED_FIELD_SIZE),
+        }
+    }
 }
 
 impl<'a, W: 'a> AsMut<Serializer<W>> for StructSerializer<'a, W> {
@@ -1448,10 +1516,16 @@ impl<'a, W: Write + 'a> ser::SerializeStruct for StructSerializer<'a, W> {
                 StructEncoding::None => {
                     // normal struct will be serialized as a list
                     let mut serializer = Serializer::new(&mut self.buf);
-                    serializer.is_array_elem = self.se.is_array_elem.clone();
                     value.serialize(&mut serializer)
                 }
-                StructEncoding::DescribedBasic => value.serialize(self.as_mut()),
+                StructEncoding::DescribedBasic => {
+                    // The value is written right after the descriptor, but it must not inherit
+                    // the struct encoding of the wrapper
+                    self.se.struct_encoding.push(StructEncoding::None);
+                    let result = value.serialize(self.as_mut());
+                    self.se.struct_encoding.pop();
+                    result
+                }
                 StructEncoding::DescribedList => {
                     let mut serializer = Serializer::described_list(&mut self.buf);
                     value.serialize(&mut serializer)
@@ -1773,6 +1847,11 @@ mod test {
         let mut expected = vec![EncodingCodes::Char as u8];
         expected.append(&mut (val as u32).to_be_bytes().to_vec());
         assert_eq_on_serialized_vs_expected(val, &expected);
+
+        // Code points outside of the Basic Multilingual Plane
+        let val = '\u{1F600}';
+        let expected = vec![EncodingCodes::Char as u8, 0x00, 0x01, 0xF6, 0x00];
+        assert_eq_on_serialized_vs_expected(val, &expected);
     }
 
     #[test]
@@ -1810,8 +1889,15 @@ mod test {
         expected.append(&mut val.as_bytes().to_vec());
         assert_eq_on_serialized_vs_expected(val, &expected);
 
-        // u8 max length
+        // u8 max length still fits in str8
         let val = String::from_utf8(vec![b'X'; u8::MAX as usize]).unwrap();
+        let len = val.len() as u8;
+        let mut expected = vec![EncodingCodes::Str8 as u8, len];
+        expected.append(&mut val.as_bytes().to_vec());
+        assert_eq_on_serialized_vs_expected(val, &expected);
+
+        // u8 max + 1 length
+        let val = String::from_utf8(vec![b'X'; u8::MAX as usize + 1]).unwrap();
         let len = val.len() as u32;
         let mut expected = vec![EncodingCodes::Str32 as u8];
         expected.append(&mut len.to_be_bytes().to_vec());
@@ -1826,6 +1912,7 @@ mod test {
         const SMALL_BYTES_VALUE: &[u8] = &[133u8; 200];
         const U8_MAX_MINUS_ONE_BYTES_VALUE: &[u8] = &[255u8; 254];
         const U8_MAX_BYTES_VALUE: &[u8] = &[255u8; 255];
+        const U8_MAX_PLUS_ONE_BYTES_VALUE: &[u8] = &[255u8; 256];
         const LARGE_BYTES_VALUE: &[u8] = &[199u8; 1000];
 
         // vbin8
@@ -1850,8 +1937,15 @@ mod test {
         expected.append(&mut val.to_vec());
         assert_eq_on_serialized_vs_expected(val, &expected);
 
-        // u8 max + 1 length
+        // u8 max length still fits in vbin8
         let val = ByteBuf::from(U8_MAX_BYTES_VALUE);
+        let len = val.len() as u8;
+        let mut expected = vec![EncodingCodes::Vbin8 as u8, len];
+        expected.append(&mut val.to_vec());
+        assert_eq_on_serialized_vs_expected(val, &expected);
+
+        // u8 max + 1 length
+        let val = ByteBuf::from(U8_MAX_PLUS_ONE_BYTES_VALUE);
         let len = val.len() as u32;
         let mut expected = vec![EncodingCodes::Vbin32 as u8];
         expected.append(&mut len.to_be_bytes().to_vec());
@@ -2073,6 +2167,84 @@ mod test {
         assert_eq_on_serialized_vs_expected(val, &expected);
     }
 
+    #[test]
+    fn test_smallest_encoding_format_codes() {
+        use std::collections::BTreeMap;
+
+        fn format_code<T: Serialize>(val: T) -> u8 {
+            to_vec(&val).unwrap()[0]
+        }
+
+        // bool
+        assert_eq!(format_code(true), EncodingCodes::BooleanTrue as u8);
+        assert_eq!(format_code(false), EncodingCodes::BooleanFalse as u8);
+
+        // uint
+        assert_eq!(format_code(0u32), EncodingCodes::Uint0 as u8);
+        assert_eq!(format_code(1u32), EncodingCodes::SmallUint as u8);
+        assert_eq!(format_code(255u32), EncodingCodes::SmallUint as u8);
+        assert_eq!(format_code(256u32), EncodingCodes::Uint as u8);
+        assert_eq!(format_code(u32::MAX), EncodingCodes::Uint as u8);
+
+        // ulong
+        assert_eq!(format_code(0u64), EncodingCodes::Ulong0 as u8);
+        assert_eq!(format_code(1u64), EncodingCodes::SmallUlong as u8);
+        assert_eq!(format_code(255u64), EncodingCodes::SmallUlong as u8);
+        assert_eq!(format_code(256u64), EncodingCodes::Ulong as u8);
+        assert_eq!(format_code(u64::MAX), EncodingCodes::Ulong as u8);
+
+        // int
+        assert_eq!(format_code(0i32), EncodingCodes::SmallInt as u8);
+        assert_eq!(format_code(-128i32), EncodingCodes::SmallInt as u8);
+        assert_eq!(format_code(127i32), EncodingCodes::SmallInt as u8);
+        assert_eq!(format_code(-129i32), EncodingCodes::Int as u8);
+        assert_eq!(format_code(128i32), EncodingCodes::Int as u8);
+
+        // long
+        assert_eq!(format_code(0i64), EncodingCodes::SmallLong as u8);
+        assert_eq!(format_code(-128i64), EncodingCodes::SmallLong as u8);
+        assert_eq!(format_code(127i64), EncodingCodes::SmallLong as u8);
+        assert_eq!(format_code(-129i64), EncodingCodes::Long as u8);
+        assert_eq!(format_code(128i64), EncodingCodes::Long as u8);
+
+        // string
+        assert_eq!(format_code(""), EncodingCodes::Str8 as u8);
+        assert_eq!(format_code("X".repeat(255)), EncodingCodes::Str8 as u8);
+        assert_eq!(format_code("X".repeat(256)), EncodingCodes::Str32 as u8);
+
+        // symbol
+        assert_eq!(format_code(Symbol::from("")), EncodingCodes::Sym8 as u8);
+        assert_eq!(
+            format_code(Symbol::from("X".repeat(255))),
+            EncodingCodes::Sym8 as u8
+        );
+        assert_eq!(
+            format_code(Symbol::from("X".repeat(256))),
+            EncodingCodes::Sym32 as u8
+        );
+
+        // binary
+        assert_eq!(
+            format_code(serde_bytes::ByteBuf::from(vec![0u8; 255])),
+            EncodingCodes::Vbin8 as u8
+        );
+        assert_eq!(
+            format_code(serde_bytes::ByteBuf::from(vec![0u8; 256])),
+            EncodingCodes::Vbin32 as u8
+        );
+
+        // list, each `true` takes one byte
+        assert_eq!(format_code(Vec::<bool>::new()), EncodingCodes::List0 as u8);
+        assert_eq!(format_code(vec![true; 254]), EncodingCodes::List8 as u8);
+        assert_eq!(format_code(vec![true; 255]), EncodingCodes::List32 as u8);
+
+        // map, each `(u8, bool)` entry takes three bytes
+        let map: BTreeMap<u8, bool> = (0..84).map(|i| (i, true)).collect();
+        assert_eq!(format_code(map), EncodingCodes::Map8 as u8);
+        let map: BTreeMap<u8, bool> = (0..85).map(|i| (i, true)).collect();
+        assert_eq!(format_code(map), EncodingCodes::Map32 as u8);
+    }
+
     #[test]
     fn test_serialize_symbol() {
         use crate::primitives::Symbol;
@@ -8,8 +8,10 @@ use crate::{
         ARRAY, DECIMAL128, DECIMAL32, DECIMAL64, DESCRIBED_BASIC, DESCRIBED_LIST, DESCRIBED_MAP,
         DESCRIPTOR, LAZY_VALUE, SYMBOL, SYMBOL_REF, TIMESTAMP, TRANSPARENT_VEC, UUID,
     },
-    ser::{U32_MAX_MINUS_4, U8_MAX, U8_MAX_MINUS_1},
-    util::{FieldRole, IsArrayElement, NonNativeType, SequenceType, StructEncoding},
+    ser::{U32_MAX_MINUS_4, U8_MAX, U8_MAX_MINUS_1, U8_MAX_PLUS_1},
+    util::{
+        FieldRole, IsArrayElement, NonNativeType, SequenceType, StructEncoding, StructEncodingStack,
+    },
 };
 
 /// Obtain the serialized size without allocating `Vec<u8>`
@@ -25,7 +27,7 @@ where
 #[derive(Debug)]
 pub struct SizeSerializer {
     /// How a struct should be encoded
-    pub(crate) struct_encoding: Vec<StructEncoding>,
+    pub(crate) struct_encoding: StructEncodingStack,
     pub(crate) non_native_type: Option<NonNativeType>,
     pub(crate) seq_type: Option<SequenceType>,
     pub(crate) is_array_element: IsArrayElement,
@@ -41,7 +43,7 @@ impl SizeSerializer {
     /// Create a new `SizeSerializer`
     pub fn new() -> Self {
         Self {
-            struct_encoding: Vec::new(),
+            struct_encoding: StructEncodingStack::default(),
             non_native_type: None,
             seq_type: None,
             is_array_element: IsArrayElement::False,
@@ -50,7 +52,7 @@ impl SizeSerializer {
 
     fn described_list() -> Self {
         Self {
-            struct_encoding: vec![StructEncoding::DescribedList],
+            struct_encoding: StructEncodingStack::new(StructEncoding::DescribedList),
             non_native_type: None,
             seq_type: None,
             is_array_element: IsArrayElement::False,
@@ -59,7 +61,7 @@ impl SizeSerializer {
 
     fn described_map() -> Self {
         Self {
-            struct_encoding: vec![StructEncoding::DescribedMap],
+            struct_encoding: StructEncodingStack::new(StructEncoding::DescribedMap),
             non_native_type: None,
             seq_type: None,
             is_array_element: IsArrayElement::False,
@@ -182,6 +184,20 @@ impl<'a> ser::Serializer for &'a mut SizeSerializer {
         }
     }
 
+    /// AMQP 1.0 doesn't have a 128-bit integer type, so `i128` is encoded as a 16 bytes `binary`
+    /// holding the big-endian two's complement representation of the value
+    #[inline]
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bytes(&v.to_be_bytes())
+    }
+
+    /// AMQP 1.0 doesn't have a 128-bit integer type, so `u128` is encoded as a 16 bytes `binary`
+    /// holding the big-endian representation of the value
+    #[inline]
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bytes(&v.to_be_bytes())
+    }
+
     fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
         match self.is_array_element {
             IsArrayElement::False => Ok(5),
@@ -210,19 +226,19 @@ impl<'a> ser::Serializer for &'a mut SizeSerializer {
         match self.is_array_element {
             IsArrayElement::False => match self.non_native_type {
                 Some(NonNativeType::Symbol) | Some(NonNativeType::SymbolRef) => match v.len() {
-                    0..=U8_MAX_MINUS_1 => {
+                    0..=U8_MAX => {
                         self.non_native_type = None;
                         Ok(2 + v.len())
                     }
-                    U8_MAX..=U32_MAX_MINUS_4 => {
+                    U8_MAX_PLUS_1..=U32_MAX_MINUS_4 => {
                         self.non_native_type = None;
                         Ok(5 + v.len())
                     }
                     _ => Err(Error::too_long()),
                 },
                 None => match v.len() {
-                    0..=U8_MAX_MINUS_1 => Ok(2 + v.len()),
-                    U8_MAX..=U32_MAX_MINUS_4 => Ok(5 + v.len()),
+                    0..=U8_MAX => Ok(2 + v.len()),
+                    U8_MAX_PLUS_1..=U32_MAX_MINUS_4 => Ok(5 + v.len()),
                     _ => Err(Error::too_long()),
                 },
                 _ => unreachable!("serialize_str is only used for Symbol and String"),
@@ -247,8 +263,8 @@ impl<'a> ser::Serializer for &'a mut SizeSerializer {
         match self.non_native_type {
             None => match self.is_array_element {
                 IsArrayElement::False => match l {
-                    0..=U8_MAX_MINUS_1 => Ok(2 + l),
-                    U8_MAX..=U32_MAX_MINUS_4 => Ok(5 + l),
+                    0..=U8_MAX => Ok(2 + l),
+                    U8_MAX_PLUS_1..=U32_MAX_MINUS_4 => Ok(5 + l),
                     _ => Err(Error::too_long()),
                 },
                 IsArrayElement::FirstElement => Ok(5 + l),
@@ -352,7 +368,14 @@ impl<'a> ser::Serializer for &'a mut SizeSerializer {
         T: serde::Serialize + ?Sized,
     {
         if name == DESCRIPTOR {
-            value.serialize(self).map(|len| len + 1)
+            match self.is_array_element {
+                IsArrayElement::False => value.serialize(self).map(|len| len + 1),
+                IsArrayElement::FirstElement => {
+                    let mut serializer = SizeSerializer::new();
+                    value.serialize(&mut serializer).map(|len| len + 1)
+                }
+                IsArrayElement::OtherElement => Ok(0),
+            }
         } else {
             let mut state = self.serialize_map(Some(1))?;
             state.serialize_entry(&variant_index, value)?;
@@ -685,18 +708,17 @@ impl ser::SerializeTupleStruct for TupleStructSerializer<'_> {
             FieldRole::Fields => match self.se.struct_encoding() {
                 StructEncoding::None => {
                     let mut serializer = SizeSerializer::new();
-                    serializer.is_array_element = self.se.is_array_element.clone();
                     self.cumulated_size += value.serialize(&mut serializer)?;
                     Ok(())
                 }
                 StructEncoding::DescribedList => {
                     let mut serializer = SizeSerializer::new();
-                    serializer.is_array_element = self.se.is_array_element.clone();
                     self.cumulated_size += value.serialize(&mut serializer)?;
                     Ok(())
                 }
                 StructEncoding::DescribedBasic => {
                     let mut serializer = SizeSerializer::new();
+                    serializer.is_array_element = self.se.is_array_element.clone();
                     self.cumulated_size += value.serialize(&mut serializer)?;
                     Ok(())
                 }
@@ -760,7 +782,6 @@ impl ser::SerializeStruct for StructSerializer<'_> {
             match self.se.struct_encoding() {
                 StructEncoding::None => {
                     let mut serializer = SizeSerializer::new();
-                    serializer.is_array_element = self.se.is_array_element.clone();
                     self.cumulated_size += value.serialize(&mut serializer)?;
                     Ok(())
                 }
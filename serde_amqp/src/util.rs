@@ -63,6 +63,40 @@ impl Default for StructEncoding {
     }
 }
 
+/// A stack of [`StructEncoding`]s whose bottom element is stored inline
+///
+/// A new serializer is created for every field of a described list, so keeping the first element
+/// inline avoids a heap allocation per field
+#[derive(Debug, Default)]
+pub(crate) struct StructEncodingStack {
+    bottom: Option<StructEncoding>,
+    rest: Vec<StructEncoding>,
+}
+
+impl StructEncodingStack {
+    pub(crate) fn new(encoding: StructEncoding) -> Self {
+        Self {
+            bottom: Some(encoding),
+            rest: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, encoding: StructEncoding) {
+        match self.bottom {
+            Some(_) => self.rest.push(encoding),
+            None => self.bottom = Some(encoding),
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<StructEncoding> {
+        self.rest.pop().or_else(|| self.bottom.take())
+    }
+
+    pub(crate) fn last(&self) -> Option<&StructEncoding> {
+        self.rest.last().or(self.bottom.as_ref())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) enum PeekTypeCode {
     Primitive(ValueType),
@@ -706,6 +706,36 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         }
     }
 
+    /// `i128` is expected to be a 16 bytes `Value::Binary` in big-endian
+    #[inline]
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::Binary(v) => {
+                let bytes = <[u8; 16]>::try_from(v.as_slice()).map_err(|_| Error::InvalidLength)?;
+                visitor.visit_i128(i128::from_be_bytes(bytes))
+            }
+            _ => Err(Error::InvalidValue),
+        }
+    }
+
+    /// `u128` is expected to be a 16 bytes `Value::Binary` in big-endian
+    #[inline]
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Value::Binary(v) => {
+                let bytes = <[u8; 16]>::try_from(v.as_slice()).map_err(|_| Error::InvalidLength)?;
+                visitor.visit_u128(u128::from_be_bytes(bytes))
+            }
+            _ => Err(Error::InvalidValue),
+        }
+    }
+
     #[inline]
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
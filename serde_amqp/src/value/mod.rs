@@ -309,13 +309,201 @@ impl Value {
         code as u8
     }
 
+    /// Returns the exact number of bytes that the value takes on the wire
+    ///
+    /// This is a shorthand for [`serialized_size`](crate::serialized_size)
+    pub fn encoded_len(&self) -> Result<usize, Error> {
+        crate::serialized_size(self)
+    }
+
     /// Try to convert a serializable value to a Value
     pub fn try_from_serializable<T: Serialize>(value: T) -> Result<Self, Error> {
         use crate::value::ser::Serializer;
         value.serialize(&mut Serializer::new())
     }
+
+    /// Returns the value if it is a [`Value::Bool`]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `i64` if it is an integer that fits in an `i64`
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Value::Byte(n) => Some(n as i64),
+            Value::Short(n) => Some(n as i64),
+            Value::Int(n) => Some(n as i64),
+            Value::Long(n) => Some(n),
+            Value::Ubyte(n) => Some(n as i64),
+            Value::Ushort(n) => Some(n as i64),
+            Value::Uint(n) => Some(n as i64),
+            Value::Ulong(n) => i64::try_from(n).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `u64` if it is an integer that fits in a `u64`
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Value::Ubyte(n) => Some(n as u64),
+            Value::Ushort(n) => Some(n as u64),
+            Value::Uint(n) => Some(n as u64),
+            Value::Ulong(n) => Some(n),
+            Value::Byte(n) => u64::try_from(n).ok(),
+            Value::Short(n) => u64::try_from(n).ok(),
+            Value::Int(n) => u64::try_from(n).ok(),
+            Value::Long(n) => u64::try_from(n).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the string if the value is a [`Value::String`]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbol if the value is a [`Value::Symbol`]
+    pub fn as_symbol(&self) -> Option<&Symbol> {
+        match self {
+            Value::Symbol(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes if the value is a [`Value::Binary`]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Binary(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns the timestamp if the value is a [`Value::Timestamp`]
+    pub fn as_timestamp(&self) -> Option<&Timestamp> {
+        match self {
+            Value::Timestamp(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// Returns the uuid if the value is a [`Value::Uuid`]
+    pub fn as_uuid(&self) -> Option<&Uuid> {
+        match self {
+            Value::Uuid(u) => Some(u),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements if the value is a [`Value::List`]
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements if the value is a [`Value::Array`]
+    pub fn as_array(&self) -> Option<&Array<Value>> {
+        match self {
+            Value::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the map if the value is a [`Value::Map`]
+    pub fn as_map(&self) -> Option<&OrderedMap<Value, Value>> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Converts a [`Value::Map`] whose keys are all [`Value::Symbol`] or [`Value::String`] into an
+    /// `OrderedMap<Symbol, Value>`
+    ///
+    /// This is the counterpart of `Value::from(OrderedMap<Symbol, Value>)`, which is commonly used
+    /// to build the body of management requests. The values are left untouched.
+    ///
+    /// Returns the value itself if it is not a map, or the first key that is neither a symbol
+    /// nor a string.
+    pub fn try_into_symbol_map(self) -> Result<OrderedMap<Symbol, Value>, Value> {
+        match self {
+            Value::Map(map) => map
+                .into_iter()
+                .map(|(key, value)| match key {
+                    Value::Symbol(key) => Ok((key, value)),
+                    Value::String(key) => Ok((Symbol::from(key), value)),
+                    _ => Err(key),
+                })
+                .collect(),
+            _ => Err(self),
+        }
+    }
+
+    /// Recursively converts the [`Value::String`] keys of all maps, including the maps nested in
+    /// lists and arrays, into [`Value::Symbol`]
+    ///
+    /// Peers may encode the keys of a map as either strings or symbols. Normalizing the keys
+    /// allows comparing a received map with one that is built from `OrderedMap<Symbol, Value>`.
+    pub fn into_symbol_keys(self) -> Value {
+        match self {
+            Value::Map(map) => Value::Map(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let key = match key {
+                            Value::String(key) => Value::Symbol(Symbol::from(key)),
+                            key => key,
+                        };
+                        (key, value.into_symbol_keys())
+                    })
+                    .collect(),
+            ),
+            Value::List(list) => {
+                Value::List(list.into_iter().map(Value::into_symbol_keys).collect())
+            }
+            Value::Array(array) => {
+                Value::Array(array.into_iter().map(Value::into_symbol_keys).collect())
+            }
+            value => value,
+        }
+    }
+
+    /// Gets a value nested in maps by following the keys in `path`
+    ///
+    /// At each level, the key may be either a [`Value::Symbol`] or a [`Value::String`]. Returns
+    /// `None` if any of the keys is not found or if an intermediate value is not a map. An empty
+    /// `path` returns the value itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde_amqp::{primitives::{OrderedMap, Symbol}, Value};
+    ///
+    /// let mut object_id = OrderedMap::new();
+    /// object_id.insert(Symbol::from("_object_name"), Value::from("broker"));
+    /// let mut content = OrderedMap::new();
+    /// content.insert(Symbol::from("_object_id"), Value::from(object_id));
+    ///
+    /// let content = Value::from(content);
+    /// let name = content.get_path(&["_object_id", "_object_name"]);
+    /// assert_eq!(name.and_then(Value::as_str), Some("broker"));
+    /// ```
+    pub fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        path.iter().try_fold(self, |value, key| {
+            let map = value.as_map()?;
+            map.get(&Value::Symbol(Symbol::from(*key)))
+                .or_else(|| map.get(&Value::String(String::from(*key))))
+        })
+    }
 }
 
+/// A writer that only counts the number of bytes written to it
 macro_rules! impl_from_for_value {
     ($variant:ident, $variant_ty:ty) => {
         impl From<$variant_ty> for Value {
@@ -384,6 +572,22 @@ impl From<&str> for Value {
     }
 }
 
+/// AMQP 1.0 doesn't have a 128-bit integer type, so the value is converted to a 16 bytes
+/// [`Value::Binary`] in big-endian
+impl From<u128> for Value {
+    fn from(val: u128) -> Self {
+        Self::Binary(ByteBuf::from(val.to_be_bytes().to_vec()))
+    }
+}
+
+/// AMQP 1.0 doesn't have a 128-bit integer type, so the value is converted to a 16 bytes
+/// [`Value::Binary`] in big-endian
+impl From<i128> for Value {
+    fn from(val: i128) -> Self {
+        Self::Binary(ByteBuf::from(val.to_be_bytes().to_vec()))
+    }
+}
+
 impl<T> From<Array<T>> for Value
 where
     T: Into<Value>,
@@ -517,6 +721,30 @@ impl TryFrom<Value> for f64 {
     }
 }
 
+/// Converts a 16 bytes [`Value::Binary`] in big-endian back to `u128`
+impl TryFrom<Value> for u128 {
+    type Error = Value;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value.as_bytes().map(<[u8; 16]>::try_from) {
+            Some(Ok(bytes)) => Ok(u128::from_be_bytes(bytes)),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Converts a 16 bytes [`Value::Binary`] in big-endian back to `i128`
+impl TryFrom<Value> for i128 {
+    type Error = Value;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value.as_bytes().map(<[u8; 16]>::try_from) {
+            Some(Ok(bytes)) => Ok(i128::from_be_bytes(bytes)),
+            _ => Err(value),
+        }
+    }
+}
+
 impl<K, V> TryFrom<Value> for BTreeMap<K, V>
 where
     K: TryFrom<Value, Error = Value> + Ord,
@@ -644,6 +872,7 @@ mod tests {
     use serde::de::DeserializeOwned;
 
     use crate::de::from_reader;
+    use crate::format_code::EncodingCodes;
     use crate::primitives::OrderedMap;
     use crate::ser::to_vec;
 
@@ -824,6 +1053,11 @@ mod tests {
         let expected = Value::Char('a');
         let buf = to_vec(&expected).unwrap();
         assert_eq_from_reader_vs_expected(buf, expected);
+
+        let expected = Value::Char('\u{1F600}');
+        let buf = to_vec(&expected).unwrap();
+        assert_eq!(buf, [EncodingCodes::Char as u8, 0x00, 0x01, 0xF6, 0x00]);
+        assert_eq_from_reader_vs_expected(buf, expected);
     }
 
     #[test]
@@ -918,4 +1152,122 @@ mod tests {
         let value: Value = from_slice(&buf).unwrap();
         println!("{:?}", value);
     }
+
+    #[test]
+    fn test_value_accessors() {
+        use crate::primitives::{Symbol, Timestamp};
+
+        assert_eq!(Value::Bool(true).as_bool(), Some(true));
+        assert_eq!(Value::Int(-13).as_i64(), Some(-13));
+        assert_eq!(Value::Uint(13).as_i64(), Some(13));
+        assert_eq!(Value::Ulong(u64::MAX).as_i64(), None);
+        assert_eq!(Value::Ulong(u64::MAX).as_u64(), Some(u64::MAX));
+        assert_eq!(Value::Long(13).as_u64(), Some(13));
+        assert_eq!(Value::Long(-13).as_u64(), None);
+        assert_eq!(Value::String(String::from("amqp")).as_str(), Some("amqp"));
+        assert_eq!(Value::Symbol(Symbol::from("amqp")).as_str(), None);
+        assert_eq!(
+            Value::Symbol(Symbol::from("amqp")).as_symbol(),
+            Some(&Symbol::from("amqp"))
+        );
+        assert_eq!(
+            Value::Binary(serde_bytes::ByteBuf::from(vec![1, 2])).as_bytes(),
+            Some(&[1u8, 2][..])
+        );
+        assert_eq!(
+            Value::Timestamp(Timestamp::from_milliseconds(13)).as_timestamp(),
+            Some(&Timestamp::from_milliseconds(13))
+        );
+        assert_eq!(
+            Value::List(vec![Value::Null]).as_list(),
+            Some(&[Value::Null][..])
+        );
+        assert!(Value::Null.as_map().is_none());
+
+        let mut map = OrderedMap::new();
+        map.insert(Value::from("key"), Value::from(1i32));
+        let value = Value::Map(map);
+        let entry = value
+            .as_map()
+            .and_then(|map| map.get(&Value::from("key")))
+            .and_then(Value::as_i64);
+        assert_eq!(entry, Some(1));
+    }
+
+    #[test]
+    fn test_symbol_map_helpers() {
+        use crate::primitives::Symbol;
+
+        let mut inner = OrderedMap::new();
+        inner.insert(Value::from("exchange-type"), Value::from("fanout"));
+        let mut map = OrderedMap::new();
+        map.insert(Value::Symbol(Symbol::from("name")), Value::from("test"));
+        map.insert(Value::from("properties"), Value::Map(inner));
+        let received = Value::Map(map);
+
+        assert_eq!(
+            received
+                .get_path(&["properties", "exchange-type"])
+                .and_then(Value::as_str),
+            Some("fanout")
+        );
+        assert_eq!(received.get_path(&[]), Some(&received));
+        assert!(received.get_path(&["name", "exchange-type"]).is_none());
+        assert!(received.get_path(&["missing"]).is_none());
+
+        // The same map built on the send side
+        let mut inner = OrderedMap::new();
+        inner.insert(Symbol::from("exchange-type"), Value::from("fanout"));
+        let mut expected = OrderedMap::new();
+        expected.insert(Symbol::from("name"), Value::from("test"));
+        expected.insert(Symbol::from("properties"), Value::from(inner));
+
+        let map = received.clone().into_symbol_keys().try_into_symbol_map();
+        assert_eq!(map, Ok(expected));
+
+        let map = received.try_into_symbol_map().unwrap();
+        assert_eq!(map.get(&Symbol::from("name")), Some(&Value::from("test")));
+
+        let mut invalid = OrderedMap::new();
+        invalid.insert(Value::from(1i32), Value::Null);
+        assert_eq!(
+            Value::Map(invalid).try_into_symbol_map(),
+            Err(Value::from(1i32))
+        );
+        assert_eq!(Value::Null.try_into_symbol_map(), Err(Value::Null));
+    }
+
+    #[test]
+    fn test_value_encoded_len() {
+        use crate::described::Described;
+        use crate::descriptor::Descriptor;
+        use crate::primitives::{Array, Symbol};
+
+        let mut map = OrderedMap::new();
+        map.insert(
+            Value::from(Symbol::from("key")),
+            Value::from("x".repeat(255)),
+        );
+        map.insert(Value::from("list"), Value::List(vec![Value::Null; 300]));
+        let values = vec![
+            Value::Null,
+            Value::Bool(true),
+            Value::Uint(0),
+            Value::Ulong(256),
+            Value::from("x".repeat(256)),
+            Value::Binary(vec![0u8; 10].into()),
+            Value::List(vec![]),
+            Value::List(vec![Value::from(1i32), Value::from("a")]),
+            Value::Array(Array::from(vec![Value::Long(1), Value::Long(2)])),
+            Value::Map(map),
+            Value::Described(Box::new(Described {
+                descriptor: Descriptor::Code(0x70),
+                value: Value::List(vec![Value::Uint(1)]),
+            })),
+        ];
+
+        for value in values {
+            assert_eq!(value.encoded_len().unwrap(), to_vec(&value).unwrap().len());
+        }
+    }
 }
@@ -153,6 +153,20 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         Ok(Value::Ulong(v))
     }
 
+    /// AMQP 1.0 doesn't have a 128-bit integer type, so `i128` is encoded as a 16 bytes `binary`
+    /// holding the big-endian two's complement representation of the value
+    #[inline]
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bytes(&v.to_be_bytes())
+    }
+
+    /// AMQP 1.0 doesn't have a 128-bit integer type, so `u128` is encoded as a 16 bytes `binary`
+    /// holding the big-endian representation of the value
+    #[inline]
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.serialize_bytes(&v.to_be_bytes())
+    }
+
     #[inline]
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         Ok(Value::Float(OrderedFloat::from(v)))
@@ -14,3 +14,72 @@ use serde_amqp::{DeserializeComposite, SerializeComposite};
 struct Example {
     a: i32,
 }
+
+#[cfg(feature = "derive")]
+#[derive(SerializeComposite, DeserializeComposite)]
+#[amqp_contract(
+    name = "test:example:bool",
+    descriptor = "0x00000000:0x00000075",
+    encoding = "list"
+)]
+struct ExampleWithDescriptor {
+    a: i32,
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn descriptor_matches_code() {
+    let from_code = serde_amqp::to_vec(&Example { a: 13 }).unwrap();
+    let from_descriptor = serde_amqp::to_vec(&ExampleWithDescriptor { a: 13 }).unwrap();
+    assert_eq!(from_code, from_descriptor);
+
+    let decoded: ExampleWithDescriptor = serde_amqp::from_slice(&from_code).unwrap();
+    assert_eq!(decoded.a, 13);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derived_described_type() {
+    use serde_amqp::descriptor::{DescribedType, Descriptor};
+
+    assert_eq!(Example::DESCRIPTOR_NAME, "test:example:bool");
+    assert_eq!(Example::DESCRIPTOR_CODE, Some(0x0000_0000_0000_0075));
+    assert_eq!(Example::descriptor(), Descriptor::Code(0x75));
+    assert_eq!(
+        ExampleWithDescriptor::DESCRIPTOR_CODE,
+        Example::DESCRIPTOR_CODE
+    );
+}
+
+#[cfg(feature = "derive")]
+#[derive(SerializeComposite, DeserializeComposite)]
+#[amqp_contract(code = "0x0000_0000:0x0000_0076", encoding = "list")]
+struct ExampleWithNull {
+    a: i32,
+    b: Option<u32>,
+    c: i32,
+}
+
+#[cfg(feature = "derive")]
+#[derive(Debug, PartialEq, SerializeComposite, DeserializeComposite)]
+#[amqp_contract(code = "0x0000_0000:0x0000_0076", encoding = "list")]
+struct ExampleWithDefault {
+    a: i32,
+    #[amqp_contract(default)]
+    b: u32,
+    c: i32,
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn explicit_null_decodes_to_default() {
+    // The `None` in the middle of the list is encoded as an explicit null
+    let buf = serde_amqp::to_vec(&ExampleWithNull {
+        a: 1,
+        b: None,
+        c: 3,
+    })
+    .unwrap();
+    let decoded: ExampleWithDefault = serde_amqp::from_slice(&buf).unwrap();
+    assert_eq!(decoded, ExampleWithDefault { a: 1, b: 0, c: 3 });
+}
@@ -13,7 +13,7 @@ use crate::{
 pub(crate) fn expand_deserialize(
     input: &syn::DeriveInput,
 ) -> Result<proc_macro2::TokenStream, syn::Error> {
-    let attr = parse_described_struct_attr(input);
+    let attr = parse_described_struct_attr(input)?;
     let ident = &input.ident;
     let generics = &input.generics;
     match &input.data {
@@ -104,6 +104,9 @@ fn impl_visit_seq_for_unit_struct(
 
             #evaluate_descriptor
 
+            // Skip any trailing fields unknown to this version for forward compatibility
+            while __seq.next_element::<serde_amqp::Value>()?.is_some() {}
+
             Ok( #ident )
         }
     }
@@ -192,6 +195,9 @@ fn impl_visit_seq_for_tuple_struct(
 
             #( unwrap_or_none!(#field_idents, __seq.next_element()?, #field_types); )*
 
+            // Skip any trailing fields unknown to this version for forward compatibility
+            while __seq.next_element::<serde_amqp::Value>()?.is_some() {}
+
             Ok( #ident( #(#field_idents, )* ) )
         }
     }
@@ -491,6 +497,9 @@ fn impl_visit_seq_for_struct(
             // #( unwrap_or_none!(#field_idents, __seq, #field_types); )*
             #( #field_impls; )*
 
+            // Skip any trailing fields unknown to this version for forward compatibility
+            while __seq.next_element::<serde_amqp::Value>()?.is_some() {}
+
             Ok( #ident{ #(#field_idents, )* } )
         }
     }
@@ -19,22 +19,38 @@
 //!    serializable/deserializable type. The inner struct will be serialized/deserialized with the
 //!    descriptor prepended to the struct.
 //!
+//! ## Descriptor
+//!
+//! The descriptor code can be given either with `code`, which accepts a raw `u64` (ie.
+//! `"0x0000_0000_0000_0012"`) or the `"<domain-id>:<descriptor-id>"` form, or with `descriptor`,
+//! which only accepts the `"<domain-id>:<descriptor-id>"` form used in the specification (ie.
+//! `descriptor = "0x00000000:0x00000012"`). Both the domain-id and the descriptor-id must fit in
+//! 32 bits. `code` and `descriptor` cannot be used at the same time.
+//!
+//! `SerializeComposite` also implements `serde_amqp::descriptor::DescribedType` for the struct,
+//! which exposes the descriptor name and code as associated constants.
+//!
 //! ## Details with the `"list"` encoding
 //!
 //! Optinal fields
 //!
 //! If a field is not marked with `"mandatory"` in the specification, the field can be an `Option`.
-//! During serialization, the optional fields may be skipped completely or encoded as an AMQP1.0
-//! `null` primitive (`0x40`). During deserialization, an AMQP1.0 `null` primitive or an empty field
-//! will be decoded as a `None`.
+//! During serialization, a `None` field is encoded as an AMQP1.0 `null` primitive (`0x40`) only if
+//! it is followed by a field that is present; trailing `None` fields are omitted from the list, and
+//! a list with no present fields is encoded as an empty list (`0x45`). During deserialization, an
+//! AMQP1.0 `null` primitive or an empty field will be decoded as a `None`.
+//!
+//! The specification treats a `null` field and an omitted trailing field as equivalent, so this
+//! rule is fixed rather than configurable. A typed `Option<T>` field cannot hold an explicit `null`
+//! value; use a `Value` typed field if the distinction has to be preserved.
 //!
 //! Fields with default values:
 //!
 //! For fields that have default values defined in the specification, the field type must implement
 //! both the `Default` and `PartialEq` trait. During serialization, if the field is equal to the
-//! default value of the field type, the field will be either ignored completely or encoded as an
-//! AMQP1.0 `null` primitive (`0x40`). During deserialization, an AMQP1.0 `null` primitive or an
-//! empty field will be decoded as the default value of the type.
+//! default value of the field type, the field follows the same rule as a `None` field, ie. it is
+//! encoded as `0x40` if a later field is present and omitted otherwise. During deserialization, an
+//! AMQP1.0 `null` primitive or an empty field will be decoded as the default value of the type.
 //!
 //! # Example
 //!
@@ -141,6 +157,8 @@ struct DescribedAttr {
     #[darling(default)]
     pub code: Option<String>,
     #[darling(default)]
+    pub descriptor: Option<String>,
+    #[darling(default)]
     pub encoding: Option<EncodingType>,
     #[darling(default)]
     pub rename_all: String,
@@ -165,7 +183,10 @@ struct DescribedStructAttr {
 #[proc_macro_derive(SerializeComposite, attributes(amqp_contract))]
 pub fn derive_serialize_described(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(item as DeriveInput);
-    let impl_ser = ser::expand_serialize(&input).unwrap();
+    let impl_ser = match ser::expand_serialize(&input) {
+        Ok(impl_ser) => impl_ser,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let output = quote! {
         const _: () = {
             #impl_ser
@@ -177,7 +198,10 @@ pub fn derive_serialize_described(item: proc_macro::TokenStream) -> proc_macro::
 #[proc_macro_derive(DeserializeComposite, attributes(amqp_contract))]
 pub fn derive_deserialize_described(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(item as DeriveInput);
-    let impl_de = de::expand_deserialize(&input).unwrap();
+    let impl_de = match de::expand_deserialize(&input) {
+        Ok(impl_de) => impl_de,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let output = quote! {
         const _:() = {
             #impl_de
@@ -14,7 +14,7 @@ use crate::{
 pub(crate) fn expand_serialize(
     input: &syn::DeriveInput,
 ) -> Result<proc_macro2::TokenStream, syn::Error> {
-    let amqp_attr = parse_described_struct_attr(input);
+    let amqp_attr = parse_described_struct_attr(input)?;
     let ident = &input.ident;
     let generics = &input.generics;
     match &input.data {
@@ -40,7 +40,8 @@ fn expand_serialize_on_datastruct(
         }
     };
 
-    match &data.fields {
+    let impl_described_type = impl_described_type(amqp_attr, ident, generics);
+    let impl_ser = match &data.fields {
         Fields::Named(fields) => {
             let token = match fields.named.len() {
                 0 => expand_serialize_unit_struct(ident, &descriptor, &amqp_attr.encoding),
@@ -74,6 +75,33 @@ fn expand_serialize_on_datastruct(
             &descriptor,
             &amqp_attr.encoding,
         )),
+    }?;
+
+    Ok(quote! {
+        #impl_described_type
+
+        #impl_ser
+    })
+}
+
+fn impl_described_type(
+    amqp_attr: &DescribedStructAttr,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let name = &amqp_attr.name[..];
+    let code = match amqp_attr.code {
+        Some(code) => quote!(Some(#code)),
+        None => quote!(None),
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics serde_amqp::descriptor::DescribedType for #ident #ty_generics #where_clause {
+            const DESCRIPTOR_NAME: &'static str = #name;
+            const DESCRIPTOR_CODE: Option<u64> = #code;
+        }
     }
 }
 
@@ -7,19 +7,37 @@ use syn::{parse::Parser, DeriveInput, Field};
 
 use crate::{DescribedAttr, DescribedStructAttr, EncodingType, FieldAttr};
 
-pub(crate) fn parse_described_struct_attr(input: &syn::DeriveInput) -> DescribedStructAttr {
+pub(crate) fn parse_described_struct_attr(
+    input: &syn::DeriveInput,
+) -> Result<DescribedStructAttr, syn::Error> {
     let attr = DescribedAttr::from_derive_input(input).unwrap();
 
     let name = attr.name.unwrap_or_else(|| input.ident.to_string());
-    let code = attr.code.map(parse_descriptor_code).transpose().unwrap();
+    let code = match (attr.code, attr.descriptor) {
+        (Some(_), Some(_)) => {
+            let span = get_span_of("amqp_contract", input).unwrap_or_else(|| input.ident.span());
+            return Err(syn::Error::new(
+                span,
+                "`code` and `descriptor` cannot be used at the same time",
+            ));
+        }
+        (Some(code), None) => Some(parse_descriptor_code(&code)),
+        (None, Some(descriptor)) => Some(parse_domain_descriptor(&descriptor)),
+        (None, None) => None,
+    }
+    .transpose()
+    .map_err(|err| {
+        let span = get_span_of("amqp_contract", input).unwrap_or_else(|| input.ident.span());
+        syn::Error::new(span, err)
+    })?;
     let encoding = attr.encoding.unwrap_or(EncodingType::List);
     let rename_field = attr.rename_all;
-    DescribedStructAttr {
+    Ok(DescribedStructAttr {
         name,
         code,
         encoding,
         rename_field,
-    }
+    })
 }
 
 /// Error with parsing descriptor code
@@ -28,38 +46,59 @@ pub enum ParseDescriptorCodeError {
     IncorrectDescriptorFormat,
     DomainIdParseError,
     DescriptorIdParseError,
+    DomainIdOutOfRange,
+    DescriptorIdOutOfRange,
 }
 
-fn parse_descriptor_code(s: String) -> Result<u64, ParseDescriptorCodeError> {
-    let mut split = s.split(':');
+impl std::fmt::Display for ParseDescriptorCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseDescriptorCodeError::IncorrectDescriptorFormat => {
+                write!(
+                    f,
+                    "Expecting descriptor in the form of \"<domain-id>:<descriptor-id>\""
+                )
+            }
+            ParseDescriptorCodeError::DomainIdParseError => write!(f, "Invalid domain-id"),
+            ParseDescriptorCodeError::DescriptorIdParseError => write!(f, "Invalid descriptor-id"),
+            ParseDescriptorCodeError::DomainIdOutOfRange => {
+                write!(f, "domain-id must fit in 32 bits")
+            }
+            ParseDescriptorCodeError::DescriptorIdOutOfRange => {
+                write!(f, "descriptor-id must fit in 32 bits")
+            }
+        }
+    }
+}
 
-    let first_half = split.next();
-    let second_half = split.next();
+fn parse_descriptor_code(s: &str) -> Result<u64, ParseDescriptorCodeError> {
+    if s.contains(':') {
+        return parse_domain_descriptor(s);
+    }
 
-    match second_half {
-        Some(descriptor_id_str) => {
-            let domain_id_str = first_half
-                .ok_or(ParseDescriptorCodeError::IncorrectDescriptorFormat)?
-                .replace('_', "");
-            let domain_id = parse_code_based_on_prefix(&domain_id_str)
-                .map_err(|_| ParseDescriptorCodeError::DomainIdParseError)?;
+    let descriptor_id_str = s.replace('_', "");
+    parse_code_based_on_prefix(&descriptor_id_str)
+        .map_err(|_| ParseDescriptorCodeError::DescriptorIdParseError)
+}
 
-            let descriptor_id_str = descriptor_id_str.replace('_', "");
-            let descriptor_id = parse_code_based_on_prefix(&descriptor_id_str)
-                .map_err(|_| ParseDescriptorCodeError::DescriptorIdParseError)?;
-            // numeric descriptors
-            // (domain-id << 32) | descriptor-id
-            Ok((domain_id << 32) | descriptor_id)
-        }
-        None => {
-            let descriptor_id_str = first_half
-                .ok_or(ParseDescriptorCodeError::IncorrectDescriptorFormat)?
-                .replace('_', "");
-            let descriptor_id = parse_code_based_on_prefix(&descriptor_id_str)
-                .map_err(|_| ParseDescriptorCodeError::DescriptorIdParseError)?;
-            Ok(descriptor_id)
-        }
-    }
+/// Parses a descriptor in the form of `"<domain-id>:<descriptor-id>"` where both ids must fit in
+/// 32 bits. The numeric descriptor code is `(domain-id << 32) | descriptor-id`
+fn parse_domain_descriptor(s: &str) -> Result<u64, ParseDescriptorCodeError> {
+    let (domain_id_str, descriptor_id_str) = s
+        .split_once(':')
+        .ok_or(ParseDescriptorCodeError::IncorrectDescriptorFormat)?;
+
+    let domain_id = parse_code_based_on_prefix(&domain_id_str.replace('_', ""))
+        .map_err(|_| ParseDescriptorCodeError::DomainIdParseError)?;
+    let domain_id =
+        u32::try_from(domain_id).map_err(|_| ParseDescriptorCodeError::DomainIdOutOfRange)?;
+
+    let descriptor_id = parse_code_based_on_prefix(&descriptor_id_str.replace('_', ""))
+        .map_err(|_| ParseDescriptorCodeError::DescriptorIdParseError)?;
+    let descriptor_id = u32::try_from(descriptor_id)
+        .map_err(|_| ParseDescriptorCodeError::DescriptorIdOutOfRange)?;
+
+    Ok(((domain_id as u64) << 32) | descriptor_id as u64)
 }
 
 fn parse_code_based_on_prefix(src: &str) -> Result<u64, ParseIntError> {